@@ -1,23 +1,26 @@
 use egui::plot::{GridInput, GridMark, Legend, Plot};
 use egui::{ScrollArea, Slider, Widget};
-use nalgebra::Vector2;
 
 use crate::app::audio::player::MusicPlayer;
 
 use crate::app::graphics::plot::InputMessage;
 use crate::app::manager::SimulationManager;
-use crate::app::simulations::classic_simulation::template::get_sim_list;
+use crate::app::session::Session;
+use crate::app::simulations::template::get_sim_list;
 use crate::app::util::FrameHistory;
 
 mod audio;
 mod graphics;
+mod history;
 mod io;
 pub mod manager;
+mod playback;
+mod session;
 mod simulations;
+mod stepper;
 mod util;
 
-pub type Float = f64;
-pub type NVec2 = Vector2<Float>;
+pub use crate::engine::{Float, NVec2};
 
 #[derive(Default)]
 pub struct State {
@@ -28,22 +31,23 @@ pub struct State {
 
 impl State {
     /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        // Load previous app state (if any).
-        // Note that you must enable the `persistence` feature for this to work.
-
-        // if let Some(storage) = cc.storage {
-        //     return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        // }
-        let ctx = &_cc.egui_ctx;
+        let ctx = &cc.egui_ctx;
 
         new_with_context(ctx);
-        Self {
+        let mut state = Self {
             ..Default::default()
+        };
+
+        // 지난 세션이 있으면 이어서 연다.
+        if let Some(session) = cc.storage.and_then(Session::load) {
+            state.simulation_manager.restore(session);
         }
+
+        state
     }
 }
 
@@ -89,6 +93,11 @@ fn new_with_context(ctx: &egui::Context) {
 }
 
 impl eframe::App for State {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.simulation_manager.collect_steps();
+        self.simulation_manager.session().save(storage);
+    }
+
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let current_time = ctx.input(|i| i.time);
         let cpu_usage = frame.info().cpu_usage;
@@ -97,7 +106,13 @@ impl eframe::App for State {
         puffin::GlobalProfiler::lock().new_frame(); // call once per frame!
         puffin_egui::profiler_window(ctx);
 
+        // 작업 스레드가 계산한 스텝을 받아 온다. 고칠 입력이 없는 프레임은 스냅샷을 그린다.
+        self.simulation_manager.receive_steps(ctx);
+
+        // 지난 스텝에서 고친 상태는 그 스텝을 보고 있을 때만 있으므로 넘어가기 전에 기록한다.
+        self.simulation_manager.history_input(ctx);
         self.simulation_manager.step();
+        self.simulation_manager.export_frames(ctx);
 
         self.frame_history.on_new_frame(current_time, cpu_usage);
 
@@ -224,12 +239,7 @@ impl eframe::App for State {
                             self.simulation_manager.get_time()
                         ));
 
-                        ui.horizontal(|ui| {
-                            ui.label("Time mul");
-                            let _slider =
-                                Slider::new(self.simulation_manager.time_multiplier(), 1..=5)
-                                    .ui(ui);
-                        });
+                        self.simulation_manager.playback_ui(ui);
 
                         ui.separator();
 
@@ -252,9 +262,16 @@ impl eframe::App for State {
                             }
                         });
 
+                        let mut domain = "";
+
                         let _buttons = get_sim_list()
                             .iter()
                             .map(|sim_type| {
+                                if sim_type.get_domain() != domain {
+                                    domain = sim_type.get_domain();
+                                    ui.label(domain);
+                                }
+
                                 let button = ui.button(sim_type.get_name());
 
                                 if button.clicked() {
@@ -267,6 +284,36 @@ impl eframe::App for State {
 
                         ui.separator();
 
+                        ui.collapsing("Edit History", |ui| {
+                            self.simulation_manager.history_ui(ui);
+                        });
+
+                        ui.collapsing("Branches", |ui| {
+                            self.simulation_manager.branch_ui(ui);
+                        });
+
+                        ui.collapsing("Timeline Memory", |ui| {
+                            self.simulation_manager.timeline_ui(ui);
+                        });
+
+                        ui.collapsing("Scenario File", |ui| {
+                            self.simulation_manager.scenario_ui(ui);
+                        });
+
+                        ui.collapsing("Export Timeline", |ui| {
+                            self.simulation_manager.export_ui(ui);
+                        });
+
+                        ui.collapsing("Export Figure", |ui| {
+                            self.simulation_manager.figure_ui(ui);
+
+                            ui.separator();
+                            ui.label("Animation");
+                            self.simulation_manager.animation_ui(ui);
+                        });
+
+                        ui.separator();
+
                         ui.collapsing("Laboratory", |ui| {
                             self.music_player.ui(ui);
                         });
@@ -374,7 +421,6 @@ impl eframe::App for State {
                     simulation_plot.draw(simulation.as_mut(), plot_ui, state);
 
                     InputMessage {
-
                         clicked: plot_ui.plot_clicked(),
                         hovered: plot_ui.plot_hovered(),
                         pointer_pos: plot_ui.pointer_coordinate(),
@@ -385,6 +431,9 @@ impl eframe::App for State {
             }
         });
 
+        // 다음 프레임을 기다리는 동안에도 작업 스레드가 밀린 새 스텝을 계산한다.
+        self.simulation_manager.dispatch_steps();
+
         ctx.request_repaint();
     }
 }
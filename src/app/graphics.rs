@@ -1,4 +1,5 @@
 pub mod define;
+pub mod figure;
 pub mod image;
 pub mod plot;
 
@@ -1,10 +1,9 @@
-
-
 use eframe::epaint::Color32;
-use egui::plot::{Arrows, Line, PlotImage, PlotUi, Points, Polygon, Text};
 
 pub mod items;
 
+pub use items::PlotItem;
+
 pub enum PlotColor {
     Object,
     VelocityVector,
@@ -13,6 +12,25 @@ pub enum PlotColor {
     TraceLine,
     Stamp,
     StampText,
+    GravityZone,
+    DragZone,
+    ConveyorZone,
+    Boundary,
+    Spring,
+    SoftBody,
+    Selected,
+    Branch,
+    Mirror,
+    Lens,
+    Glass,
+    LightSource,
+    WaveSource,
+    FixedTemperature,
+    Probe,
+    FluidEmitter,
+    Wire,
+    Current,
+    BulbGlow,
 }
 
 impl PlotColor {
@@ -25,10 +43,30 @@ impl PlotColor {
             PlotColor::TraceLine => Color32::GOLD,
             PlotColor::Stamp => Color32::YELLOW,
             PlotColor::StampText => Color32::GREEN,
+            PlotColor::GravityZone => Color32::LIGHT_BLUE,
+            PlotColor::DragZone => Color32::LIGHT_GREEN,
+            PlotColor::ConveyorZone => Color32::KHAKI,
+            PlotColor::Boundary => Color32::LIGHT_GRAY,
+            PlotColor::Spring => Color32::DARK_GRAY,
+            PlotColor::SoftBody => Color32::LIGHT_RED,
+            PlotColor::Selected => Color32::WHITE,
+            PlotColor::Branch => Color32::from_rgb(230, 130, 230),
+            PlotColor::Mirror => Color32::from_rgb(200, 200, 230),
+            PlotColor::Lens => Color32::LIGHT_BLUE,
+            PlotColor::Glass => Color32::from_rgb(120, 200, 220),
+            PlotColor::LightSource => Color32::YELLOW,
+            PlotColor::WaveSource => Color32::from_rgb(120, 255, 140),
+            PlotColor::FixedTemperature => Color32::LIGHT_RED,
+            PlotColor::Probe => Color32::WHITE,
+            PlotColor::FluidEmitter => Color32::from_rgb(80, 160, 255),
+            PlotColor::Wire => Color32::LIGHT_GRAY,
+            PlotColor::Current => Color32::GOLD,
+            PlotColor::BulbGlow => Color32::YELLOW,
         }
     }
 }
 
+#[allow(dead_code)]
 pub enum PlotTextSize {
     Small,
     Medium,
@@ -44,69 +82,3 @@ impl PlotTextSize {
         }
     }
 }
-
-
-pub enum PlotItem{
-    Points(Points),
-    Polygon(Polygon),
-    Arrows(Arrows),
-    Line(Line),
-    Text(Text),
-    PlotImage(PlotImage),
-}
-
-impl PlotItem{
-    pub fn draw(self, plot_ui: &mut PlotUi){
-        match self {
-            PlotItem::Points(points) => {
-                plot_ui.points(points)
-            }
-            PlotItem::Polygon(polygon) => {
-                plot_ui.polygon(polygon)
-            }
-            PlotItem::Arrows(arrows) => {
-                plot_ui.arrows(arrows)
-            }
-            PlotItem::Line(line) => {
-                plot_ui.line(line)
-            }
-            PlotItem::Text(text) => {
-                plot_ui.text(text)
-            }
-            PlotItem::PlotImage(plot_image) => {
-                plot_ui.image(plot_image)
-            }
-        }
-    }
-}
-
-impl From<Points> for PlotItem{
-    fn from(points: Points) -> Self {
-        PlotItem::Points(points)
-    }
-}
-
-impl From<Polygon> for PlotItem{
-    fn from(polygon: Polygon) -> Self {
-        PlotItem::Polygon(polygon)
-    }
-}
-
-impl From<Arrows> for PlotItem{
-    fn from(arrows: Arrows) -> Self {
-        PlotItem::Arrows(arrows)
-    }
-}
-
-impl From<Line> for PlotItem{
-    fn from(line: Line) -> Self {
-        PlotItem::Line(line)
-    }
-}
-
-impl From<Text> for PlotItem{
-    fn from(text: Text) -> Self {
-        PlotItem::Text(text)
-    }
-}
-
@@ -1,3 +1,369 @@
-pub struct PlotItem{
+//! 플롯에 그릴 항목. egui의 플롯 항목과 같은 빌더를 쓰지만 좌표를 그대로 들고 있어서
+//! 화면에 그리는 것 말고도 그림 파일로 내보낼 수 있다.
 
-}
\ No newline at end of file
+use std::sync::Arc;
+
+use egui::plot::{self, LineStyle, MarkerShape, PlotPoint, PlotPoints};
+use egui::{Align2, Color32, ColorImage, RichText, TextureId, Vec2};
+
+// 콜백으로 만든 점은 그리기 전에 계산되지 않으므로 받지 않는다.
+fn collect_points(series: impl Into<PlotPoints>) -> Vec<[f64; 2]> {
+    series
+        .into()
+        .points()
+        .iter()
+        .map(|point| [point.x, point.y])
+        .collect()
+}
+
+pub struct Line {
+    pub points: Vec<[f64; 2]>,
+    /// 투명하면 egui가 색을 고른다.
+    pub color: Color32,
+    pub width: f32,
+    pub style: LineStyle,
+    pub name: String,
+}
+
+impl Line {
+    pub fn new(series: impl Into<PlotPoints>) -> Self {
+        Self {
+            points: collect_points(series),
+            color: Color32::TRANSPARENT,
+            width: 1.0,
+            style: LineStyle::Solid,
+            name: String::new(),
+        }
+    }
+
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn style(mut self, style: LineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::Line {
+        plot::Line::new(self.points)
+            .color(self.color)
+            .width(self.width)
+            .style(self.style)
+            .name(self.name)
+    }
+}
+
+pub struct Polygon {
+    pub points: Vec<[f64; 2]>,
+    pub color: Color32,
+    pub width: f32,
+    pub fill_alpha: f32,
+    pub style: LineStyle,
+    pub name: String,
+}
+
+impl Polygon {
+    /// egui의 기본 채움 불투명도
+    pub const DEFAULT_FILL_ALPHA: f32 = 0.05;
+
+    pub fn new(series: impl Into<PlotPoints>) -> Self {
+        Self {
+            points: collect_points(series),
+            color: Color32::TRANSPARENT,
+            width: 1.0,
+            fill_alpha: Self::DEFAULT_FILL_ALPHA,
+            style: LineStyle::Solid,
+            name: String::new(),
+        }
+    }
+
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn width(mut self, width: impl Into<f32>) -> Self {
+        self.width = width.into();
+        self
+    }
+
+    pub fn fill_alpha(mut self, alpha: impl Into<f32>) -> Self {
+        self.fill_alpha = alpha.into();
+        self
+    }
+
+    pub fn style(mut self, style: LineStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::Polygon {
+        plot::Polygon::new(self.points)
+            .color(self.color)
+            .width(self.width)
+            .fill_alpha(self.fill_alpha)
+            .style(self.style)
+            .name(self.name)
+    }
+}
+
+pub struct Points {
+    pub points: Vec<[f64; 2]>,
+    pub color: Color32,
+    /// 화면 점 단위의 반지름
+    pub radius: f32,
+    pub filled: bool,
+    pub shape: MarkerShape,
+    pub name: String,
+}
+
+impl Points {
+    pub fn new(series: impl Into<PlotPoints>) -> Self {
+        Self {
+            points: collect_points(series),
+            color: Color32::TRANSPARENT,
+            radius: 1.0,
+            filled: true,
+            shape: MarkerShape::Circle,
+            name: String::new(),
+        }
+    }
+
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn radius(mut self, radius: impl Into<f32>) -> Self {
+        self.radius = radius.into();
+        self
+    }
+
+    pub fn filled(mut self, filled: bool) -> Self {
+        self.filled = filled;
+        self
+    }
+
+    pub fn shape(mut self, shape: MarkerShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::Points {
+        plot::Points::new(self.points)
+            .color(self.color)
+            .radius(self.radius)
+            .filled(self.filled)
+            .shape(self.shape)
+            .name(self.name)
+    }
+}
+
+pub struct Arrows {
+    pub origins: Vec<[f64; 2]>,
+    pub tips: Vec<[f64; 2]>,
+    pub color: Color32,
+    pub name: String,
+}
+
+impl Arrows {
+    pub fn new(origins: impl Into<PlotPoints>, tips: impl Into<PlotPoints>) -> Self {
+        Self {
+            origins: collect_points(origins),
+            tips: collect_points(tips),
+            color: Color32::TRANSPARENT,
+            name: String::new(),
+        }
+    }
+
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::Arrows {
+        plot::Arrows::new(self.origins, self.tips)
+            .color(self.color)
+            .name(self.name)
+    }
+}
+
+pub struct Text {
+    pub position: [f64; 2],
+    pub text: String,
+    /// 글자 크기. 없으면 egui의 작은 글씨 크기를 쓴다.
+    pub size: Option<f32>,
+    /// 투명하면 테마의 글자색을 쓴다.
+    pub color: Color32,
+    pub anchor: Align2,
+    pub name: String,
+}
+
+impl Text {
+    /// egui의 작은 글씨 크기
+    pub const DEFAULT_SIZE: f32 = 9.0;
+
+    pub fn new(position: PlotPoint, text: impl ToString) -> Self {
+        Self {
+            position: [position.x, position.y],
+            text: text.to_string(),
+            size: None,
+            color: Color32::TRANSPARENT,
+            anchor: Align2::CENTER_CENTER,
+            name: String::new(),
+        }
+    }
+
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<Color32>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn anchor(mut self, anchor: Align2) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::Text {
+        let mut text = RichText::new(self.text);
+        if let Some(size) = self.size {
+            text = text.size(size);
+        }
+
+        plot::Text::new(PlotPoint::from(self.position), text)
+            .color(self.color)
+            .anchor(self.anchor)
+            .name(self.name)
+    }
+}
+
+pub struct PlotImage {
+    pub texture: TextureId,
+    pub center: [f64; 2],
+    /// 플롯 단위의 크기
+    pub size: Vec2,
+    /// 내보낼 때 쓰는 픽셀. 텍스처만 있는 그림은 내보내지 않는다.
+    pub pixels: Option<Arc<ColorImage>>,
+    pub name: String,
+}
+
+impl PlotImage {
+    pub fn new(texture: TextureId, center: PlotPoint, size: impl Into<Vec2>) -> Self {
+        Self {
+            texture,
+            center: [center.x, center.y],
+            size: size.into(),
+            pixels: None,
+            name: String::new(),
+        }
+    }
+
+    pub fn pixels(mut self, pixels: Arc<ColorImage>) -> Self {
+        self.pixels = Some(pixels);
+        self
+    }
+
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn into_egui(self) -> plot::PlotImage {
+        plot::PlotImage::new(self.texture, PlotPoint::from(self.center), self.size).name(self.name)
+    }
+}
+
+pub enum PlotItem {
+    Points(Points),
+    Polygon(Polygon),
+    Arrows(Arrows),
+    Line(Line),
+    Text(Text),
+    PlotImage(PlotImage),
+}
+
+impl PlotItem {
+    pub fn draw(self, plot_ui: &mut plot::PlotUi) {
+        match self {
+            PlotItem::Points(points) => plot_ui.points(points.into_egui()),
+            PlotItem::Polygon(polygon) => plot_ui.polygon(polygon.into_egui()),
+            PlotItem::Arrows(arrows) => plot_ui.arrows(arrows.into_egui()),
+            PlotItem::Line(line) => plot_ui.line(line.into_egui()),
+            PlotItem::Text(text) => plot_ui.text(text.into_egui()),
+            PlotItem::PlotImage(plot_image) => plot_ui.image(plot_image.into_egui()),
+        }
+    }
+}
+
+impl From<Points> for PlotItem {
+    fn from(points: Points) -> Self {
+        PlotItem::Points(points)
+    }
+}
+
+impl From<Polygon> for PlotItem {
+    fn from(polygon: Polygon) -> Self {
+        PlotItem::Polygon(polygon)
+    }
+}
+
+impl From<Arrows> for PlotItem {
+    fn from(arrows: Arrows) -> Self {
+        PlotItem::Arrows(arrows)
+    }
+}
+
+impl From<Line> for PlotItem {
+    fn from(line: Line) -> Self {
+        PlotItem::Line(line)
+    }
+}
+
+impl From<PlotImage> for PlotItem {
+    fn from(image: PlotImage) -> Self {
+        PlotItem::PlotImage(image)
+    }
+}
+
+impl From<Text> for PlotItem {
+    fn from(text: Text) -> Self {
+        PlotItem::Text(text)
+    }
+}
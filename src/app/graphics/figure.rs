@@ -0,0 +1,946 @@
+//! 플롯 항목을 인쇄용 그림(SVG, PDF)으로 바꾼다.
+//! 그림은 화면에 보이던 영역을 그대로 담고, 선 굵기와 글자 크기는 화면에서와 같은 비율로 키운다.
+
+use std::fmt::Write;
+use std::sync::Arc;
+
+use egui::plot::LineStyle;
+use egui::{Align, Align2, Color32, ColorImage};
+use image::ImageEncoder;
+
+use crate::app::graphics::define::items::Text;
+use crate::app::graphics::define::PlotItem;
+use crate::app::NVec2;
+
+pub mod animation;
+pub mod raster;
+
+/// 텍스트 한 줄의 높이. 글자 크기에 곱한다.
+const LINE_HEIGHT: f64 = 1.2;
+
+/// 밝은 배경에 그릴 색의 가장 큰 밝기 (0 ~ 255)
+const MAX_LIGHTNESS_ON_LIGHT: f64 = 160.0;
+
+/// 화살촉이 화살표 방향과 이루는 각 (egui와 같다)
+const ARROW_TIP_ANGLE: f64 = std::f64::consts::TAU / 10.0;
+
+#[derive(Clone, Copy)]
+struct Stroke {
+    width: f64,
+    color: Color32,
+    /// 선과 빈칸의 길이
+    dash: Option<[f64; 2]>,
+}
+
+// 그림 좌표(왼쪽 위가 원점, 아래로 y 증가)로 옮긴 도형
+enum Shape {
+    Path {
+        points: Vec<[f64; 2]>,
+        closed: bool,
+        fill: Option<Color32>,
+        stroke: Option<Stroke>,
+    },
+    Circle {
+        center: [f64; 2],
+        radius: f64,
+        fill: Option<Color32>,
+        stroke: Option<Stroke>,
+    },
+    Text {
+        position: [f64; 2],
+        lines: Vec<String>,
+        size: f64,
+        color: Color32,
+        anchor: Align2,
+    },
+    Image {
+        min: [f64; 2],
+        size: [f64; 2],
+        pixels: Arc<ColorImage>,
+    },
+}
+
+/// 플롯에서 보이던 영역을 그림 한 장으로 옮긴다.
+#[derive(Clone)]
+pub struct Figure {
+    min: [f64; 2],
+    max: [f64; 2],
+    width: f64,
+    height: f64,
+    /// 플롯 한 단위가 그림에서 몇 단위인지
+    unit: f64,
+    /// 화면의 한 점이 그림에서 몇 단위인지. 선 굵기, 점, 글자 크기에 쓴다.
+    point: f64,
+    background: Color32,
+    /// 색을 정하지 않은 선과 글자의 색
+    foreground: Color32,
+    grid: bool,
+}
+
+impl Figure {
+    /// `view`는 플롯에 보이던 영역, `width`는 그림의 가로 크기(SVG는 px, PDF는 pt)
+    pub fn new(view: [NVec2; 2], pixels_per_unit: f64, width: f64) -> Self {
+        let [min, max] = view;
+        let unit = width / (max.x - min.x);
+
+        Self {
+            min: [min.x, min.y],
+            max: [max.x, max.y],
+            width,
+            height: (max.y - min.y) * unit,
+            unit,
+            point: unit / pixels_per_unit,
+            background: Color32::WHITE,
+            foreground: Color32::BLACK,
+            grid: false,
+        }
+    }
+
+    /// 배경색을 바꾼다. 글자색은 배경의 밝기에 맞춰 고른다.
+    pub fn with_background(mut self, background: Color32) -> Self {
+        self.background = background;
+        self.foreground = if luminance(background) > 127.0 {
+            Color32::BLACK
+        } else {
+            Color32::LIGHT_GRAY
+        };
+        self
+    }
+
+    pub fn with_grid(mut self, grid: bool) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    fn to_figure(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [(x - self.min[0]) * self.unit, (self.max[1] - y) * self.unit]
+    }
+
+    // 흰 바탕에서는 화면용의 밝은 색이 보이지 않으므로 읽을 수 있을 만큼 어둡게 한다.
+    fn color_or_foreground(&self, color: Color32) -> Color32 {
+        if color == Color32::TRANSPARENT {
+            return self.foreground;
+        }
+
+        let lightness = luminance(color);
+        if luminance(self.background) > 127.0 && lightness > MAX_LIGHTNESS_ON_LIGHT {
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            let darken = |c: u8| (c as f64 * MAX_LIGHTNESS_ON_LIGHT / lightness) as u8;
+
+            Color32::from_rgba_unmultiplied(darken(r), darken(g), darken(b), a)
+        } else {
+            color
+        }
+    }
+
+    fn stroke(&self, width: f32, color: Color32, style: LineStyle) -> Stroke {
+        let width = width as f64 * self.point;
+        let dash = match style {
+            LineStyle::Solid => None,
+            LineStyle::Dotted { spacing } => Some([0.0, spacing as f64 * self.point]),
+            LineStyle::Dashed { length } => {
+                let length = length as f64 * self.point;
+                Some([length, length * 0.618])
+            }
+        };
+
+        Stroke {
+            width,
+            color: self.color_or_foreground(color),
+            dash,
+        }
+    }
+
+    fn grid_shapes(&self, shapes: &mut Vec<Shape>) {
+        let span = (self.max[0] - self.min[0]).max(self.max[1] - self.min[1]);
+        // 선이 열 개 남짓 되도록 1, 2, 5 단위로 간격을 고른다.
+        let rough = span / 10.0;
+        let magnitude = 10f64.powf(rough.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|factor| factor * magnitude)
+            .find(|step| *step >= rough)
+            .unwrap_or(rough);
+        let faded = |alpha: f32| {
+            let [r, g, b, _] = self.foreground.to_array();
+            Color32::from_rgba_unmultiplied(r, g, b, (alpha * 255.0) as u8)
+        };
+
+        for axis in 0..2 {
+            let first = (self.min[axis] / step).ceil() as i64;
+            let last = (self.max[axis] / step).floor() as i64;
+
+            for i in first..=last {
+                let value = i as f64 * step;
+                let (start, end) = if axis == 0 {
+                    ([value, self.min[1]], [value, self.max[1]])
+                } else {
+                    ([self.min[0], value], [self.max[0], value])
+                };
+
+                let color = if i == 0 { faded(0.5) } else { faded(0.15) };
+
+                shapes.push(Shape::Path {
+                    points: vec![self.to_figure(start), self.to_figure(end)],
+                    closed: false,
+                    fill: None,
+                    stroke: Some(Stroke {
+                        width: 0.5 * self.point,
+                        color,
+                        dash: None,
+                    }),
+                });
+            }
+        }
+    }
+
+    fn shapes(&self, items: &[PlotItem]) -> Vec<Shape> {
+        let mut shapes = vec![];
+
+        if self.grid {
+            self.grid_shapes(&mut shapes);
+        }
+
+        for item in items {
+            match item {
+                PlotItem::Line(line) => shapes.push(Shape::Path {
+                    points: line.points.iter().map(|p| self.to_figure(*p)).collect(),
+                    closed: false,
+                    fill: None,
+                    stroke: Some(self.stroke(line.width, line.color, line.style)),
+                }),
+                PlotItem::Polygon(polygon) => {
+                    let stroke = self.stroke(polygon.width, polygon.color, polygon.style);
+                    let [r, g, b, _] = stroke.color.to_array();
+                    let alpha = (polygon.fill_alpha.clamp(0.0, 1.0) * 255.0) as u8;
+
+                    shapes.push(Shape::Path {
+                        points: polygon.points.iter().map(|p| self.to_figure(*p)).collect(),
+                        closed: true,
+                        fill: Some(Color32::from_rgba_unmultiplied(r, g, b, alpha)),
+                        stroke: Some(stroke),
+                    });
+                }
+                PlotItem::Points(points) => {
+                    let color = self.color_or_foreground(points.color);
+
+                    for point in &points.points {
+                        shapes.push(Shape::Circle {
+                            center: self.to_figure(*point),
+                            radius: points.radius as f64 * self.point,
+                            fill: points.filled.then_some(color),
+                            stroke: (!points.filled).then_some(Stroke {
+                                width: self.point,
+                                color,
+                                dash: None,
+                            }),
+                        });
+                    }
+                }
+                PlotItem::Arrows(arrows) => {
+                    let stroke = self.stroke(1.0, arrows.color, LineStyle::Solid);
+
+                    for (origin, tip) in arrows.origins.iter().zip(&arrows.tips) {
+                        let origin = self.to_figure(*origin);
+                        let tip = self.to_figure(*tip);
+                        let vector = [tip[0] - origin[0], tip[1] - origin[1]];
+                        let length = vector[0].hypot(vector[1]);
+
+                        shapes.push(Shape::Path {
+                            points: vec![origin, tip],
+                            closed: false,
+                            fill: None,
+                            stroke: Some(stroke),
+                        });
+
+                        if length == 0.0 {
+                            continue;
+                        }
+
+                        let tip_length = length / 4.0;
+                        let dir = [vector[0] / length, vector[1] / length];
+                        let barb = |angle: f64| {
+                            let (sin, cos) = angle.sin_cos();
+                            [
+                                tip[0] - tip_length * (cos * dir[0] - sin * dir[1]),
+                                tip[1] - tip_length * (sin * dir[0] + cos * dir[1]),
+                            ]
+                        };
+
+                        shapes.push(Shape::Path {
+                            points: vec![barb(-ARROW_TIP_ANGLE), tip, barb(ARROW_TIP_ANGLE)],
+                            closed: false,
+                            fill: None,
+                            stroke: Some(stroke),
+                        });
+                    }
+                }
+                PlotItem::Text(text) => {
+                    if text.text.is_empty() {
+                        continue;
+                    }
+
+                    shapes.push(Shape::Text {
+                        position: self.to_figure(text.position),
+                        lines: text.text.lines().map(str::to_string).collect(),
+                        size: text.size.unwrap_or(Text::DEFAULT_SIZE) as f64 * self.point,
+                        color: self.color_or_foreground(text.color),
+                        anchor: text.anchor,
+                    });
+                }
+                PlotItem::PlotImage(image) => {
+                    let Some(pixels) = &image.pixels else {
+                        continue;
+                    };
+
+                    let half = [image.size.x as f64 / 2.0, image.size.y as f64 / 2.0];
+                    let min =
+                        self.to_figure([image.center[0] - half[0], image.center[1] + half[1]]);
+
+                    shapes.push(Shape::Image {
+                        min,
+                        size: [
+                            image.size.x as f64 * self.unit,
+                            image.size.y as f64 * self.unit,
+                        ],
+                        pixels: pixels.clone(),
+                    });
+                }
+            }
+        }
+
+        shapes
+    }
+}
+
+fn luminance(color: Color32) -> f64 {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64
+}
+
+// 여러 줄 글의 첫 줄 기준선 위치. 가로 위치는 정렬과 함께 쓴다.
+fn text_origin(position: [f64; 2], lines: usize, size: f64, anchor: Align2) -> [f64; 2] {
+    let height = lines as f64 * size * LINE_HEIGHT;
+    let top = match anchor.y() {
+        Align::Min => position[1],
+        Align::Center => position[1] - height / 2.0,
+        Align::Max => position[1] - height,
+    };
+
+    [position[0], top + size]
+}
+
+/// for SVG
+impl Figure {
+    pub fn to_svg(&self, items: &[PlotItem]) -> String {
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.2} {h:.2}" font-family="sans-serif">"#,
+            w = self.width,
+            h = self.height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" {}/>"#,
+            svg_paint("fill", self.background)
+        );
+
+        for shape in self.shapes(items) {
+            match shape {
+                Shape::Path {
+                    points,
+                    closed,
+                    fill,
+                    stroke,
+                } => {
+                    if points.is_empty() {
+                        continue;
+                    }
+
+                    let mut data = String::new();
+                    for (i, [x, y]) in points.iter().enumerate() {
+                        let command = if i == 0 { 'M' } else { 'L' };
+                        let _ = write!(data, "{}{:.2} {:.2} ", command, x, y);
+                    }
+                    if closed {
+                        data.push('Z');
+                    }
+
+                    let _ = writeln!(
+                        svg,
+                        r#"<path d="{}" {} {}/>"#,
+                        data.trim_end(),
+                        fill.map_or("fill=\"none\"".to_string(), |fill| svg_paint("fill", fill)),
+                        svg_stroke(stroke)
+                    );
+                }
+                Shape::Circle {
+                    center: [x, y],
+                    radius,
+                    fill,
+                    stroke,
+                } => {
+                    let _ = writeln!(
+                        svg,
+                        r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" {} {}/>"#,
+                        x,
+                        y,
+                        radius,
+                        fill.map_or("fill=\"none\"".to_string(), |fill| svg_paint("fill", fill)),
+                        svg_stroke(stroke)
+                    );
+                }
+                Shape::Text {
+                    position,
+                    lines,
+                    size,
+                    color,
+                    anchor,
+                } => {
+                    let [x, y] = text_origin(position, lines.len(), size, anchor);
+                    let text_anchor = match anchor.x() {
+                        Align::Min => "start",
+                        Align::Center => "middle",
+                        Align::Max => "end",
+                    };
+
+                    let _ = write!(
+                        svg,
+                        r#"<text x="{:.2}" y="{:.2}" font-size="{:.2}" text-anchor="{}" {}>"#,
+                        x,
+                        y,
+                        size,
+                        text_anchor,
+                        svg_paint("fill", color)
+                    );
+                    for (i, line) in lines.iter().enumerate() {
+                        let dy = if i == 0 { 0.0 } else { size * LINE_HEIGHT };
+                        let _ = write!(
+                            svg,
+                            r#"<tspan x="{:.2}" dy="{:.2}">{}</tspan>"#,
+                            x,
+                            dy,
+                            xml_escape(line)
+                        );
+                    }
+                    svg.push_str("</text>\n");
+                }
+                Shape::Image {
+                    min: [x, y],
+                    size: [w, h],
+                    pixels,
+                } => {
+                    let Some(png) = encode_png(&pixels) else {
+                        continue;
+                    };
+
+                    let _ = writeln!(
+                        svg,
+                        r#"<image x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+                        x,
+                        y,
+                        w,
+                        h,
+                        base64(&png)
+                    );
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn svg_paint(attribute: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let mut paint = format!(r#"{}="rgb({},{},{})""#, attribute, r, g, b);
+
+    if a < 255 {
+        let _ = write!(paint, r#" {}-opacity="{:.3}""#, attribute, a as f64 / 255.0);
+    }
+
+    paint
+}
+
+fn svg_stroke(stroke: Option<Stroke>) -> String {
+    let Some(stroke) = stroke else {
+        return "stroke=\"none\"".to_string();
+    };
+
+    let mut attributes = format!(
+        r#"{} stroke-width="{:.2}" stroke-linecap="round" stroke-linejoin="round""#,
+        svg_paint("stroke", stroke.color),
+        stroke.width
+    );
+
+    if let Some([on, off]) = stroke.dash {
+        let _ = write!(attributes, r#" stroke-dasharray="{:.2} {:.2}""#, on, off);
+    }
+
+    attributes
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn encode_png(pixels: &ColorImage) -> Option<Vec<u8>> {
+    let [width, height] = pixels.size;
+    let rgba = pixels
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect::<Vec<_>>();
+
+    let mut png = vec![];
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(&rgba, width as u32, height as u32, image::ColorType::Rgba8)
+        .ok()?;
+
+    Some(png)
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut text = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(TABLE[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+
+    text
+}
+
+/// for PDF
+impl Figure {
+    /// 한 쪽짜리 PDF. 투명도는 넣지 않고 배경과 섞은 색으로 칠하며, 글꼴은 Helvetica를 쓴다.
+    pub fn to_pdf(&self, items: &[PlotItem]) -> Vec<u8> {
+        let mut images = vec![];
+
+        // 그림 좌표를 그대로 쓰도록 y축을 뒤집는다.
+        let mut content = format!("1 0 0 -1 0 {:.2} cm\n", self.height);
+        let _ = writeln!(
+            content,
+            "{} 0 0 {:.2} {:.2} re f",
+            pdf_color(self.background, "rg"),
+            self.width,
+            self.height
+        );
+
+        for shape in self.shapes(items) {
+            match shape {
+                Shape::Path {
+                    points,
+                    closed,
+                    fill,
+                    stroke,
+                } => {
+                    if points.is_empty() {
+                        continue;
+                    }
+
+                    let mut path = String::new();
+                    for (i, [x, y]) in points.iter().enumerate() {
+                        let command = if i == 0 { "m" } else { "l" };
+                        let _ = writeln!(path, "{:.2} {:.2} {}", x, y, command);
+                    }
+                    if closed {
+                        path.push_str("h\n");
+                    }
+
+                    self.pdf_paint(&mut content, &path, fill, stroke);
+                }
+                Shape::Circle {
+                    center: [x, y],
+                    radius: r,
+                    fill,
+                    stroke,
+                } => {
+                    // 네 개의 베지어 곡선으로 원을 그린다.
+                    let k = 0.5523 * r;
+                    let path = format!(
+                        "{x0:.2} {y:.2} m\n\
+                         {x0:.2} {a:.2} {b:.2} {y0:.2} {x:.2} {y0:.2} c\n\
+                         {c:.2} {y0:.2} {x1:.2} {a:.2} {x1:.2} {y:.2} c\n\
+                         {x1:.2} {d:.2} {c:.2} {y1:.2} {x:.2} {y1:.2} c\n\
+                         {b:.2} {y1:.2} {x0:.2} {d:.2} {x0:.2} {y:.2} c\nh\n",
+                        x = x,
+                        y = y,
+                        x0 = x - r,
+                        x1 = x + r,
+                        y0 = y - r,
+                        y1 = y + r,
+                        a = y - k,
+                        b = x - k,
+                        c = x + k,
+                        d = y + k,
+                    );
+
+                    self.pdf_paint(&mut content, &path, fill, stroke);
+                }
+                Shape::Text {
+                    position,
+                    lines,
+                    size,
+                    color,
+                    anchor,
+                } => {
+                    let [x, y] = text_origin(position, lines.len(), size, anchor);
+                    let _ = writeln!(content, "{}", pdf_color(self.blend(color), "rg"));
+
+                    for (i, line) in lines.iter().enumerate() {
+                        // Helvetica의 평균 글자 폭으로 가로 정렬을 맞춘다.
+                        let width = line.chars().count() as f64 * size * 0.5;
+                        let x = match anchor.x() {
+                            Align::Min => x,
+                            Align::Center => x - width / 2.0,
+                            Align::Max => x - width,
+                        };
+                        let y = y + i as f64 * size * LINE_HEIGHT;
+
+                        let _ = writeln!(
+                            content,
+                            "BT /F1 {:.2} Tf 1 0 0 -1 {:.2} {:.2} Tm ({}) Tj ET",
+                            size,
+                            x,
+                            y,
+                            pdf_string(line)
+                        );
+                    }
+                }
+                Shape::Image {
+                    min: [x, y],
+                    size: [w, h],
+                    pixels,
+                } => {
+                    let _ = writeln!(
+                        content,
+                        "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q",
+                        w,
+                        -h,
+                        x,
+                        y + h,
+                        images.len()
+                    );
+                    images.push(pixels);
+                }
+            }
+        }
+
+        // 1: 카탈로그, 2: 페이지 목록, 3: 페이지, 4: 글꼴, 5: 내용, 6..: 이미지
+        let image_refs = match images.len() {
+            0 => String::new(),
+            count => format!(
+                " /XObject << {} >>",
+                (0..count)
+                    .map(|i| format!("/Im{} {} 0 R", i, 6 + i))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        };
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                 /Resources << /Font << /F1 4 0 R >>{} >> /Contents 5 0 R >>",
+                self.width, self.height, image_refs
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+            pdf_stream("", content.as_bytes()),
+        ];
+
+        for pixels in images {
+            let [width, height] = pixels.size;
+            let rgb = pixels
+                .pixels
+                .iter()
+                .flat_map(|pixel| {
+                    let [r, g, b, _] = self.blend(*pixel).to_array();
+                    [r, g, b]
+                })
+                .collect::<Vec<_>>();
+
+            objects.push(pdf_stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                     /ColorSpace /DeviceRGB /BitsPerComponent 8 ",
+                    width, height
+                ),
+                &rgb,
+            ));
+        }
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = vec![];
+
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+            pdf.extend(object);
+            pdf.extend(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        pdf.extend(trailer.into_bytes());
+
+        pdf
+    }
+
+    // 반투명한 색을 배경 위에 칠했을 때의 색
+    fn blend(&self, color: Color32) -> Color32 {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let [br, bg, bb, _] = self.background.to_array();
+        let alpha = a as f32 / 255.0;
+        let mix = |c: u8, back: u8| (c as f32 * alpha + back as f32 * (1.0 - alpha)) as u8;
+
+        Color32::from_rgb(mix(r, br), mix(g, bg), mix(b, bb))
+    }
+
+    fn pdf_paint(
+        &self,
+        content: &mut String,
+        path: &str,
+        fill: Option<Color32>,
+        stroke: Option<Stroke>,
+    ) {
+        if let Some(fill) = fill {
+            let _ = write!(
+                content,
+                "{}\n{}f\n",
+                pdf_color(self.blend(fill), "rg"),
+                path
+            );
+        }
+
+        if let Some(stroke) = stroke {
+            let dash = match stroke.dash {
+                Some([on, off]) => format!("[{:.2} {:.2}] 0 d", on, off),
+                None => "[] 0 d".to_string(),
+            };
+
+            let _ = write!(
+                content,
+                "{} {:.2} w 1 J 1 j {}\n{}S\n",
+                pdf_color(self.blend(stroke.color), "RG"),
+                stroke.width,
+                dash,
+                path
+            );
+        }
+    }
+}
+
+fn pdf_color(color: Color32, operator: &str) -> String {
+    let [r, g, b, _] = color.to_array();
+
+    format!(
+        "{:.3} {:.3} {:.3} {}",
+        r as f64 / 255.0,
+        g as f64 / 255.0,
+        b as f64 / 255.0,
+        operator
+    )
+}
+
+// WinAnsi로 쓸 수 없는 글자는 '?'로 바꾼다.
+fn pdf_string(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(escaped, "\\{:03o}", c as u32);
+            }
+            _ => escaped.push('?'),
+        }
+    }
+
+    escaped
+}
+
+fn pdf_stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut stream = format!("<< {}/Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+    stream.extend(data);
+    stream.extend(b"\nendstream");
+    stream
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::plot::PlotPoint;
+    use egui::TextureId;
+
+    use super::*;
+    use crate::app::graphics::define::items::{Line, PlotImage, Polygon};
+
+    // 가로세로 2인 영역을 200 단위 폭의 그림으로 옮긴다. 플롯 한 단위가 그림의 100 단위다.
+    fn figure() -> Figure {
+        Figure::new([NVec2::new(-1.0, -1.0), NVec2::new(1.0, 1.0)], 50.0, 200.0)
+    }
+
+    fn items() -> Vec<PlotItem> {
+        vec![
+            Line::new(vec![[0.0, 0.0], [1.0, 1.0]]).into(),
+            Polygon::new(vec![[-1.0, -1.0], [0.0, -1.0], [0.0, 0.0]]).into(),
+            Text::new(PlotPoint::new(0.5, -0.5), "a < b & (c)").into(),
+            PlotImage::new(TextureId::default(), PlotPoint::new(0.0, 0.0), [1.0, 1.0])
+                .pixels(Arc::new(ColorImage::new([2, 2], Color32::RED)))
+                .into(),
+        ]
+    }
+
+    // 태그가 모두 닫히는지 보고 (이름, 속성 문자열) 목록을 돌려준다.
+    fn parse_tags(svg: &str) -> Vec<(String, String)> {
+        let mut tags = vec![];
+        let mut open: Vec<String> = vec![];
+        let mut rest = svg;
+
+        while let Some(start) = rest.find('<') {
+            let text = &rest[..start];
+            assert!(!text.contains('>'), "unescaped text {:?}", text);
+            for (i, _) in text.match_indices('&') {
+                let entity = &text[i..i + text[i..].find(';').expect("unterminated entity") + 1];
+                assert!(
+                    ["&lt;", "&gt;", "&amp;", "&quot;", "&apos;"].contains(&entity),
+                    "unknown entity {}",
+                    entity
+                );
+            }
+            let end = rest[start..].find('>').expect("unterminated tag") + start;
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop().as_deref(), Some(name));
+                continue;
+            }
+
+            let (body, closed) = match tag.strip_suffix('/') {
+                Some(body) => (body, true),
+                None => (tag, false),
+            };
+            let (name, attributes) = body.split_once(' ').unwrap_or((body, ""));
+            assert_eq!(attributes.matches('"').count() % 2, 0, "{}", tag);
+
+            if !closed {
+                open.push(name.to_string());
+            }
+            tags.push((name.to_string(), attributes.to_string()));
+        }
+
+        assert!(open.is_empty(), "unclosed tags {:?}", open);
+        tags
+    }
+
+    fn attribute<'a>(attributes: &'a str, name: &str) -> &'a str {
+        let start = attributes
+            .find(&format!("{}=\"", name))
+            .unwrap_or_else(|| panic!("no {} in {}", name, attributes))
+            + name.len()
+            + 2;
+        let end = attributes[start..].find('"').unwrap() + start;
+        &attributes[start..end]
+    }
+
+    #[test]
+    fn svg_is_well_formed_and_has_the_item_paths() {
+        let svg = figure().with_grid(true).to_svg(&items());
+        let tags = parse_tags(&svg);
+
+        assert_eq!(tags[0].0, "svg");
+        assert_eq!(attribute(&tags[0].1, "width"), "200");
+        assert_eq!(attribute(&tags[0].1, "height"), "200");
+
+        let paths = tags
+            .iter()
+            .filter(|(name, _)| name == "path")
+            .map(|(_, attributes)| attribute(attributes, "d"))
+            .collect::<Vec<_>>();
+
+        // 격자 다음에 선과 다각형이 온다.
+        assert!(paths.len() > 2);
+        assert_eq!(paths[paths.len() - 2], "M100.00 100.00 L200.00 0.00");
+        assert_eq!(
+            paths[paths.len() - 1],
+            "M0.00 200.00 L100.00 200.00 L100.00 100.00 Z"
+        );
+
+        assert!(svg.contains("a &lt; b &amp; (c)"));
+        let image = tags.iter().find(|(name, _)| name == "image").unwrap();
+        assert_eq!(attribute(&image.1, "x"), "50.00");
+        assert_eq!(attribute(&image.1, "width"), "100.00");
+        assert!(attribute(&image.1, "href").starts_with("data:image/png;base64,"));
+    }
+
+    #[test]
+    fn pdf_xref_points_at_every_object() {
+        let pdf = figure().to_pdf(&items());
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let startxref = text.rfind("startxref\n").unwrap();
+        let xref: usize = text[startxref + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with(b"xref\n"));
+
+        let mut lines = std::str::from_utf8(&pdf[xref..]).unwrap().lines().skip(1);
+        let size: usize = lines.next().unwrap()["0 ".len()..].parse().unwrap();
+        // 카탈로그, 페이지 목록, 페이지, 글꼴, 내용, 이미지 하나
+        assert_eq!(size, 7);
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+
+        for object in 1..size {
+            let entry = lines.next().unwrap();
+            assert_eq!(entry.len(), 19);
+            assert!(entry.ends_with(" 00000 n "));
+
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj\n", object).as_bytes()));
+        }
+
+        assert_eq!(lines.next(), Some("trailer"));
+        assert!(text.contains(&format!("/Size {} /Root 1 0 R", size)));
+        assert!(text.contains("(a < b & \\(c\\)) Tj"));
+    }
+}
@@ -0,0 +1,429 @@
+//! 타임라인의 한 구간을 프레임마다 그려서 움직이는 GIF나 번호 붙은 PNG 묶음으로 만든다.
+//! PNG 묶음은 웹에서도 파일 하나로 내려받을 수 있도록 압축하지 않은 zip에 담는다.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use eframe::epaint::mutex::Mutex;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::codecs::png::PngEncoder;
+use image::{Delay, ImageEncoder, RgbaImage};
+
+use crate::app::graphics::define::PlotItem;
+use crate::app::graphics::figure::raster::Rasterizer;
+use crate::app::graphics::figure::Figure;
+
+/// 한 번에 내보낼 수 있는 프레임 수. PNG 이름이 네 자리 번호이고 zip의 항목 수는 16비트다.
+pub const MAX_FRAMES: usize = 10_000;
+
+/// 프레임을 담을 파일 형식
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameFormat {
+    Gif,
+    /// `frame-0000.png`부터 번호를 붙여 zip에 담는다.
+    PngSequence,
+}
+
+impl FrameFormat {
+    pub const ALL: [FrameFormat; 2] = [FrameFormat::Gif, FrameFormat::PngSequence];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Gif => "GIF",
+            Self::PngSequence => "PNG sequence",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::PngSequence => "zip",
+        }
+    }
+}
+
+// GIF 인코더가 쓰는 버퍼. 인코더를 닫은 뒤에 내용을 꺼낸다.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+enum FrameWriter {
+    Gif {
+        encoder: GifEncoder<SharedBuffer>,
+        buffer: SharedBuffer,
+        delay: Delay,
+    },
+    Png(ZipWriter),
+}
+
+impl FrameWriter {
+    fn new(format: FrameFormat, fps: u32) -> Result<Self, String> {
+        match format {
+            FrameFormat::Gif => {
+                let buffer = SharedBuffer::default();
+                // 품질보다 속도를 조금 더 본다. 1이 가장 느리고 30이 가장 빠르다.
+                let mut encoder = GifEncoder::new_with_speed(buffer.clone(), 10);
+                encoder
+                    .set_repeat(Repeat::Infinite)
+                    .map_err(|e| e.to_string())?;
+
+                Ok(Self::Gif {
+                    encoder,
+                    buffer,
+                    delay: Delay::from_numer_denom_ms(1000, fps.max(1)),
+                })
+            }
+            FrameFormat::PngSequence => Ok(Self::Png(ZipWriter::default())),
+        }
+    }
+
+    fn push(&mut self, frame: RgbaImage) -> Result<(), String> {
+        match self {
+            Self::Gif { encoder, delay, .. } => encoder
+                .encode_frame(image::Frame::from_parts(frame, 0, 0, *delay))
+                .map_err(|e| e.to_string()),
+            Self::Png(zip) => {
+                let mut png = vec![];
+                PngEncoder::new(&mut png)
+                    .write_image(
+                        frame.as_raw(),
+                        frame.width(),
+                        frame.height(),
+                        image::ColorType::Rgba8,
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                zip.add(&format!("frame-{:04}.png", zip.len()), png)
+            }
+        }
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Gif {
+                encoder, buffer, ..
+            } => {
+                // 인코더를 닫아야 GIF의 끝이 기록된다.
+                drop(encoder);
+                let bytes = std::mem::take(&mut *buffer.0.lock());
+                Ok(bytes)
+            }
+            Self::Png(zip) => zip.finish(),
+        }
+    }
+}
+
+/// 여러 UI 프레임에 나눠서 그리는 프레임 내보내기.
+/// 시뮬레이션을 스텝으로 옮기는 일은 부르는 쪽이 한다.
+pub struct FrameExport {
+    format: FrameFormat,
+    figure: Figure,
+    rasterizer: Rasterizer,
+    writer: FrameWriter,
+    steps: Vec<usize>,
+    done: usize,
+}
+
+impl FrameExport {
+    /// `start`부터 `end`까지 `every` 스텝마다 한 프레임씩 그린다. 처음 `MAX_FRAMES`개까지만 그린다.
+    pub fn new(
+        format: FrameFormat,
+        figure: Figure,
+        (start, end): (usize, usize),
+        every: usize,
+        fps: u32,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            format,
+            figure,
+            rasterizer: Rasterizer::default(),
+            writer: FrameWriter::new(format, fps)?,
+            steps: (start..=end)
+                .step_by(every.max(1))
+                .take(MAX_FRAMES)
+                .collect(),
+            done: 0,
+        })
+    }
+
+    pub fn format(&self) -> FrameFormat {
+        self.format
+    }
+
+    /// 다음에 그릴 스텝. 다 그렸으면 None.
+    pub fn next_step(&self) -> Option<usize> {
+        self.steps.get(self.done).copied()
+    }
+
+    /// 지금 스텝의 항목을 그려서 한 프레임으로 넣는다.
+    pub fn push(&mut self, items: &[PlotItem]) -> Result<(), String> {
+        let frame = self.rasterizer.render(&self.figure, items);
+        self.writer.push(frame)?;
+        self.done += 1;
+        Ok(())
+    }
+
+    /// 그린 프레임 수와 전체 프레임 수
+    pub fn progress(&self) -> (usize, usize) {
+        (self.done, self.steps.len())
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        self.writer.finish()
+    }
+}
+
+const TOO_LARGE: &str = "The frames do not fit in a zip file. Export fewer or smaller frames.";
+
+// 압축하지 않고 파일을 담는 zip
+#[derive(Default)]
+struct ZipWriter {
+    bytes: Vec<u8>,
+    central_directory: Vec<u8>,
+    count: u16,
+}
+
+impl ZipWriter {
+    fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    fn add(&mut self, name: &str, data: Vec<u8>) -> Result<(), String> {
+        let count = self.count.checked_add(1).ok_or(TOO_LARGE)?;
+        let crc = crc32(&data);
+        let offset = u32::try_from(self.bytes.len()).map_err(|_| TOO_LARGE)?;
+        let size = u32::try_from(data.len()).map_err(|_| TOO_LARGE)?;
+        let name = name.as_bytes();
+
+        // 버전, 플래그, 압축 방식(저장), 시각, 날짜(1980-01-01), CRC, 크기, 크기, 이름 길이, 추가 필드 길이
+        let mut fields = vec![];
+        fields.extend(20u16.to_le_bytes());
+        fields.extend(0u16.to_le_bytes());
+        fields.extend(0u16.to_le_bytes());
+        fields.extend(0u16.to_le_bytes());
+        fields.extend(0x21u16.to_le_bytes());
+        fields.extend(crc.to_le_bytes());
+        fields.extend(size.to_le_bytes());
+        fields.extend(size.to_le_bytes());
+        fields.extend((name.len() as u16).to_le_bytes());
+        fields.extend(0u16.to_le_bytes());
+
+        self.bytes.extend(0x04034b50u32.to_le_bytes());
+        self.bytes.extend(&fields);
+        self.bytes.extend(name);
+        self.bytes.extend(data);
+
+        // 만든 버전, 위의 필드, 설명 길이, 디스크 번호, 내부 속성, 외부 속성, 로컬 헤더 위치
+        self.central_directory.extend(0x02014b50u32.to_le_bytes());
+        self.central_directory.extend(20u16.to_le_bytes());
+        self.central_directory.extend(&fields);
+        self.central_directory.extend(0u16.to_le_bytes());
+        self.central_directory.extend(0u16.to_le_bytes());
+        self.central_directory.extend(0u16.to_le_bytes());
+        self.central_directory.extend(0u32.to_le_bytes());
+        self.central_directory.extend(offset.to_le_bytes());
+        self.central_directory.extend(name);
+
+        self.count = count;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, String> {
+        let offset = u32::try_from(self.bytes.len()).map_err(|_| TOO_LARGE)?;
+        let size = u32::try_from(self.central_directory.len()).map_err(|_| TOO_LARGE)?;
+
+        self.bytes.extend(&self.central_directory);
+        self.bytes.extend(0x06054b50u32.to_le_bytes());
+        self.bytes.extend(0u16.to_le_bytes());
+        self.bytes.extend(0u16.to_le_bytes());
+        self.bytes.extend(self.count.to_le_bytes());
+        self.bytes.extend(self.count.to_le_bytes());
+        self.bytes.extend(size.to_le_bytes());
+        self.bytes.extend(offset.to_le_bytes());
+        self.bytes.extend(0u16.to_le_bytes());
+
+        Ok(self.bytes)
+    }
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        CRC32_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::codecs::gif::GifDecoder;
+    use image::AnimationDecoder;
+
+    use super::*;
+    use crate::app::graphics::define::items::Line;
+    use crate::app::NVec2;
+
+    fn export(format: FrameFormat) -> Vec<u8> {
+        let figure = Figure::new([NVec2::new(0.0, 0.0), NVec2::new(2.0, 1.0)], 10.0, 24.0);
+        // 0, 3, 6, 9: 네 프레임
+        let mut export = FrameExport::new(format, figure, (0, 10), 3, 20).unwrap();
+        assert_eq!(export.progress(), (0, 4));
+
+        while let Some(step) = export.next_step() {
+            let x = step as f64 / 10.0;
+            export
+                .push(&[Line::new(vec![[x, 0.0], [x, 1.0]]).into()])
+                .unwrap();
+        }
+
+        assert_eq!(export.progress(), (4, 4));
+        export.finish().unwrap()
+    }
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    // 끝의 디렉터리에서 (이름, 내용)을 읽으며 로컬 헤더와 맞는지 본다.
+    fn read_zip(zip: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(zip, end), 0x06054b50);
+        let count = u16_at(zip, end + 10) as usize;
+        assert_eq!(u16_at(zip, end + 8) as usize, count);
+        let size = u32_at(zip, end + 12) as usize;
+        let mut at = u32_at(zip, end + 16) as usize;
+        assert_eq!(at + size, end);
+
+        let mut entries = vec![];
+        for _ in 0..count {
+            assert_eq!(u32_at(zip, at), 0x02014b50);
+            let crc = u32_at(zip, at + 16);
+            let compressed = u32_at(zip, at + 20) as usize;
+            assert_eq!(u32_at(zip, at + 24) as usize, compressed);
+            let name_length = u16_at(zip, at + 28) as usize;
+            let local = u32_at(zip, at + 42) as usize;
+            let name = &zip[at + 46..at + 46 + name_length];
+
+            // 로컬 헤더는 만든 버전 필드만 빼고 같은 필드를 쓴다.
+            assert_eq!(u32_at(zip, local), 0x04034b50);
+            assert_eq!(zip[local + 4..local + 30], zip[at + 6..at + 32]);
+            let data_start = local + 30 + name_length;
+            assert_eq!(&zip[local + 30..data_start], name);
+            let data = &zip[data_start..data_start + compressed];
+            assert_eq!(crc32(data), crc);
+
+            entries.push((String::from_utf8(name.to_vec()).unwrap(), data.to_vec()));
+            at += 46 + name_length;
+        }
+
+        assert_eq!(at, end);
+        entries
+    }
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn gif_has_a_header_and_every_frame() {
+        let gif = export(FrameFormat::Gif);
+
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(u16_at(&gif, 6), 24);
+        assert_eq!(u16_at(&gif, 8), 12);
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        let frames = GifDecoder::new(gif.as_slice())
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(frames.len(), 4);
+        for frame in &frames {
+            assert_eq!(frame.buffer().dimensions(), (24, 12));
+            assert_eq!(frame.delay().numer_denom_ms(), (50, 1));
+        }
+    }
+
+    #[test]
+    fn zip_directory_matches_the_frames() {
+        let zip = export(FrameFormat::PngSequence);
+        let entries = read_zip(&zip);
+
+        let names = entries
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "frame-0000.png",
+                "frame-0001.png",
+                "frame-0002.png",
+                "frame-0003.png"
+            ]
+        );
+
+        for (_, png) in entries {
+            let frame = image::load_from_memory(&png).unwrap();
+            assert_eq!((frame.width(), frame.height()), (24, 12));
+        }
+    }
+
+    #[test]
+    fn zip_keeps_arbitrary_entries_and_refuses_too_many() {
+        let mut writer = ZipWriter::default();
+        writer.add("empty", vec![]).unwrap();
+        writer.add("a/b.txt", b"hello".to_vec()).unwrap();
+        assert_eq!(writer.len(), 2);
+
+        let entries = read_zip(&writer.finish().unwrap());
+        assert_eq!(entries[0], ("empty".to_string(), vec![]));
+        assert_eq!(entries[1], ("a/b.txt".to_string(), b"hello".to_vec()));
+
+        let mut full = ZipWriter {
+            count: u16::MAX,
+            ..Default::default()
+        };
+        assert_eq!(full.add("one more", vec![]), Err(TOO_LARGE.to_string()));
+        assert_eq!(full.len(), u16::MAX as usize);
+    }
+}
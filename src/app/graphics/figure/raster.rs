@@ -0,0 +1,318 @@
+//! 그림을 CPU에서 픽셀로 그린다. 도형은 epaint로 삼각형을 만들어 칠하고,
+//! 글자는 egui의 기본 글꼴로 그리므로 화면과 같은 모양이 나온다.
+
+use std::sync::Arc;
+
+use egui::epaint::text::{FontDefinitions, Fonts};
+use egui::epaint::{
+    Color32, ColorImage, FontId, FontImage, ImageData, Mesh, Pos2, Rect, Shape as EShape,
+    Stroke as EStroke, TessellationOptions, Tessellator, TextureId, Vertex,
+};
+use image::RgbaImage;
+
+use crate::app::graphics::define::PlotItem;
+use crate::app::graphics::figure::{Figure, Shape};
+
+const MAX_TEXTURE_SIDE: usize = 8192;
+
+/// 같은 글꼴로 여러 프레임을 그릴 수 있도록 글꼴과 글자 아틀라스를 들고 있는다.
+pub struct Rasterizer {
+    fonts: Fonts,
+    /// 글꼴 아틀라스의 사본. 새 글자가 생길 때마다 바뀐 부분만 받아온다.
+    atlas: FontImage,
+}
+
+impl Default for Rasterizer {
+    fn default() -> Self {
+        Self {
+            fonts: Fonts::new(1.0, MAX_TEXTURE_SIDE, FontDefinitions::default()),
+            atlas: FontImage::new([0, 0]),
+        }
+    }
+}
+
+impl Rasterizer {
+    /// 그림 한 장을 그린다. 그림의 한 단위가 한 픽셀이다.
+    pub fn render(&mut self, figure: &Figure, items: &[PlotItem]) -> RgbaImage {
+        self.fonts.begin_frame(1.0, MAX_TEXTURE_SIDE);
+
+        let mut images = vec![];
+        let shapes = figure
+            .shapes(items)
+            .into_iter()
+            .map(|shape| self.to_epaint(shape, &mut images))
+            .collect::<Vec<_>>();
+
+        // 글자를 배치하면서 아틀라스에 새로 올라간 글리프를 받아온다.
+        while let Some(delta) = self.fonts.font_image_delta() {
+            if let ImageData::Font(patch) = delta.image {
+                match delta.pos {
+                    None => self.atlas = patch,
+                    Some(pos) => self.copy_patch(pos, &patch),
+                }
+            }
+        }
+
+        let options = TessellationOptions {
+            prerasterized_discs: false,
+            ..Default::default()
+        };
+        let mut tessellator = Tessellator::new(1.0, options, self.atlas.size, vec![]);
+
+        let mut canvas = Canvas::new(
+            figure.width.round() as usize,
+            figure.height.round() as usize,
+            figure.background,
+        );
+
+        for shape in shapes {
+            let mut mesh = Mesh::default();
+            tessellator.tessellate_shape(shape, &mut mesh);
+
+            let texture = match mesh.texture_id {
+                TextureId::User(index) => Texture::Image(&images[index as usize]),
+                TextureId::Managed(_) => Texture::Font(&self.atlas),
+            };
+            canvas.fill_mesh(&mesh, &texture);
+        }
+
+        canvas.into_image()
+    }
+
+    fn copy_patch(&mut self, [x, y]: [usize; 2], patch: &FontImage) {
+        for row in 0..patch.height() {
+            let start = (y + row) * self.atlas.width() + x;
+            let source = row * patch.width();
+
+            self.atlas.pixels[start..start + patch.width()]
+                .copy_from_slice(&patch.pixels[source..source + patch.width()]);
+        }
+    }
+
+    fn to_epaint(&self, shape: Shape, images: &mut Vec<Arc<ColorImage>>) -> EShape {
+        let pos = |[x, y]: [f64; 2]| Pos2::new(x as f32, y as f32);
+
+        match shape {
+            Shape::Path {
+                points,
+                closed,
+                fill,
+                stroke,
+            } => {
+                let mut points = points.into_iter().map(pos).collect::<Vec<_>>();
+                let mut shapes = vec![];
+
+                if let (Some(fill), true) = (fill, points.len() >= 3) {
+                    shapes.push(EShape::convex_polygon(points.clone(), fill, EStroke::NONE));
+                }
+
+                if let Some(stroke) = stroke {
+                    if closed {
+                        points.extend(points.first().cloned());
+                    }
+
+                    let width = stroke.width as f32;
+                    match stroke.dash {
+                        None => shapes.push(EShape::line(points, (width, stroke.color))),
+                        Some([on, off]) if on == 0.0 => shapes.extend(EShape::dotted_line(
+                            &points,
+                            stroke.color,
+                            off as f32,
+                            width,
+                        )),
+                        Some([on, off]) => shapes.extend(EShape::dashed_line(
+                            &points,
+                            (width, stroke.color),
+                            on as f32,
+                            off as f32,
+                        )),
+                    }
+                }
+
+                EShape::Vec(shapes)
+            }
+            Shape::Circle {
+                center,
+                radius,
+                fill,
+                stroke,
+            } => {
+                let mut shapes = vec![];
+
+                if let Some(fill) = fill {
+                    shapes.push(EShape::circle_filled(pos(center), radius as f32, fill));
+                }
+                if let Some(stroke) = stroke {
+                    shapes.push(EShape::circle_stroke(
+                        pos(center),
+                        radius as f32,
+                        (stroke.width as f32, stroke.color),
+                    ));
+                }
+
+                EShape::Vec(shapes)
+            }
+            Shape::Text {
+                position,
+                lines,
+                size,
+                color,
+                anchor,
+            } => {
+                let galley = self.fonts.layout_no_wrap(
+                    lines.join("\n"),
+                    FontId::proportional(size as f32),
+                    color,
+                );
+                let rect = anchor.anchor_rect(Rect::from_min_size(pos(position), galley.size()));
+
+                EShape::galley(rect.min, galley)
+            }
+            Shape::Image { min, size, pixels } => {
+                images.push(pixels);
+
+                EShape::image(
+                    TextureId::User(images.len() as u64 - 1),
+                    Rect::from_min_size(pos(min), pos(size).to_vec2()),
+                    Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                    Color32::WHITE,
+                )
+            }
+        }
+    }
+}
+
+enum Texture<'a> {
+    /// 흰색에 글자의 덮임 정도를 알파로 쓴다.
+    Font(&'a FontImage),
+    Image(&'a ColorImage),
+}
+
+impl Texture<'_> {
+    // 가장 가까운 텍셀을 미리 곱한 알파의 RGBA로 돌려준다.
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let texel = |size: [usize; 2]| {
+            let x = ((u * size[0] as f32) as usize).min(size[0].saturating_sub(1));
+            let y = ((v * size[1] as f32) as usize).min(size[1].saturating_sub(1));
+            y * size[0] + x
+        };
+
+        match self {
+            Texture::Font(atlas) => {
+                let Some(coverage) = atlas.pixels.get(texel(atlas.size)) else {
+                    return [1.0; 4];
+                };
+                // egui가 글자에 쓰는 감마
+                let alpha = coverage.powf(0.55);
+                [alpha; 4]
+            }
+            Texture::Image(image) => image
+                .pixels
+                .get(texel(image.size))
+                .map_or([0.0; 4], |pixel| pixel.to_array().map(|c| c as f32 / 255.0)),
+        }
+    }
+}
+
+// 미리 곱한 알파로 섞는 RGBA 화면
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Color32) -> Self {
+        let background = background.to_array().map(|c| c as f32 / 255.0);
+
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    fn fill_mesh(&mut self, mesh: &Mesh, texture: &Texture<'_>) {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| &mesh.vertices[triangle[i] as usize]);
+            self.fill_triangle(a, b, c, texture);
+        }
+    }
+
+    fn fill_triangle(&mut self, a: &Vertex, b: &Vertex, c: &Vertex, texture: &Texture<'_>) {
+        let edge =
+            |p: Pos2, q: Pos2, r: Pos2| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+
+        let area = edge(a.pos, b.pos, c.pos);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+
+        let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).floor().max(0.0) as usize;
+        let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).floor().max(0.0) as usize;
+        let max_x = (a.pos.x.max(b.pos.x).max(c.pos.x).ceil() as usize).min(self.width);
+        let max_y = (a.pos.y.max(b.pos.y).max(c.pos.y).ceil() as usize).min(self.height);
+
+        let colors = [a, b, c].map(|v| v.color.to_array().map(|c| c as f32 / 255.0));
+
+        // 두 삼각형이 나눠 가진 변 위의 픽셀은 한쪽에서만 칠해야 반투명한 면에 줄이 생기지 않는다.
+        // 맞닿은 삼각형에서 같은 변은 방향이 반대이므로 방향으로 주인을 정한다.
+        let owns_edge = [(b.pos, c.pos), (c.pos, a.pos), (a.pos, b.pos)].map(|(p, q)| {
+            let d = (q - p) * area.signum();
+            d.y > 0.0 || (d.y == 0.0 && d.x > 0.0)
+        });
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                // 픽셀 가운데에서 무게중심 좌표를 구한다.
+                let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let weights = [
+                    edge(b.pos, c.pos, p) / area,
+                    edge(c.pos, a.pos, p) / area,
+                    edge(a.pos, b.pos, p) / area,
+                ];
+
+                if weights
+                    .iter()
+                    .zip(owns_edge)
+                    .any(|(w, owns)| *w < 0.0 || (*w == 0.0 && !owns))
+                {
+                    continue;
+                }
+
+                let u = weights[0] * a.uv.x + weights[1] * b.uv.x + weights[2] * c.uv.x;
+                let v = weights[0] * a.uv.y + weights[1] * b.uv.y + weights[2] * c.uv.y;
+                let texel = texture.sample(u, v);
+
+                let mut source = [0.0; 4];
+                for (channel, value) in source.iter_mut().enumerate() {
+                    let color = weights[0] * colors[0][channel]
+                        + weights[1] * colors[1][channel]
+                        + weights[2] * colors[2][channel];
+                    *value = color * texel[channel];
+                }
+
+                let target = &mut self.pixels[y * self.width + x];
+                for channel in 0..4 {
+                    target[channel] = source[channel] + target[channel] * (1.0 - source[3]);
+                }
+            }
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let bytes = self
+            .pixels
+            .into_iter()
+            .flat_map(|[r, g, b, a]| {
+                // 바탕이 불투명하지 않을 때를 위해 알파를 되돌린다.
+                let unmultiply = |c: f32| if a > 0.0 { c / a } else { 0.0 };
+                [unmultiply(r), unmultiply(g), unmultiply(b), a]
+                    .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+            })
+            .collect();
+
+        RgbaImage::from_raw(self.width as u32, self.height as u32, bytes)
+            .expect("canvas size matches its pixels")
+    }
+}
@@ -1,14 +1,19 @@
+use crate::app::graphics::define::items::PlotImage;
 use crate::app::NVec2;
 
-use egui::plot::{PlotImage, PlotPoint};
+use egui::plot::PlotPoint;
 use egui::{vec2, ColorImage, Context, ImageData, TextureOptions};
+use std::sync::Arc;
 
 #[derive(Clone, Default)]
 pub struct ImageManager {
     texture: Vec<egui::TextureHandle>,
+    /// 텍스처에 올린 픽셀. 그림을 파일로 내보낼 때 쓴다.
+    pixels: Vec<Option<Arc<ColorImage>>>,
 }
 
 impl ImageManager {
+    #[allow(dead_code)]
     pub fn new(ctx: &Context) -> Self {
         let img_data = include_bytes!("../../../assets/equation01.png");
         let img = image::io::Reader::new(std::io::Cursor::new(img_data)) // Read the bytes of the image
@@ -36,9 +41,24 @@ impl ImageManager {
 
         Self {
             texture: vec![texture],
+            pixels: vec![None],
         }
     }
 
+    /// 매 프레임 내용이 바뀌는 텍스처를 추가하고 그 인덱스를 돌려준다.
+    pub fn load(&mut self, ctx: &Context, name: &str, image: ColorImage) -> usize {
+        self.texture
+            .push(ctx.load_texture(name, image.clone(), TextureOptions::LINEAR));
+        self.pixels.push(Some(Arc::new(image)));
+
+        self.texture.len() - 1
+    }
+
+    pub fn update(&mut self, index: usize, image: ColorImage) {
+        self.texture[index].set(image.clone(), TextureOptions::LINEAR);
+        self.pixels[index] = Some(Arc::new(image));
+    }
+
     pub fn get_plot_image(&mut self, index: usize, pos: NVec2, size: f64) -> PlotImage {
         let tex = &mut self.texture[index];
 
@@ -48,6 +68,9 @@ impl ImageManager {
             (size as f32) * vec2(tex.aspect_ratio(), 1.0),
         );
 
-        image
+        match &self.pixels[index] {
+            Some(pixels) => image.pixels(pixels.clone()),
+            None => image,
+        }
     }
 }
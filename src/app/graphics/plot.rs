@@ -1,13 +1,12 @@
 use eframe::epaint::FontFamily;
 
-use egui::plot::{Line, PlotBounds, PlotPoint, PlotUi, Polygon, Text};
+use egui::plot::{Line, PlotBounds, PlotPoint, PlotUi, Text};
 use egui::{Align2, InnerResponse, Pos2, RichText, TextStyle};
 
 use crate::app::graphics::define::PlotColor;
-use crate::app::graphics::CSPlotObjects;
 
-use crate::app::simulations::classic_simulation::{CSimObject, Simulation};
 use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation};
 
 pub mod object;
 
@@ -16,7 +15,8 @@ pub struct PlotData {
     pub nearest_label: String,
     pub nearest_point: PlotPoint,
     pub dragging_object: bool,
-    pub selected_index: usize,
+    pub selected: Option<PickTarget>,
+    pub targets: Vec<PickTarget>,
 }
 
 impl Default for PlotData {
@@ -26,16 +26,28 @@ impl Default for PlotData {
             nearest_label: String::new(),
             nearest_point: PlotPoint::new(0.0, 0.0),
             dragging_object: false,
-            selected_index: 0,
+            selected: None,
+            targets: vec![],
         }
     }
 }
 
+impl PlotData {
+    // 포인터 아래에 있는 대상을 찾는다. 위에 그려진 것부터 찾는다.
+    pub fn pick(&self, pos: PlotPoint) -> Option<PickTarget> {
+        self.targets
+            .iter()
+            .rev()
+            .find(|target| target.contains(pos))
+            .cloned()
+    }
+}
+
 #[derive(Default)]
 pub struct SimPlot {
-    pub plot_objects: CSPlotObjects,
-
     plot_data: PlotData,
+    /// 지난 프레임에 플롯이 차지한 화면 영역
+    rect: Option<egui::Rect>,
 }
 
 pub const PLOT_MAX_DISTANCE: f64 = 225.0;
@@ -44,7 +56,7 @@ impl SimPlot {
     // 그래프를 그린다.
     pub fn draw(
         &mut self,
-        simulation: &dyn Simulation,
+        simulation: &mut dyn Simulation,
         plot_ui: &mut PlotUi,
         state: &mut SimulationState,
     ) {
@@ -58,42 +70,55 @@ impl SimPlot {
             state.sim_started = true;
         }
 
-        if let Some(x) = simulation.get_events(state.current_step) {
-            x.get_shapes().into_iter().for_each(|shape| {
-                shape.draw(plot_ui);
-            })
+        // 선택된 대상을 현재 스텝의 모습으로 갱신한다.
+        self.plot_data.targets = simulation.pick_targets(state);
+        if let Some(selected) = self.plot_data.selected.take() {
+            self.plot_data.selected = self
+                .plot_data
+                .targets
+                .iter()
+                .find(|target| target.id == selected.id)
+                .cloned();
         }
 
-        let simulation_objects = simulation.get_children();
-
-        // 마우스를 이 오브젝트에 포커싱 중이면서 드래그할 때 선을 그려준다.
-        if let Some(pointer_pos) = plot_ui.pointer_coordinate() {
-            if self.plot_data.dragging_object {
-                let pos = simulation_objects[self.plot_data.selected_index]
-                    .current_state()
-                    .position;
-
-                plot_ui.line(Line::new(vec![
-                    [pos.x, pos.y],
-                    [pointer_pos.x, pointer_pos.y],
-                ]));
-            }
+        let ctx = plot_ui.ctx().clone();
+        for item in simulation.plot_items(&ctx, state) {
+            item.draw(plot_ui);
         }
 
-        // 시뮬레이션 오브젝트 그리기
-        for (index, obj) in simulation_objects.iter().enumerate() {
-            if *obj.hide() {
-                continue;
-            }
-
-            let obj_state = &mut obj.current_state();
-
-            plot_ui.polygon(
-                Polygon::new(obj_state.shape.get_plot_points(obj_state.position))
-                    .color(PlotColor::Object.get_color()),
+        if let Some(selected) = &self.plot_data.selected {
+            let mut outline = selected
+                .outline
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>();
+            outline.extend(outline.first().cloned());
+
+            plot_ui.line(
+                Line::new(outline)
+                    .color(PlotColor::Selected.get_color())
+                    .width(1.5),
             );
+        }
 
-            self.draw_object(obj, state, plot_ui, index);
+        if let Some(pointer_pos) = plot_ui.pointer_coordinate() {
+            match &self.plot_data.selected {
+                // 마우스를 대상에 포커싱 중이면서 드래그할 때 선을 그려준다.
+                Some(selected) if self.plot_data.dragging_object => {
+                    let pos = selected.anchor;
+
+                    plot_ui.line(Line::new(vec![
+                        [pos.x, pos.y],
+                        [pointer_pos.x, pointer_pos.y],
+                    ]));
+                }
+                _ => {
+                    if let Some(hovered) = self.plot_data.pick(pointer_pos) {
+                        self.plot_data.nearest_label = hovered.name;
+                        self.plot_data.nearest_point = pointer_pos;
+                    }
+                }
+            }
         }
 
         // 가장 가까운 점의 좌표를 플롯에 표시한다.
@@ -113,10 +138,6 @@ impl SimPlot {
 
             plot_ui.text(text);
         }
-
-        for func in self.plot_objects.get_plot_items() {
-            func.draw(plot_ui)
-        }
     }
 }
 
@@ -127,17 +148,18 @@ pub struct InputMessage {
 }
 
 impl SimPlot {
-    pub fn new(plot_objects: CSPlotObjects) -> Self {
-        Self {
-            plot_objects,
-            ..Self::default()
-        }
-    }
-
     pub fn is_dragging_object(&self) -> bool {
         self.plot_data.dragging_object
     }
 
+    pub fn selected(&self) -> Option<&PickTarget> {
+        self.plot_data.selected.as_ref()
+    }
+
+    pub fn rect(&self) -> Option<egui::Rect> {
+        self.rect
+    }
+
     // 입력을 받아서 상태를 업데이트한다.
     pub fn input(
         &mut self,
@@ -147,6 +169,7 @@ impl SimPlot {
         state: &mut SimulationState,
     ) {
         let response = inner_response.response;
+        self.rect = Some(response.rect);
 
         simulation.input(
             &mut self.plot_data,
@@ -156,17 +179,4 @@ impl SimPlot {
             state,
         );
     }
-
-    // 시뮬레이션 오브젝트를 그린다.
-    pub fn draw_object(
-        &mut self,
-        obj: &CSimObject,
-        sim_state: &SimulationState,
-        plot_ui: &mut PlotUi,
-        timestep: usize,
-    ) {
-        obj.draw(sim_state, timestep, self.plot_objects.get_stamps())
-            .into_iter()
-            .for_each(|item| item.draw(plot_ui));
-    }
 }
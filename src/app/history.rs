@@ -0,0 +1,356 @@
+//! 장면 편집과 설정 변경의 기록. Ctrl+Z로 되돌리고 Ctrl+Y로 다시 한다.
+
+use crate::app::simulations::state::{SimulationSettings, SimulationState};
+use crate::app::simulations::{BranchAction, Simulation};
+use crate::engine::classic::edit::SceneEdit;
+
+/// 기록에 남기는 편집의 최대 개수
+const MAX_EDITS: usize = 100;
+
+pub enum Edit {
+    Scene(SceneEdit),
+    Settings {
+        before: SimulationSettings,
+        after: SimulationSettings,
+    },
+    /// 갈래와 지금 타임라인을 맞바꿨다. 되돌리기와 다시 하기 모두 다시 맞바꾼다.
+    Branch {
+        label: String,
+        id: usize,
+    },
+}
+
+impl Edit {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Scene(edit) => edit.label(),
+            Self::Settings { .. } => "Change Settings".to_string(),
+            Self::Branch { label, .. } => label.clone(),
+        }
+    }
+
+    fn merge(&mut self, next: Edit) -> Option<Edit> {
+        match (self, next) {
+            (Self::Scene(edit), Self::Scene(next)) => edit.merge(next).map(Self::Scene),
+            (Self::Settings { after, .. }, Self::Settings { after: next, .. }) => {
+                *after = next;
+                None
+            }
+            (_, next) => Some(next),
+        }
+    }
+
+    fn undo(&mut self, simulation: &mut dyn Simulation, state: &mut SimulationState) {
+        match self {
+            Self::Scene(edit) => simulation.undo_edit(edit, state),
+            Self::Settings { before, .. } => set_settings(state, before),
+            Self::Branch { id, .. } => {
+                simulation.change_branch(BranchAction::Switch(*id), state);
+            }
+        }
+    }
+
+    fn redo(&mut self, simulation: &mut dyn Simulation, state: &mut SimulationState) {
+        match self {
+            Self::Scene(edit) => simulation.redo_edit(edit, state),
+            Self::Settings { after, .. } => set_settings(state, after),
+            Self::Branch { id, .. } => {
+                simulation.change_branch(BranchAction::Switch(*id), state);
+            }
+        }
+    }
+}
+
+fn set_settings(state: &mut SimulationState, settings: &SimulationSettings) {
+    state.settings = settings.clone();
+
+    // 중력은 바뀌었다고 알려야 세계에 적용된다.
+    if let Some(settings) = state.settings.specific.as_c_sim_settings_mut() {
+        settings.gravity.changed();
+    }
+}
+
+// 설정에는 비교 연산이 없으므로 저장되는 모습으로 비교한다.
+pub(crate) fn same_settings(a: &SimulationSettings, b: &SimulationSettings) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+#[derive(Default)]
+pub struct EditHistory {
+    done: Vec<Edit>,
+    /// 되돌린 편집. 마지막 것이 다음에 다시 할 편집이다.
+    undone: Vec<Edit>,
+    /// 마지막 편집이 아직 진행 중인지. 값을 끄는 동안의 변경은 한 편집으로 합친다.
+    open: bool,
+}
+
+impl EditHistory {
+    pub fn done(&self) -> &[Edit] {
+        &self.done
+    }
+
+    pub fn undone(&self) -> &[Edit] {
+        &self.undone
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 타임라인이 자라면 되돌린 편집은 다시 할 수 없다.
+    pub fn forget_undone(&mut self) {
+        self.undone.clear();
+    }
+
+    /// 포인터를 누르고 있지 않으면 진행 중인 편집을 닫는다.
+    pub fn update(&mut self, pointer_down: bool) {
+        self.open &= pointer_down;
+    }
+
+    /// 이미 적용된 편집을 기록한다. 포인터를 누르고 있으면 다음 편집과 합칠 수 있다.
+    pub fn push(&mut self, edit: Edit, pointer_down: bool) {
+        self.undone.clear();
+
+        let edit = match self.done.last_mut() {
+            Some(last) if self.open => last.merge(edit),
+            _ => Some(edit),
+        };
+
+        if let Some(edit) = edit {
+            self.done.push(edit);
+
+            if self.done.len() > MAX_EDITS {
+                self.done.remove(0);
+            }
+        }
+
+        self.open = pointer_down;
+    }
+
+    /// 시뮬레이션이 한 편집을 기록한다. 지난 스텝을 고쳤으면 그 뒤에 기록된 스텝은 갈래로 잘라 낸다.
+    pub fn record(
+        &mut self,
+        simulation: &mut dyn Simulation,
+        state: &mut SimulationState,
+        edits: Vec<SceneEdit>,
+        pointer_down: bool,
+    ) {
+        let step = state.current_step;
+        let forked = (step < state.max_step)
+            .then(|| simulation.fork(step, &edits, state))
+            .flatten();
+
+        if let Some(id) = forked {
+            self.push(
+                Edit::Branch {
+                    label: format!("Fork at step {}", step),
+                    id,
+                },
+                pointer_down,
+            );
+        }
+
+        for edit in edits {
+            self.push(Edit::Scene(edit), pointer_down);
+        }
+    }
+
+    /// 설정 UI를 그리기 전과 후의 설정을 비교해서 바뀌었으면 기록한다.
+    pub fn push_settings(
+        &mut self,
+        before: SimulationSettings,
+        after: &SimulationSettings,
+        pointer_down: bool,
+    ) {
+        if !same_settings(&before, after) {
+            self.push(
+                Edit::Settings {
+                    before,
+                    after: after.clone(),
+                },
+                pointer_down,
+            );
+        }
+    }
+
+    pub fn undo(&mut self, simulation: &mut dyn Simulation, state: &mut SimulationState) -> bool {
+        let Some(mut edit) = self.done.pop() else {
+            return false;
+        };
+
+        edit.undo(simulation, state);
+        self.undone.push(edit);
+        self.open = false;
+        true
+    }
+
+    pub fn redo(&mut self, simulation: &mut dyn Simulation, state: &mut SimulationState) -> bool {
+        let Some(mut edit) = self.undone.pop() else {
+            return false;
+        };
+
+        edit.redo(simulation, state);
+        self.done.push(edit);
+        self.open = false;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::vector;
+
+    use super::*;
+    use crate::app::simulations::classic_simulation::ClassicSimulation;
+    use crate::app::simulations::state::SpecificSimulationSettings;
+    use crate::engine::classic::template::CSTemplate;
+    use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+
+    fn simulation() -> (ClassicSimulation, SimulationState) {
+        let preset = CSTemplate::DefaultSim.get_preset_with_ui();
+        let state = SimulationState {
+            settings: SimulationSettings::new(SpecificSimulationSettings::CSimSettings(
+                preset.settings.clone(),
+            )),
+            ..Default::default()
+        };
+
+        (ClassicSimulation::from(preset), state)
+    }
+
+    // 시뮬레이션이 하듯 영역을 붙이고 편집을 돌려준다.
+    fn add_zone(simulation: &mut ClassicSimulation, name: &str) -> Edit {
+        let zone = FieldZone::polygon(
+            name,
+            vec![vector![0.0, 0.0], vector![1.0, 0.0], vector![0.0, 1.0]],
+            ZoneEffect::Drag(1.0),
+        );
+        simulation.world.zones.push(zone.clone());
+
+        Edit::Scene(SceneEdit::AddZone(zone))
+    }
+
+    fn zones(simulation: &ClassicSimulation) -> Vec<&str> {
+        simulation
+            .world
+            .zones
+            .iter()
+            .map(|zone| zone.name.as_str())
+            .collect()
+    }
+
+    fn labels(edits: &[Edit]) -> Vec<String> {
+        edits.iter().map(Edit::label).collect()
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_edits_in_order() {
+        let (mut simulation, mut state) = simulation();
+        let mut history = EditHistory::default();
+
+        for name in ["a", "b"] {
+            let edit = add_zone(&mut simulation, name);
+            history.push(edit, false);
+        }
+        let before = state.settings.clone();
+        state.settings.grid = !before.grid;
+        history.push_settings(before, &state.settings, false);
+        let edited = state.settings.clone();
+
+        assert!(history.undo(&mut simulation, &mut state));
+        assert!(same_settings(
+            &state.settings,
+            &SimulationSettings {
+                grid: !edited.grid,
+                ..edited.clone()
+            }
+        ));
+        assert!(history.undo(&mut simulation, &mut state));
+        assert_eq!(zones(&simulation), ["a"]);
+        assert!(history.undo(&mut simulation, &mut state));
+        assert!(zones(&simulation).is_empty());
+        assert!(!history.undo(&mut simulation, &mut state));
+        assert!(!history.can_undo());
+
+        for _ in 0..3 {
+            assert!(history.redo(&mut simulation, &mut state));
+        }
+        assert_eq!(zones(&simulation), ["a", "b"]);
+        assert!(same_settings(&state.settings, &edited));
+        assert!(!history.redo(&mut simulation, &mut state));
+        assert!(history.undone().is_empty());
+    }
+
+    #[test]
+    fn a_new_edit_after_undo_clears_redo() {
+        let (mut simulation, mut state) = simulation();
+        let mut history = EditHistory::default();
+
+        for name in ["a", "b"] {
+            let edit = add_zone(&mut simulation, name);
+            history.push(edit, false);
+        }
+        history.undo(&mut simulation, &mut state);
+        assert!(history.can_redo());
+
+        let edit = add_zone(&mut simulation, "c");
+        history.push(edit, false);
+
+        assert!(!history.can_redo());
+        assert_eq!(labels(history.done()), ["Add a", "Add c"]);
+        assert!(!history.redo(&mut simulation, &mut state));
+        assert_eq!(zones(&simulation), ["a", "c"]);
+    }
+
+    #[test]
+    fn edits_merge_only_while_the_pointer_is_down() {
+        let (_, mut state) = simulation();
+        let mut history = EditHistory::default();
+        let initial = state.settings.clone();
+
+        // 같은 설정은 기록하지 않는다.
+        history.push_settings(initial.clone(), &state.settings, true);
+        assert!(!history.can_undo());
+
+        for grid in [false, true, false] {
+            let before = state.settings.clone();
+            state.settings.grid = grid;
+            history.push_settings(before, &state.settings, true);
+        }
+        assert_eq!(history.done().len(), 1);
+
+        history.update(false);
+        let before = state.settings.clone();
+        state.settings.grid = true;
+        history.push_settings(before, &state.settings, false);
+        assert_eq!(history.done().len(), 2);
+
+        let Edit::Settings { before, after } = &history.done()[0] else {
+            unreachable!();
+        };
+        assert!(same_settings(before, &initial));
+        assert!(!after.grid);
+    }
+
+    #[test]
+    fn only_the_latest_edits_are_kept() {
+        let (mut simulation, _) = simulation();
+        let mut history = EditHistory::default();
+
+        for i in 0..MAX_EDITS + 5 {
+            let edit = add_zone(&mut simulation, &i.to_string());
+            history.push(edit, false);
+        }
+
+        assert_eq!(history.done().len(), MAX_EDITS);
+        assert_eq!(history.done()[0].label(), "Add 5");
+    }
+}
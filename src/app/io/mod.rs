@@ -15,3 +15,69 @@ pub fn get_file(name: impl Into<String>, buffer: Arc<Mutex<Vec<u8>>>) {
         let _ = mem::replace(locked, result.bytes);
     });
 }
+
+/// 파일을 골라 `buffer`에 읽어 넣는다. 웹에서는 고른 뒤에 비동기로 채워진다.
+pub fn open_file(extension: &'static str, buffer: Arc<Mutex<Option<Vec<u8>>>>) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = rfd::FileDialog::new()
+        .add_filter(extension, &[extension])
+        .pick_file()
+    {
+        if let Ok(bytes) = std::fs::read(path) {
+            buffer.lock().replace(bytes);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(async move {
+        let dialog = rfd::AsyncFileDialog::new().add_filter(extension, &[extension]);
+
+        if let Some(file) = dialog.pick_file().await {
+            buffer.lock().replace(file.read().await);
+        }
+    });
+}
+
+/// 네이티브에서는 저장 창을 띄우고, 웹에서는 `name`으로 내려받는다.
+pub fn save_file(name: &str, bytes: &[u8]) -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let extension = name.rsplit('.').next().unwrap_or_default();
+
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name(name)
+            .add_filter(extension, &[extension])
+            .save_file()
+        {
+            std::fs::write(&path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use wasm_bindgen::JsCast;
+
+        let download = || -> Result<(), wasm_bindgen::JsValue> {
+            let array = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+            let blob = web_sys::Blob::new_with_u8_array_sequence(&array)?;
+            let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+            let document = web_sys::window()
+                .and_then(|window| window.document())
+                .ok_or("no document")?;
+            let anchor = document
+                .create_element("a")?
+                .dyn_into::<web_sys::HtmlAnchorElement>()?;
+
+            anchor.set_href(&url);
+            anchor.set_download(name);
+            anchor.click();
+
+            web_sys::Url::revoke_object_url(&url)
+        };
+
+        download().map_err(|e| format!("download failed: {:?}", e))
+    }
+}
@@ -1,18 +1,63 @@
+use crate::app::graphics::figure::animation::{FrameExport, FrameFormat, MAX_FRAMES};
+use crate::app::graphics::figure::Figure;
 use crate::app::graphics::plot::SimPlot;
+use crate::app::history::{self, Edit, EditHistory};
 
-use crate::app::simulations::classic_simulation::sim_state::CSimSettings;
-use crate::app::simulations::classic_simulation::{ClassicSimulation, Simulation};
-use egui::Ui;
+use crate::app::io;
+use crate::app::playback::{Playback, SPEED_PRESETS};
+use crate::app::session::{Session, SessionSimulation, SESSION_VERSION};
+use crate::app::simulations::classic_simulation::ClassicSimulation;
+use crate::app::simulations::{BranchAction, Simulation, TimelineFile, TIMELINE_FILES};
+use crate::app::stepper::{StepJob, Stepper};
+use eframe::epaint::mutex::Mutex;
+use egui::{Color32, DragValue, Key, Modifiers, ProgressBar, TextEdit, Ui};
 use getset::Getters;
 use instant::Instant;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::app::simulations::classic_simulation::template::init::SimulationInit;
-use crate::app::simulations::classic_simulation::template::{CSPreset, CSTemplate};
-use crate::app::simulations::state::{
-    SimulationSettings, SimulationState, SpecificSimulationSettings,
+use crate::app::simulations::state::{SimulationSettings, SimulationState};
+use crate::app::simulations::template::{
+    get_sim_list, SimulationInit, SimulationPreset, SimulationType,
 };
+use crate::engine::classic::scenario::Scenario;
+use crate::engine::classic::template::CSTemplate;
+use crate::engine::classic::timeline::MEGABYTE;
 
-pub const SIMULATION_TICK: f64 = 1.0 / 240.0;
+pub use crate::engine::SIMULATION_TICK;
+
+/// 고를 수 있는 재생 속도의 범위
+const MIN_SPEED: f64 = 0.01;
+const MAX_SPEED: f64 = 100.0;
+
+/// 프레임을 내보낼 때 UI 프레임 하나에서 그림을 그리는 데 쓸 시간
+const FRAME_EXPORT_BUDGET: Duration = Duration::from_millis(30);
+
+/// 지금 화면을 내보낼 그림 형식
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FigureFormat {
+    Svg,
+    Pdf,
+}
+
+// 이번 프레임에 포인터를 누르고 있거나 뗐으면 그 위치. 드래그 중이면 누르기 시작한 위치다.
+fn pressed_at(input: &egui::InputState) -> Option<egui::Pos2> {
+    let pointer = &input.pointer;
+
+    (pointer.any_down() || pointer.any_pressed() || pointer.any_released())
+        .then(|| pointer.press_origin().or(pointer.interact_pos()))
+        .flatten()
+}
+
+// 이번 프레임에 글자나 키를 입력했는지
+fn typed(input: &egui::InputState) -> bool {
+    input.events.iter().any(|event| {
+        matches!(
+            event,
+            egui::Event::Key { .. } | egui::Event::Text(_) | egui::Event::Paste(_)
+        )
+    })
+}
 
 /// This is the main simulation manager. It is responsible for managing the simulation and the plot.
 #[derive(Getters)]
@@ -22,9 +67,44 @@ pub struct SimulationManager {
     sim_state: SimulationState,
     simulation_plot: SimPlot,
     is_paused: bool,
-    last_time_stamp: Instant,
+    /// 실제 시간에 맞춰 진행할 스텝을 센다.
+    playback: Playback,
+    /// 이번 프레임에 새로 계산해야 하는 스텝 수. 프레임이 끝나면 `stepper`에 맡긴다.
+    owed_steps: usize,
+    /// `stepper`가 계산할 마지막 스텝
+    step_target: usize,
+    /// 맡겨 둔 동안 `simulation`과 `sim_state`는 작업 스레드가 보낸 스냅샷이다.
+    stepper: Stepper,
+    /// 지난 프레임에 시뮬레이션의 위젯이 있던 화면 영역. 이 안을 누르면 맡긴 것을 돌려받는다.
+    edit_areas: Vec<egui::Rect>,
+    /// 마지막으로 누른 곳이 시뮬레이션의 위젯이었는지. 그러면 입력하는 글자도 시뮬레이션을 고친다.
+    typing: bool,
     is_sim_initializing: bool,
     initializing_data: Option<Box<dyn SimulationInit>>,
+    /// 지금 시뮬레이션을 만든 템플릿
+    template: Option<SimulationType>,
+    keep_timeline: bool,
+    /// 되돌릴 수 있는 편집
+    history: EditHistory,
+    /// 내보낼 때 오브젝트 상태를 몇 스텝마다 뽑을지
+    export_every: usize,
+    /// 내보낼 그림의 가로 크기
+    figure_width: f64,
+    /// 인쇄하기 좋게 흰 배경으로 내보낼지
+    figure_white: bool,
+    /// 프레임으로 내보낼 스텝 구간
+    frame_range: (usize, usize),
+    frame_every: usize,
+    frame_fps: u32,
+    frame_format: FrameFormat,
+    /// 그리는 중인 프레임 내보내기와 끝나면 돌아갈 스텝
+    frame_export: Option<(FrameExport, usize)>,
+
+    /// 열기 창에서 고른 시나리오 파일. 웹에서는 나중에 채워진다.
+    opened_scenario: Arc<Mutex<Option<Vec<u8>>>>,
+    scenario_name: String,
+    scenario_description: String,
+    file_error: Option<String>,
 }
 
 impl Default for SimulationManager {
@@ -34,18 +114,35 @@ impl Default for SimulationManager {
             sim_state: SimulationState::default(),
             simulation_plot: SimPlot::default(),
             is_paused: true,
-            last_time_stamp: Instant::now(),
+            playback: Playback::default(),
+            owed_steps: 0,
+            step_target: 0,
+            stepper: Stepper::default(),
+            edit_areas: vec![],
+            typing: false,
             is_sim_initializing: false,
             initializing_data: None,
+            template: None,
+            keep_timeline: false,
+            history: EditHistory::default(),
+            export_every: 1,
+            figure_width: 800.0,
+            figure_white: true,
+            frame_range: (0, 0),
+            frame_every: 1,
+            frame_fps: 30,
+            frame_format: FrameFormat::Gif,
+            frame_export: None,
+            opened_scenario: Arc::new(Mutex::new(None)),
+            scenario_name: String::new(),
+            scenario_description: String::new(),
+            file_error: None,
         }
     }
 }
 
 /// simple getter and setter
 impl SimulationManager {
-    pub fn time_multiplier(&mut self) -> &mut usize {
-        &mut self.sim_state.time_mul
-    }
     pub fn get_time(&self) -> f64 {
         self.sim_state.time
     }
@@ -65,26 +162,29 @@ impl SimulationManager {
 
 /// getter and setter for the simulation
 impl SimulationManager {
-    pub fn new_simulation(&mut self, simulation_template: CSTemplate) {
+    pub fn new_simulation(&mut self, simulation_template: SimulationType) {
+        self.collect_steps();
         self.is_sim_initializing = false;
 
         self.initializing_data = simulation_template.get_data();
+        self.scenario_name = simulation_template.get_name();
+        self.scenario_description = simulation_template.get_description().to_string();
+        self.template = Some(simulation_template.clone());
 
-        let CSPreset {
-            simulation_objects,
-            plot_objects,
-        } = simulation_template.get_preset_with_ui();
+        let SimulationPreset {
+            simulation,
+            settings,
+        } = simulation_template.build();
 
         if self.initializing_data.is_some() {
             self.is_sim_initializing = true;
         }
 
         self.pause();
-        self.simulation_plot = SimPlot::new(plot_objects);
-        let simulation: Box<dyn Simulation> = Box::new(ClassicSimulation::from(simulation_objects));
-        self.sim_state.settings = SimulationSettings::new(
-            SpecificSimulationSettings::CSimSettings(CSimSettings::default()),
-        );
+        self.simulation_plot = SimPlot::default();
+        self.sim_state.settings = SimulationSettings::new(settings);
+        self.frame_export = None;
+        self.history.clear();
 
         self.simulation.replace(simulation);
 
@@ -107,6 +207,7 @@ impl SimulationManager {
 
     pub(super) fn pause(&mut self) {
         self.is_paused = true;
+        self.owed_steps = 0;
     }
 
     pub(super) fn resume(&mut self) {
@@ -114,9 +215,7 @@ impl SimulationManager {
             self.is_sim_initializing = false;
         }
 
-        if self.sim_state.max_step == self.sim_state.current_step {
-            self.is_paused = false;
-        }
+        self.is_paused = false;
     }
 
     pub fn toggle_pause(&mut self) {
@@ -128,6 +227,7 @@ impl SimulationManager {
     }
 
     pub fn timestep_changed(&mut self) {
+        self.collect_steps();
         self.pause();
         self.sim_state.time = SIMULATION_TICK * self.sim_state.current_step as f64;
 
@@ -147,19 +247,48 @@ impl SimulationManager {
     }
 
     pub fn operation_ui(&mut self, ui: &mut Ui) {
+        self.edit_areas.push(ui.max_rect());
+
         if let Some(simulation) = self.simulation.as_mut() {
             simulation.operation_ui(ui);
         }
     }
 
     pub fn settings_ui(&mut self, ui: &mut Ui) {
+        let before = self.sim_state.settings.clone();
         self.sim_state.settings.ui(ui);
+
+        // 맡긴 동안 고친 설정은 돌려받은 상태에 다시 넣는다.
+        if self.stepper.is_running() && !history::same_settings(&before, &self.sim_state.settings) {
+            let after = self.sim_state.settings.clone();
+            self.collect_steps();
+            self.sim_state.settings = after;
+        }
+
+        let pointer_down = ui.input(|i| i.pointer.any_down());
+        self.history
+            .push_settings(before, &self.sim_state.settings, pointer_down);
     }
 
     pub fn inspection_ui(&mut self, ui: &mut Ui) {
+        self.edit_areas.push(ui.clip_rect());
+
         if let Some(simulation) = self.simulation.as_mut() {
+            if let Some(selected) = self.simulation_plot.selected() {
+                ui.collapsing(format!("Selected : {}", selected.name), |ui| {
+                    egui::Grid::new("selected_inspection_ui").show(ui, |ui| {
+                        for (label, value) in simulation.inspect(selected.id, &self.sim_state) {
+                            ui.label(label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                });
+            }
+
             ui.collapsing("Simulation Inspect", |ui| {
-                simulation.inspection_ui(ui, self.sim_state.current_step);
+                simulation.inspection_ui(ui, &self.sim_state);
                 ui.separator();
             });
 
@@ -171,46 +300,1116 @@ impl SimulationManager {
     }
 }
 
+/// for undo and redo
+impl SimulationManager {
+    /// 시뮬레이션이 한 편집을 기록에 넣고 되돌리기 단축키를 처리한다.
+    pub fn history_input(&mut self, ctx: &egui::Context) {
+        let pointer_down = ctx.input(|i| i.pointer.any_down());
+        self.history.update(pointer_down);
+
+        if let Some(simulation) = self.simulation.as_mut() {
+            let edits = simulation.take_edits();
+
+            // 초기화 중에는 시뮬레이션을 프레임마다 새로 만들므로 편집을 남기지 않는다.
+            if !self.is_sim_initializing && !edits.is_empty() {
+                self.history.record(
+                    simulation.as_mut(),
+                    &mut self.sim_state,
+                    edits,
+                    pointer_down,
+                );
+            }
+        }
+
+        // 글자를 입력하는 중이면 글 상자의 되돌리기가 먼저다.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (undo, redo) = ctx.input_mut(|i| {
+            let undo = i.consume_key(Modifiers::COMMAND, Key::Z);
+            let redo = i.consume_key(Modifiers::COMMAND, Key::Y)
+                || i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+            (undo, redo)
+        });
+
+        if undo {
+            self.undo(1);
+        }
+        if redo {
+            self.redo(1);
+        }
+    }
+
+    pub fn history_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.history.can_undo(), egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                self.undo(1);
+            }
+
+            if ui
+                .add_enabled(self.history.can_redo(), egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Y")
+                .clicked()
+            {
+                self.redo(1);
+            }
+        });
+
+        ui.label("Undoing a force drag recomputes the steps recorded after it.");
+
+        // 항목을 누르면 그 편집을 한 직후로 돌아간다.
+        let done = self.history.done().len();
+        let mut target = None;
+
+        if ui.selectable_label(done == 0, "Start").clicked() {
+            target = Some(0);
+        }
+
+        for (i, edit) in self.history.done().iter().enumerate() {
+            if ui.selectable_label(i + 1 == done, edit.label()).clicked() {
+                target = Some(i + 1);
+            }
+        }
+
+        for (i, edit) in self.history.undone().iter().rev().enumerate() {
+            let text = egui::RichText::new(edit.label()).weak();
+            if ui.selectable_label(false, text).clicked() {
+                target = Some(done + i + 1);
+            }
+        }
+
+        match target {
+            Some(target) if target < done => self.undo(done - target),
+            Some(target) if target > done => self.redo(target - done),
+            _ => {}
+        }
+    }
+
+    pub fn branch_ui(&mut self, ui: &mut Ui) {
+        let Some(simulation) = self.simulation.as_mut() else {
+            return;
+        };
+        let Some(action) = simulation.branch_ui(ui, &self.sim_state) else {
+            return;
+        };
+
+        // 맡긴 동안 보이는 것은 스냅샷이므로 돌려받아서 고친다.
+        self.collect_steps();
+
+        match action {
+            BranchAction::Switch(id) => self.switch_branch(id),
+            action => {
+                if let Some(simulation) = self.simulation.as_mut() {
+                    simulation.change_branch(action, &mut self.sim_state);
+                }
+                if let BranchAction::Remove(_) = action {
+                    self.history.clear();
+                }
+            }
+        }
+    }
+
+    // 갈래로 바꾸고 되돌릴 수 있게 기록한다.
+    fn switch_branch(&mut self, id: usize) {
+        if self.frame_export.is_some() {
+            return;
+        }
+
+        self.pause();
+        let Some(simulation) = &mut self.simulation else {
+            return;
+        };
+        if !simulation.change_branch(BranchAction::Switch(id), &mut self.sim_state) {
+            return;
+        }
+        simulation.at_time_step(self.sim_state.current_step);
+
+        self.history.push(
+            Edit::Branch {
+                label: format!("Switch to Branch {}", id),
+                id,
+            },
+            false,
+        );
+    }
+
+    /// 재생 속도와 방향, 실제로 재생되는 배율
+    pub fn playback_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Speed");
+
+            for speed in SPEED_PRESETS {
+                let selected = self.sim_state.speed == speed;
+
+                if ui
+                    .selectable_label(selected, format!("{}×", speed))
+                    .clicked()
+                {
+                    self.sim_state.speed = speed;
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                DragValue::new(&mut self.sim_state.speed)
+                    .clamp_range(MIN_SPEED..=MAX_SPEED)
+                    .speed(0.01)
+                    .suffix("×"),
+            );
+            ui.checkbox(&mut self.playback.reverse, "Reverse")
+                .on_hover_text("Play the recorded steps backwards.");
+        });
+
+        match self.playback.ratio() {
+            Some(ratio) => {
+                let text = format!("Simulated / real time : {:.2}×", ratio);
+
+                // 계산이 따라가지 못하면 고른 속도보다 느리게 흐른다.
+                if !self.playback.reverse && ratio < self.sim_state.speed * 0.9 {
+                    ui.colored_label(Color32::YELLOW, text)
+                        .on_hover_text("Computing new steps cannot keep up with this speed.");
+                } else {
+                    ui.label(text);
+                }
+            }
+            None => {
+                ui.weak("Simulated / real time : -");
+            }
+        }
+    }
+
+    /// 타임라인이 쓰는 메모리와 그 한도
+    pub fn timeline_ui(&mut self, ui: &mut Ui) {
+        let Some(usage) = self
+            .simulation
+            .as_mut()
+            .and_then(|simulation| simulation.memory_usage())
+        else {
+            ui.label("This simulation does not keep its timeline within a memory budget.");
+            return;
+        };
+        let Some(settings) = self.sim_state.settings.specific.as_c_sim_settings_mut() else {
+            return;
+        };
+
+        let total = usage.parts.iter().map(|(_, bytes)| bytes).sum::<usize>();
+        let megabytes = |bytes: usize| bytes as f64 / MEGABYTE as f64;
+
+        let budget = settings.memory_budget;
+        ui.add(
+            ProgressBar::new(total as f32 / budget.saturating_mul(MEGABYTE).max(1) as f32)
+                .text(format!("{:.1} / {} MB", megabytes(total), budget)),
+        );
+
+        for (name, bytes) in usage.parts {
+            ui.label(format!("{} : {:.1} MB", name, megabytes(bytes)));
+        }
+
+        if let Some(note) = usage.note {
+            ui.weak(note);
+        }
+
+        let changed = ui
+            .horizontal(|ui| {
+                ui.label("Budget");
+                ui.add(
+                    DragValue::new(&mut settings.memory_budget)
+                        .suffix(" MB")
+                        .clamp_range(16..=2048),
+                )
+                .changed()
+            })
+            .inner;
+
+        if changed {
+            let budget = settings.memory_budget;
+            self.collect_steps();
+            if let Some(settings) = self.sim_state.settings.specific.as_c_sim_settings_mut() {
+                settings.memory_budget = budget;
+            }
+
+            if let Some(simulation) = self.simulation.as_mut() {
+                simulation.fit_budget(budget.saturating_mul(MEGABYTE));
+            }
+        }
+    }
+
+    fn undo(&mut self, count: usize) {
+        self.collect_steps();
+        let Some(simulation) = &mut self.simulation else {
+            return;
+        };
+        if self.frame_export.is_some() {
+            return;
+        }
+
+        for _ in 0..count {
+            if !self.history.undo(simulation.as_mut(), &mut self.sim_state) {
+                break;
+            }
+        }
+
+        simulation.at_time_step(self.sim_state.current_step);
+    }
+
+    fn redo(&mut self, count: usize) {
+        self.collect_steps();
+        let Some(simulation) = &mut self.simulation else {
+            return;
+        };
+        if self.frame_export.is_some() {
+            return;
+        }
+
+        for _ in 0..count {
+            if !self.history.redo(simulation.as_mut(), &mut self.sim_state) {
+                break;
+            }
+        }
+
+        simulation.at_time_step(self.sim_state.current_step);
+    }
+}
+
+/// for session persistence
+impl SimulationManager {
+    pub fn session(&self) -> Session<'_> {
+        let template = self
+            .template
+            .as_ref()
+            .map(|template| SessionSimulation::Template {
+                domain: template.get_domain().to_string(),
+                name: template.get_name(),
+            });
+
+        let simulation = match &self.simulation {
+            Some(simulation) if !self.is_sim_initializing => simulation
+                .session(&self.sim_state, self.keep_timeline)
+                .or(template),
+            _ => template,
+        };
+
+        Session {
+            version: SESSION_VERSION,
+            simulation,
+            settings: self.sim_state.settings.clone(),
+            speed: self.sim_state.speed,
+            scenario_name: self.scenario_name.clone(),
+            scenario_description: self.scenario_description.clone(),
+            keep_timeline: self.keep_timeline,
+        }
+    }
+
+    pub fn restore(&mut self, session: Session<'static>) {
+        self.collect_steps();
+
+        let restored = match session.simulation {
+            Some(SessionSimulation::Template { domain, name }) => {
+                match get_sim_list()
+                    .into_iter()
+                    .find(|template| template.get_domain() == domain && template.get_name() == name)
+                {
+                    Some(template) => {
+                        self.new_simulation(template);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Some(SessionSimulation::Scenario(scenario)) => {
+                self.new_simulation(SimulationType::Classic(CSTemplate::Scenario(scenario)));
+                true
+            }
+            Some(SessionSimulation::Layout(layout)) => {
+                self.new_simulation(layout.into_template());
+                true
+            }
+            Some(SessionSimulation::Timeline {
+                world,
+                current_step,
+                max_step,
+            }) => {
+                self.is_sim_initializing = false;
+                self.initializing_data = None;
+                self.template = None;
+
+                self.pause();
+                self.simulation_plot = SimPlot::default();
+                self.history.clear();
+                self.simulation = Some(Box::new(ClassicSimulation::from(world.into_owned())));
+
+                self.sim_state.reset();
+                self.sim_state.max_step = max_step;
+                *self.current_timestep_mut() = current_step.min(max_step);
+                self.timestep_changed();
+                true
+            }
+            None => false,
+        };
+
+        if restored {
+            self.sim_state.settings = session.settings;
+            self.scenario_name = session.scenario_name;
+            self.scenario_description = session.scenario_description;
+        }
+
+        self.sim_state.speed = session.speed.clamp(MIN_SPEED, MAX_SPEED);
+        self.keep_timeline = session.keep_timeline;
+    }
+}
+
+/// for scenario files
+impl SimulationManager {
+    pub fn scenario_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Open").clicked() {
+                io::open_file("json", self.opened_scenario.clone());
+            }
+
+            if ui
+                .add_enabled(self.simulation.is_some(), egui::Button::new("Save"))
+                .on_hover_text("Save the current step as a scenario file")
+                .clicked()
+            {
+                self.save_scenario();
+            }
+        });
+
+        ui.checkbox(&mut self.keep_timeline, "Keep timeline on reload")
+            .on_hover_text("Store every recorded step. Large simulations make the session big.");
+
+        ui.add(TextEdit::singleline(&mut self.scenario_name).hint_text("Name"));
+        ui.add(
+            TextEdit::multiline(&mut self.scenario_description)
+                .hint_text("Description")
+                .desired_rows(2),
+        );
+
+        self.file_error_ui(ui);
+    }
+
+    fn file_error_ui(&self, ui: &mut Ui) {
+        if let Some(error) = &self.file_error {
+            ui.colored_label(Color32::LIGHT_RED, error);
+        }
+    }
+
+    // 저장할 파일 이름의 앞부분
+    fn file_stem(&self) -> String {
+        match self.scenario_name.trim() {
+            "" => "scenario".to_string(),
+            name => name.to_string(),
+        }
+    }
+
+    fn save_scenario(&mut self) {
+        let Some(mut scenario) = self
+            .simulation
+            .as_ref()
+            .and_then(|simulation| simulation.scenario(&self.sim_state))
+        else {
+            self.file_error = Some("This simulation cannot be saved as a scenario".to_string());
+            return;
+        };
+
+        scenario.name = self.scenario_name.clone();
+        scenario.description = self.scenario_description.clone();
+
+        let file_name = format!("{}.json", self.file_stem());
+
+        self.file_error = scenario
+            .to_json()
+            .map_err(|e| e.to_string())
+            .and_then(|json| io::save_file(&file_name, json.as_bytes()))
+            .err();
+    }
+
+    fn load_opened_scenario(&mut self) {
+        let Some(bytes) = self.opened_scenario.lock().take() else {
+            return;
+        };
+
+        let scenario = String::from_utf8(bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Scenario::from_json(&text).map_err(|e| e.to_string()));
+
+        match scenario {
+            Ok(scenario) => {
+                self.file_error = None;
+                self.new_simulation(SimulationType::Classic(CSTemplate::Scenario(Box::new(
+                    scenario,
+                ))));
+            }
+            Err(error) => self.file_error = Some(format!("Cannot open scenario: {}", error)),
+        }
+    }
+}
+
+/// for timeline export
+impl SimulationManager {
+    pub fn export_ui(&mut self, ui: &mut Ui) {
+        let can_export = self
+            .simulation
+            .as_ref()
+            .map_or(false, |simulation| simulation.can_export_timeline());
+
+        ui.label(format!("{} steps recorded", self.sim_state.max_step));
+
+        ui.horizontal(|ui| {
+            ui.label("Every");
+            ui.add(
+                DragValue::new(&mut self.export_every)
+                    .clamp_range(1..=240)
+                    .suffix(" steps"),
+            );
+        });
+
+        ui.add_enabled_ui(can_export, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for file in TIMELINE_FILES {
+                    if ui.button(file.label()).clicked() {
+                        self.export_timeline(file);
+                    }
+                }
+            });
+        });
+
+        if !can_export {
+            ui.label("This simulation does not record a timeline to export.");
+        }
+
+        self.file_error_ui(ui);
+    }
+
+    fn export_timeline(&mut self, file: TimelineFile) {
+        match self.timeline_file(file) {
+            Some(Ok((name, contents))) => {
+                self.file_error = io::save_file(&name, contents.as_bytes()).err();
+            }
+            Some(Err(error)) => self.file_error = Some(error),
+            None => {}
+        }
+    }
+
+    // 내보낼 파일 이름과 내용. 타임라인을 내보낼 수 없는 시뮬레이션이면 None.
+    fn timeline_file(&self, file: TimelineFile) -> Option<Result<(String, String), String>> {
+        let contents = self
+            .simulation
+            .as_ref()?
+            .export_timeline(file, self.export_every)?;
+
+        let suffix = match file {
+            TimelineFile::Json => ".json",
+            TimelineFile::Objects => "-objects.csv",
+            TimelineFile::Events => "-events.csv",
+            TimelineFile::Stamps => "-stamps.csv",
+        };
+
+        Some(contents.map(|contents| (format!("{}{}", self.file_stem(), suffix), contents)))
+    }
+}
+
+/// for figure export
+impl SimulationManager {
+    pub fn figure_ui(&mut self, ui: &mut Ui) {
+        let ctx = ui.ctx().clone();
+
+        ui.horizontal(|ui| {
+            ui.label("Width");
+            ui.add(
+                DragValue::new(&mut self.figure_width)
+                    .clamp_range(100.0..=4000.0)
+                    .suffix(" px"),
+            );
+        });
+
+        ui.checkbox(&mut self.figure_white, "White background")
+            .on_hover_text("Lines and text without their own color are drawn in black");
+
+        ui.add_enabled_ui(self.simulation.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("SVG").clicked() {
+                    self.export_figure(&ctx, FigureFormat::Svg);
+                }
+
+                if ui
+                    .button("PDF")
+                    .on_hover_text("Transparent fills are mixed with the background")
+                    .clicked()
+                {
+                    self.export_figure(&ctx, FigureFormat::Pdf);
+                }
+            });
+        });
+    }
+
+    // 플롯에 보이는 영역을 담을 그림
+    fn figure(&self, ctx: &egui::Context) -> Figure {
+        let background = if self.figure_white {
+            Color32::WHITE
+        } else {
+            ctx.style().visuals.extreme_bg_color
+        };
+
+        Figure::new(
+            self.sim_state.view,
+            self.sim_state.pixels_per_unit,
+            self.figure_width,
+        )
+        .with_background(background)
+        .with_grid(self.sim_state.settings.grid)
+    }
+
+    // 플롯에 보이는 영역을 지금 스텝의 모습으로 내보낸다.
+    fn export_figure(&mut self, ctx: &egui::Context, format: FigureFormat) {
+        let figure = self.figure(ctx);
+        let Some(simulation) = &mut self.simulation else {
+            return;
+        };
+
+        let items = simulation.plot_items(ctx, &self.sim_state);
+        let stem = format!("{}-{}", self.file_stem(), self.sim_state.current_step);
+
+        self.file_error = match format {
+            FigureFormat::Svg => {
+                io::save_file(&format!("{}.svg", stem), figure.to_svg(&items).as_bytes())
+            }
+            FigureFormat::Pdf => io::save_file(&format!("{}.pdf", stem), &figure.to_pdf(&items)),
+        }
+        .err();
+    }
+}
+
+/// for frame export
+impl SimulationManager {
+    pub fn animation_ui(&mut self, ui: &mut Ui) {
+        if let Some((export, _)) = &self.frame_export {
+            let (done, total) = export.progress();
+
+            ui.add(
+                ProgressBar::new(done as f32 / total.max(1) as f32)
+                    .text(format!("{} / {} frames", done, total)),
+            );
+
+            if ui.button("Cancel").clicked() {
+                self.stop_frame_export();
+            }
+            return;
+        }
+
+        let max_step = self.sim_state.max_step;
+        let (start, end) = &mut self.frame_range;
+        *end = (*end).min(max_step);
+        *start = (*start).min(*end);
+
+        ui.horizontal(|ui| {
+            ui.label("Steps");
+            ui.add(DragValue::new(start).clamp_range(0..=*end));
+            ui.label("to");
+            ui.add(DragValue::new(end).clamp_range(0..=max_step));
+        });
+
+        ui.horizontal(|ui| {
+            let current = self.sim_state.current_step;
+
+            if ui.button("Start here").clicked() {
+                self.frame_range = (current, self.frame_range.1.max(current));
+            }
+            if ui.button("End here").clicked() {
+                self.frame_range = (self.frame_range.0.min(current), current);
+            }
+            if ui.button("Whole").clicked() {
+                self.frame_range = (0, max_step);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Every");
+            ui.add(
+                DragValue::new(&mut self.frame_every)
+                    .clamp_range(1..=240)
+                    .suffix(" steps"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            for format in FrameFormat::ALL {
+                ui.selectable_value(&mut self.frame_format, format, format.label());
+            }
+        });
+
+        if self.frame_format == FrameFormat::Gif {
+            ui.horizontal(|ui| {
+                ui.label("Speed");
+                ui.add(
+                    DragValue::new(&mut self.frame_fps)
+                        .clamp_range(1..=50)
+                        .suffix(" fps"),
+                );
+            });
+        }
+
+        let frames = (self.frame_range.1 - self.frame_range.0) / self.frame_every.max(1) + 1;
+        if frames > MAX_FRAMES {
+            ui.weak(format!(
+                "Only the first {} of {} frames are exported. Raise \"Every\" to cover the whole range.",
+                MAX_FRAMES, frames
+            ));
+        }
+        let frames = frames.min(MAX_FRAMES);
+
+        if ui
+            .add_enabled(
+                self.simulation.is_some(),
+                egui::Button::new(format!("Export {} frames", frames)),
+            )
+            .clicked()
+        {
+            self.start_frame_export(ui.ctx());
+        }
+
+        self.file_error_ui(ui);
+    }
+
+    fn start_frame_export(&mut self, ctx: &egui::Context) {
+        self.collect_steps();
+        let export = FrameExport::new(
+            self.frame_format,
+            self.figure(ctx),
+            self.frame_range,
+            self.frame_every,
+            self.frame_fps,
+        );
+
+        match export {
+            Ok(export) => {
+                self.pause();
+                self.file_error = None;
+                self.frame_export = Some((export, self.sim_state.current_step));
+            }
+            Err(error) => self.file_error = Some(error),
+        }
+    }
+
+    // 내보내기를 멈추고 보던 스텝으로 돌아간다.
+    fn stop_frame_export(&mut self) -> Option<FrameExport> {
+        let (export, resume_step) = self.frame_export.take()?;
+
+        self.sim_state.current_step = resume_step;
+        self.sim_state.time = SIMULATION_TICK * resume_step as f64;
+        if let Some(simulation) = &mut self.simulation {
+            simulation.at_time_step(resume_step);
+        }
+
+        Some(export)
+    }
+
+    /// 내보내는 중이면 정해진 시간만큼 프레임을 그린다. 화면에는 그리는 스텝이 보인다.
+    pub fn export_frames(&mut self, ctx: &egui::Context) {
+        let (Some((export, _)), Some(simulation)) = (&mut self.frame_export, &mut self.simulation)
+        else {
+            return;
+        };
+
+        let started = Instant::now();
+
+        while let Some(step) = export.next_step() {
+            if started.elapsed() > FRAME_EXPORT_BUDGET {
+                return;
+            }
+
+            self.sim_state.current_step = step;
+            self.sim_state.time = SIMULATION_TICK * step as f64;
+            simulation.at_time_step(step);
+
+            let items = simulation.plot_items(ctx, &self.sim_state);
+            if let Err(error) = export.push(&items) {
+                self.file_error = Some(error);
+                self.stop_frame_export();
+                return;
+            }
+        }
+
+        if let Some(export) = self.stop_frame_export() {
+            let name = format!("{}.{}", self.file_stem(), export.format().extension());
+            self.file_error = export
+                .finish()
+                .and_then(|bytes| io::save_file(&name, &bytes))
+                .err();
+        }
+    }
+}
+
 /// for simulation tick
 impl SimulationManager {
-    pub fn simulation_step(&mut self) {
+    /// 이번 프레임에 밀린 새 스텝을 `stepper`에 맡긴다. 이미 맡겨 두었으면 목표만 늘린다.
+    /// 맡긴 뒤에는 작업 스레드가 계산을 이어 가고 다음 프레임부터 스냅샷을 그리므로 프레임의 맨 끝에 부른다.
+    pub fn dispatch_steps(&mut self) {
+        let steps = std::mem::take(&mut self.owed_steps);
+
+        // 멈췄거나 새 시뮬레이션을 열었거나 지난 스텝으로 옮겼으면 맡긴 것을 돌려받는다.
+        if self.is_paused
+            || self.is_sim_initializing
+            || self.sim_state.current_step != self.sim_state.max_step
+        {
+            self.collect_steps();
+            return;
+        }
+
+        // 화면보다 너무 앞서 계산하지 않도록 목표를 잡는다. 따라잡지 못한 스텝은 버린다.
+        let known = self.sim_state.max_step;
+        if self.stepper.is_running() {
+            let lead = self.playback.lead(self.sim_state.speed).max(steps);
+            self.step_target = (self.step_target + steps).min(known + lead);
+            self.stepper.aim(self.step_target);
+            return;
+        }
+
+        // 아직 기록에 넣지 않은 편집이 있으면 다음 프레임에 맡긴다.
+        let has_edits = self
+            .simulation
+            .as_ref()
+            .map_or(false, |simulation| simulation.has_edits());
+        if steps == 0 || has_edits {
+            return;
+        }
+        let Some(simulation) = self.simulation.take() else {
+            return;
+        };
+
+        self.history.forget_undone();
+        self.step_target = known + steps;
+        self.stepper.start(StepJob::new(
+            simulation,
+            self.sim_state.clone(),
+            self.step_target,
+        ));
+    }
+
+    /// 작업 스레드가 계산한 스텝을 받아 온다. 이번 프레임의 입력이 시뮬레이션을 고치면
+    /// 맡긴 것을 돌려받고, 아니면 작업 스레드가 계산을 이어 가는 동안 가장 새 스냅샷을 그린다.
+    /// 관리자의 다른 UI는 시뮬레이션을 고치기 직전에 돌려받는다. 프레임의 맨 처음에 부른다.
+    pub fn receive_steps(&mut self, ctx: &egui::Context) {
+        let take_back = self.edits_simulation(ctx);
+        self.edit_areas.clear();
+        self.receive(take_back);
+    }
+
+    // 이번 프레임의 입력이 시뮬레이션을 고치는지. 시뮬레이션의 위젯이나 고치는 도구를 고른 플롯을
+    // 누르고 있거나 뗐을 때, 대상을 끌고 있을 때, 시뮬레이션의 위젯에 글자를 입력할 때다.
+    fn edits_simulation(&mut self, ctx: &egui::Context) -> bool {
+        if self.simulation_plot.is_dragging_object() {
+            return true;
+        }
+
+        if let Some(pos) = ctx.input(pressed_at) {
+            self.typing = self.edit_areas.iter().any(|area| area.contains(pos));
+
+            let on_plot = self.simulation_plot.rect().map_or(false, |rect| {
+                rect.contains(pos)
+                    && self
+                        .simulation
+                        .as_ref()
+                        .map_or(false, |simulation| simulation.pointer_edits())
+            });
+
+            return self.typing || on_plot;
+        }
+
+        self.typing && ctx.wants_keyboard_input() && ctx.input(typed)
+    }
+
+    /// 맡긴 스텝 계산을 멈추고 시뮬레이션과 상태를 돌려받는다. 다 못 한 스텝은 버린다.
+    pub fn collect_steps(&mut self) {
+        self.receive(true);
+    }
+
+    fn receive(&mut self, take_back: bool) {
+        if let Some(received) = self.stepper.receive(take_back) {
+            let advanced = received
+                .state
+                .max_step
+                .saturating_sub(self.sim_state.max_step);
+
+            // 속도, 보는 영역처럼 UI가 정하는 값은 그대로 둔다. 기록 끝을 보고 있었으면
+            // 받은 끝을 따라가고, 돌려받기 직전에 지난 스텝으로 옮겼으면 그 스텝에 머문다.
+            if self.sim_state.current_step >= self.sim_state.max_step {
+                self.sim_state.current_step = received.state.current_step;
+                self.sim_state.time = received.state.time;
+            }
+            self.simulation = Some(received.simulation);
+            self.sim_state.settings = received.state.settings;
+            self.sim_state.max_step = received.state.max_step;
+            self.playback.advanced(advanced);
+        }
+    }
+
+    // 기록된 스텝 가운데 current_step 을 보여 준다.
+    fn show_current_step(&mut self) {
+        self.collect_steps();
+        self.sim_state.time = SIMULATION_TICK * self.sim_state.current_step as f64;
+
         if let Some(simulation) = &mut self.simulation {
-            self.sim_state.max_step += 1;
-            self.sim_state.current_step = self.sim_state.max_step;
+            simulation.at_time_step(self.sim_state.current_step);
+        }
+    }
+
+    // 앞으로 `steps` 스텝 나아간다. 기록된 스텝은 건너뛰어 마지막 것만 보여 주고,
+    // 새 스텝은 프레임이 끝나면 계산하도록 남겨 둔다. 지금 나아간 스텝 수를 돌려준다.
+    fn play_forward(&mut self, steps: usize) -> usize {
+        let replayed = steps.min(self.sim_state.max_step - self.sim_state.current_step);
 
-            simulation.step(&mut self.sim_state);
+        if replayed > 0 {
+            self.sim_state.current_step += replayed;
+            self.show_current_step();
         }
 
-        self.sim_state.time += SIMULATION_TICK;
+        if self.simulation.is_some() {
+            self.owed_steps = steps - replayed;
+        }
+
+        replayed
+    }
+
+    // 기록된 스텝을 `steps` 스텝 되감는다. 처음에 닿으면 멈춘다.
+    fn play_backward(&mut self, steps: usize) -> usize {
+        let rewound = self.playback.rewind(self.sim_state.current_step, steps);
+
+        if rewound > 0 {
+            self.sim_state.current_step -= rewound;
+            self.show_current_step();
+        }
+
+        if !self.playback.reverse {
+            self.pause();
+        }
+
+        rewound
     }
 
     pub fn step(&mut self) {
+        self.load_opened_scenario();
+
         if !self.is_paused && self.sim_state.sim_started {
-            //let mut dt = self.last_time_stamp.elapsed().as_secs_f64();
+            let steps = self.playback.due(self.sim_state.speed);
 
-            self.last_time_stamp = Instant::now();
+            let advanced = if self.playback.reverse {
+                self.play_backward(steps)
+            } else {
+                self.play_forward(steps)
+            };
 
-            for _ in 0..self.sim_state.time_mul {
-                self.simulation_step();
-            }
+            self.playback.advanced(advanced);
         } else {
             if self.is_sim_initializing {
-                let CSPreset {
-                    simulation_objects,
-                    plot_objects,
-                } = self
+                let SimulationPreset { simulation, .. } = self
                     .initializing_data
                     .as_ref()
                     .unwrap()
                     .to_simulation_type()
-                    .get_preset_with_ui();
-
-                self.simulation = Some(Box::new(ClassicSimulation::from(simulation_objects)));
+                    .build();
 
-                self.simulation_plot.plot_objects = plot_objects;
+                self.simulation = Some(simulation);
             }
 
-            self.last_time_stamp = Instant::now();
+            self.playback.stop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::simulations::circuit_simulation::CircuitSimulation;
+    use crate::app::simulations::optics_simulation::OpticsSimulation;
+    use crate::app::simulations::state::SpecificSimulationSettings;
+    use crate::engine::circuit::component::ComponentKind;
+    use crate::engine::circuit::template::CircuitTemplate;
+    use crate::engine::classic::object::builder::CSimObjectBuilder;
+    use crate::engine::classic::object::state::CSObjectState;
+    use crate::engine::classic::sim_state::CSimSettings;
+    use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+    use crate::engine::classic::ClassicWorld;
+    use crate::engine::optics::template::OpticsTemplate;
+    use crate::engine::NVec2;
+    use serde_json::json;
+
+    /// 달 중력 영역 안에서 던진 공을 1초 기록한 관리자
+    fn thrown_on_the_moon() -> SimulationManager {
+        let moon = NVec2::new(0.0, -1.6);
+        let mut world = ClassicWorld::from(vec![CSimObjectBuilder::new(CSObjectState {
+            velocity: NVec2::new(3.0, 4.0),
+            ..CSObjectState::default()
+        })
+        .build()])
+        .with_zones(vec![FieldZone::rect(
+            "Moon",
+            NVec2::new(-50.0, -50.0),
+            NVec2::new(50.0, 50.0),
+            ZoneEffect::Gravity(moon),
+        )]);
+
+        let mut settings = CSimSettings::default();
+        for step in 0..240 {
+            world.step(&mut settings, SIMULATION_TICK * step as f64);
+        }
+
+        let mut manager = SimulationManager {
+            simulation: Some(Box::new(ClassicSimulation::from(world))),
+            export_every: 24,
+            scenario_name: "moon".to_string(),
+            ..SimulationManager::default()
+        };
+        manager.sim_state.max_step = 240;
+        manager
+    }
+
+    #[test]
+    fn exported_objects_csv_has_the_zone_gravity() {
+        let manager = thrown_on_the_moon();
+        let (name, csv) = manager
+            .timeline_file(TimelineFile::Objects)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(name, "moon-objects.csv");
+
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+        let ay = header.iter().position(|h| *h == "ay").unwrap();
+
+        let accelerations = lines
+            .map(|line| line.split(',').nth(ay).unwrap().parse::<f64>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(accelerations.len(), 11);
+        assert!(accelerations.iter().all(|a| (a + 1.6).abs() < 1e-9));
+    }
+
+    #[test]
+    fn exported_json_has_the_zone_gravity() {
+        let manager = thrown_on_the_moon();
+        let (name, json) = manager.timeline_file(TimelineFile::Json).unwrap().unwrap();
+
+        assert_eq!(name, "moon.json");
+
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        let objects = value["objects"].as_array().unwrap();
+        assert_eq!(objects.len(), 11);
+        for sample in objects {
+            let ay = sample["acceleration"][1].as_f64().unwrap();
+            assert!((ay + 1.6).abs() < 1e-9, "{}", ay);
+        }
+    }
+
+    /// 메모리에만 두는 eframe 저장소
+    #[derive(Default)]
+    struct MemoryStorage(std::collections::HashMap<String, String>);
+
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_string(), value);
+        }
+
+        fn flush(&mut self) {}
+    }
+
+    // 세션을 저장하고 새 관리자에서 다시 연다.
+    fn reopen(manager: &SimulationManager) -> SimulationManager {
+        let mut storage = MemoryStorage::default();
+        manager.session().save(&mut storage);
+
+        let mut reopened = SimulationManager::default();
+        reopened.restore(Session::load(&storage).unwrap());
+        reopened
+    }
+
+    fn saved_layout(manager: &SimulationManager) -> serde_json::Value {
+        match manager.session().simulation {
+            Some(SessionSimulation::Layout(layout)) => serde_json::to_value(layout).unwrap(),
+            _ => panic!("the layout was not saved"),
         }
     }
+
+    #[test]
+    fn edited_circuit_survives_a_session() {
+        let mut preset = CircuitTemplate::RcChargingSim.get_preset();
+        preset.layout.add(
+            "Extra Bulb",
+            ComponentKind::Bulb {
+                resistance: 12.0,
+                rated_power: 3.0,
+            },
+            NVec2::new(100.0, 0.0),
+            NVec2::new(100.0, 40.0),
+        );
+
+        let mut manager = SimulationManager::default();
+        manager.sim_state.settings = SimulationSettings::new(
+            SpecificSimulationSettings::CircuitSettings(preset.settings.clone()),
+        );
+        manager.simulation = Some(Box::new(CircuitSimulation::from(preset)));
+
+        let layout = saved_layout(&manager);
+        assert!(layout.to_string().contains("Extra Bulb"));
+
+        let reopened = reopen(&manager);
+        assert_eq!(saved_layout(&reopened), layout);
+    }
+
+    #[test]
+    fn moved_lens_survives_a_session() {
+        let mut preset = OpticsTemplate::ThinLensSim.get_preset();
+        preset.elements[0].position = NVec2::new(-12.5, 30.0);
+        preset.elements[0].angle = 0.25;
+        preset.sources.pop();
+
+        let mut manager = SimulationManager::default();
+        manager.sim_state.settings = SimulationSettings::new(
+            SpecificSimulationSettings::OpticsSettings(preset.settings.clone()),
+        );
+        manager.simulation = Some(Box::new(OpticsSimulation::from(preset)));
+
+        let reopened = reopen(&manager);
+        let layout = saved_layout(&reopened);
+        assert_eq!(layout, saved_layout(&manager));
+        assert_eq!(
+            layout["Optics"]["elements"][0]["position"],
+            json!([-12.5, 30.0])
+        );
+        assert_eq!(layout["Optics"]["sources"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reverse_playback_pauses_at_step_zero() {
+        let mut manager = thrown_on_the_moon();
+        manager.sim_state.current_step = 10;
+        manager.is_paused = false;
+        manager.playback.reverse = true;
+
+        assert_eq!(manager.play_backward(4), 4);
+        assert_eq!(manager.sim_state.current_step, 6);
+        assert!(!manager.is_paused);
+
+        assert_eq!(manager.play_backward(100), 6);
+        assert_eq!(manager.sim_state.current_step, 0);
+        assert!(manager.is_paused);
+        assert!(!manager.playback.reverse);
+    }
+
+    #[test]
+    fn only_classic_timelines_are_exported() {
+        assert!(SimulationManager::default()
+            .timeline_file(TimelineFile::Json)
+            .is_none());
+    }
 }
@@ -0,0 +1,198 @@
+//! 실제 시간에 맞춰 프레임마다 진행할 스텝 수를 정한다.
+
+use instant::Instant;
+
+use crate::engine::SIMULATION_TICK;
+
+/// 버튼으로 고를 수 있는 재생 속도
+pub const SPEED_PRESETS: [f64; 8] = [0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0];
+
+/// 한 프레임에 따라잡는 가장 긴 실제 시간. 창이 멈췄다 돌아와도 밀린 스텝을 한꺼번에 계산하지 않는다.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// 실제로 재생된 배율을 이 시간(초)마다 다시 잰다.
+const METER_WINDOW: f64 = 0.5;
+
+pub struct Playback {
+    /// 기록된 스텝을 거꾸로 되감는지
+    pub reverse: bool,
+    last_frame: Instant,
+    /// 아직 진행하지 않은 스텝. 한 스텝이 안 되는 나머지는 다음 프레임으로 넘긴다.
+    owed: f64,
+    /// 재는 중인 구간의 실제 시간과 그동안 진행한 스텝
+    wall: f64,
+    steps: usize,
+    /// 마지막으로 잰 시뮬레이션 시간 / 실제 시간
+    ratio: Option<f64>,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            reverse: false,
+            last_frame: Instant::now(),
+            owed: 0.0,
+            wall: 0.0,
+            steps: 0,
+            ratio: None,
+        }
+    }
+}
+
+impl Playback {
+    /// 멈춰 있는 동안 부른다. 다시 재생할 때 멈춘 시간을 따라잡지 않는다.
+    pub fn stop(&mut self) {
+        self.last_frame = Instant::now();
+        self.owed = 0.0;
+        self.wall = 0.0;
+        self.steps = 0;
+        self.ratio = None;
+    }
+
+    /// 지난 프레임부터 흐른 실제 시간에 `speed`를 곱한 만큼의 스텝 수
+    pub fn due(&mut self, speed: f64) -> usize {
+        let elapsed = self.last_frame.elapsed().as_secs_f64();
+        self.last_frame = Instant::now();
+
+        self.due_after(elapsed, speed)
+    }
+
+    // `elapsed`초가 흘렀을 때 진행할 스텝 수
+    fn due_after(&mut self, elapsed: f64, speed: f64) -> usize {
+        let elapsed = elapsed.min(MAX_FRAME_TIME);
+        self.wall += elapsed;
+
+        self.owed += elapsed * speed.max(0.0) / SIMULATION_TICK;
+        let due = self.owed.floor();
+        self.owed -= due;
+
+        due as usize
+    }
+
+    /// 작업 스레드가 화면에 보인 스텝보다 앞서 계산해 둘 수 있는 스텝 수.
+    /// 한 프레임에 따라잡는 가장 긴 시간만큼이고, 이보다 밀린 스텝은 버린다.
+    pub fn lead(&self, speed: f64) -> usize {
+        (MAX_FRAME_TIME * speed.max(0.0) / SIMULATION_TICK).ceil() as usize
+    }
+
+    /// 보고 있는 `step`에서 되감을 스텝 수. 처음에 닿으면 거꾸로 재생을 끝낸다.
+    pub fn rewind(&mut self, step: usize, steps: usize) -> usize {
+        let rewound = steps.min(step);
+        if rewound == step {
+            self.reverse = false;
+        }
+
+        rewound
+    }
+
+    /// 이번 프레임에 실제로 진행한 스텝 수를 센다.
+    pub fn advanced(&mut self, steps: usize) {
+        self.steps += steps;
+
+        if self.wall >= METER_WINDOW {
+            self.ratio = Some(self.steps as f64 * SIMULATION_TICK / self.wall);
+            self.wall = 0.0;
+            self.steps = 0;
+        }
+    }
+
+    /// 실제 시간 1초에 시뮬레이션 시간이 몇 초 흘렀는지. 아직 재지 못했으면 None.
+    pub fn ratio(&self) -> Option<f64> {
+        self.ratio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 60 fps로 `seconds`초 동안 재생한 스텝 수
+    fn play(playback: &mut Playback, speed: f64, seconds: f64) -> usize {
+        let frame = 1.0 / 60.0;
+        let frames = (seconds / frame).round() as usize;
+
+        (0..frames)
+            .map(|_| {
+                let steps = playback.due_after(frame, speed);
+                playback.advanced(steps);
+                steps
+            })
+            .sum()
+    }
+
+    #[test]
+    fn speed_scales_the_steps_per_second() {
+        let per_second = 1.0 / SIMULATION_TICK;
+
+        for speed in [0.5, 1.0, 2.0] {
+            let mut playback = Playback::default();
+            let steps = play(&mut playback, speed, 2.0) as f64;
+
+            assert!(
+                (steps - 2.0 * speed * per_second).abs() <= 1.0,
+                "{} {}",
+                speed,
+                steps
+            );
+            let ratio = playback.ratio().unwrap();
+            assert!((ratio - speed).abs() < 0.01, "{} {}", speed, ratio);
+        }
+    }
+
+    #[test]
+    fn fractional_steps_carry_over() {
+        // 한 프레임에 0.4 스텝씩
+        let mut playback = Playback::default();
+        let steps = (0..5)
+            .map(|_| playback.due_after(SIMULATION_TICK * 0.4, 1.0))
+            .collect::<Vec<_>>();
+
+        assert_eq!(steps.iter().sum::<usize>(), 2);
+        assert_eq!(steps[0], 0);
+    }
+
+    #[test]
+    fn a_long_pause_is_capped_to_one_frame() {
+        for speed in [0.5, 1.0, 2.0] {
+            let mut playback = Playback::default();
+            let cap = playback.lead(speed);
+
+            assert_eq!(playback.due_after(10.0, speed), cap);
+            assert_eq!(cap as f64, MAX_FRAME_TIME * speed / SIMULATION_TICK);
+            // 버린 시간은 다음 프레임으로 넘기지 않는다.
+            assert_eq!(playback.due_after(0.0, speed), 0);
+        }
+
+        let mut playback = Playback::default();
+        assert_eq!(playback.due_after(1.0, -1.0), 0);
+    }
+
+    #[test]
+    fn stop_forgets_the_owed_steps_and_the_meter() {
+        let mut playback = Playback::default();
+        play(&mut playback, 1.0, 1.0);
+        playback.due_after(SIMULATION_TICK * 0.9, 1.0);
+        assert!(playback.ratio().is_some());
+
+        playback.stop();
+        assert!(playback.ratio().is_none());
+        assert_eq!(playback.due_after(SIMULATION_TICK * 0.2, 1.0), 0);
+    }
+
+    #[test]
+    fn reverse_playback_stops_at_step_zero() {
+        let mut playback = Playback {
+            reverse: true,
+            ..Playback::default()
+        };
+
+        assert_eq!(playback.rewind(10, 4), 4);
+        assert!(playback.reverse);
+        assert_eq!(playback.rewind(6, 100), 6);
+        assert!(!playback.reverse);
+
+        playback.reverse = true;
+        assert_eq!(playback.rewind(0, 3), 0);
+        assert!(!playback.reverse);
+    }
+}
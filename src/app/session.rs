@@ -0,0 +1,380 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::app::simulations::state::SimulationSettings;
+use crate::app::simulations::template::SimulationType;
+use crate::engine::circuit::template::{CircuitPreset, CircuitTemplate};
+use crate::engine::classic::boundary::WorldBoundary;
+use crate::engine::classic::scenario::Scenario;
+use crate::engine::classic::timeline::KEYFRAME_INTERVAL;
+use crate::engine::classic::ClassicWorld;
+use crate::engine::fluid::template::{FluidPreset, FluidTemplate};
+use crate::engine::heat::template::{HeatPreset, HeatTemplate};
+use crate::engine::optics::template::{OpticsPreset, OpticsTemplate};
+use crate::engine::wave::template::{WavePreset, WaveTemplate};
+use crate::engine::SIMULATION_TICK;
+
+/// eframe 저장소에서 세션을 담는 키
+const SESSION_KEY: &str = "session";
+
+/// `MIGRATIONS[i]`는 버전 i + 1 의 세션을 버전 i + 2 의 모양으로 고친다.
+/// 형식을 바꿀 때는 여기에 함수를 하나 더하면 버전도 같이 올라간다.
+const MIGRATIONS: [fn(&mut Value); 2] = [keyframe_timeline, playback_speed];
+
+pub const SESSION_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+// 1 → 2: 오브젝트가 스텝마다 온전한 상태를 남기던 것을 위치, 속도와 키프레임으로 바꾼다.
+// 장면은 저장할 때의 것 하나로 보고, 드래그로 준 가속도는 상태에서 읽어 기록한다.
+fn keyframe_timeline(session: &mut Value) {
+    let boundary = session
+        .pointer("/settings/specific/CSimSettings/boundary")
+        .cloned()
+        .unwrap_or_else(|| json!(WorldBoundary::default()));
+    let Some(world) = session.pointer_mut("/simulation/Timeline/world") else {
+        return;
+    };
+
+    let steps = world["events"].as_array().map_or(0, Vec::len);
+    let Some(objects) = world["objects"].as_array_mut() else {
+        return;
+    };
+
+    let step_of = |value: &Value| value.as_u64().unwrap_or(0) as usize;
+
+    // 처음과 오브젝트가 생긴 스텝은 꼭 남기고, 그 사이는 간격마다 남긴다.
+    let mut forced = objects
+        .iter()
+        .map(|obj| step_of(&obj["init_timestep"]))
+        .filter(|init| *init < steps)
+        .collect::<Vec<_>>();
+    forced.push(0);
+    forced.sort_unstable();
+    forced.dedup();
+
+    let mut keyframe_steps = (0..steps).step_by(KEYFRAME_INTERVAL).collect::<Vec<_>>();
+    keyframe_steps.extend(forced.iter().filter(|step| **step < steps));
+    keyframe_steps.sort_unstable();
+    keyframe_steps.dedup();
+
+    let mut user_forces = vec![];
+
+    for (index, obj) in objects.iter_mut().enumerate() {
+        let Some(mut states) = obj
+            .get_mut("state_timeline")
+            .map(Value::take)
+            .and_then(|states| states.as_array().cloned())
+            .filter(|states| !states.is_empty())
+        else {
+            continue;
+        };
+
+        let init = step_of(&obj["init_timestep"]);
+        let live = states.pop().unwrap_or_default();
+
+        for (local, state) in states.iter().enumerate() {
+            let force = &state["acc_list"][1];
+            let is_zero = force
+                .as_array()
+                .map_or(true, |xy| xy.iter().all(|v| v.as_f64() == Some(0.0)));
+
+            if !is_zero {
+                user_forces.push(json!({"step": init + local, "index": index, "force": force}));
+            }
+        }
+
+        let keyframes = keyframe_steps
+            .iter()
+            .filter(|step| **step >= init && **step - init < states.len())
+            .map(|step| json!({"step": step, "state": states[step - init]}))
+            .collect::<Vec<_>>();
+
+        obj["track"] = json!({
+            "start": init,
+            "positions": states.iter().map(|state| &state["position"]).collect::<Vec<_>>(),
+            "velocities": states.iter().map(|state| &state["velocity"]).collect::<Vec<_>>(),
+        });
+        obj["keyframes"] = json!(keyframes);
+        obj["live"] = live;
+        if let Some(obj) = obj.as_object_mut() {
+            obj.remove("state_timeline");
+        }
+    }
+
+    user_forces.sort_by_key(|force| (step_of(&force["step"]), step_of(&force["index"])));
+
+    let keyframes = keyframe_steps
+        .iter()
+        .map(|step| json!({"step": step, "scene": 0, "periodic": forced.binary_search(step).is_err()}))
+        .collect::<Vec<_>>();
+    let scene = json!({
+        "global_acc_list": world["global_acc_list"],
+        "zones": world["zones"],
+        "soft_bodies": world["soft_bodies"],
+        "chains": world["chains"],
+        "boundary": boundary,
+    });
+
+    world["timeline"] = json!({
+        "keyframes": keyframes,
+        "scenes": [scene],
+        "user_forces": user_forces,
+        "detail_from": 0,
+    });
+}
+
+// 2 → 3: 프레임마다 몇 스텝 나아갈지를 실제 시간에 대한 배율로 바꾼다.
+// 예전에는 초당 60 프레임쯤에서 한 프레임에 한 스텝이었으므로 그 빠르기를 그대로 옮긴다.
+fn playback_speed(session: &mut Value) {
+    let Some(session) = session.as_object_mut() else {
+        return;
+    };
+    let time_mul = session
+        .remove("time_mul")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(1)
+        .max(1);
+
+    session.insert(
+        "speed".to_owned(),
+        (time_mul as f64 * 60.0 * SIMULATION_TICK).into(),
+    );
+}
+
+/// 다시 열 때 되살릴 시뮬레이션
+#[derive(Serialize, Deserialize)]
+pub enum SessionSimulation<'a> {
+    /// 템플릿 목록에서 영역과 이름으로 찾아 처음부터 다시 만든다.
+    Template { domain: String, name: String },
+    /// 저장할 때 보던 스텝을 시작 상태로 삼는다.
+    Scenario(Box<Scenario>),
+    /// 고전 역학이 아닌 영역에서 보던 스텝의 배치
+    Layout(Box<SessionLayout>),
+    /// 기록된 타임라인 전체와 보던 스텝
+    Timeline {
+        world: Cow<'a, ClassicWorld>,
+        current_step: usize,
+        max_step: usize,
+    },
+}
+
+/// 다시 열면 이 배치로 처음부터 계산한다. 격자의 값이나 입자의 속도는 남기지 않는다.
+#[derive(Serialize, Deserialize)]
+pub enum SessionLayout {
+    Optics(OpticsPreset),
+    Wave(WavePreset),
+    Heat(HeatPreset),
+    Fluid(FluidPreset),
+    Circuit(CircuitPreset),
+}
+
+impl SessionLayout {
+    pub fn into_template(self) -> SimulationType {
+        match self {
+            Self::Optics(preset) => SimulationType::Optics(OpticsTemplate::Saved(Box::new(preset))),
+            Self::Wave(preset) => SimulationType::Wave(WaveTemplate::Saved(Box::new(preset))),
+            Self::Heat(preset) => SimulationType::Heat(HeatTemplate::Saved(Box::new(preset))),
+            Self::Fluid(preset) => SimulationType::Fluid(FluidTemplate::Saved(Box::new(preset))),
+            Self::Circuit(preset) => {
+                SimulationType::Circuit(CircuitTemplate::Saved(Box::new(preset)))
+            }
+        }
+    }
+}
+
+/// 앱을 다시 열었을 때 이어서 쓸 수 있도록 저장하는 상태.
+#[derive(Serialize, Deserialize)]
+pub struct Session<'a> {
+    pub version: u64,
+    pub simulation: Option<SessionSimulation<'a>>,
+    pub settings: SimulationSettings,
+    pub speed: f64,
+    pub scenario_name: String,
+    pub scenario_description: String,
+    /// 타임라인 전체를 저장할지. 물체가 많으면 저장소가 커진다.
+    pub keep_timeline: bool,
+}
+
+impl Session<'_> {
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        match serde_json::to_string(self) {
+            Ok(json) => storage.set_string(SESSION_KEY, json),
+            Err(error) => warn!("cannot save session: {}", error),
+        }
+    }
+}
+
+impl Session<'static> {
+    /// 저장된 세션을 지금 버전으로 올려서 읽는다. 없거나 읽을 수 없으면 None.
+    pub fn load(storage: &dyn eframe::Storage) -> Option<Self> {
+        Self::parse(&storage.get_string(SESSION_KEY)?)
+    }
+
+    fn parse(json: &str) -> Option<Self> {
+        let mut value: Value = serde_json::from_str(json).ok()?;
+        let version = value.get("version")?.as_u64()?;
+
+        if version == 0 || version > SESSION_VERSION {
+            warn!("ignoring session version {}", version);
+            return None;
+        }
+
+        for migrate in &MIGRATIONS[version as usize - 1..] {
+            migrate(&mut value);
+        }
+        value["version"] = SESSION_VERSION.into();
+
+        serde_json::from_value(value)
+            .map_err(|error| warn!("cannot load session: {}", error))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::NVec2;
+
+    // 같은 장면(달에서 던진 공, 2 ~ 3 스텝에 드래그, 5 스텝에 붙인 공)을 버전마다 저장한 세션
+    const V1: &str = include_str!("../../tests/fixtures/session-v1.json");
+    const V2: &str = include_str!("../../tests/fixtures/session-v2.json");
+
+    // 저장된 세션과 그 안의 세계. 타임라인이 없으면 패닉한다.
+    fn load(json: &str) -> (Session<'static>, ClassicWorld) {
+        let mut session = Session::parse(json).unwrap();
+        let Some(SessionSimulation::Timeline { world, .. }) = session.simulation.take() else {
+            panic!("the timeline was not loaded");
+        };
+
+        (session, world.into_owned())
+    }
+
+    fn vector(value: &Value) -> NVec2 {
+        NVec2::new(value[0].as_f64().unwrap(), value[1].as_f64().unwrap())
+    }
+
+    // 오브젝트마다 (생긴 스텝, 스텝마다의 위치와 속도)
+    fn tracks(world: &mut ClassicWorld) -> Vec<(usize, Vec<(NVec2, NVec2)>)> {
+        let steps = world.recorded_steps();
+
+        (0..world.objects.len())
+            .map(|index| {
+                let init = *world.objects[index].init_timestep();
+                let states = (init..=steps)
+                    .map(|step| {
+                        world.at_time_step(step);
+                        let state = world.objects[index].current_state();
+                        (state.position, state.velocity)
+                    })
+                    .collect();
+
+                (init, states)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn version_1_keeps_its_states_and_forces() {
+        let old: Value = serde_json::from_str(V1).unwrap();
+        assert_eq!(old["version"], 1);
+        let (session, mut world) = load(V1);
+
+        assert_eq!(session.version, SESSION_VERSION);
+        assert_eq!(session.speed, 3.0 * 60.0 * SIMULATION_TICK);
+        assert_eq!(session.scenario_name, "moon");
+        assert!(session.keep_timeline);
+        assert!(session.settings.specific.as_c_sim_settings().is_some());
+
+        let old_world = &old["simulation"]["Timeline"]["world"];
+        assert_eq!(world.recorded_steps(), 12);
+        assert_eq!(
+            serde_json::to_value(world.events_at(5).unwrap()).unwrap(),
+            old_world["events"][5]
+        );
+
+        // 드래그한 가속도는 타임라인으로 옮겨진다.
+        assert!((0..12).any(|step| world.user_force(step, 0) != NVec2::zeros()));
+
+        let old_objects = old_world["objects"].as_array().unwrap();
+        let tracks = tracks(&mut world);
+        assert_eq!(tracks.len(), old_objects.len());
+
+        for (index, ((init, states), old)) in tracks.iter().zip(old_objects).enumerate() {
+            // 예전 기록은 마지막에 계산하던 상태를 하나 더 들고 있다.
+            let old_states = old["state_timeline"].as_array().unwrap();
+            assert_eq!(*init as u64, old["init_timestep"].as_u64().unwrap());
+            assert_eq!(states.len(), old_states.len());
+
+            for (local, (state, old)) in states.iter().zip(old_states).enumerate() {
+                let step = init + local;
+                assert_eq!(state.0, vector(&old["position"]), "{} {}", index, step);
+                assert_eq!(state.1, vector(&old["velocity"]), "{} {}", index, step);
+                if step < 12 {
+                    assert_eq!(
+                        world.user_force(step, index),
+                        vector(&old["acc_list"][1]),
+                        "{} {}",
+                        index,
+                        step
+                    );
+                }
+            }
+        }
+
+        // 처음과 오브젝트가 생긴 스텝에는 키프레임이 있다.
+        let keyframes = world
+            .timeline()
+            .keyframes()
+            .iter()
+            .map(|keyframe| keyframe.step)
+            .collect::<Vec<_>>();
+        assert!(keyframes.contains(&0) && keyframes.contains(&5));
+    }
+
+    #[test]
+    fn version_2_gets_a_playback_speed() {
+        let old: Value = serde_json::from_str(V2).unwrap();
+        assert_eq!(old["version"], 2);
+        assert_eq!(old["time_mul"], 3);
+        let (session, mut world) = load(V2);
+
+        assert_eq!(session.version, SESSION_VERSION);
+        assert_eq!(session.speed, 3.0 * 60.0 * SIMULATION_TICK);
+        assert_eq!(session.scenario_description, "A ball thrown on the moon");
+
+        let forces = (0..12)
+            .map(|step| world.user_force(step, 0))
+            .collect::<Vec<_>>();
+        let drag = NVec2::new(0.0, 10.0);
+        assert_eq!(forces[2..4], [drag, drag]);
+        assert!(forces[..2]
+            .iter()
+            .chain(&forces[4..])
+            .all(|force| *force == NVec2::zeros()));
+
+        // 버전 1에서 올린 같은 장면과 같은 타임라인이 된다.
+        let (_, mut from_v1) = load(V1);
+        assert_eq!(tracks(&mut world), tracks(&mut from_v1));
+        assert_eq!(
+            serde_json::to_value(world.timeline().keyframes()).unwrap(),
+            serde_json::to_value(from_v1.timeline().keyframes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn version_1_timeline_recomputes_like_it_was_recorded() {
+        let (session, mut world) = load(V1);
+        let recorded = tracks(&mut world);
+        let mut settings = session
+            .settings
+            .specific
+            .as_c_sim_settings()
+            .cloned()
+            .unwrap();
+
+        world.recompute(5, 12, &mut settings, |_, _| {});
+        assert_eq!(tracks(&mut world), recorded);
+    }
+}
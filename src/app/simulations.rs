@@ -1,3 +1,223 @@
+pub mod circuit_simulation;
 pub mod classic_simulation;
-pub mod polygon;
+pub mod fluid_simulation;
+pub mod grid;
+pub mod heat_simulation;
+pub mod optics_simulation;
 pub mod state;
+pub mod template;
+pub mod wave_simulation;
+
+use egui::plot::PlotPoint;
+use egui::{Response, Ui};
+
+use crate::app::graphics::define::PlotItem;
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::session::{SessionLayout, SessionSimulation};
+use crate::app::simulations::state::SimulationState;
+use crate::app::NVec2;
+use crate::engine::classic::edit::SceneEdit;
+use crate::engine::classic::scenario::Scenario;
+use crate::engine::polygon::is_inside;
+
+/// 플롯에서 고를 수 있는 대상의 식별자. `group`이 무엇을 뜻하는지는 시뮬레이션이 정한다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TargetId {
+    pub group: usize,
+    pub index: usize,
+}
+
+impl TargetId {
+    pub fn new(group: usize, index: usize) -> Self {
+        Self { group, index }
+    }
+}
+
+/// 클릭, 드래그로 고를 수 있는 대상.
+#[derive(Clone, Debug)]
+pub struct PickTarget {
+    pub id: TargetId,
+    pub name: String,
+    /// 드래그 선이 시작되는 위치
+    pub anchor: NVec2,
+    /// 이 다각형 안을 누르면 대상이 선택된다.
+    pub outline: Vec<NVec2>,
+}
+
+impl PickTarget {
+    pub fn contains(&self, pos: PlotPoint) -> bool {
+        is_inside(NVec2::new(pos.x, pos.y), &self.outline)
+    }
+}
+
+/// 갈래 목록에서 누른 것
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchAction {
+    /// 지금 타임라인과 나란히 볼 갈래. None이면 나란히 보지 않는다.
+    Compare(Option<usize>),
+    /// 갈래를 지금 타임라인과 맞바꾼다.
+    Switch(usize),
+    Remove(usize),
+}
+
+/// 타임라인을 내보낼 파일
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimelineFile {
+    /// 오브젝트, 이벤트, 스탬프를 모두 담는다.
+    Json,
+    Objects,
+    Events,
+    Stamps,
+}
+
+pub const TIMELINE_FILES: [TimelineFile; 4] = [
+    TimelineFile::Json,
+    TimelineFile::Objects,
+    TimelineFile::Events,
+    TimelineFile::Stamps,
+];
+
+impl TimelineFile {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Objects => "Objects CSV",
+            Self::Events => "Events CSV",
+            Self::Stamps => "Stamps CSV",
+        }
+    }
+}
+
+/// 기록이 쓰는 메모리
+pub struct MemoryUsage {
+    /// (이름, 바이트 수)
+    pub parts: Vec<(String, usize)>,
+    /// 예산에 맞추려고 버린 기록이 있으면 그 설명
+    pub note: Option<String>,
+}
+
+/// 시뮬레이션 하나의 영역(역학, 광학, 회로 ...)을 나타낸다.
+/// `SimulationManager`와 `SimPlot`은 이 트레잇만 보고 타임라인과 UI를 돌린다.
+pub trait Simulation: Send + Sync {
+    fn inspection_ui(&mut self, ui: &mut Ui, _state: &SimulationState) {
+        ui.label("No inspection UI");
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.label("No operations UI");
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        input_msg: InputMessage,
+        response: Response,
+        ctx: &egui::Context,
+        state: &mut SimulationState,
+    );
+
+    fn step(&mut self, state: &mut SimulationState);
+
+    /// 작업 스레드가 계속 계산하는 동안 화면에 그릴 복사본
+    fn snapshot(&self) -> Box<dyn Simulation>;
+
+    /// 지금 도구로 플롯을 누르면 시뮬레이션을 고치는지. 고치면 관리자가 맡긴 계산을 돌려받고 누른다.
+    fn pointer_edits(&self) -> bool {
+        false
+    }
+
+    /// 관리자가 아직 기록에 넣지 않은 편집이 있는지. 있으면 계산을 맡기지 않는다.
+    fn has_edits(&self) -> bool {
+        false
+    }
+
+    /// 지난번 이후에 한 편집. 관리자가 가져가서 되돌리기 기록에 넣는다.
+    fn take_edits(&mut self) -> Vec<SceneEdit> {
+        vec![]
+    }
+
+    /// 기록에 넣은 편집을 되돌린다.
+    fn undo_edit(&mut self, _edit: &mut SceneEdit, _state: &mut SimulationState) {}
+
+    /// 되돌린 편집을 다시 한다.
+    fn redo_edit(&mut self, _edit: &mut SceneEdit, _state: &mut SimulationState) {}
+
+    /// 지난 스텝 `step`에서 `edits`를 했다. 그 뒤에 기록된 스텝을 갈래로 잘라 냈으면 그 번호.
+    fn fork(
+        &mut self,
+        _step: usize,
+        _edits: &[SceneEdit],
+        _state: &mut SimulationState,
+    ) -> Option<usize> {
+        None
+    }
+
+    /// 갈래 목록. 누른 것은 관리자가 맡긴 계산을 돌려받은 뒤 `change_branch`로 한다.
+    fn branch_ui(&mut self, ui: &mut Ui, _state: &SimulationState) -> Option<BranchAction> {
+        ui.label("This simulation does not keep branches.");
+        None
+    }
+
+    /// 갈래를 바꾼다. 맞바꾸면 기록된 스텝 수를 `state`에 맞춘다. 갈래가 없으면 false.
+    fn change_branch(&mut self, _action: BranchAction, _state: &mut SimulationState) -> bool {
+        false
+    }
+
+    /// 기록이 쓰는 메모리. 메모리 예산 안에서 기록하지 않으면 None.
+    fn memory_usage(&mut self) -> Option<MemoryUsage> {
+        None
+    }
+
+    /// 기록이 `budget` 바이트 안에 들도록 오래된 기록을 버린다.
+    fn fit_budget(&mut self, _budget: usize) {}
+
+    /// 기록된 타임라인을 파일로 내보낼 수 있는지
+    fn can_export_timeline(&self) -> bool {
+        false
+    }
+
+    /// `every` 스텝마다 기록을 뽑은 파일 내용. 내보낼 수 없으면 None.
+    fn export_timeline(
+        &self,
+        _file: TimelineFile,
+        _every: usize,
+    ) -> Option<Result<String, String>> {
+        None
+    }
+
+    fn at_time_step(&mut self, step: usize);
+
+    /// 현재 스텝에서 플롯에 그릴 아이템. 먼저 온 것이 아래에 깔린다.
+    /// `ctx`는 텍스처를 올릴 때 쓴다.
+    fn plot_items(&mut self, ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem>;
+
+    /// 플롯에서 고를 수 있는 대상들. 겹친 경우 뒤에 있는 것이 먼저 골라진다.
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        vec![]
+    }
+
+    /// 선택된 대상의 (이름, 값) 목록.
+    fn inspect(&self, _target: TargetId, _state: &SimulationState) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    /// 현재 스텝을 파일로 저장할 시나리오. 시나리오로 저장할 수 없으면 None.
+    fn scenario(&self, _state: &SimulationState) -> Option<Scenario> {
+        None
+    }
+
+    /// 세션에 저장할 지금 스텝의 배치. 고전 역학은 시나리오로 저장하므로 None이다.
+    fn layout(&self, _state: &SimulationState) -> Option<SessionLayout> {
+        None
+    }
+
+    /// 세션에 저장할 시뮬레이션. `keep_timeline`이면 기록된 타임라인도 함께 저장한다.
+    fn session(
+        &self,
+        state: &SimulationState,
+        _keep_timeline: bool,
+    ) -> Option<SessionSimulation<'_>> {
+        self.layout(state)
+            .map(|layout| SessionSimulation::Layout(Box::new(layout)))
+    }
+}
@@ -0,0 +1,591 @@
+pub mod component;
+pub mod sim_state;
+
+use crate::app::graphics::define::items::{Line, Points, Text};
+use crate::app::NVec2;
+
+use egui::plot::PlotPoint;
+use egui::{CollapsingHeader, Color32, ComboBox, Response, Ui};
+
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::manager::SIMULATION_TICK;
+use crate::app::session::SessionLayout;
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation, TargetId};
+use crate::engine::keyframe::KeyframeTimeline;
+
+use self::component::si;
+use crate::engine::circuit::component::{
+    grid_point, grid_position, snap, CircuitLayout, Component, ComponentKind, COMPONENT_KINDS,
+};
+use crate::engine::circuit::sim_state::CircuitSettings;
+use crate::engine::circuit::solver::{ground, CircuitState, SHORT_CIRCUIT_CURRENT};
+use crate::engine::circuit::template::CircuitPreset;
+
+/// `TargetId::group` 값
+const COMPONENT_TARGET: usize = 0;
+
+/// 이 간격마다 회로 상태를 저장하고, 그 사이 스텝은 다시 계산한다.
+const KEYFRAME_INTERVAL: usize = 60;
+
+/// 전류를 나타내는 점 사이의 거리
+const DOT_SPACING: f64 = 5.0;
+
+/// 이보다 약한 전류는 점을 그리지 않는다. (A)
+const MIN_VISIBLE_CURRENT: f64 = 1e-6;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitOperation {
+    #[default]
+    Navigate,
+    Move,
+    AddWire,
+    AddComponent,
+    Remove,
+}
+
+const CIRCUIT_OPERATION_ITER: [CircuitOperation; 5] = [
+    CircuitOperation::Navigate,
+    CircuitOperation::Move,
+    CircuitOperation::AddWire,
+    CircuitOperation::AddComponent,
+    CircuitOperation::Remove,
+];
+
+/// 직류와 RC 회로. 스텝마다 수정 절점 해석으로 노드 전위를 풀고 전류를 점으로 흘린다.
+#[derive(Clone)]
+pub struct CircuitSimulation {
+    pub layout: CircuitLayout,
+    circuit: CircuitState,
+    timeline: KeyframeTimeline<CircuitLayout, CircuitState>,
+    /// 타임라인을 다시 계산할 때 쓸 마지막 설정
+    settings: CircuitSettings,
+
+    pub operation: CircuitOperation,
+    /// `AddComponent`로 놓을 부품
+    pub brush: ComponentKind,
+    /// 드래그로 놓고 있는 부품의 시작 격자점
+    drag_start: Option<NVec2>,
+    /// 드래그를 시작할 때 대상 위치와 포인터의 차이
+    grab_offset: NVec2,
+}
+
+impl From<CircuitPreset> for CircuitSimulation {
+    fn from(preset: CircuitPreset) -> Self {
+        let circuit = CircuitState::new(&preset.layout);
+
+        Self {
+            timeline: KeyframeTimeline::new(
+                preset.layout.clone(),
+                circuit.clone(),
+                KEYFRAME_INTERVAL,
+            ),
+            layout: preset.layout,
+            circuit,
+            settings: preset.settings,
+            operation: CircuitOperation::default(),
+            brush: COMPONENT_KINDS[0].clone(),
+            drag_start: None,
+            grab_offset: NVec2::zeros(),
+        }
+    }
+}
+
+impl CircuitSimulation {
+    fn settings(state: &SimulationState) -> CircuitSettings {
+        state
+            .settings
+            .specific
+            .as_circuit_settings()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn dt(&self) -> f64 {
+        SIMULATION_TICK * self.settings.time_scale
+    }
+
+    /// 그 스텝의 배치로 한 스텝 진행하고, 간격마다 회로를 저장한다.
+    fn advance(&mut self) {
+        self.layout = self.timeline.layout_at(self.circuit.step).clone();
+
+        let dt = self.dt();
+        self.circuit.advance(&self.layout, dt);
+
+        self.timeline.store(self.circuit.step, &self.circuit);
+    }
+
+    // 현재 배치를 타임라인의 `step`부터 쓰고, 다시 푼 전위와 전류를 그 스텝의 저장본으로 둔다.
+    fn record(&mut self, step: usize) {
+        self.timeline.record(step, self.layout.clone());
+        self.circuit.refresh(&self.layout);
+        self.timeline.edit(step, &self.circuit);
+    }
+
+    fn move_target(&mut self, target: TargetId, anchor: NVec2) {
+        if let Some(component) = self.layout.components.get_mut(target.index) {
+            let delta = anchor - component.center();
+            component.translate(delta);
+        }
+    }
+
+    fn remove_target(&mut self, target: TargetId, step: usize) {
+        if target.index < self.layout.components.len() {
+            self.layout.components.remove(target.index);
+            self.record(step);
+        }
+    }
+
+    /// 전구가 정격 전력에 견주어 밝은 정도
+    fn brightness(&self, component: &Component) -> f64 {
+        match component.kind {
+            ComponentKind::Bulb { rated_power, .. } => {
+                let state = self.circuit.get(component);
+                (state.voltage * state.current).abs() / rated_power
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// 흐른 전하만큼 움직인 전류 점들
+    fn current_dots(&self, component: &Component) -> Vec<[f64; 2]> {
+        let state = self.circuit.get(component);
+        let length = component.length();
+
+        if state.current.abs() < MIN_VISIBLE_CURRENT || length == 0.0 {
+            return vec![];
+        }
+
+        let offset = (state.charge * self.settings.current_scale).rem_euclid(DOT_SPACING);
+
+        (0..)
+            .map(|k| offset + k as f64 * DOT_SPACING)
+            .take_while(|distance| *distance < length)
+            .map(|distance| {
+                let p = component.point_at(distance);
+                [p.x, p.y]
+            })
+            .collect()
+    }
+
+    fn ground_symbol(&self) -> Vec<PlotItem> {
+        let Some(origin) = ground(&self.layout).map(grid_position) else {
+            return vec![];
+        };
+
+        let line = |points: Vec<[f64; 2]>| -> PlotItem {
+            Line::new(points)
+                .color(PlotColor::Wire.get_color())
+                .width(2.0)
+                .into()
+        };
+
+        let mut items = vec![line(vec![[origin.x, origin.y], [origin.x, origin.y - 3.0]])];
+        items.extend([(3.0, 3.0), (4.0, 2.0), (5.0, 1.0)].map(|(depth, half)| {
+            line(vec![
+                [origin.x - half, origin.y - depth],
+                [origin.x + half, origin.y - depth],
+            ])
+        }));
+        items
+    }
+}
+
+impl Simulation for CircuitSimulation {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let before = self.layout.clone();
+
+        // 전지가 내는 전력
+        let supplied = self
+            .layout
+            .components
+            .iter()
+            .filter(|component| matches!(component.kind, ComponentKind::Battery { .. }))
+            .map(|component| {
+                let state = self.circuit.get(component);
+                -state.voltage * state.current
+            })
+            .sum::<f64>();
+
+        egui::Grid::new("circuit_summary").show(ui, |ui| {
+            ui.label("Components");
+            ui.label(format!("{}", self.layout.components.len()));
+            ui.end_row();
+
+            ui.label("Nodes");
+            ui.label(format!("{}", self.layout.degrees().len()));
+            ui.end_row();
+
+            ui.label("Circuit Time");
+            ui.label(format!("{:.3} s", self.circuit.time));
+            ui.end_row();
+
+            ui.label("Supplied Power");
+            ui.label(si(supplied, "W"));
+            ui.end_row();
+
+            ui.label("Keyframes");
+            ui.label(format!("{}", self.timeline.keyframe_count()));
+            ui.end_row();
+        });
+
+        if let Some(error) = self
+            .circuit
+            .error
+            .as_ref()
+            .filter(|_| !self.layout.components.is_empty())
+        {
+            ui.colored_label(Color32::RED, error);
+        }
+
+        for component in &self.layout.components {
+            let current = self.circuit.get(component).current;
+
+            if matches!(component.kind, ComponentKind::Battery { .. })
+                && current.abs() > SHORT_CIRCUIT_CURRENT
+            {
+                ui.colored_label(
+                    Color32::RED,
+                    format!(
+                        "{} is short-circuited ({})",
+                        component.name,
+                        si(current, "A")
+                    ),
+                );
+            }
+        }
+
+        let mut remove = None;
+
+        for (i, component) in self.layout.components.iter_mut().enumerate() {
+            ui.push_id(("component", component.id), |ui| {
+                CollapsingHeader::new(&component.name).show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| component.kind.ui(ui));
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Flip").clicked() {
+                            component.flip();
+                        }
+
+                        if ui.button("Remove this Component").clicked() {
+                            remove.replace(TargetId::new(COMPONENT_TARGET, i));
+                        }
+                    });
+                });
+            });
+        }
+
+        if let Some(target) = remove {
+            self.remove_target(target, state.current_step);
+        }
+
+        if before != self.layout {
+            self.record(state.current_step);
+        }
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operations");
+            ui.separator();
+            ui.horizontal(|ui| {
+                CIRCUIT_OPERATION_ITER.iter().for_each(|operation| {
+                    ui.selectable_value(
+                        &mut self.operation,
+                        *operation,
+                        format!("{:?}", operation),
+                    );
+                });
+            });
+
+            if self.operation == CircuitOperation::AddComponent {
+                ui.separator();
+
+                ComboBox::from_id_source("circuit_brush")
+                    .selected_text(self.brush.get_name())
+                    .show_ui(ui, |ui| {
+                        for kind in COMPONENT_KINDS {
+                            let name = kind.get_name();
+                            let selected = self.brush.get_name() == name;
+
+                            if ui.selectable_label(selected, name).clicked() && !selected {
+                                self.brush = kind;
+                            }
+                        }
+                    });
+
+                self.brush.ui(ui);
+            }
+        });
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        msg: InputMessage,
+        response: Response,
+        _ctx: &egui::Context,
+        state: &mut SimulationState,
+    ) {
+        let Some(pointer_pos) = msg.pointer_pos else {
+            return;
+        };
+        let pointer = NVec2::new(pointer_pos.x, pointer_pos.y);
+
+        match self.operation {
+            CircuitOperation::Navigate => {
+                if response.clicked() {
+                    plot.selected = plot.pick(pointer_pos);
+
+                    // 스위치는 누르면 열리고 닫힌다.
+                    let target = plot.selected.as_ref().map(|target| target.id.index);
+                    if let Some(ComponentKind::Switch { closed }) = target
+                        .and_then(|index| self.layout.components.get_mut(index))
+                        .map(|component| &mut component.kind)
+                    {
+                        *closed = !*closed;
+                        self.record(state.current_step);
+                    }
+                }
+            }
+            CircuitOperation::Move => {
+                if response.dragged() {
+                    if !plot.dragging_object {
+                        // 드래그 시작할 때
+                        plot.selected = plot.pick(pointer_pos);
+                        plot.dragging_object = plot.selected.is_some();
+
+                        if let Some(target) = &plot.selected {
+                            self.grab_offset = target.anchor - pointer;
+                        }
+                    } else if let Some(target) = plot.selected.as_ref().map(|target| target.id) {
+                        let before = self.layout.clone();
+                        self.move_target(target, pointer + self.grab_offset);
+
+                        if before != self.layout {
+                            self.record(state.current_step);
+                        }
+                    }
+                } else if plot.dragging_object {
+                    // 드래그가 끝났을 때
+                    plot.dragging_object = false;
+                }
+            }
+            CircuitOperation::AddWire | CircuitOperation::AddComponent => {
+                if response.dragged() {
+                    if self.drag_start.is_none() {
+                        self.drag_start = Some(snap(pointer));
+                        plot.selected = None;
+                        plot.dragging_object = true;
+                    }
+                } else if let Some(start) = self.drag_start.take() {
+                    plot.dragging_object = false;
+
+                    let end = snap(pointer);
+                    if start == end {
+                        return;
+                    }
+
+                    let kind = match self.operation {
+                        CircuitOperation::AddWire => ComponentKind::Wire,
+                        _ => self.brush.clone(),
+                    };
+                    let name = format!("{} {}", kind.get_name(), self.layout.components.len());
+
+                    self.layout.add(name, kind, start, end);
+                    self.record(state.current_step);
+                }
+            }
+            CircuitOperation::Remove => {
+                if response.clicked() {
+                    if let Some(target) = plot.pick(pointer_pos) {
+                        self.remove_target(target.id, state.current_step);
+
+                        plot.selected = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, state: &mut SimulationState) {
+        puffin::profile_scope!("CircuitSimulation::step");
+
+        self.settings = Self::settings(state);
+        self.advance();
+    }
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    // 둘러보기에서도 스위치를 누르면 열고 닫는다.
+    fn pointer_edits(&self) -> bool {
+        self.operation != CircuitOperation::Navigate
+            || self
+                .layout
+                .components
+                .iter()
+                .any(|component| matches!(component.kind, ComponentKind::Switch { .. }))
+    }
+
+    fn at_time_step(&mut self, step: usize) {
+        for _ in 0..self.timeline.rewind(step, &mut self.circuit) {
+            self.advance();
+        }
+
+        self.layout = self.timeline.layout_at(step).clone();
+    }
+
+    fn layout(&self, state: &SimulationState) -> Option<SessionLayout> {
+        Some(SessionLayout::Circuit(CircuitPreset {
+            layout: self.layout.clone(),
+            settings: Self::settings(state),
+        }))
+    }
+
+    fn plot_items(&mut self, _ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        puffin::profile_scope!("CircuitSimulation::plot_items");
+
+        self.settings = Self::settings(state);
+
+        let mut items: Vec<PlotItem> = vec![];
+        let mut dots = vec![];
+
+        for component in &self.layout.components {
+            items.extend(component.draw(self.brightness(component)));
+            dots.extend(self.current_dots(component));
+        }
+
+        // 셋 이상 만나는 곳과 끊긴 끝
+        let terminals = self
+            .layout
+            .degrees()
+            .into_iter()
+            .filter(|(_, degree)| *degree != 2)
+            .map(|(point, _)| {
+                let p = grid_position(point);
+                [p.x, p.y]
+            })
+            .collect::<Vec<_>>();
+        items.push(
+            Points::new(terminals)
+                .color(PlotColor::Wire.get_color())
+                .radius(3.0)
+                .filled(true)
+                .into(),
+        );
+
+        items.extend(self.ground_symbol());
+
+        let radius = (0.8 * state.pixels_per_unit).max(1.5) as f32;
+        items.push(
+            Points::new(dots)
+                .color(PlotColor::Current.get_color())
+                .radius(radius)
+                .filled(true)
+                .into(),
+        );
+
+        if self.settings.show_potentials {
+            for (point, potential) in &self.circuit.potentials {
+                let p = grid_position(*point);
+                let position = PlotPoint::new(p.x + 1.5, p.y + 2.5);
+
+                items.push(
+                    Text::new(position, format!("{:.2} V", potential))
+                        .color(PlotColor::Wire.get_color())
+                        .into(),
+                );
+            }
+        }
+
+        // 드래그로 놓고 있는 부품
+        if let (Some(start), Some(pointer)) = (self.drag_start, state.pointer) {
+            let end = snap(NVec2::new(pointer.x, pointer.y));
+
+            items.push(
+                Line::new(vec![[start.x, start.y], [end.x, end.y]])
+                    .color(PlotColor::Wire.get_color())
+                    .style(egui::plot::LineStyle::dashed_loose())
+                    .into(),
+            );
+        }
+
+        items
+    }
+
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        self.layout
+            .components
+            .iter()
+            .enumerate()
+            .map(|(index, component)| PickTarget {
+                id: TargetId::new(COMPONENT_TARGET, index),
+                name: component.name.clone(),
+                anchor: component.center(),
+                outline: component.outline(),
+            })
+            .collect()
+    }
+
+    fn inspect(&self, target: TargetId, _state: &SimulationState) -> Vec<(String, String)> {
+        let Some(component) = self.layout.components.get(target.index) else {
+            return vec![];
+        };
+        let state = self.circuit.get(component);
+        let potential = |p: NVec2| {
+            self.circuit
+                .potentials
+                .get(&grid_point(p))
+                .map_or("-".to_string(), |v| si(*v, "V"))
+        };
+
+        let mut rows = vec![
+            ("Type".to_string(), component.kind.get_name().to_string()),
+            ("Start Potential".into(), potential(component.start)),
+            ("End Potential".into(), potential(component.end)),
+            ("Voltage".into(), si(state.voltage, "V")),
+            ("Current".into(), si(state.current, "A")),
+        ];
+
+        match component.kind {
+            ComponentKind::Battery { voltage, .. } => {
+                rows.push(("EMF".into(), si(voltage, "V")));
+                rows.push(("Terminal Voltage".into(), si(-state.voltage, "V")));
+                rows.push((
+                    "Supplied Power".into(),
+                    si(-state.voltage * state.current, "W"),
+                ));
+            }
+            ComponentKind::Capacitor { capacitance, .. } => {
+                rows.push(("Stored Charge".into(), si(capacitance * state.voltage, "C")));
+                rows.push((
+                    "Stored Energy".into(),
+                    si(0.5 * capacitance * state.voltage.powi(2), "J"),
+                ));
+            }
+            ComponentKind::Switch { closed } => {
+                rows.push((
+                    "State".into(),
+                    if closed { "Closed" } else { "Open" }.to_string(),
+                ));
+            }
+            ComponentKind::Bulb { .. } => {
+                rows.push(("Power".into(), si(state.voltage * state.current, "W")));
+                rows.push((
+                    "Brightness".into(),
+                    format!("{:.1}%", self.brightness(component) * 100.0),
+                ));
+            }
+            ComponentKind::Resistor { .. } => {
+                rows.push(("Power".into(), si(state.voltage * state.current, "W")));
+            }
+            ComponentKind::Wire => {}
+        }
+
+        rows.push(("Charge Passed".into(), si(state.charge, "C")));
+        rows
+    }
+}
@@ -0,0 +1,217 @@
+use crate::app::graphics::define::items::{Line, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::circuit::component::{Component, ComponentKind};
+use crate::engine::NVec2;
+use egui::{Checkbox, DragValue, Ui};
+use std::f64::consts::TAU;
+
+/// 부품 기호가 차지하는 길이의 절반
+const SYMBOL_HALF: f64 = 4.0;
+
+/// `0.0012, "A"` -> `1.200 mA`
+pub fn si(value: f64, unit: &str) -> String {
+    const PREFIXES: [(f64, &str); 7] = [
+        (1e9, "G"),
+        (1e6, "M"),
+        (1e3, "k"),
+        (1.0, ""),
+        (1e-3, "m"),
+        (1e-6, "µ"),
+        (1e-9, "n"),
+    ];
+
+    let (scale, prefix) = PREFIXES
+        .iter()
+        .find(|(scale, _)| value.abs() >= *scale * 0.9995)
+        .unwrap_or(&PREFIXES[PREFIXES.len() - 1]);
+
+    if value == 0.0 {
+        format!("0 {}", unit)
+    } else {
+        format!("{:.3} {}{}", value / scale, prefix, unit)
+    }
+}
+
+impl ComponentKind {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        match self {
+            ComponentKind::Wire => {}
+            ComponentKind::Battery {
+                voltage,
+                internal_resistance,
+            } => {
+                ui.add(
+                    DragValue::new(voltage)
+                        .speed(0.1)
+                        .clamp_range(-1000.0..=1000.0)
+                        .prefix("ε: ")
+                        .suffix(" V"),
+                );
+                ui.add(
+                    DragValue::new(internal_resistance)
+                        .speed(0.01)
+                        .clamp_range(0.0..=1000.0)
+                        .prefix("r: ")
+                        .suffix(" Ω"),
+                );
+            }
+            ComponentKind::Resistor { resistance } => {
+                ui.add(
+                    DragValue::new(resistance)
+                        .speed(1.0)
+                        .clamp_range(0.01..=1e7)
+                        .prefix("R: ")
+                        .suffix(" Ω"),
+                );
+            }
+            ComponentKind::Capacitor {
+                capacitance,
+                initial_voltage,
+            } => {
+                // mF 단위로 고친다.
+                let mut millifarad = *capacitance * 1e3;
+                if ui
+                    .add(
+                        DragValue::new(&mut millifarad)
+                            .speed(0.01)
+                            .clamp_range(1e-3..=1e4)
+                            .prefix("C: ")
+                            .suffix(" mF"),
+                    )
+                    .changed()
+                {
+                    *capacitance = millifarad * 1e-3;
+                }
+                ui.add(
+                    DragValue::new(initial_voltage)
+                        .speed(0.1)
+                        .clamp_range(-1000.0..=1000.0)
+                        .prefix("V₀: ")
+                        .suffix(" V"),
+                );
+            }
+            ComponentKind::Switch { closed } => {
+                ui.add(Checkbox::new(closed, "Closed"));
+            }
+            ComponentKind::Bulb {
+                resistance,
+                rated_power,
+            } => {
+                ui.add(
+                    DragValue::new(resistance)
+                        .speed(0.1)
+                        .clamp_range(0.01..=1e6)
+                        .prefix("R: ")
+                        .suffix(" Ω"),
+                );
+                ui.add(
+                    DragValue::new(rated_power)
+                        .speed(0.1)
+                        .clamp_range(0.01..=1e4)
+                        .prefix("P: ")
+                        .suffix(" W"),
+                );
+            }
+        }
+    }
+}
+
+impl Component {
+    /// 회로 기호. `brightness`는 전구의 밝기 (0~1)이다.
+    pub fn draw(&self, brightness: f64) -> Vec<PlotItem> {
+        let (a, b) = (self.start, self.end);
+        let length = self.length();
+        if length == 0.0 {
+            return vec![];
+        }
+
+        let u = (b - a) / length;
+        let n = NVec2::new(-u.y, u.x);
+        let mid = self.center();
+        let half = SYMBOL_HALF.min(length / 2.0);
+
+        let colour = PlotColor::Wire.get_color();
+        let line = |points: Vec<NVec2>| -> PlotItem {
+            Line::new(points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                .color(colour)
+                .width(2.0)
+                .name(&self.name)
+                .into()
+        };
+        // 기호 양쪽의 도선
+        let leads = |gap: f64| vec![line(vec![a, mid - u * gap]), line(vec![mid + u * gap, b])];
+
+        match &self.kind {
+            ComponentKind::Wire => vec![line(vec![a, b])],
+            ComponentKind::Battery { .. } => {
+                let gap = half * 0.2;
+                let mut items = leads(gap);
+                items.push(line(vec![mid - u * gap - n * 1.5, mid - u * gap + n * 1.5]));
+                items.push(line(vec![mid + u * gap - n * 3.0, mid + u * gap + n * 3.0]));
+                items
+            }
+            ComponentKind::Resistor { .. } => {
+                let mut zigzag = vec![mid - u * half];
+                zigzag.extend((1..6).map(|k| {
+                    let side = if k % 2 == 0 { -1.5 } else { 1.5 };
+                    mid - u * half + u * (half * 2.0 * k as f64 / 6.0) + n * side
+                }));
+                zigzag.push(mid + u * half);
+
+                let mut items = leads(half);
+                items.push(line(zigzag));
+                items
+            }
+            ComponentKind::Capacitor { .. } => {
+                let gap = half * 0.2;
+                let mut items = leads(gap);
+                items.push(line(vec![mid - u * gap - n * 3.0, mid - u * gap + n * 3.0]));
+                items.push(line(vec![mid + u * gap - n * 3.0, mid + u * gap + n * 3.0]));
+                items
+            }
+            ComponentKind::Switch { closed } => {
+                let pivot = mid - u * half;
+                let lever = if *closed {
+                    mid + u * half
+                } else {
+                    let angle = 30f64.to_radians();
+                    pivot + (u * angle.cos() + n * angle.sin()) * half * 2.0
+                };
+
+                let mut items = leads(half);
+                items.push(line(vec![pivot, lever]));
+                items
+            }
+            ComponentKind::Bulb { .. } => {
+                let circle = (0..24)
+                    .map(|k| {
+                        let angle = TAU * k as f64 / 24.0;
+                        mid + NVec2::new(angle.cos(), angle.sin()) * half
+                    })
+                    .collect::<Vec<_>>();
+                let diagonal = (u + n) * half * std::f64::consts::FRAC_1_SQRT_2;
+                let cross = (u - n) * half * std::f64::consts::FRAC_1_SQRT_2;
+
+                let mut items = leads(half);
+                items.push(
+                    Polygon::new(circle.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                        .color(colour)
+                        .fill_alpha(0.0)
+                        .name(&self.name)
+                        .into(),
+                );
+                if brightness > 0.0 {
+                    items.push(
+                        Polygon::new(circle.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                            .color(PlotColor::BulbGlow.get_color())
+                            .fill_alpha(brightness.min(1.0) as f32 * 0.9)
+                            .into(),
+                    );
+                }
+                items.push(line(vec![mid - diagonal, mid + diagonal]));
+                items.push(line(vec![mid - cross, mid + cross]));
+                items
+            }
+        }
+    }
+}
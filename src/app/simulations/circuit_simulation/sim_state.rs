@@ -0,0 +1,20 @@
+use crate::engine::circuit::sim_state::CircuitSettings;
+use egui::{Slider, Widget};
+
+impl CircuitSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Circuit Settings", |ui| {
+            Slider::new(&mut self.time_scale, 0.01..=10.0)
+                .logarithmic(true)
+                .text("Time Scale")
+                .ui(ui);
+
+            Slider::new(&mut self.current_scale, 1.0..=1e5)
+                .logarithmic(true)
+                .text("Current Animation")
+                .ui(ui);
+
+            ui.checkbox(&mut self.show_potentials, "Show Potentials");
+        });
+    }
+}
@@ -1,62 +1,87 @@
+pub mod boundary;
+pub mod chain;
 pub mod event;
 pub mod object;
+pub mod probe;
 pub mod sim_state;
+pub mod soft_body;
 pub mod template;
+pub mod zone;
 
+use crate::app::graphics::define::items::{Arrows, Line, Points, Polygon};
 use crate::app::NVec2;
 
-use egui::plot::PlotPoint;
-use egui::{CollapsingHeader, Response, Ui};
-use nalgebra::{vector, SMatrix};
+use egui::plot::LineStyle;
+use egui::{CollapsingHeader, DragValue, Response, Ui};
+use nalgebra::vector;
+use std::borrow::Cow;
 
+use crate::app::graphics::define::{PlotColor, PlotItem};
 use crate::app::graphics::plot::{InputMessage, PlotData};
-use crate::app::manager::SIMULATION_TICK;
-use crate::app::simulations::polygon::is_inside;
+use crate::app::graphics::CSPlotObjects;
+use crate::app::session::SessionSimulation;
 use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{
+    BranchAction, MemoryUsage, PickTarget, Simulation, TargetId, TimelineFile,
+};
+use crate::engine::classic::branch::Branches;
+use crate::engine::classic::edit::{ObjectValues, SceneEdit};
+use crate::engine::classic::export::ClassicExport;
+use crate::engine::classic::object::builder::CSimObjectBuilder;
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::probe::GasProbe;
+use crate::engine::classic::scenario::Scenario;
+use crate::engine::classic::sim_state::CSimSettings;
+use crate::engine::classic::soft_body::{SoftBody, SoftBodyParams};
+use crate::engine::classic::template::CSPreset;
+use crate::engine::classic::timeline::MEGABYTE;
+use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+use crate::engine::classic::{ClassicWorld, ZERO_FORCE};
+use crate::engine::polygon::is_inside;
+use crate::engine::SIMULATION_TICK;
 
-use self::object::builder::CSimObjectBuilder;
-use self::object::state::{CSObjectState, ForceIndex};
-use crate::app::simulations::classic_simulation::object::state::Collision;
-pub use object::CSimObject;
+/// `TargetId::group` 값
+const OBJECT_TARGET: usize = 0;
+const SOFT_BODY_TARGET: usize = 1;
 
-use crate::app::simulations::classic_simulation::event::{
-    CollisionEvent, SimulationEvents,
-};
+/// 편집 손잡이의 화면 점 단위 반지름
+const HANDLE_RADIUS: f64 = 6.0;
 
-pub const GRAVITY: SMatrix<f64, 2, 1> = vector![0.0, -9.8];
-pub const ZERO_FORCE: SMatrix<f64, 2, 1> = vector![0.0, 0.0];
+/// EditObject에서 잡은 손잡이
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EditHandle {
+    Position,
+    /// 속도 화살표의 끝
+    Velocity,
+}
 
-#[repr(usize)]
-pub enum GlobalForceSlot {
-    Gravity = 0,
-    MAX = 1,
+// 잡고 있는 손잡이와 잡은 곳에서 손잡이까지의 거리, 잡기 전의 값
+#[derive(Clone)]
+struct HandleDrag {
+    handle: EditHandle,
+    grab: NVec2,
+    before: ObjectValues,
 }
 
-pub trait Simulation: Send + Sync {
-    fn inspection_ui(&mut self, ui: &mut Ui, _timestep: usize) {
-        ui.label("No inspection UI");
-    }
+// 고친 값이 기록된 타임라인에 어떻게 남는지 알려준다.
+fn edit_note_ui(ui: &mut Ui, state: &SimulationState) {
+    let later = state.max_step.saturating_sub(state.current_step);
 
-    fn operation_ui(&mut self, ui: &mut Ui) {
-        ui.label("No operations UI");
+    if later == 0 {
+        ui.weak(format!(
+            "Edits change step {} and the simulation continues from them.",
+            state.current_step
+        ));
+    } else {
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!(
+                "Editing step {} cuts off the {} steps recorded after it. \
+                 They are kept as a branch to compare with or switch back to.",
+                state.current_step, later
+            ),
+        );
     }
-
-    fn input(
-        &mut self,
-        plot: &mut PlotData,
-        input_msg: InputMessage,
-        response: Response,
-        ctx: &egui::Context,
-        state: &mut SimulationState,
-    );
-
-    fn step(&mut self, state: &mut SimulationState);
-
-    fn at_time_step(&mut self, step: usize);
-
-    fn get_children(&self) -> &Vec<CSimObject>;
-
-    fn get_events(&self, idx: usize) -> Option<&SimulationEvents>;
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -67,51 +92,299 @@ pub enum Operation {
     AddObject,
     RemoveObject,
     EditObject,
+    AddZone,
+    AddSoftBody,
 }
 
-const OPERATION_ITER: [Operation; 5] = [
+const OPERATION_ITER: [Operation; 7] = [
     Operation::Navigate,
     Operation::ForceDrag,
     Operation::AddObject,
     Operation::RemoveObject,
     Operation::EditObject,
+    Operation::AddZone,
+    Operation::AddSoftBody,
 ];
 
-#[derive()]
+#[derive(Clone)]
 pub struct ClassicSimulation {
-    pub objects: Vec<CSimObject>,
-    pub global_acc_list: Vec<NVec2>,
-    pub events: Vec<SimulationEvents>,
+    pub world: ClassicWorld,
+    pub plot_objects: CSPlotObjects,
 
     pub operation: Operation,
+    pub zone_brush: ZoneEffect,
+    pub soft_body_brush: SoftBodyParams,
+    /// 그리고 있는 영역의 시작점
+    zone_start: Option<NVec2>,
+    /// 힘을 주기 시작한 스텝
+    drag_start: Option<usize>,
+    /// 관리자가 아직 가져가지 않은 편집
+    edits: Vec<SceneEdit>,
+    /// EditObject로 고치고 있는 오브젝트
+    edit_target: Option<usize>,
+    handle_drag: Option<HandleDrag>,
+    /// 지난 스텝을 고쳐서 잘라 낸 타임라인
+    branches: Branches,
 }
 
-impl From<Vec<CSimObject>> for ClassicSimulation {
-    fn from(object: Vec<CSimObject>) -> Self {
+impl From<CSPreset> for ClassicSimulation {
+    fn from(preset: CSPreset) -> Self {
+        ClassicSimulation::from(ClassicWorld::from(preset))
+    }
+}
+
+impl From<ClassicWorld> for ClassicSimulation {
+    fn from(world: ClassicWorld) -> Self {
         ClassicSimulation {
-            objects: object,
-            global_acc_list: vec![GRAVITY],
-            events: vec![],
+            world,
+            plot_objects: CSPlotObjects::default(),
             operation: Operation::default(),
+            zone_brush: ZoneEffect::default(),
+            soft_body_brush: SoftBodyParams::default(),
+            zone_start: None,
+            drag_start: None,
+            edits: vec![],
+            edit_target: None,
+            handle_drag: None,
+            branches: Branches::default(),
+        }
+    }
+}
+
+impl ClassicSimulation {
+    // 대상에 속한 오브젝트의 인덱스
+    fn target_objects(&self, target: TargetId) -> Vec<usize> {
+        match target.group {
+            OBJECT_TARGET => vec![target.index],
+            SOFT_BODY_TARGET => self
+                .world
+                .soft_bodies
+                .get(target.index)
+                .map(|body| body.nodes.clone())
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    // 포인터 아래에 있는 편집 손잡이. 속도 화살표의 끝이 먼저 잡힌다.
+    fn handle_at(
+        &self,
+        index: usize,
+        pointer: NVec2,
+        state: &SimulationState,
+    ) -> Option<(EditHandle, NVec2)> {
+        let obj = self.world.objects.get(index).filter(|obj| !*obj.hide())?;
+        let obj_state = obj.current_state();
+        let radius = HANDLE_RADIUS / state.pixels_per_unit.max(f64::EPSILON);
+
+        let tip = obj_state.position + obj_state.velocity;
+        if (pointer - tip).norm() <= radius {
+            return Some((EditHandle::Velocity, pointer - tip));
+        }
+
+        let outline = obj_state
+            .shape
+            .get_points()
+            .into_iter()
+            .map(|[x, y]| obj_state.position + NVec2::new(x, y))
+            .collect::<Vec<_>>();
+
+        if (pointer - obj_state.position).norm() <= radius || is_inside(pointer, &outline) {
+            return Some((EditHandle::Position, pointer - obj_state.position));
+        }
+
+        None
+    }
+
+    // 고치는 오브젝트 옆에 질량과 모양을 고치는 창을 띄운다.
+    fn object_editor(&mut self, ctx: &egui::Context, rect: egui::Rect, state: &SimulationState) {
+        let Some(index) = self.edit_target else {
+            return;
+        };
+        let Some(obj) = self.world.objects.get_mut(index).filter(|obj| !*obj.hide()) else {
+            return;
+        };
+
+        // 모양의 오른쪽 위 모서리 옆에 놓는다.
+        let obj_state = obj.current_state();
+        let corner = obj_state.position + obj_state.shape.half_extent();
+        let [min, _] = state.view;
+        let pos = egui::pos2(
+            rect.left() + ((corner.x - min.x) * state.pixels_per_unit) as f32 + 8.0,
+            rect.bottom() - ((corner.y - min.y) * state.pixels_per_unit) as f32,
+        );
+
+        if !rect.contains(pos) {
+            return;
         }
+
+        egui::Area::new("object_editor")
+            .fixed_pos(pos)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(240.0);
+                    ui.label(format!("Object {}", index));
+
+                    let before = ObjectValues::of(obj);
+                    let obj_state = obj.current_state_mut();
+
+                    let mut changed = false;
+                    ui.horizontal(|ui| {
+                        ui.label("Mass");
+                        changed |= ui
+                            .add(
+                                DragValue::new(&mut obj_state.mass)
+                                    .speed(0.1)
+                                    .clamp_range(0.001..=f64::MAX),
+                            )
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Shape");
+                        changed |= obj_state.shape.ui(ui, index);
+                    });
+
+                    if changed {
+                        self.edits.push(SceneEdit::EditObject {
+                            index,
+                            step: state.current_step,
+                            before,
+                            after: ObjectValues::of(obj),
+                        });
+                    }
+
+                    edit_note_ui(ui, state);
+                });
+            });
     }
 }
 
 impl Simulation for ClassicSimulation {
-    fn inspection_ui(&mut self, ui: &mut Ui, timestep: usize) {
-        for (i, child) in self.objects.iter_mut().enumerate() {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let timestep = state.current_step;
+        let boundary = state
+            .settings
+            .specific
+            .as_c_sim_settings()
+            .map(|settings| settings.boundary)
+            .unwrap_or_default();
+
+        if boundary.has_walls() {
+            CollapsingHeader::new("Gas Probe")
+                .default_open(true)
+                .show(ui, |ui| {
+                    let states = self
+                        .world
+                        .objects
+                        .iter()
+                        .filter_map(|obj| obj.visible_state())
+                        .collect::<Vec<_>>();
+
+                    GasProbe::measure(&states, &self.world.wall_impulse, &boundary, timestep)
+                        .inspection_ui(ui);
+                });
+        }
+        for (i, chain) in self.world.chains.iter_mut().enumerate() {
+            CollapsingHeader::new(format!("Oscillator Chain {}", i))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let states = chain
+                        .nodes
+                        .iter()
+                        .filter_map(|node| self.world.objects[*node].visible_state())
+                        .collect::<Vec<_>>();
+
+                    chain.inspection_ui(ui, &states);
+                });
+        }
+
+        if !self.world.objects.is_empty() {
+            edit_note_ui(ui, state);
+        }
+
+        for (i, child) in self.world.objects.iter_mut().enumerate() {
             ui.push_id(i, |ui| {
                 ui.collapsing(format!("Object {}", i), |ui| {
-                    child.inspection_ui(ui);
+                    if *child.hide() {
+                        match child.end_timestep() {
+                            Some(end) if timestep >= *end => {
+                                ui.label(format!("Removed at step {}", end))
+                            }
+                            _ => ui.label(format!("Created at step {}", child.init_timestep())),
+                        };
+                        return;
+                    }
+
+                    let before = ObjectValues::of(child);
+                    if child.inspection_ui(ui, i) {
+                        self.edits.push(SceneEdit::EditObject {
+                            index: i,
+                            step: timestep,
+                            before,
+                            after: ObjectValues::of(child),
+                        });
+                    }
+
+                    let position = child.current_state().position;
+                    let zones = self
+                        .world
+                        .zones
+                        .iter()
+                        .filter(|zone| zone.contains(position))
+                        .map(|zone| zone.name.as_str())
+                        .collect::<Vec<_>>();
+
+                    if zones.is_empty() {
+                        ui.label("Inside Zones : None");
+                    } else {
+                        ui.label(format!("Inside Zones : {}", zones.join(", ")));
+                    }
                 });
             });
         }
 
+        if !self.world.zones.is_empty() {
+            ui.collapsing("Zones", |ui| {
+                let mut remove = None;
+
+                for (i, zone) in self.world.zones.iter_mut().enumerate() {
+                    ui.push_id(("zone", i), |ui| {
+                        ui.label(&zone.name);
+
+                        let before = zone.effect;
+                        zone.effect.ui(ui);
+
+                        if zone.effect != before {
+                            self.edits.push(SceneEdit::EditZone {
+                                index: i,
+                                name: zone.name.clone(),
+                                before,
+                                after: zone.effect,
+                            });
+                        }
+
+                        if ui.button("Remove this Zone").clicked() {
+                            remove.replace(i);
+                        }
+                        ui.separator();
+                    });
+                }
+
+                if let Some(index) = remove {
+                    let zone = self.world.zones.remove(index);
+                    self.edits.push(SceneEdit::RemoveZone { index, zone });
+                }
+            });
+        }
+
         if let Some(x) = timestep.checked_sub(1) {
             CollapsingHeader::new(format!("Event {:?}", x))
                 .default_open(true)
-                .show(ui, |ui| {
-                    self.events[x].inspection_ui(ui);
+                .show(ui, |ui| match self.world.events_at(x) {
+                    Some(events) => events.inspection_ui(ui),
+                    None => {
+                        ui.weak("Not recorded");
+                    }
                 });
         }
     }
@@ -129,6 +402,16 @@ impl Simulation for ClassicSimulation {
                     );
                 });
             });
+
+            if self.operation == Operation::AddZone {
+                ui.separator();
+                self.zone_brush.ui(ui);
+            }
+
+            if self.operation == Operation::AddSoftBody {
+                ui.separator();
+                self.soft_body_brush.ui(ui);
+            }
         });
     }
 
@@ -137,23 +420,15 @@ impl Simulation for ClassicSimulation {
         plot: &mut PlotData,
         msg: InputMessage,
         response: Response,
-        _ctx: &egui::Context,
+        ctx: &egui::Context,
         state: &mut SimulationState,
     ) {
         //TODO: 모바일 환경에서의 터치도 감지하기.
-        let simulation_objects = &mut self.objects;
         match self.operation {
             Operation::Navigate => {
                 if let Some(pointer_pos) = msg.pointer_pos {
                     if response.clicked() {
-                        for (index, obj) in simulation_objects.iter().enumerate() {
-                            if let Some(obj_state) = obj.state_at_timestep(state.current_step) {
-                                if is_inside(pointer_pos, obj_state.shape.get_points()) {
-                                    plot.selected_index = index;
-                                    break;
-                                }
-                            }
-                        }
+                        plot.selected = plot.pick(pointer_pos);
                     }
                 }
             }
@@ -162,60 +437,62 @@ impl Simulation for ClassicSimulation {
                     if response.dragged() {
                         if plot.dragging_object {
                             // 드래그 중일 때
-                            let pos = simulation_objects[plot.selected_index]
-                                .current_state()
-                                .position;
-
-                            let selected = &mut simulation_objects[plot.selected_index];
-
-                            let user_vec = vector![pointer_pos.x - pos.x, pointer_pos.y - pos.y];
-                            selected.current_state_mut().acc_list
-                                [ForceIndex::UserInteraction as usize] = user_vec;
-                        } else {
-                            // 드래그 시작할 때
-                            for (index, obj) in simulation_objects.iter().enumerate() {
-                                let obj_state = obj.current_state();
+                            if let Some(target) = &plot.selected {
+                                // 소프트 바디는 모든 질점에 같은 가속도를 준다.
+                                let user_vec =
+                                    vector![pointer_pos.x, pointer_pos.y] - target.anchor;
 
-                                {
-                                    if is_inside(
-                                        pointer_pos,
-                                        obj_state
-                                            .shape
-                                            .get_points()
-                                            .into_iter()
-                                            .map(|a| {
-                                                let b = obj_state.position;
-                                                PlotPoint::new(a[0] + b.x, a[1] + b.y)
-                                            })
-                                            .collect::<Vec<_>>(),
-                                    ) {
-                                        plot.selected_index = index;
-                                        plot.dragging_object = true;
-                                        break;
-                                    }
+                                for index in self.target_objects(target.id) {
+                                    self.world.objects[index].set_user_force(user_vec);
                                 }
                             }
+                        } else {
+                            // 드래그 시작할 때
+                            plot.selected = plot.pick(pointer_pos);
+                            plot.dragging_object = plot.selected.is_some();
+                            self.drag_start = Some(state.current_step);
                         }
-                    } else {
-                        // 드래그 중이 아닐 때
                     }
                 }
 
                 if !response.dragged() && plot.dragging_object {
                     // 드래그가 끝났을 때
-                    let selected = &mut simulation_objects[plot.selected_index];
+                    if let Some(target) = &plot.selected {
+                        let targets = self.target_objects(target.id);
+
+                        for index in targets.iter() {
+                            self.world.objects[*index].set_user_force(ZERO_FORCE);
+                        }
+
+                        // 드래그하는 동안 스텝이 지났을 때만 타임라인에 힘이 남는다.
+                        if let (Some(from), Some(first)) = (self.drag_start, targets.first()) {
+                            let forces = (from..state.current_step)
+                                .map(|step| self.world.user_force(step, *first))
+                                .collect::<Vec<_>>();
 
-                    selected.current_state_mut().acc_list[ForceIndex::UserInteraction as usize] =
-                        ZERO_FORCE;
+                            if !forces.is_empty() {
+                                self.edits.push(SceneEdit::Force {
+                                    name: target.name.clone(),
+                                    targets,
+                                    from,
+                                    forces,
+                                });
+                            }
+                        }
+                    }
 
                     plot.dragging_object = false;
+                    self.drag_start = None;
                 }
             }
             Operation::AddObject => {
                 if let Some(pointer_pos) = msg.pointer_pos {
-
                     if response.drag_released() {
-                        simulation_objects.push(
+                        self.edits.push(SceneEdit::AddObject {
+                            index: self.world.objects.len(),
+                            removed: Default::default(),
+                        });
+                        self.world.objects.push(
                             CSimObjectBuilder::new(CSObjectState {
                                 position: vector![pointer_pos.x, pointer_pos.y],
                                 ..CSObjectState::default()
@@ -226,128 +503,632 @@ impl Simulation for ClassicSimulation {
                     }
                 }
             }
-            Operation::RemoveObject => {}
-            Operation::EditObject => {}
+            Operation::AddSoftBody => {
+                if let Some(pointer_pos) = msg.pointer_pos {
+                    if response.clicked() {
+                        let (body, nodes) = SoftBody::build(
+                            format!(
+                                "{} {}",
+                                self.soft_body_brush.kind.get_name(),
+                                self.world.soft_bodies.len()
+                            ),
+                            self.soft_body_brush,
+                            vector![pointer_pos.x, pointer_pos.y],
+                            self.world.objects.len(),
+                            state.current_step,
+                        );
+
+                        self.edits.push(SceneEdit::AddSoftBody {
+                            name: body.name.clone(),
+                            nodes: body.nodes.clone(),
+                            removed: Default::default(),
+                        });
+                        self.world.objects.extend(nodes);
+                        self.world.soft_bodies.push(body);
+                    }
+                }
+            }
+            Operation::RemoveObject => {
+                if let Some(pointer_pos) = msg.pointer_pos {
+                    if response.clicked() {
+                        // 소프트 바디를 누르면 몸체와 질점을 모두 뺀다.
+                        if let Some(target) = plot.pick(pointer_pos) {
+                            // 지난 스텝에는 남도록 보고 있는 스텝에서 뺀다.
+                            let indices = self.target_objects(target.id);
+                            let step = state.current_step;
+                            let ended = self.world.end_objects(indices.iter().cloned(), step);
+
+                            self.edits.push(SceneEdit::RemoveObjects {
+                                name: target.name,
+                                indices,
+                                step,
+                                ended,
+                            });
+                            plot.selected = None;
+                        }
+                    }
+                }
+            }
+            Operation::EditObject => {
+                // 소프트 바디의 질점은 몸체로만 고를 수 있으므로 오브젝트만 고친다.
+                self.edit_target = plot
+                    .selected
+                    .as_ref()
+                    .filter(|target| target.id.group == OBJECT_TARGET)
+                    .map(|target| target.id.index);
+
+                if let Some(pointer_pos) = msg.pointer_pos {
+                    let pointer = vector![pointer_pos.x, pointer_pos.y];
+
+                    if response.dragged() {
+                        if let (Some(drag), Some(obj)) = (
+                            &self.handle_drag,
+                            self.edit_target
+                                .and_then(|index| self.world.objects.get_mut(index)),
+                        ) {
+                            // 드래그 중일 때
+                            let obj_state = obj.current_state_mut();
+                            let target = pointer - drag.grab;
+
+                            match drag.handle {
+                                EditHandle::Position => obj_state.position = target,
+                                EditHandle::Velocity => {
+                                    obj_state.velocity = target - obj_state.position
+                                }
+                            }
+                        } else if !plot.dragging_object {
+                            // 드래그 시작할 때. 고르지 않은 오브젝트를 잡으면 그 오브젝트를 고른다.
+                            let mut handle = self.edit_target.and_then(|index| {
+                                Some((index, self.handle_at(index, pointer, state)?))
+                            });
+
+                            if handle.is_none() {
+                                if let Some(target) = plot
+                                    .pick(pointer_pos)
+                                    .filter(|target| target.id.group == OBJECT_TARGET)
+                                {
+                                    self.edit_target = Some(target.id.index);
+                                    handle = self
+                                        .handle_at(target.id.index, pointer, state)
+                                        .map(|handle| (target.id.index, handle));
+                                    plot.selected = Some(target);
+                                }
+                            }
+
+                            if let Some((index, (handle, grab))) = handle {
+                                self.handle_drag = Some(HandleDrag {
+                                    handle,
+                                    grab,
+                                    before: ObjectValues::of(&self.world.objects[index]),
+                                });
+                                plot.dragging_object = true;
+                            }
+                        }
+                    } else if response.clicked() {
+                        plot.selected = plot.pick(pointer_pos);
+                    }
+                }
+
+                if !response.dragged() {
+                    // 드래그가 끝났을 때
+                    if let (Some(drag), Some(index)) = (self.handle_drag.take(), self.edit_target) {
+                        if let Some(obj) = self.world.objects.get(index) {
+                            self.edits.push(SceneEdit::EditObject {
+                                index,
+                                step: state.current_step,
+                                before: drag.before,
+                                after: ObjectValues::of(obj),
+                            });
+                        }
+                        plot.dragging_object = false;
+                    }
+                }
+
+                self.object_editor(ctx, response.rect, state);
+            }
+            Operation::AddZone => {
+                if response.dragged() {
+                    // 드래그 시작점을 영역의 한 꼭짓점으로 쓴다.
+                    if self.zone_start.is_none() {
+                        self.zone_start = msg
+                            .pointer_pos
+                            .map(|pointer_pos| vector![pointer_pos.x, pointer_pos.y]);
+                        plot.selected = None;
+                        plot.dragging_object = self.zone_start.is_some();
+                    }
+                } else if let Some(start) = self.zone_start.take() {
+                    plot.dragging_object = false;
+
+                    if let Some(end) = msg.pointer_pos {
+                        if start.x != end.x && start.y != end.y {
+                            let name = format!(
+                                "{} Zone {}",
+                                self.zone_brush.get_name(),
+                                self.world.zones.len()
+                            );
+
+                            let zone = FieldZone::rect(
+                                name,
+                                start,
+                                vector![end.x, end.y],
+                                self.zone_brush,
+                            );
+
+                            self.edits.push(SceneEdit::AddZone(zone.clone()));
+                            self.world.zones.push(zone);
+                        }
+                    }
+                }
+            }
         }
     }
 
     fn step(&mut self, state: &mut SimulationState) {
-        let mut event = SimulationEvents::default();
-        puffin::profile_scope!("ClassicSimulation::step");
-
-        //TODO: 이거 더 좋은 방법 없나?
-        if let Some(settings) = state.settings.specific.as_c_sim_settings_mut() {
-            if let Some(is_grav) = settings.gravity.get() {
-                if is_grav {
-                    self.global_acc_list[GlobalForceSlot::Gravity as usize] = GRAVITY;
-                } else {
-                    self.global_acc_list[GlobalForceSlot::Gravity as usize] = ZERO_FORCE;
+        let mut fallback = CSimSettings::default();
+        let settings = state
+            .settings
+            .specific
+            .as_c_sim_settings_mut()
+            .unwrap_or(&mut fallback);
+
+        // 갈래도 같은 예산을 쓰므로 세계가 맞출 예산에서 미리 뺀다.
+        let budget = settings.memory_budget.saturating_mul(MEGABYTE);
+        self.branches.share_budget(&mut self.world, budget);
+
+        self.world.step(settings, state.time);
+    }
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    fn pointer_edits(&self) -> bool {
+        self.operation != Operation::Navigate
+    }
+
+    fn has_edits(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    fn take_edits(&mut self) -> Vec<SceneEdit> {
+        std::mem::take(&mut self.edits)
+    }
+
+    fn undo_edit(&mut self, edit: &mut SceneEdit, state: &mut SimulationState) {
+        let mut fallback = CSimSettings::default();
+        let settings = state
+            .settings
+            .specific
+            .as_c_sim_settings_mut()
+            .unwrap_or(&mut fallback);
+
+        edit.undo(&mut self.world, settings);
+    }
+
+    fn redo_edit(&mut self, edit: &mut SceneEdit, state: &mut SimulationState) {
+        let mut fallback = CSimSettings::default();
+        let settings = state
+            .settings
+            .specific
+            .as_c_sim_settings_mut()
+            .unwrap_or(&mut fallback);
+
+        edit.redo(&mut self.world, settings);
+    }
+
+    fn fork(
+        &mut self,
+        step: usize,
+        edits: &[SceneEdit],
+        state: &mut SimulationState,
+    ) -> Option<usize> {
+        let mut fallback = CSimSettings::default();
+        let settings = state
+            .settings
+            .specific
+            .as_c_sim_settings_mut()
+            .unwrap_or(&mut fallback);
+
+        let id = self.branches.fork(&mut self.world, step, edits, settings);
+        state.max_step = step;
+
+        Some(id)
+    }
+
+    fn branch_ui(&mut self, ui: &mut Ui, state: &SimulationState) -> Option<BranchAction> {
+        ui.label("Editing a past step cuts off the steps recorded after it. They are kept here.");
+        ui.weak("Branches share the memory budget. Old branches are dropped past half of it.");
+
+        if self.branches.is_empty() {
+            ui.weak("No branches");
+            return None;
+        }
+
+        let rows = self
+            .branches
+            .iter()
+            .map(|branch| (branch.id, branch.step, branch.world.recorded_steps()))
+            .collect::<Vec<_>>();
+
+        let mut action = None;
+
+        for (id, from, steps) in rows {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Branch {} : from step {}, {} steps",
+                    id, from, steps
+                ));
+
+                let compared = self.branches.compared == Some(id);
+                if ui.selectable_label(compared, "Compare").clicked() {
+                    action = Some(BranchAction::Compare((!compared).then_some(id)));
+                }
+
+                if ui
+                    .button("Switch")
+                    .on_hover_text("Make this branch the current timeline")
+                    .clicked()
+                {
+                    action = Some(BranchAction::Switch(id));
+                }
+
+                if ui
+                    .button("Delete")
+                    .on_hover_text("Discard these steps. The edit history is cleared too.")
+                    .clicked()
+                {
+                    action = Some(BranchAction::Remove(id));
+                }
+            });
+        }
+
+        let step = state.current_step;
+        if let Some(from) = self.branches.compared().map(|branch| branch.step) {
+            match self.branches.gap(&self.world, step) {
+                Some((index, gap)) if step > from => {
+                    ui.label(format!("Largest gap : {:.3} (Object {})", gap, index));
+                }
+                _ => {
+                    ui.weak("Same as the current timeline at this step");
                 }
             }
         }
 
-        let length = self.objects.len();
+        action
+    }
+
+    fn memory_usage(&mut self) -> Option<MemoryUsage> {
+        let usage = self.world.usage();
+        let timeline = self.world.timeline();
 
-        //물리 처리 부분
-        for obj in self.objects.iter_mut() {
-            if let Some(attached_fn) = &obj.attached() {
-                attached_fn(obj.current_state_mut());
-            }
+        let parts = vec![
+            ("Positions".to_string(), usage.records),
+            (
+                format!("Keyframes ({})", timeline.keyframes().len()),
+                usage.keyframes,
+            ),
+            ("Events".to_string(), usage.events),
+            ("Recomputed".to_string(), usage.replays),
+            ("Branches".to_string(), self.branches.usage()),
+        ];
 
-            Self::physics(obj, &self.global_acc_list);
-            obj.save_state();
-        }
+        let note = (timeline.detail_from() > 0).then(|| {
+            format!(
+                "Positions and events before step {} were dropped to stay under the budget. \
+                 They are recomputed from keyframes when you view those steps.",
+                timeline.detail_from()
+            )
+        });
+
+        Some(MemoryUsage { parts, note })
+    }
+
+    /// 갈래와 지금 타임라인이 함께 `budget` 바이트 안에 들게 한다.
+    fn fit_budget(&mut self, budget: usize) {
+        self.branches.share_budget(&mut self.world, budget);
+        self.world
+            .fit_budget(budget.saturating_sub(self.branches.usage()));
+    }
 
-        //충돌 처리 부분
-        for i in 1..length + 1 {
-            let (_front, end) = self.objects.split_at_mut(i - 1);
+    fn can_export_timeline(&self) -> bool {
+        true
+    }
+
+    fn export_timeline(&self, file: TimelineFile, every: usize) -> Option<Result<String, String>> {
+        let export = ClassicExport::new(&self.world, every);
 
-            let Some((obj, rest)) = end.split_first_mut() else {panic!("Cannot Reach")};
+        Some(match file {
+            TimelineFile::Json => export.to_json().map_err(|error| error.to_string()),
+            TimelineFile::Objects => Ok(export.objects_csv()),
+            TimelineFile::Events => Ok(export.events_csv()),
+            TimelineFile::Stamps => Ok(export.stamps_csv()),
+        })
+    }
 
-            for obj2 in rest {
-                if let Some(x) = Self::collision(obj, obj2) {
-                    event.add_event(x);
+    fn change_branch(&mut self, action: BranchAction, state: &mut SimulationState) -> bool {
+        match action {
+            BranchAction::Compare(id) => self.branches.compared = id,
+            BranchAction::Remove(id) => self.branches.remove(id),
+            BranchAction::Switch(id) => {
+                if !self.branches.switch(id, &mut self.world) {
+                    return false;
                 }
+
+                state.max_step = self.world.recorded_steps();
+                state.current_step = state.current_step.min(state.max_step);
+                state.time = SIMULATION_TICK * state.current_step as f64;
             }
         }
 
-        self.events.push(event);
+        true
     }
 
     fn at_time_step(&mut self, step: usize) {
-        for obj in self.objects.iter_mut() {
-            obj.at_timestep(step);
-        }
+        self.world.at_time_step(step);
     }
 
-    fn get_children(&self) -> &Vec<CSimObject> {
-        &self.objects
+    fn scenario(&self, state: &SimulationState) -> Option<Scenario> {
+        let settings = state.settings.specific.as_c_sim_settings()?;
+
+        Some(Scenario::from_world("", &self.world, settings))
     }
 
-    fn get_events(&self, idx: usize) -> Option<&SimulationEvents> {
-        if idx == 0 {
-            None
-        } else {
-            Some(&self.events[idx.saturating_sub(1)])
+    fn session(
+        &self,
+        state: &SimulationState,
+        keep_timeline: bool,
+    ) -> Option<SessionSimulation<'_>> {
+        if keep_timeline {
+            return Some(SessionSimulation::Timeline {
+                world: Cow::Borrowed(&self.world),
+                current_step: state.current_step,
+                max_step: state.max_step,
+            });
         }
+
+        self.scenario(state)
+            .map(|scenario| SessionSimulation::Scenario(Box::new(scenario)))
     }
-}
 
-impl ClassicSimulation {
-    fn collision(obj: &mut CSimObject, obj2: &mut CSimObject) -> Option<CollisionEvent> {
-        let obj_state = obj.current_state_mut();
-        let obj2_state = obj2.current_state_mut();
-
-        if let Some(contact) = obj_state.contact(obj2_state) {
-            obj_state.velocity += contact.obj1_velocity;
-            obj2_state.velocity += contact.obj2_velocity;
-            // obj_state.position += contact.penetration * contact.contact_normal;
-            // obj2_state.position += contact.penetration * -contact.contact_normal;
-
-            Some(contact)
-        } else {
-            None
+    fn plot_items(&mut self, _ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        let mut items = vec![];
+
+        if let Some(events) = state
+            .current_step
+            .checked_sub(1)
+            .and_then(|x| self.world.events_at(x))
+        {
+            items.extend(events.get_shapes());
+        }
+
+        if let Some(settings) = state.settings.specific.as_c_sim_settings() {
+            items.extend(settings.boundary.draw());
+        }
+
+        // 영역은 오브젝트 아래에 깔리도록 먼저 그린다.
+        for zone in &self.world.zones {
+            items.extend(zone.draw());
+        }
+
+        for chain in &self.world.chains {
+            items.extend(chain.draw(&self.world.objects));
+        }
+
+        for body in &self.world.soft_bodies {
+            if body.is_visible(&self.world.objects) {
+                items.extend(body.draw(&self.world.objects));
+            }
+        }
+
+        // 그리고 있는 영역의 미리보기
+        if let (Some(start), Some(end)) = (self.zone_start, state.pointer) {
+            items.push(
+                Polygon::new(vec![
+                    [start.x, start.y],
+                    [end.x, start.y],
+                    [end.x, end.y],
+                    [start.x, end.y],
+                ])
+                .fill_alpha(0.1)
+                .into(),
+            );
         }
-    }
 
-    fn physics(obj: &mut CSimObject, global_acc_list: &[NVec2]) {
-        // Physics
-        let global_acc: NVec2 = global_acc_list.iter().sum();
-        let previous_state = obj.previous_state().unwrap_or(obj.current_state());
-        let state = obj.current_state_mut();
+        let soft_body_nodes = self.world.soft_body_nodes();
 
-        let dt = SIMULATION_TICK;
+        for (index, obj) in self.world.objects.iter().enumerate() {
+            if *obj.hide() {
+                continue;
+            }
+
+            let obj_state = obj.current_state();
+
+            items.push(
+                Polygon::new(obj_state.shape.get_plot_points(obj_state.position))
+                    .color(PlotColor::Object.get_color())
+                    .into(),
+            );
+
+            // 소프트 바디의 질점은 몸체 외곽선으로 표시하므로 자세한 정보는 그리지 않는다.
+            if soft_body_nodes.contains(&index) {
+                continue;
+            }
+
+            items.extend(obj.draw(state, index, &self.world.stamps));
+        }
+
+        items.extend(self.plot_objects.get_plot_items());
 
-        // ΣF
-        // ΣF = ma
-        // a = ΣF / m
-        // Δv = a * Δt
-        // Δp = ΣF * Δt
-        // Δs = v * Δt
+        // 나란히 보는 갈래는 갈라진 뒤부터 외곽선만 그리고 같은 오브젝트끼리 잇는다.
+        self.branches.at_time_step(state.current_step);
+        if let Some(branch) = self
+            .branches
+            .compared()
+            .filter(|branch| state.current_step > branch.step)
+        {
+            let color = PlotColor::Branch.get_color();
+
+            for (index, other) in branch.world.objects.iter().enumerate() {
+                let Some(other_state) = other.visible_state() else {
+                    continue;
+                };
+
+                items.push(
+                    Polygon::new(other_state.shape.get_plot_points(other_state.position))
+                        .color(color)
+                        .fill_alpha(0.0)
+                        .style(LineStyle::dashed_dense())
+                        .into(),
+                );
+
+                if let Some(obj) = self
+                    .world
+                    .objects
+                    .get(index)
+                    .filter(|obj| !*obj.hide() && !soft_body_nodes.contains(&index))
+                {
+                    let position = obj.current_state().position;
+                    items.push(
+                        Line::new(vec![
+                            [position.x, position.y],
+                            [other_state.position.x, other_state.position.y],
+                        ])
+                        .color(color)
+                        .into(),
+                    );
+                }
+            }
+        }
 
+        // 고치고 있는 오브젝트의 위치와 속도 손잡이
+        if let Some(obj) = self
+            .edit_target
+            .filter(|_| self.operation == Operation::EditObject)
+            .and_then(|index| self.world.objects.get(index))
+            .filter(|obj| !*obj.hide())
         {
-            let current_acc = state.acceleration();
+            let obj_state = obj.current_state();
+            let position = [obj_state.position.x, obj_state.position.y];
+            let tip = obj_state.position + obj_state.velocity;
+            let tip = [tip.x, tip.y];
 
-            let sum_acc = current_acc + global_acc; // Σa
+            items.push(
+                Arrows::new(position, tip)
+                    .color(PlotColor::Selected.get_color())
+                    .into(),
+            );
+            items.push(
+                Points::new(vec![position, tip])
+                    .radius(HANDLE_RADIUS as f32)
+                    .filled(false)
+                    .color(PlotColor::Selected.get_color())
+                    .into(),
+            );
+        }
+
+        items
+    }
+
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        let soft_body_nodes = self.world.soft_body_nodes();
+
+        let objects = self
+            .world
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(index, obj)| !*obj.hide() && !soft_body_nodes.contains(index))
+            .map(|(index, obj)| {
+                let obj_state = obj.current_state();
+
+                PickTarget {
+                    id: TargetId::new(OBJECT_TARGET, index),
+                    name: format!("Object {}", index),
+                    anchor: obj_state.position,
+                    outline: obj_state
+                        .shape
+                        .get_points()
+                        .into_iter()
+                        .map(|[x, y]| obj_state.position + NVec2::new(x, y))
+                        .collect(),
+                }
+            });
 
-            let delta_a = current_acc - previous_state.acceleration();
+        let soft_bodies = self
+            .world
+            .soft_bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, body)| body.is_visible(&self.world.objects))
+            .map(|(index, body)| PickTarget {
+                id: TargetId::new(SOFT_BODY_TARGET, index),
+                name: body.name.clone(),
+                anchor: body.center(&self.world.objects),
+                outline: body.outline_points(&self.world.objects),
+            });
+
+        objects.chain(soft_bodies).collect()
+    }
+
+    fn inspect(&self, target: TargetId, _state: &SimulationState) -> Vec<(String, String)> {
+        match target.group {
+            OBJECT_TARGET => {
+                let Some(obj) = self.world.objects.get(target.index) else {
+                    return vec![];
+                };
+                let obj_state = obj.current_state();
 
-            let delta_v = sum_acc * dt; // 등가속도 운동에서의 보정.
-            let dv_error = (delta_a * dt) / 2.0;
-            let delta_v = delta_v + dv_error;
+                let zones = self
+                    .world
+                    .zones_at(obj_state.position)
+                    .map(|zone| zone.name.as_str())
+                    .collect::<Vec<_>>();
 
-            let v = state.velocity;
+                vec![
+                    ("Position".into(), format!("{:.3?}", obj_state.position)),
+                    ("Velocity".into(), format!("{:.3?}", obj_state.velocity)),
+                    ("Mass".into(), format!("{:.3}", obj_state.mass)),
+                    ("Momentum".into(), format!("{:.3?}", obj_state.momentum())),
+                    (
+                        "Kinetic Energy".into(),
+                        format!(
+                            "{:.3}",
+                            0.5 * obj_state.mass * obj_state.velocity.norm_squared()
+                        ),
+                    ),
+                    (
+                        "Inside Zones".into(),
+                        if zones.is_empty() {
+                            "None".into()
+                        } else {
+                            zones.join(", ")
+                        },
+                    ),
+                ]
+            }
+            SOFT_BODY_TARGET => {
+                let Some(body) = self.world.soft_bodies.get(target.index) else {
+                    return vec![];
+                };
 
-            let delta_pos = v * dt;
-            let dpos_error = (delta_v * dt) / 2.0; // 등가속도 운동에서의 보정.
-            let delta_pos = delta_pos + dpos_error;
-            // Δs = v * Δt
+                let velocity = body
+                    .nodes
+                    .iter()
+                    .map(|node| self.world.objects[*node].current_state().velocity)
+                    .sum::<NVec2>()
+                    / body.nodes.len().max(1) as f64;
 
-            state.last_velocity = state.velocity;
-            state.velocity += delta_v;
-            state.position += delta_pos
+                vec![
+                    ("Nodes".into(), format!("{}", body.nodes.len())),
+                    ("Springs".into(), format!("{}", body.springs.len())),
+                    (
+                        "Center".into(),
+                        format!("{:.3?}", body.center(&self.world.objects)),
+                    ),
+                    ("Mean Velocity".into(), format!("{:.3?}", velocity)),
+                ]
+            }
+            _ => vec![],
         }
     }
 }
-
-//
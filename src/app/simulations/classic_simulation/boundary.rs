@@ -0,0 +1,72 @@
+use crate::app::graphics::define::items::Line;
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::classic::boundary::{BoundaryMode, WorldBoundary};
+use crate::engine::NVec2;
+use egui::plot::LineStyle;
+use egui::{ComboBox, DragValue, Ui};
+
+const BOUNDARY_MODE_ITER: [BoundaryMode; 3] = [
+    BoundaryMode::Open,
+    BoundaryMode::Periodic,
+    BoundaryMode::Wall,
+];
+
+impl WorldBoundary {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        for (label, mode) in [("Boundary X", &mut self.x), ("Boundary Y", &mut self.y)] {
+            ComboBox::from_label(label)
+                .selected_text(format!("{:?}", mode))
+                .show_ui(ui, |ui| {
+                    for item in BOUNDARY_MODE_ITER {
+                        ui.selectable_value(mode, item, format!("{:?}", item));
+                    }
+                });
+        }
+
+        if !self.is_open() {
+            ui.horizontal(|ui| {
+                ui.label("Min");
+                ui.add(DragValue::new(&mut self.min.x).prefix("x: "));
+                ui.add(DragValue::new(&mut self.min.y).prefix("y: "));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Max");
+                ui.add(DragValue::new(&mut self.max.x).prefix("x: "));
+                ui.add(DragValue::new(&mut self.max.y).prefix("y: "));
+            });
+
+            self.max = self.max.sup(&(self.min + NVec2::repeat(1.0)));
+        }
+    }
+
+    pub fn draw(&self) -> Vec<PlotItem> {
+        if self.is_open() {
+            return vec![];
+        }
+
+        let (min, max) = (self.min, self.max);
+
+        let sides = [
+            (self.x, [[min.x, min.y], [min.x, max.y]]),
+            (self.x, [[max.x, min.y], [max.x, max.y]]),
+            (self.y, [[min.x, min.y], [max.x, min.y]]),
+            (self.y, [[min.x, max.y], [max.x, max.y]]),
+        ];
+
+        sides
+            .into_iter()
+            .filter(|(mode, _)| *mode != BoundaryMode::Open)
+            .map(|(mode, side)| {
+                let line = Line::new(side.to_vec())
+                    .color(PlotColor::Boundary.get_color())
+                    .name("boundary");
+
+                match mode {
+                    BoundaryMode::Wall => line.width(2.0),
+                    _ => line.style(LineStyle::dashed_dense()),
+                }
+                .into()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,166 @@
+use crate::app::graphics::define::items::Line;
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::classic::chain::{ChainEnd, OscillatorChain};
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::CSimObject;
+use egui::plot::{Bar, BarChart, Legend, Plot, Points, VLine};
+use egui::{ComboBox, DragValue, Ui};
+use std::f64::consts::TAU;
+
+impl ChainEnd {
+    pub fn ui(&mut self, ui: &mut Ui, label: &str) {
+        ComboBox::from_label(label)
+            .selected_text(format!("{:?}", self))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(self, ChainEnd::Fixed, "Fixed");
+                ui.selectable_value(self, ChainEnd::Free, "Free");
+            });
+    }
+}
+
+impl OscillatorChain {
+    pub fn draw(&self, objects: &[CSimObject]) -> Vec<PlotItem> {
+        let mut points = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let pos = objects[*node].current_state().position;
+                [pos.x, pos.y]
+            })
+            .collect::<Vec<_>>();
+
+        let spacing = self.spacing();
+        if let (Some(first), Some(last)) = (self.equilibrium.first(), self.equilibrium.last()) {
+            if self.left == ChainEnd::Fixed && self.driver.is_none() {
+                points.insert(0, [first.x - spacing, first.y]);
+            }
+            if self.right == ChainEnd::Fixed {
+                points.push([last.x + spacing, last.y]);
+            }
+        }
+
+        vec![Line::new(points)
+            .color(PlotColor::Spring.get_color())
+            .name("chain")
+            .into()]
+    }
+
+    pub fn inspection_ui(&mut self, ui: &mut Ui, states: &[CSObjectState]) {
+        ui.horizontal(|ui| {
+            ui.label("Ends");
+            self.left.ui(ui, "Left");
+            self.right.ui(ui, "Right");
+        });
+
+        if let Some(driver) = self.driver.as_mut() {
+            ui.horizontal(|ui| {
+                ui.label("Driver");
+                ui.add(
+                    DragValue::new(&mut driver.frequency)
+                        .speed(0.01)
+                        .clamp_range(0.0..=20.0)
+                        .suffix(" Hz"),
+                );
+                ui.add(
+                    DragValue::new(&mut driver.amplitude)
+                        .speed(0.1)
+                        .prefix("A: "),
+                );
+            });
+        }
+
+        if states.len() != self.nodes.len() {
+            return;
+        }
+
+        let y = Self::displacements(states, &self.equilibrium);
+        let v = states
+            .iter()
+            .map(|state| state.velocity.y)
+            .collect::<Vec<_>>();
+
+        ui.label("Displacement vs Position");
+        Plot::new("chain_waveform")
+            .height(120.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                let wave = self
+                    .equilibrium
+                    .iter()
+                    .zip(&y)
+                    .map(|(eq, y)| [eq.x, *y])
+                    .collect::<Vec<_>>();
+
+                plot_ui.line(egui::plot::Line::new(wave.clone()));
+                plot_ui.points(Points::new(wave).radius(2.0));
+            });
+
+        let offset = usize::from(self.driver.is_some());
+        let modes = self.normal_modes();
+
+        // E_n = ½m(φ·v)² + ½mω²(φ·y)²
+        let energies = modes
+            .iter()
+            .map(|mode| {
+                let q = mode
+                    .shape
+                    .iter()
+                    .zip(&y[offset..])
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>();
+                let p = mode
+                    .shape
+                    .iter()
+                    .zip(&v[offset..])
+                    .map(|(a, b)| a * b)
+                    .sum::<f64>();
+                let omega = mode.angular_frequency;
+
+                0.5 * self.mass * (p * p + omega * omega * q * q)
+            })
+            .collect::<Vec<_>>();
+
+        ui.label("Normal Mode Energy");
+        Plot::new("chain_modes")
+            .height(120.0)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                let bar_width = modes.last().map_or(0.1, |mode| {
+                    mode.angular_frequency / TAU / (2.0 * modes.len() as f64)
+                });
+
+                let bars = modes
+                    .iter()
+                    .zip(&energies)
+                    .map(|(mode, energy)| {
+                        Bar::new(mode.angular_frequency / TAU, *energy).width(bar_width)
+                    })
+                    .collect();
+
+                plot_ui.bar_chart(BarChart::new(bars).name("mode"));
+
+                if let Some(driver) = self.driver {
+                    plot_ui.vline(VLine::new(driver.frequency).name("driver"));
+                }
+            });
+
+        egui::Grid::new("chain_modes_grid").show(ui, |ui| {
+            ui.label("Mode");
+            ui.label("f (Hz)");
+            ui.label("Energy");
+            ui.end_row();
+
+            for (n, (mode, energy)) in modes.iter().zip(&energies).enumerate() {
+                ui.label(format!("{}", n + 1));
+                ui.label(format!("{:.3}", mode.angular_frequency / TAU));
+                ui.label(format!("{:.3}", energy));
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -1,35 +1,19 @@
+use crate::app::graphics::define::items::Arrows;
 use crate::app::graphics::define::PlotItem;
-use crate::app::simulations::classic_simulation::object::state::{CSObjectState};
-use crate::app::NVec2;
-use egui::plot::Arrows;
+use crate::engine::classic::event::{CollisionEvent, SimulationEvent, SimulationEvents};
+use crate::engine::NVec2;
 use egui::CollapsingHeader;
 
-pub struct SimulationEvents(Vec<SimulationEvent>);
-
-impl Default for SimulationEvents {
-    fn default() -> Self {
-        Self(vec![])
-    }
-}
-
 impl SimulationEvents {
-    pub fn add_events(&mut self, events: Vec<impl Into<SimulationEvent>>) {
-        self.0.extend(events.into_iter().map(|x| x.into()));
-    }
-
-    pub fn add_event(&mut self, event: impl Into<SimulationEvent>) {
-        self.0.push(event.into());
-    }
-
     pub fn get_shapes(&self) -> Vec<PlotItem> {
-        self.0.iter().fold(vec![], |mut acc, x| {
+        self.iter().fold(vec![], |mut acc, x| {
             acc.extend(x.get_shapes());
             acc
         })
     }
 
     pub fn inspection_ui(&self, ui: &mut egui::Ui) {
-        self.0.iter().enumerate().for_each(|(i, x)| {
+        self.iter().enumerate().for_each(|(i, x)| {
             CollapsingHeader::new(format!("CollisionEvent, {:?}", i))
                 .default_open(false)
                 .show(ui, |ui| {
@@ -39,16 +23,6 @@ impl SimulationEvents {
     }
 }
 
-impl Into<SimulationEvent> for CollisionEvent {
-    fn into(self) -> SimulationEvent {
-        SimulationEvent::Collision(self)
-    }
-}
-
-pub enum SimulationEvent {
-    Collision(CollisionEvent),
-}
-
 impl SimulationEvent {
     pub fn get_shapes(&self) -> Vec<PlotItem> {
         match self {
@@ -59,6 +33,7 @@ impl SimulationEvent {
     pub fn inspection_ui(&self, ui: &mut egui::Ui) {
         match self {
             Self::Collision(event) => {
+                ui.label(format!("objects: {:?}", event.objects));
                 ui.label(format!("penetration: {:?}", event.penetration));
                 ui.label(format!("contact_point: {:?}", event.contact_point));
                 ui.label(format!("contact_normal: {:?}", event.contact_normal));
@@ -71,16 +46,6 @@ impl SimulationEvent {
     }
 }
 
-pub struct CollisionEvent {
-    pub contact_point: NVec2,
-    pub contact_normal: NVec2,
-    pub obj1_velocity: NVec2,
-    pub obj2_velocity: NVec2,
-    pub obj1_state: CSObjectState,
-    pub obj2_state: CSObjectState,
-    pub penetration: f64,
-}
-
 impl CollisionEvent {
     pub fn get_shapes(&self) -> Vec<impl Into<PlotItem>> {
         let obj1_pos = self.obj1_state.position;
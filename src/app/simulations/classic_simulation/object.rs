@@ -1,93 +1,290 @@
-pub mod builder;
 pub mod drawing;
-pub mod shape;
-pub mod state;
 
-use getset::Getters;
+use crate::engine::classic::object::shape::{Circle, ObjectShape, Rect};
+use crate::engine::classic::object::{attached_name, AttachedFn, NAMED_ATTACHED};
+use crate::engine::classic::CSimObject;
+use crate::engine::NVec2;
+use egui::plot::PlotPoints;
+use egui::{ComboBox, DragValue, Ui};
+use std::f64::consts::TAU;
+use std::hash::Hash;
 
-use state::CSObjectState;
+impl CSimObject {
+    /// 지금 보고 있는 스텝의 값을 고친다. 바뀌었으면 true.
+    /// `index`는 여러 오브젝트를 한 화면에 그릴 때 위젯 id를 나눈다.
+    pub fn inspection_ui(&mut self, ui: &mut Ui, index: usize) -> bool {
+        let mut changed = false;
+        let mut attached = *self.attached();
+        let state = self.current_state_mut();
 
-pub type AttachedFn = fn(&mut CSObjectState);
+        egui::Grid::new(("object_inspection_ui", index)).show(ui, |ui| {
+            ui.label("Position");
+            changed |= vector_ui(ui, &mut state.position);
+            ui.end_row();
 
-#[derive(Default, Clone, Getters)]
-pub struct CSimObject {
-    state_timeline: Vec<CSObjectState>,
-    init_timestep: usize,
-    timestep: usize,
+            ui.label("Velocity");
+            changed |= vector_ui(ui, &mut state.velocity);
+            ui.end_row();
 
-    #[getset(get = "pub")]
-    hide: bool,
-    #[getset(get = "pub")]
-    attached: Option<AttachedFn>,
-}
+            ui.label("Mass");
+            changed |= ui
+                .add(
+                    DragValue::new(&mut state.mass)
+                        .speed(0.1)
+                        .clamp_range(0.001..=f64::MAX),
+                )
+                .changed();
+            ui.end_row();
 
-impl CSimObject {
-    pub fn save_state(&mut self) {
-        self.state_timeline.push(self.current_state());
-        self.timestep += 1;
-    }
+            ui.label("Shape");
+            changed |= state.shape.ui(ui, index);
+            ui.end_row();
+
+            ui.label("Behaviour");
+            attached_ui(ui, &mut attached, index);
+            ui.end_row();
+
+            ui.label("Acceleration");
+            ui.label(format!("{:.3?}", state.acceleration()));
+            ui.end_row();
 
-    pub fn local_timestep(&self, timestep: usize) -> Option<usize> {
-        timestep.checked_sub(self.init_timestep)
+            ui.label("Sigma Force");
+            ui.label(format!("{:.3?}", state.sigma_force()));
+            ui.end_row();
+        });
+
+        changed | self.change_attached(attached)
     }
 
-    pub fn at_timestep(&mut self, timestep: usize) {
-        if self.local_timestep(timestep).is_some() {
-            self.hide = false;
-            self.timestep = timestep;
-        } else {
-            self.hide = true;
+    // 함수는 주소로 비교한다. 바뀌었으면 붙이고 true.
+    fn change_attached(&mut self, attached: Option<AttachedFn>) -> bool {
+        if attached.map(|f| f as usize) == self.attached().map(|f| f as usize) {
+            return false;
         }
+
+        self.set_attached(attached);
+        true
+    }
+}
+
+fn vector_ui(ui: &mut Ui, vector: &mut NVec2) -> bool {
+    ui.horizontal(|ui| {
+        let x = ui.add(DragValue::new(&mut vector.x).speed(0.1).prefix("x: "));
+        let y = ui.add(DragValue::new(&mut vector.y).speed(0.1).prefix("y: "));
+        x.changed() || y.changed()
+    })
+    .inner
+}
+
+fn size_value(value: &mut f64) -> DragValue<'_> {
+    DragValue::new(value).speed(0.1).clamp_range(0.1..=f64::MAX)
+}
+
+// 이름이 있는 부착 함수 중에서 고른다. 코드로만 붙인 함수는 Custom으로 보인다.
+fn attached_ui(ui: &mut Ui, attached: &mut Option<AttachedFn>, index: usize) {
+    let name = attached.map_or("None", |attached| {
+        attached_name(attached).unwrap_or("Custom")
+    });
+
+    ComboBox::from_id_source(("attached", index))
+        .selected_text(name)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(attached.is_none(), "None").clicked() {
+                *attached = None;
+            }
+
+            for (attached_name, named) in NAMED_ATTACHED {
+                if ui
+                    .selectable_label(name == attached_name, attached_name)
+                    .clicked()
+                {
+                    *attached = Some(named);
+                }
+            }
+        });
+}
+
+impl ObjectShape {
+    /// 모양의 종류와 크기를 한 줄에 고친다. 바뀌었으면 true.
+    pub fn ui(&mut self, ui: &mut Ui, id_source: impl Hash) -> bool {
+        let mut changed = false;
+        let name = match self {
+            Self::Circle(_) => "Circle",
+            Self::Rect(_) => "Rect",
+        };
+
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source(("shape", id_source))
+                .selected_text(name)
+                .show_ui(ui, |ui| {
+                    // 종류를 바꿔도 대략 같은 크기가 되도록 한다.
+                    let extent = self.half_extent();
+
+                    if ui.selectable_label(name == "Circle", "Circle").clicked() && name != "Circle"
+                    {
+                        *self = Self::circle(extent.x.max(extent.y));
+                        changed = true;
+                    }
+                    if ui.selectable_label(name == "Rect", "Rect").clicked() && name != "Rect" {
+                        *self = Self::rect(extent.x * 2.0, extent.y * 2.0);
+                        changed = true;
+                    }
+                });
+
+            match self {
+                Self::Circle(circle) => {
+                    changed |= ui
+                        .add(size_value(&mut circle.radius).prefix("r: "))
+                        .changed();
+                }
+                Self::Rect(rect) => {
+                    changed |= ui.add(size_value(&mut rect.width).prefix("w: ")).changed();
+                    changed |= ui.add(size_value(&mut rect.height).prefix("h: ")).changed();
+                }
+            }
+        });
+
+        changed
     }
 
-    pub fn current_state(&self) -> CSObjectState {
-        self.local_timestep(self.timestep)
-            .and_then(|timestep| self.state_timeline.get(timestep).cloned())
-            .unwrap()
+    pub fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
+        match self {
+            Self::Circle(circle) => circle.get_plot_points(pos),
+            Self::Rect(rect) => rect.get_plot_points(pos),
+        }
     }
+}
 
-    pub fn previous_state(&self) -> Option<CSObjectState> {
-        self.timestep
-            .checked_sub(1)
-            .and_then(|timestep| self.local_timestep(timestep))
-            .and_then(|timestep| self.state_timeline.get(timestep).cloned())
+impl Circle {
+    pub fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
+        PlotPoints::from_parametric_callback(
+            move |t| (t.sin() * self.radius + pos.x, t.cos() * self.radius + pos.y),
+            0.0..TAU,
+            Self::DEFAULT_RESOLUTION as usize,
+        )
     }
+}
 
-    pub fn current_state_mut(&mut self) -> &mut CSObjectState {
-        self.local_timestep(self.timestep)
-            .and_then(|timestep| self.state_timeline.get_mut(timestep))
-            .unwrap()
+impl Rect {
+    pub fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
+        let width = self.width;
+        let height = self.height;
+        vec![
+            (-width / 2.0, -height / 2.0),
+            (width / 2.0, -height / 2.0),
+            (width / 2.0, height / 2.0),
+            (-width / 2.0, height / 2.0),
+        ]
+        .into_iter()
+        .map(|e| [e.0 + pos.x, e.1 + pos.y])
+        .collect::<Vec<_>>()
+        .into()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::vector;
+
+    use crate::app::history::{Edit, EditHistory};
+    use crate::app::simulations::classic_simulation::ClassicSimulation;
+    use crate::app::simulations::state::{
+        SimulationSettings, SimulationState, SpecificSimulationSettings,
+    };
+    use crate::app::simulations::Simulation;
+    use crate::engine::classic::edit::{ObjectValues, SceneEdit};
+    use crate::engine::classic::object::perpendicular;
+    use crate::engine::classic::template::init::IdealGasSimInit;
+    use crate::engine::classic::template::CSTemplate;
+    use crate::engine::{NVec2, SIMULATION_TICK};
 
-    pub fn state_at_timestep(&self, current_timestep: usize) -> Option<CSObjectState> {
-        if self.init_timestep <= current_timestep {
-            Some(self.state_timeline[current_timestep - self.init_timestep].clone())
-        } else {
-            None
+    const STEPS: usize = 200;
+    const EDITED: usize = 80;
+    const OBJECT: usize = 5;
+
+    fn recorded() -> (ClassicSimulation, SimulationState) {
+        let preset = CSTemplate::IdealGasSimInit(IdealGasSimInit {
+            count: 20,
+            ..IdealGasSimInit::default()
+        })
+        .get_preset_with_ui();
+        let mut settings = preset.settings.clone();
+        let mut simulation = ClassicSimulation::from(preset);
+
+        for step in 0..STEPS {
+            simulation
+                .world
+                .step(&mut settings, SIMULATION_TICK * step as f64);
         }
+
+        let state = SimulationState {
+            settings: SimulationSettings::new(SpecificSimulationSettings::CSimSettings(settings)),
+            current_step: STEPS,
+            max_step: STEPS,
+            ..Default::default()
+        };
+        (simulation, state)
     }
 
-    pub fn inspection_ui(&self, ui: &mut egui::Ui) {
-        egui::Grid::new("object_inspection_ui").show(ui, |ui| {
-            ui.label("Position");
-            ui.label(format!("{:?}", self.current_state().position));
-            ui.end_row();
+    // 기록된 모든 스텝에서 오브젝트의 위치와 속도
+    fn track(simulation: &mut ClassicSimulation) -> Vec<(NVec2, NVec2)> {
+        (0..=simulation.world.recorded_steps())
+            .map(|step| {
+                simulation.at_time_step(step);
+                let state = simulation.world.objects[OBJECT].current_state();
+                (state.position, state.velocity)
+            })
+            .collect()
+    }
 
-            ui.label("Velocity");
-            ui.label(format!("{:?}", self.current_state().velocity));
-            ui.end_row();
+    #[test]
+    fn editing_a_past_step_forks_and_undoes() {
+        let (mut simulation, mut state) = recorded();
+        let original = track(&mut simulation);
 
-            ui.label("Acceleration");
-            ui.label(format!("{:?}", self.current_state().acceleration()));
-            ui.end_row();
+        // inspection_ui처럼 보고 있는 스텝의 상태와 부착 함수를 고친다.
+        state.current_step = EDITED;
+        simulation.at_time_step(EDITED);
+        let obj = &mut simulation.world.objects[OBJECT];
+        let before = ObjectValues::of(obj);
+        obj.current_state_mut().velocity = vector![20.0, 0.0];
+        assert!(obj.change_attached(Some(perpendicular)));
+        assert!(!obj.change_attached(Some(perpendicular)));
+        simulation.edits.push(SceneEdit::EditObject {
+            index: OBJECT,
+            step: EDITED,
+            before,
+            after: ObjectValues::of(obj),
+        });
 
-            ui.label("Sigma Force");
-            ui.label(format!("{:?}", self.current_state().sigma_force()));
-            ui.end_row();
+        let mut history = EditHistory::default();
+        let edits = simulation.take_edits();
+        history.record(&mut simulation, &mut state, edits, false);
 
-            ui.label("Mass");
-            ui.label(format!("{:?}", self.current_state().mass));
-            ui.end_row();
-        });
+        // 그 뒤에 기록된 스텝은 갈래로 잘라 냈다.
+        let labels = history.done().iter().map(Edit::label).collect::<Vec<_>>();
+        assert_eq!(labels, ["Fork at step 80", "Edit Object 5 at step 80"]);
+        assert_eq!(state.max_step, EDITED);
+        assert_eq!(simulation.world.recorded_steps(), EDITED);
+        let edited = track(&mut simulation);
+        assert_eq!(edited[..EDITED], original[..EDITED]);
+        assert_eq!(edited[EDITED].1, vector![20.0, 0.0]);
+
+        assert!(history.undo(&mut simulation, &mut state));
+        simulation.at_time_step(state.current_step);
+        assert!(simulation.world.objects[OBJECT].attached().is_none());
+        assert_eq!(track(&mut simulation), original[..=EDITED]);
+
+        // 갈래를 되돌리면 잘라 낸 스텝이 돌아온다.
+        assert!(history.undo(&mut simulation, &mut state));
+        assert_eq!(state.max_step, STEPS);
+        assert_eq!(track(&mut simulation), original);
+
+        for _ in 0..2 {
+            assert!(history.redo(&mut simulation, &mut state));
+        }
+        assert_eq!(state.max_step, EDITED);
+        assert!(simulation.world.objects[OBJECT].attached().is_some());
+        assert_eq!(track(&mut simulation), edited);
     }
 }
@@ -1,17 +1,17 @@
-use crate::app::graphics::define::{PlotItem, PlotColor, PlotTextSize};
+use crate::app::graphics::define::items::{Arrows, Line, Points, Text};
+use crate::app::graphics::define::{PlotColor, PlotItem, PlotTextSize};
 
-use crate::app::simulations::classic_simulation::object::state::CSObjectState;
-
-use crate::app::simulations::classic_simulation::template::stamp::CSObjectStamp;
-use crate::app::simulations::classic_simulation::CSimObject;
 use crate::app::simulations::state::SimulationState;
-use crate::app::NVec2;
-use egui::plot::{Arrows, Line, PlotPoint, Points, Text};
-use egui::{Align2, RichText};
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::template::stamp::CSObjectStamp;
+use crate::engine::classic::CSimObject;
+use crate::engine::NVec2;
+use egui::plot::PlotPoint;
+use egui::Align2;
 
 use std::fmt::Debug;
 
-fn get_sized_text(zoom: f64, text: String, scale: f64) -> RichText {
+fn get_sized_text(position: PlotPoint, zoom: f64, text: String, scale: f64) -> Text {
     let font_size_raw = ((((1.0 / zoom) * 1000.0) * scale) + 10.0) / 5.0;
 
     let default_max = 64.0;
@@ -19,9 +19,9 @@ fn get_sized_text(zoom: f64, text: String, scale: f64) -> RichText {
     let default_min = 5.0;
 
     match font_size_raw {
-        _x if font_size_raw > default_max => RichText::new(""),
-        _x if font_size_raw < default_min => RichText::new(""),
-        x => RichText::new(text).size(x as f32),
+        _x if font_size_raw > default_max => Text::new(position, ""),
+        _x if font_size_raw < default_min => Text::new(position, ""),
+        x => Text::new(position, text).size(x as f32),
     }
 }
 
@@ -40,13 +40,11 @@ fn get_info_vector(
 
     let arrows = Arrows::new([start.x, start.y], [(end.x), (end.y)]);
 
-    let text = Text::new(
+    let text = get_sized_text(
         PlotPoint::from(((start + end) / 2.0).data.0[0]),
-        get_sized_text(
-            zoom,
-            format!("{string} : {value:?}"),
-            PlotTextSize::Medium.get_size(),
-        ),
+        zoom,
+        format!("{string} : {value:?}"),
+        PlotTextSize::Medium.get_size(),
     )
     .color(color.get_color())
     .name(string.clone());
@@ -61,7 +59,7 @@ impl CSimObject {
         &self,
         sim_state: &SimulationState,
         index: usize,
-        stamps: &mut Vec<CSObjectStamp>,
+        stamps: &[CSObjectStamp],
     ) -> Vec<PlotItem> {
         puffin::profile_function!();
 
@@ -83,16 +81,18 @@ impl CSimObject {
         let get_self_state_text = |state: &CSObjectState| {
             let text = get_state_text_raw(state);
 
-            Text::new(
+            get_sized_text(
                 PlotPoint::new(state.position.x, state.position.y),
-                get_sized_text(sim_state.zoom, text, PlotTextSize::Small.get_size()),
+                sim_state.zoom,
+                text,
+                PlotTextSize::Small.get_size(),
             )
         };
 
         // Draw stamp
         if filter.stamp && sim_state.is_sim_started() {
-            for stamp in stamps {
-                if let Some(stamp_result) = stamp.get_data(&current_state, index, sim_state.time) {
+            for stamp in stamps.iter().filter(|stamp| stamp.covers(index)) {
+                if let Some(stamp_result) = stamp.result() {
                     let text = format!(
                         "<Stamp>\nLabel:{:?}\n{:}\nOn State Time {:.3?}",
                         stamp_result.label,
@@ -100,12 +100,14 @@ impl CSimObject {
                         stamp_result.time
                     );
 
-                    let text = Text::new(
+                    let text = get_sized_text(
                         PlotPoint::new(
                             stamp_result.state.position.x,
                             stamp_result.state.position.y,
                         ),
-                        get_sized_text(sim_state.zoom, text, PlotTextSize::Medium.get_size()),
+                        sim_state.zoom,
+                        text,
+                        PlotTextSize::Medium.get_size(),
                     )
                     .anchor(Align2::LEFT_TOP)
                     .name(stamp_result.name.clone())
@@ -193,37 +195,42 @@ impl CSimObject {
         if sim_state.sim_started & filter.trace {
             const MAX_TRACE_LENGTH: usize = 5000;
 
-            let line = {
+            let lines = {
                 let current_timestep = sim_state.current_step;
-                let init_timestep = self.init_timestep;
+                let init_timestep = *self.init_timestep();
 
                 let line_len = current_timestep
                     .saturating_sub(init_timestep)
                     .clamp(0, MAX_TRACE_LENGTH);
 
-                let data_len = self.state_timeline.len();
+                // 메모리를 아끼려고 버린 스텝은 선에 나오지 않는다.
+                let positions = self
+                    .track()
+                    .positions(current_timestep - line_len, current_timestep);
 
-                let index_end = current_timestep
-                    .saturating_sub(init_timestep)
-                    .clamp(0, data_len);
-
-                let index_start = index_end.saturating_sub(line_len);
-
-                Line::new(
-                    self.state_timeline[index_start..index_end]
-                        .iter()
-                        .map(|x| {
-                            let pos = x.position;
-                            [pos.x, pos.y]
-                        })
-                        .collect::<Vec<_>>()
-                        .to_vec(),
-                )
-                .color(PlotColor::TraceLine.get_color())
-                .name("trace line")
+                // 주기 경계를 넘어간 곳에서는 선을 끊는다.
+                let mut segments: Vec<Vec<[f64; 2]>> = vec![vec![]];
+                let mut last_pos: Option<NVec2> = None;
+
+                for pos in positions {
+                    let pos = *pos;
+
+                    if last_pos.map_or(false, |last| settings.boundary.is_jump(last, pos)) {
+                        segments.push(vec![]);
+                    }
+
+                    segments.last_mut().unwrap().push([pos.x, pos.y]);
+                    last_pos = Some(pos);
+                }
+
+                segments.into_iter().map(|segment| {
+                    Line::new(segment)
+                        .color(PlotColor::TraceLine.get_color())
+                        .name("trace line")
+                })
             };
 
-            items.push(line.into());
+            items.extend(lines.map(|line| line.into()));
         }
 
         items
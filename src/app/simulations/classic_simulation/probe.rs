@@ -0,0 +1,80 @@
+use crate::engine::classic::probe::GasProbe;
+use egui::plot::{Bar, BarChart, Legend, Line, Plot};
+use egui::Ui;
+
+const HISTOGRAM_BINS: usize = 24;
+
+impl GasProbe {
+    pub fn inspection_ui(&self, ui: &mut Ui) {
+        egui::Grid::new("gas_probe_ui").show(ui, |ui| {
+            ui.label("N");
+            ui.label(format!("{}", self.count));
+            ui.end_row();
+
+            ui.label("Pressure (P)");
+            ui.label(format!("{:.3}", self.pressure));
+            ui.end_row();
+
+            ui.label("Area (V)");
+            ui.label(format!("{:.3}", self.area));
+            ui.end_row();
+
+            ui.label("Temperature (kT)");
+            ui.label(format!("{:.3}", self.temperature));
+            ui.end_row();
+
+            ui.label("PV / NkT");
+            match self.compressibility() {
+                Some(compressibility) => ui.label(format!("{:.3}", compressibility)),
+                None => ui.weak("- (no particles or T = 0)"),
+            };
+            ui.end_row();
+        });
+
+        if self.count == 0 || self.temperature <= 0.0 {
+            return;
+        }
+
+        let max_speed = self.speeds.iter().cloned().fold(0.0, f64::max).max(1e-6);
+        let bin_width = max_speed / HISTOGRAM_BINS as f64;
+
+        let mut counts = [0usize; HISTOGRAM_BINS];
+        for speed in &self.speeds {
+            let bin = ((speed / bin_width) as usize).min(HISTOGRAM_BINS - 1);
+            counts[bin] += 1;
+        }
+
+        let bars = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                Bar::new(
+                    (i as f64 + 0.5) * bin_width,
+                    *count as f64 / (self.count as f64 * bin_width),
+                )
+                .width(bin_width)
+            })
+            .collect::<Vec<_>>();
+
+        let curve = Line::new(
+            (0..=100)
+                .map(|i| {
+                    let speed = max_speed * i as f64 / 100.0;
+                    [speed, self.maxwell_boltzmann(speed)]
+                })
+                .collect::<Vec<_>>(),
+        )
+        .name("Maxwell-Boltzmann");
+
+        Plot::new("speed_histogram")
+            .height(160.0)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(bars).name("Speed"));
+                plot_ui.line(curve);
+            });
+    }
+}
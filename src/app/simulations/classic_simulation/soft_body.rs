@@ -0,0 +1,89 @@
+use crate::app::graphics::define::items::{Line, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::classic::soft_body::{SoftBody, SoftBodyKind, SoftBodyParams};
+use crate::engine::classic::CSimObject;
+use egui::{ComboBox, DragValue, Slider, Ui, Widget};
+
+impl SoftBodyParams {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut kind = self.kind.get_name();
+
+        ComboBox::from_label("Soft Body")
+            .selected_text(kind)
+            .show_ui(ui, |ui| {
+                for name in ["Grid", "Ring", "Cloth"] {
+                    ui.selectable_value(&mut kind, name, name);
+                }
+            });
+
+        if kind != self.kind.get_name() {
+            self.kind = match kind {
+                "Ring" => SoftBodyKind::Ring { nodes: 12 },
+                "Cloth" => SoftBodyKind::Cloth {
+                    columns: 8,
+                    rows: 6,
+                },
+                _ => SoftBodyKind::default(),
+            };
+        }
+
+        match &mut self.kind {
+            SoftBodyKind::Grid { columns, rows } | SoftBodyKind::Cloth { columns, rows } => {
+                ui.add(
+                    DragValue::new(columns)
+                        .clamp_range(2..=16)
+                        .prefix("columns: "),
+                );
+                ui.add(DragValue::new(rows).clamp_range(2..=16).prefix("rows: "));
+            }
+            SoftBodyKind::Ring { nodes } => {
+                ui.add(DragValue::new(nodes).clamp_range(3..=48).prefix("nodes: "));
+            }
+        }
+
+        ui.add(
+            DragValue::new(&mut self.size)
+                .clamp_range(1.0..=200.0)
+                .prefix("size: "),
+        );
+        Slider::new(&mut self.stiffness, 1.0..=1000.0)
+            .text("Stiffness")
+            .ui(ui);
+        Slider::new(&mut self.damping, 0.0..=20.0)
+            .text("Damping")
+            .ui(ui);
+    }
+}
+
+impl SoftBody {
+    pub fn draw(&self, objects: &[CSimObject]) -> Vec<PlotItem> {
+        let mut items: Vec<PlotItem> = self
+            .springs
+            .iter()
+            .map(|spring| {
+                let a = objects[spring.a].current_state().position;
+                let b = objects[spring.b].current_state().position;
+
+                Line::new(vec![[a.x, a.y], [b.x, b.y]])
+                    .color(PlotColor::Spring.get_color())
+                    .width(0.5)
+                    .into()
+            })
+            .collect();
+
+        items.push(
+            Polygon::new(
+                self.outline_points(objects)
+                    .iter()
+                    .map(|p| [p.x, p.y])
+                    .collect::<Vec<_>>(),
+            )
+            .color(PlotColor::SoftBody.get_color())
+            .fill_alpha(0.3)
+            .name(&self.name)
+            .into(),
+        );
+
+        items
+    }
+}
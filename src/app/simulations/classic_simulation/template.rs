@@ -1,171 +1,138 @@
-
-use nalgebra::Vector2;
-use std::ops::IndexMut;
-
-use crate::app::graphics::CSPlotObjects;
-use crate::app::simulations::classic_simulation::object::builder::CSimObjectBuilder;
-use crate::app::simulations::classic_simulation::object::state::{CSObjectState, ForceIndex};
-use crate::app::simulations::classic_simulation::template::init::{
-    BasicSimInitObjData, SimulationInit, ThetaThrowSimInit,
-};
-use crate::app::simulations::classic_simulation::template::stamp::{
-    CSObjectStamp, CSObjectStampResult,
+use crate::app::simulations::template::{SimulationInit, SimulationType};
+use crate::engine::classic::template::init::{
+    BasicSimInitObjData, IdealGasSimInit, OscillatorChainSimInit, ThetaThrowSimInit,
 };
-use crate::app::simulations::classic_simulation::CSimObject;
-use crate::app::NVec2;
-
-pub mod init;
-pub mod stamp;
-
-#[derive(Clone, Debug)]
-pub enum CSTemplate {
-    ThetaThrowSimInit(ThetaThrowSimInit),
-    DefaultSim,
-    CircleSim,
-}
+use crate::engine::classic::template::CSTemplate;
+use egui::{Slider, Ui, Widget};
 
 impl CSTemplate {
-    pub fn get_name(&self) -> String {
-        format!("{:?}", self).split('(').collect::<Vec<&str>>()[0].to_string()
-    }
-
-    pub fn get_preset_with_ui(self) -> CSPreset {
-        match self {
-            CSTemplate::DefaultSim => default_sim(),
-            CSTemplate::ThetaThrowSimInit(init) => theta_throw(init),
-            CSTemplate::CircleSim => circle_sim(),
-        }
-    }
-
     pub fn get_data(&self) -> Option<Box<dyn SimulationInit>> {
         match self {
             CSTemplate::ThetaThrowSimInit(data) => Some(Box::new(data.clone())),
+            CSTemplate::IdealGasSimInit(data) => Some(Box::new(data.clone())),
+            CSTemplate::OscillatorChainSimInit(data) => Some(Box::new(data.clone())),
             _ => None,
         }
     }
 }
 
-pub fn get_sim_list() -> [CSTemplate; 3] {
-    [
-        CSTemplate::ThetaThrowSimInit(ThetaThrowSimInit {
-            objects: vec![
-                BasicSimInitObjData {
-                    mass: 5.0,
-                    theta: 30.0,
-                    start_velocity_mul: 20.0,
-                },
-                BasicSimInitObjData {
-                    mass: 5.0,
-                    theta: 60.0,
-                    start_velocity_mul: 20.0,
-                },
-                BasicSimInitObjData {
-                    mass: 5.0,
-                    theta: 15.0,
-                    start_velocity_mul: 20.0,
-                },
-                BasicSimInitObjData {
-                    mass: 5.0,
-                    theta: 75.0,
-                    start_velocity_mul: 20.0,
-                },
-            ],
-        }),
-        CSTemplate::DefaultSim,
-        CSTemplate::CircleSim,
-    ]
-}
+impl SimulationInit for ThetaThrowSimInit {
+    fn ui(&mut self, ui: &mut Ui) {
+        if ui
+            .button("Add Object")
+            .on_hover_text("Add Object")
+            .clicked()
+        {
+            self.objects.push(BasicSimInitObjData {
+                theta: 0.0,
+                start_velocity_mul: 10.0,
+                mass: 10.0,
+            });
+        }
 
-pub struct CSPreset {
-    pub simulation_objects: Vec<CSimObject>,
-    pub plot_objects: CSPlotObjects,
-}
+        if ui
+            .button("Remove Object")
+            .on_hover_text("Remove Object")
+            .clicked()
+        {
+            self.objects.pop();
+        }
+
+        let mut remove = None;
+
+        let _response = self
+            .objects
+            .iter_mut()
+            .enumerate()
+            .map(|(index, obj)| {
+                ui.separator();
+
+                let theta = Slider::new(&mut obj.theta, 0.0..=90.0)
+                    .text("Theta Degree")
+                    .ui(ui);
 
-impl Default for CSPreset {
-    fn default() -> Self {
-        CSPreset {
-            simulation_objects: vec![],
-            plot_objects: CSPlotObjects::default(),
+                let velocity = Slider::new(&mut obj.start_velocity_mul, 10.0..=100.0)
+                    .text("Velocity Mul")
+                    .ui(ui);
+
+                let mass = Slider::new(&mut obj.mass, 1.0..=100.0).text("Mass").ui(ui);
+
+                if ui.button("Remove this Object").clicked() {
+                    remove.replace(index);
+                }
+                ui.separator();
+                (theta, velocity, mass)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(index) = remove {
+            self.objects.remove(index);
         }
     }
+
+    fn to_simulation_type(&self) -> SimulationType {
+        SimulationType::Classic(CSTemplate::ThetaThrowSimInit(self.clone()))
+    }
 }
 
-fn theta_throw(data: ThetaThrowSimInit) -> CSPreset {
-    // value have any item
-    // let force = value.theta * 5.0;
-    // force_list.push(force) // how to?
-    let objects = data
-        .objects
-        .iter()
-        .map(|obj| {
-            let velocity = obj.theta.to_radians().sin_cos();
-            let velocity = Vector2::new(velocity.0, velocity.1) * obj.start_velocity_mul;
-
-            CSimObjectBuilder::new(CSObjectState {
-                velocity,
-                mass: obj.mass,
-                ..CSObjectState::default()
-            })
-            .build()
-        })
-        .collect::<Vec<_>>();
-
-    let func = |state: &CSObjectState, time: f64| {
-        if state.position.y < 0.0 {
-            Some(
-                CSObjectStampResult::default()
-                    .label("WHEN pos < 0.1")
-                    .state(state.clone())
-                    .time(time),
-            )
-        } else {
-            None
-        }
-    };
+impl SimulationInit for IdealGasSimInit {
+    fn ui(&mut self, ui: &mut Ui) {
+        Slider::new(&mut self.count, 1..=500)
+            .text("Particle Count")
+            .ui(ui);
+
+        Slider::new(&mut self.speed, 1.0..=100.0)
+            .text("Start Speed")
+            .ui(ui);
 
-    let stamp = CSObjectStamp::new(func, 0..=0);
+        Slider::new(&mut self.radius, 0.2..=3.0)
+            .text("Radius")
+            .ui(ui);
 
-    let plot_objects = CSPlotObjects::default().add_stamp(stamp);
+        Slider::new(&mut self.mass, 0.1..=10.0).text("Mass").ui(ui);
 
-    CSPreset {
-        simulation_objects: objects,
-        plot_objects,
-        ..CSPreset::default()
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add(egui::DragValue::new(&mut self.seed));
+        });
+    }
+
+    fn to_simulation_type(&self) -> SimulationType {
+        SimulationType::Classic(CSTemplate::IdealGasSimInit(self.clone()))
     }
 }
 
-fn circle_sim() -> CSPreset {
-    let mass = 5.0;
+impl SimulationInit for OscillatorChainSimInit {
+    fn ui(&mut self, ui: &mut Ui) {
+        Slider::new(&mut self.count, 2..=60)
+            .text("Mass Count")
+            .ui(ui);
 
-    let sim = vec![5.0]
-        .iter()
-        .map(|x| {
-            CSimObjectBuilder::new(CSObjectState {
-                velocity: NVec2::new(*x, *x),
+        Slider::new(&mut self.stiffness, 1.0..=500.0)
+            .text("Stiffness")
+            .ui(ui);
 
-                mass,
-                position: NVec2::new(1.0, 0.0),
+        Slider::new(&mut self.mass, 0.1..=10.0).text("Mass").ui(ui);
 
-                ..CSObjectState::default()
-            })
-            .attached(|obj| {
-                let _ = std::mem::replace(obj.acc_list.index_mut(ForceIndex::Attached as usize), {
-                    let mut vector = obj.velocity.yx();
-                    vector.y *= -1.0;
-                    vector
-                });
-            })
-            .build()
-        })
-        .collect::<Vec<_>>();
+        self.left.ui(ui, "Left End");
+        self.right.ui(ui, "Right End");
+
+        ui.separator();
 
-    CSPreset {
-        simulation_objects: sim,
+        ui.checkbox(&mut self.driven, "Drive First Mass");
 
-        ..CSPreset::default()
+        if self.driven {
+            Slider::new(&mut self.frequency, 0.0..=5.0)
+                .text("Frequency (Hz)")
+                .ui(ui);
+
+            Slider::new(&mut self.amplitude, 0.0..=10.0)
+                .text("Amplitude")
+                .ui(ui);
+        }
     }
-}
 
-fn default_sim() -> CSPreset {
-    CSPreset::default()
+    fn to_simulation_type(&self) -> SimulationType {
+        SimulationType::Classic(CSTemplate::OscillatorChainSimInit(self.clone()))
+    }
 }
@@ -0,0 +1,103 @@
+use crate::app::graphics::define::items::{Arrows, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+use crate::engine::NVec2;
+use egui::{ComboBox, DragValue, Ui};
+
+impl ZoneEffect {
+    fn color(&self) -> PlotColor {
+        match self {
+            Self::Gravity(_) => PlotColor::GravityZone,
+            Self::Drag(_) => PlotColor::DragZone,
+            Self::Conveyor { .. } => PlotColor::ConveyorZone,
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut kind = self.get_name();
+
+        ComboBox::from_label("Zone Effect")
+            .selected_text(kind)
+            .show_ui(ui, |ui| {
+                for name in ["Gravity", "Drag", "Conveyor"] {
+                    ui.selectable_value(&mut kind, name, name);
+                }
+            });
+
+        if kind != self.get_name() {
+            *self = match kind {
+                "Gravity" => Self::Gravity(NVec2::new(0.0, -1.6)),
+                "Conveyor" => Self::Conveyor {
+                    velocity: NVec2::new(10.0, 0.0),
+                    grip: 5.0,
+                },
+                _ => Self::Drag(1.0),
+            };
+        }
+
+        match self {
+            Self::Gravity(gravity) => {
+                ui.horizontal(|ui| {
+                    ui.label("g");
+                    ui.add(DragValue::new(&mut gravity.x).speed(0.1).prefix("x: "));
+                    ui.add(DragValue::new(&mut gravity.y).speed(0.1).prefix("y: "));
+                });
+            }
+            Self::Drag(coefficient) => {
+                ui.add(
+                    DragValue::new(coefficient)
+                        .speed(0.1)
+                        .clamp_range(0.0..=f64::MAX)
+                        .prefix("b: "),
+                );
+            }
+            Self::Conveyor { velocity, grip } => {
+                ui.horizontal(|ui| {
+                    ui.label("v");
+                    ui.add(DragValue::new(&mut velocity.x).speed(0.1).prefix("x: "));
+                    ui.add(DragValue::new(&mut velocity.y).speed(0.1).prefix("y: "));
+                });
+                ui.add(
+                    DragValue::new(grip)
+                        .speed(0.1)
+                        .clamp_range(0.0..=f64::MAX)
+                        .prefix("grip: "),
+                );
+            }
+        }
+    }
+}
+
+impl FieldZone {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        let color = self.effect.color().get_color();
+        let center = self.center();
+
+        let mut items: Vec<PlotItem> =
+            vec![
+                Polygon::new(self.points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                    .color(color)
+                    .fill_alpha(0.15)
+                    .name(&self.name)
+                    .into(),
+            ];
+
+        let direction = match self.effect {
+            ZoneEffect::Gravity(gravity) => Some(gravity),
+            ZoneEffect::Conveyor { velocity, .. } => Some(velocity),
+            ZoneEffect::Drag(_) => None,
+        };
+
+        if let Some(direction) = direction {
+            let end = center + direction;
+            items.push(
+                Arrows::new([center.x, center.y], [end.x, end.y])
+                    .color(color)
+                    .name(&self.name)
+                    .into(),
+            );
+        }
+
+        items
+    }
+}
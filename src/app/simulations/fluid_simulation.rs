@@ -0,0 +1,704 @@
+pub mod layout;
+pub mod sim_state;
+
+use crate::app::graphics::define::items::{Line, Points};
+use crate::app::NVec2;
+
+use egui::{CollapsingHeader, DragValue, Response, Ui};
+
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::manager::SIMULATION_TICK;
+use crate::app::session::SessionLayout;
+use crate::app::simulations::grid::gradient_color;
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation, TargetId};
+use crate::engine::keyframe::KeyframeTimeline;
+use crate::engine::polygon::{closest_on_segment, segment_outline};
+
+use crate::engine::fluid::layout::{Emitter, FluidLayout, Solid};
+use crate::engine::fluid::sim_state::{FluidSettings, FluidView};
+use crate::engine::fluid::sph::{substeps, FluidState, FluidTank, Particle};
+use crate::engine::fluid::template::FluidPreset;
+
+/// `TargetId::group` 값
+const PARTICLE_TARGET: usize = 0;
+const SOLID_TARGET: usize = 1;
+const EMITTER_TARGET: usize = 2;
+
+/// 이 간격마다 입자 전체를 저장하고, 그 사이 스텝은 다시 계산한다.
+const KEYFRAME_INTERVAL: usize = 60;
+
+/// 입자를 칠하는 색의 가짓수. 색마다 `Points` 하나로 그린다.
+const COLOUR_BUCKETS: usize = 12;
+
+/// 입자 색: 남색 - 파랑 - 하늘 - 흰색
+const FLUID_STOPS: [[f32; 3]; 4] = [
+    [20.0, 60.0, 160.0],
+    [40.0, 120.0, 220.0],
+    [120.0, 200.0, 250.0],
+    [235.0, 245.0, 255.0],
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FluidOperation {
+    #[default]
+    Navigate,
+    Move,
+    AddFluid,
+    AddWall,
+    AddContainer,
+    AddEmitter,
+    Remove,
+}
+
+const FLUID_OPERATION_ITER: [FluidOperation; 7] = [
+    FluidOperation::Navigate,
+    FluidOperation::Move,
+    FluidOperation::AddFluid,
+    FluidOperation::AddWall,
+    FluidOperation::AddContainer,
+    FluidOperation::AddEmitter,
+    FluidOperation::Remove,
+];
+
+/// SPH로 액체를 푸는 시뮬레이션. 고체 벽과 그릇 사이로 물기둥이 무너지거나 관에서 쏟아진다.
+#[derive(Clone)]
+pub struct FluidSimulation {
+    pub tank: FluidTank,
+    pub layout: FluidLayout,
+    fluid: FluidState,
+    timeline: KeyframeTimeline<FluidLayout, FluidState>,
+    /// 타임라인을 다시 계산할 때 쓸 마지막 설정
+    settings: FluidSettings,
+
+    pub operation: FluidOperation,
+    pub emitter_brush: Emitter,
+    pub solid_thickness: f64,
+    /// 드래그로 그리고 있는 유체, 벽, 그릇의 시작점
+    drag_start: Option<NVec2>,
+    /// 드래그를 시작할 때 대상 위치와 포인터의 차이
+    grab_offset: NVec2,
+}
+
+impl From<FluidPreset> for FluidSimulation {
+    fn from(preset: FluidPreset) -> Self {
+        let fluid = FluidState::new(&preset.particles, &preset.settings);
+
+        Self {
+            timeline: KeyframeTimeline::new(
+                preset.layout.clone(),
+                fluid.clone(),
+                KEYFRAME_INTERVAL,
+            ),
+            tank: preset.tank,
+            layout: preset.layout,
+            fluid,
+            settings: preset.settings,
+            operation: FluidOperation::default(),
+            emitter_brush: Emitter::default(),
+            solid_thickness: 2.0,
+            drag_start: None,
+            grab_offset: NVec2::zeros(),
+        }
+    }
+}
+
+impl FluidSimulation {
+    fn settings(state: &SimulationState) -> FluidSettings {
+        state
+            .settings
+            .specific
+            .as_fluid_settings()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 그 스텝의 배치로 한 스텝 진행하고, 간격마다 입자를 저장한다.
+    fn advance(&mut self) {
+        self.layout = self.timeline.layout_at(self.fluid.step).clone();
+
+        self.fluid
+            .advance(&self.tank, &self.layout, &self.settings, SIMULATION_TICK);
+
+        self.timeline.store(self.fluid.step, &self.fluid);
+    }
+
+    // 현재 배치를 타임라인의 `step`부터 쓴다. 그 뒤의 입자는 다시 계산해야 한다.
+    fn record(&mut self, step: usize) {
+        self.timeline.record(step, self.layout.clone());
+    }
+
+    // 입자를 직접 넣거나 뺐을 때 `step`의 저장본을 바꾼다.
+    fn record_fluid(&mut self, step: usize) {
+        self.timeline.edit(step, &self.fluid);
+    }
+
+    /// 두 점이 만드는 직사각형을 유체로 채운다. 벽이나 다른 입자와 겹치는 자리는 건너뛴다.
+    fn fill(&mut self, a: NVec2, b: NVec2) {
+        let clearance = self.tank.spacing * 0.9;
+
+        let positions = self
+            .tank
+            .lattice(a, b)
+            .into_iter()
+            .filter(|p| {
+                self.layout.solids.iter().all(|solid| {
+                    solid.segments().all(|(start, end)| {
+                        let closest = closest_on_segment(start, end, *p);
+                        (p - closest).norm() >= solid.thickness / 2.0 + self.tank.radius()
+                    })
+                }) && self
+                    .fluid
+                    .particles
+                    .iter()
+                    .all(|particle| (particle.position - p).norm() >= clearance)
+            })
+            .collect::<Vec<_>>();
+
+        self.fluid.particles.extend(
+            positions
+                .into_iter()
+                .map(|p| Particle::new(p, NVec2::zeros(), self.settings.rest_density)),
+        );
+    }
+
+    fn move_target(&mut self, target: TargetId, anchor: NVec2) {
+        match target.group {
+            SOLID_TARGET => {
+                if let Some(solid) = self.layout.solids.get_mut(target.index) {
+                    let delta = anchor - solid.center();
+                    solid.translate(delta);
+                }
+            }
+            EMITTER_TARGET => {
+                if let Some(emitter) = self.layout.emitters.get_mut(target.index) {
+                    emitter.position = anchor;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn remove_target(&mut self, target: TargetId, step: usize) {
+        match target.group {
+            PARTICLE_TARGET if target.index < self.fluid.particles.len() => {
+                self.fluid.particles.remove(target.index);
+                self.record_fluid(step);
+            }
+            SOLID_TARGET if target.index < self.layout.solids.len() => {
+                self.layout.solids.remove(target.index);
+                self.record(step);
+            }
+            EMITTER_TARGET if target.index < self.layout.emitters.len() => {
+                self.layout.emitters.remove(target.index);
+                self.record(step);
+            }
+            _ => {}
+        }
+    }
+
+    /// 입자를 칠할 값을 0~1로 맞춘 것
+    fn colour_value(&self, particle: &Particle) -> f64 {
+        let settings = &self.settings;
+
+        match settings.view {
+            FluidView::Speed => {
+                let height = self.tank.max.y - self.tank.min.y;
+                particle.velocity.norm() / (settings.gravity * height).sqrt().max(1.0)
+            }
+            // 정지 밀도에서 ±5%
+            FluidView::Density => (particle.density / settings.rest_density - 0.95) / 0.1,
+            FluidView::Pressure => {
+                particle.pressure / (settings.stiffness * settings.rest_density * 0.05)
+            }
+        }
+    }
+
+    fn particle_outline(&self, particle: &Particle) -> Vec<NVec2> {
+        let half = self.tank.radius();
+
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .iter()
+            .map(|(x, y)| particle.position + NVec2::new(*x, *y) * half)
+            .collect()
+    }
+}
+
+impl Simulation for FluidSimulation {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let settings = Self::settings(state);
+        let before = self.layout.clone();
+        let particles = &self.fluid.particles;
+
+        let count = particles.len().max(1) as f64;
+        let mean_density = particles.iter().map(|p| p.density).sum::<f64>() / count;
+        let max_density = particles.iter().fold(0.0, |max: f64, p| max.max(p.density));
+        let max_pressure = particles
+            .iter()
+            .fold(0.0, |max: f64, p| max.max(p.pressure));
+        let kinetic = particles
+            .iter()
+            .map(|p| 0.5 * self.tank.mass * p.velocity.norm_squared())
+            .sum::<f64>();
+
+        egui::Grid::new("fluid_summary").show(ui, |ui| {
+            ui.label("Particles");
+            ui.label(format!("{}", particles.len()));
+            ui.end_row();
+
+            ui.label("Spacing");
+            ui.label(format!(
+                "{:.2} (h = {:.2}, m = {:.2})",
+                self.tank.spacing,
+                self.tank.smoothing_length(),
+                self.tank.mass
+            ));
+            ui.end_row();
+
+            ui.label("Substeps");
+            ui.label(format!(
+                "{}",
+                substeps(
+                    &self.tank,
+                    &settings,
+                    self.fluid.max_speed(),
+                    SIMULATION_TICK
+                )
+            ));
+            ui.end_row();
+
+            ui.label("Fluid Time");
+            ui.label(format!("{:.3} s", self.fluid.time));
+            ui.end_row();
+
+            ui.label("Mean Density");
+            ui.label(format!(
+                "{:.1} ({:+.2}%)",
+                mean_density,
+                (mean_density / settings.rest_density - 1.0) * 100.0
+            ));
+            ui.end_row();
+
+            ui.label("Max Density");
+            ui.label(format!(
+                "{:.1} ({:+.2}%)",
+                max_density,
+                (max_density / settings.rest_density - 1.0) * 100.0
+            ));
+            ui.end_row();
+
+            ui.label("Max Pressure");
+            ui.label(format!("{:.1}", max_pressure));
+            ui.end_row();
+
+            ui.label("Max Speed");
+            ui.label(format!("{:.2}", self.fluid.max_speed()));
+            ui.end_row();
+
+            ui.label("Kinetic Energy");
+            ui.label(format!("{:.1}", kinetic));
+            ui.end_row();
+
+            ui.label("Keyframes");
+            ui.label(format!("{}", self.timeline.keyframe_count()));
+            ui.end_row();
+        });
+
+        if ui.button("Clear Fluid").clicked() {
+            self.fluid.particles.clear();
+            self.record_fluid(state.current_step);
+        }
+
+        let mut remove = None;
+
+        for (i, solid) in self.layout.solids.iter_mut().enumerate() {
+            ui.push_id(("solid", i), |ui| {
+                CollapsingHeader::new(&solid.name).show(ui, |ui| {
+                    solid.ui(ui);
+
+                    if ui.button("Remove this Solid").clicked() {
+                        remove.replace(TargetId::new(SOLID_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        for (i, emitter) in self.layout.emitters.iter_mut().enumerate() {
+            ui.push_id(("emitter", i), |ui| {
+                CollapsingHeader::new(&emitter.name).show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| emitter.ui(ui));
+
+                    if ui.button("Remove this Emitter").clicked() {
+                        remove.replace(TargetId::new(EMITTER_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        if let Some(target) = remove {
+            self.remove_target(target, state.current_step);
+        }
+
+        if before != self.layout {
+            self.record(state.current_step);
+        }
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operations");
+            ui.separator();
+            ui.horizontal(|ui| {
+                FLUID_OPERATION_ITER.iter().for_each(|operation| {
+                    ui.selectable_value(
+                        &mut self.operation,
+                        *operation,
+                        format!("{:?}", operation),
+                    );
+                });
+            });
+
+            match self.operation {
+                FluidOperation::AddWall | FluidOperation::AddContainer => {
+                    ui.separator();
+                    ui.add(
+                        DragValue::new(&mut self.solid_thickness)
+                            .speed(0.1)
+                            .clamp_range(0.5..=20.0)
+                            .prefix("thickness: "),
+                    );
+                }
+                FluidOperation::AddEmitter => {
+                    ui.separator();
+                    self.emitter_brush.ui(ui);
+                }
+                _ => {}
+            }
+        });
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        msg: InputMessage,
+        response: Response,
+        _ctx: &egui::Context,
+        state: &mut SimulationState,
+    ) {
+        let Some(pointer_pos) = msg.pointer_pos else {
+            return;
+        };
+        let pointer = NVec2::new(pointer_pos.x, pointer_pos.y);
+
+        match self.operation {
+            FluidOperation::Navigate => {
+                if response.clicked() {
+                    plot.selected = plot.pick(pointer_pos);
+                }
+            }
+            FluidOperation::Move => {
+                if response.dragged() {
+                    if !plot.dragging_object {
+                        // 드래그 시작할 때. 입자는 옮기지 않는다.
+                        plot.selected = plot
+                            .pick(pointer_pos)
+                            .filter(|target| target.id.group != PARTICLE_TARGET);
+                        plot.dragging_object = plot.selected.is_some();
+
+                        if let Some(target) = &plot.selected {
+                            self.grab_offset = target.anchor - pointer;
+                        }
+                    } else if let Some(target) = plot.selected.as_ref().map(|target| target.id) {
+                        self.move_target(target, pointer + self.grab_offset);
+                        self.record(state.current_step);
+                    }
+                } else if plot.dragging_object {
+                    // 드래그가 끝났을 때
+                    plot.dragging_object = false;
+                }
+            }
+            FluidOperation::AddFluid | FluidOperation::AddWall | FluidOperation::AddContainer => {
+                if response.dragged() {
+                    if self.drag_start.is_none() {
+                        self.drag_start = Some(pointer);
+                        plot.selected = None;
+                        plot.dragging_object = true;
+                    }
+                } else if let Some(start) = self.drag_start.take() {
+                    plot.dragging_object = false;
+
+                    if start == pointer {
+                        return;
+                    }
+
+                    match self.operation {
+                        FluidOperation::AddFluid => {
+                            self.fill(start, pointer);
+                            self.record_fluid(state.current_step);
+                        }
+                        FluidOperation::AddWall => {
+                            self.layout.solids.push(Solid::wall(
+                                format!("Wall {}", self.layout.solids.len()),
+                                start,
+                                pointer,
+                                self.solid_thickness,
+                            ));
+                            self.record(state.current_step);
+                        }
+                        _ => {
+                            self.layout.solids.push(Solid::container(
+                                format!("Container {}", self.layout.solids.len()),
+                                start,
+                                pointer,
+                                self.solid_thickness,
+                            ));
+                            self.record(state.current_step);
+                        }
+                    }
+                }
+            }
+            FluidOperation::AddEmitter => {
+                if response.clicked() {
+                    self.layout.emitters.push(Emitter {
+                        name: format!("Emitter {}", self.layout.emitters.len()),
+                        position: pointer,
+                        start: self.fluid.time,
+                        ..self.emitter_brush.clone()
+                    });
+                    self.record(state.current_step);
+                }
+            }
+            FluidOperation::Remove => {
+                if response.clicked() {
+                    if let Some(target) = plot.pick(pointer_pos) {
+                        self.remove_target(target.id, state.current_step);
+
+                        plot.selected = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, state: &mut SimulationState) {
+        puffin::profile_scope!("FluidSimulation::step");
+
+        self.settings = Self::settings(state);
+        self.advance();
+    }
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    fn pointer_edits(&self) -> bool {
+        self.operation != FluidOperation::Navigate
+    }
+
+    fn at_time_step(&mut self, step: usize) {
+        for _ in 0..self.timeline.rewind(step, &mut self.fluid) {
+            self.advance();
+        }
+
+        self.layout = self.timeline.layout_at(step).clone();
+    }
+
+    // 입자는 보던 스텝의 자리에 멈춘 채로 다시 시작한다.
+    fn layout(&self, state: &SimulationState) -> Option<SessionLayout> {
+        Some(SessionLayout::Fluid(FluidPreset {
+            tank: self.tank.clone(),
+            layout: self.layout.clone(),
+            settings: Self::settings(state),
+            particles: self.fluid.particles.iter().map(|p| p.position).collect(),
+        }))
+    }
+
+    fn plot_items(&mut self, _ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        puffin::profile_scope!("FluidSimulation::plot_items");
+
+        self.settings = Self::settings(state);
+
+        let (min, max) = (self.tank.min, self.tank.max);
+        let mut items: Vec<PlotItem> = vec![Line::new(vec![
+            [min.x, max.y],
+            [min.x, min.y],
+            [max.x, min.y],
+            [max.x, max.y],
+        ])
+        .color(PlotColor::Boundary.get_color())
+        .width(2.0)
+        .into()];
+
+        for solid in &self.layout.solids {
+            items.extend(solid.draw());
+        }
+
+        let mut buckets = vec![vec![]; COLOUR_BUCKETS];
+        for particle in &self.fluid.particles {
+            let t = self.colour_value(particle).clamp(0.0, 1.0);
+            let bucket = ((t * COLOUR_BUCKETS as f64) as usize).min(COLOUR_BUCKETS - 1);
+
+            buckets[bucket].push([particle.position.x, particle.position.y]);
+        }
+
+        // 입자 크기는 확대에 맞춰 바꾼다.
+        let radius = (self.tank.radius() * state.pixels_per_unit).max(1.0) as f32;
+        for (i, points) in buckets.into_iter().enumerate() {
+            if points.is_empty() {
+                continue;
+            }
+
+            let t = (i as f32 + 0.5) / COLOUR_BUCKETS as f32;
+            items.push(
+                Points::new(points)
+                    .color(gradient_color(&FLUID_STOPS, t))
+                    .radius(radius)
+                    .filled(true)
+                    .into(),
+            );
+        }
+
+        for emitter in &self.layout.emitters {
+            items.extend(emitter.draw());
+        }
+
+        // 드래그로 그리고 있는 모양
+        if let (Some(start), Some(pointer)) = (self.drag_start, state.pointer) {
+            let pointer = NVec2::new(pointer.x, pointer.y);
+            let outline = match self.operation {
+                FluidOperation::AddFluid => {
+                    let (a, b) = (start.inf(&pointer), start.sup(&pointer));
+                    vec![a, NVec2::new(b.x, a.y), b, NVec2::new(a.x, b.y), a]
+                }
+                FluidOperation::AddWall => {
+                    let mut outline = segment_outline(start, pointer, self.solid_thickness / 2.0);
+                    outline.extend(outline.first().cloned());
+                    outline
+                }
+                _ => Solid::container("", start, pointer, self.solid_thickness).points,
+            };
+
+            items.push(
+                Line::new(outline.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                    .color(PlotColor::Boundary.get_color())
+                    .into(),
+            );
+        }
+
+        items
+    }
+
+    // 입자 위에 벽과 관이 놓인다.
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        let particles = self
+            .fluid
+            .particles
+            .iter()
+            .enumerate()
+            .map(|(index, particle)| PickTarget {
+                id: TargetId::new(PARTICLE_TARGET, index),
+                name: format!("Particle {}", index),
+                anchor: particle.position,
+                outline: self.particle_outline(particle),
+            });
+
+        let solids = self
+            .layout
+            .solids
+            .iter()
+            .enumerate()
+            .map(|(index, solid)| PickTarget {
+                id: TargetId::new(SOLID_TARGET, index),
+                name: solid.name.clone(),
+                anchor: solid.center(),
+                outline: solid.outline(),
+            });
+
+        let emitters = self
+            .layout
+            .emitters
+            .iter()
+            .enumerate()
+            .map(|(index, emitter)| PickTarget {
+                id: TargetId::new(EMITTER_TARGET, index),
+                name: emitter.name.clone(),
+                anchor: emitter.position,
+                outline: emitter.outline(),
+            });
+
+        particles.chain(solids).chain(emitters).collect()
+    }
+
+    fn inspect(&self, target: TargetId, state: &SimulationState) -> Vec<(String, String)> {
+        let settings = Self::settings(state);
+        let point = |p: NVec2| format!("[{:.2}, {:.2}]", p.x, p.y);
+
+        match target.group {
+            PARTICLE_TARGET => {
+                let Some(particle) = self.fluid.particles.get(target.index) else {
+                    return vec![];
+                };
+
+                vec![
+                    ("Position".into(), point(particle.position)),
+                    ("Velocity".into(), point(particle.velocity)),
+                    ("Speed".into(), format!("{:.3}", particle.velocity.norm())),
+                    ("Density".into(), format!("{:.2}", particle.density)),
+                    (
+                        "Density Error".into(),
+                        format!(
+                            "{:+.2}%",
+                            (particle.density / settings.rest_density - 1.0) * 100.0
+                        ),
+                    ),
+                    ("Pressure".into(), format!("{:.1}", particle.pressure)),
+                    (
+                        "Neighbours".into(),
+                        format!("{}", self.fluid.neighbour_count(&self.tank, target.index)),
+                    ),
+                ]
+            }
+            SOLID_TARGET => {
+                let Some(solid) = self.layout.solids.get(target.index) else {
+                    return vec![];
+                };
+
+                vec![
+                    ("Segments".into(), format!("{}", solid.segments().count())),
+                    ("Center".into(), point(solid.center())),
+                    ("Thickness".into(), format!("{:.2}", solid.thickness)),
+                ]
+            }
+            EMITTER_TARGET => {
+                let Some(emitter) = self.layout.emitters.get(target.index) else {
+                    return vec![];
+                };
+
+                // 한 줄에 놓는 입자 수 × 초당 줄 수
+                let per_row = (emitter.width / self.tank.spacing).round().max(1.0);
+                let rate = per_row * emitter.speed / self.tank.spacing;
+
+                vec![
+                    ("Position".into(), point(emitter.position)),
+                    (
+                        "Angle".into(),
+                        format!("{:.1}°", emitter.angle.to_degrees()),
+                    ),
+                    ("Speed".into(), format!("{:.2}", emitter.speed)),
+                    ("Width".into(), format!("{:.2}", emitter.width)),
+                    ("Flow Rate".into(), format!("{:.1} particles/s", rate)),
+                    (
+                        "Active".into(),
+                        format!(
+                            "{:.2} s - {:.2} s",
+                            emitter.start,
+                            emitter.start + emitter.duration
+                        ),
+                    ),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::app::graphics::define::items::{Arrows, Line, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::fluid::layout::{Emitter, Solid};
+use crate::engine::polygon::segment_outline;
+use egui::{DragValue, Ui};
+
+impl Solid {
+    // 오목한 다각형은 제대로 칠해지지 않으므로 선분마다 따로 그린다.
+    pub fn draw(&self) -> Vec<PlotItem> {
+        self.segments()
+            .map(|(a, b)| {
+                Polygon::new(
+                    segment_outline(a, b, self.thickness / 2.0)
+                        .iter()
+                        .map(|p| [p.x, p.y])
+                        .collect::<Vec<_>>(),
+                )
+                .color(PlotColor::Boundary.get_color())
+                .fill_alpha(0.8)
+                .name(&self.name)
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            DragValue::new(&mut self.thickness)
+                .speed(0.1)
+                .clamp_range(0.5..=20.0)
+                .prefix("thickness: "),
+        );
+    }
+}
+
+impl Emitter {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        let (a, b) = self.endpoints();
+        let tip = self.position + self.direction() * 6.0;
+
+        vec![
+            Line::new(vec![[a.x, a.y], [b.x, b.y]])
+                .color(PlotColor::FluidEmitter.get_color())
+                .width(3.0)
+                .name(&self.name)
+                .into(),
+            Arrows::new([self.position.x, self.position.y], [tip.x, tip.y])
+                .color(PlotColor::FluidEmitter.get_color())
+                .into(),
+        ]
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            DragValue::new(&mut self.speed)
+                .speed(0.5)
+                .clamp_range(1.0..=200.0)
+                .prefix("v: "),
+        );
+        ui.add(
+            DragValue::new(&mut self.width)
+                .speed(0.1)
+                .clamp_range(1.0..=40.0)
+                .prefix("width: "),
+        );
+
+        let mut degree = self.angle.to_degrees();
+        if ui.add(DragValue::new(&mut degree).suffix("°")).changed() {
+            self.angle = degree.to_radians();
+        }
+
+        ui.add(
+            DragValue::new(&mut self.duration)
+                .speed(0.1)
+                .clamp_range(0.0..=120.0)
+                .prefix("for ")
+                .suffix(" s"),
+        );
+    }
+}
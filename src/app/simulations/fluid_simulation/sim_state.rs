@@ -0,0 +1,44 @@
+use crate::engine::fluid::sim_state::{FluidSettings, FluidView};
+use egui::{ComboBox, DragValue, Slider, Widget};
+
+impl FluidSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Fluid Settings", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Rest Density");
+                ui.add(
+                    DragValue::new(&mut self.rest_density)
+                        .speed(1.0)
+                        .clamp_range(100.0..=5000.0),
+                );
+            });
+
+            Slider::new(&mut self.stiffness, 1e4..=4e6)
+                .logarithmic(true)
+                .text("Stiffness")
+                .ui(ui);
+
+            Slider::new(&mut self.viscosity, 0.0..=500.0)
+                .logarithmic(true)
+                .text("Viscosity")
+                .ui(ui);
+
+            ui.horizontal(|ui| {
+                ui.label("Gravity");
+                ui.add(
+                    DragValue::new(&mut self.gravity)
+                        .speed(0.5)
+                        .clamp_range(0.0..=500.0),
+                );
+            });
+
+            ComboBox::from_label("Colour")
+                .selected_text(format!("{:?}", self.view))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.view, FluidView::Speed, "Speed");
+                    ui.selectable_value(&mut self.view, FluidView::Density, "Density");
+                    ui.selectable_value(&mut self.view, FluidView::Pressure, "Pressure");
+                });
+        });
+    }
+}
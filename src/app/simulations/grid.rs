@@ -0,0 +1,62 @@
+use crate::app::graphics::define::PlotItem;
+use crate::app::graphics::image::ImageManager;
+use crate::engine::grid::Grid;
+use egui::{Color32, ColorImage};
+
+impl Grid {
+    /// 칸마다 색을 칠한 이미지. 이미지는 위에서부터 채우므로 y를 뒤집는다.
+    pub fn image(&self, color: impl Fn(usize) -> Color32) -> ColorImage {
+        let mut pixels = Vec::with_capacity(self.len());
+
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                pixels.push(color(self.index(x, y)));
+            }
+        }
+
+        ColorImage {
+            size: [self.width, self.height],
+            pixels,
+        }
+    }
+}
+
+/// 격자를 칠한 이미지를 텍스처로 올려 플롯에 그린다.
+#[derive(Clone, Default)]
+pub struct GridTexture {
+    images: ImageManager,
+    texture: Option<usize>,
+}
+
+impl GridTexture {
+    pub fn plot_item(
+        &mut self,
+        ctx: &egui::Context,
+        name: &str,
+        grid: &Grid,
+        image: ColorImage,
+    ) -> PlotItem {
+        let texture = match self.texture {
+            Some(index) => {
+                self.images.update(index, image);
+                index
+            }
+            None => *self.texture.insert(self.images.load(ctx, name, image)),
+        };
+
+        self.images
+            .get_plot_image(texture, grid.center(), grid.size().y)
+            .into()
+    }
+}
+
+/// `stops`를 고르게 놓은 그라디언트에서 `t` (0 ~ 1) 위치의 색
+pub fn gradient_color(stops: &[[f32; 3]], t: f32) -> Color32 {
+    let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+    let index = (t as usize).min(stops.len() - 2);
+    let local = t - index as f32;
+
+    let lerp = |c: usize| (stops[index][c] + (stops[index + 1][c] - stops[index][c]) * local) as u8;
+
+    Color32::from_rgb(lerp(0), lerp(1), lerp(2))
+}
@@ -0,0 +1,622 @@
+pub mod field;
+pub mod layout;
+pub mod sim_state;
+
+use crate::app::graphics::define::items::Polygon;
+use crate::app::NVec2;
+
+use egui::plot::{Legend, Line, Plot};
+use egui::{CollapsingHeader, Response, Ui};
+
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::manager::SIMULATION_TICK;
+use crate::app::session::SessionLayout;
+use crate::app::simulations::grid::GridTexture;
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation, TargetId};
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::grid::Grid;
+use crate::engine::keyframe::KeyframeTimeline;
+
+use crate::engine::heat::field::{substeps, HeatField, HeatMaps};
+use crate::engine::heat::layout::{
+    HeatBrush, HeatLayout, HeatRegion, Probe, RegionKind, EDGE_NAMES,
+};
+use crate::engine::heat::sim_state::HeatSettings;
+use crate::engine::heat::template::HeatPreset;
+
+/// `TargetId::group` 값
+const REGION_TARGET: usize = 0;
+const PROBE_TARGET: usize = 1;
+
+/// 이 간격마다 격자 전체를 저장하고, 그 사이 스텝은 다시 계산한다.
+const KEYFRAME_INTERVAL: usize = 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeatOperation {
+    #[default]
+    Navigate,
+    Move,
+    Paint,
+    AddRegion,
+    AddProbe,
+    Remove,
+}
+
+const HEAT_OPERATION_ITER: [HeatOperation; 6] = [
+    HeatOperation::Navigate,
+    HeatOperation::Move,
+    HeatOperation::Paint,
+    HeatOperation::AddRegion,
+    HeatOperation::AddProbe,
+    HeatOperation::Remove,
+];
+
+/// 격자 위에서 열방정식을 푸는 시뮬레이션. 물질 영역, 온도가 고정된 경계, 칠한 열원과
+/// 온도를 기록하는 탐침으로 이루어진다.
+#[derive(Clone)]
+pub struct HeatSimulation {
+    pub grid: Grid,
+    pub layout: HeatLayout,
+    field: HeatField,
+    /// 칸마다의 물성과 그걸 만든 영역들, 바깥 열확산 계수
+    maps: HeatMaps,
+    maps_key: (Vec<HeatRegion>, f64),
+    timeline: KeyframeTimeline<HeatLayout, HeatField>,
+    /// 스텝마다의 (시간, 탐침 온도)
+    history: ChunkedVec<(f64, Vec<f32>)>,
+    /// 타임라인을 다시 계산할 때 쓸 마지막 설정
+    settings: HeatSettings,
+    /// 처음 온도. 세션에 배치를 저장할 때 함께 남긴다.
+    initial: f64,
+
+    texture: GridTexture,
+
+    pub operation: HeatOperation,
+    pub brush: HeatBrush,
+    pub region_brush: RegionKind,
+    /// 그리고 있는 영역의 시작점
+    region_start: Option<NVec2>,
+    /// 드래그를 시작할 때 대상 위치와 포인터의 차이
+    grab_offset: NVec2,
+}
+
+impl From<HeatPreset> for HeatSimulation {
+    fn from(preset: HeatPreset) -> Self {
+        let field = HeatField::new(&preset.grid, preset.initial);
+        let maps = HeatMaps::new(&preset.grid, &preset.layout, &preset.settings);
+
+        let mut simulation = Self {
+            maps,
+            maps_key: (preset.layout.regions.clone(), preset.settings.diffusivity),
+            timeline: KeyframeTimeline::new(
+                preset.layout.clone(),
+                field.clone(),
+                KEYFRAME_INTERVAL,
+            ),
+            history: ChunkedVec::new(),
+            grid: preset.grid,
+            layout: preset.layout,
+            field,
+            settings: preset.settings,
+            initial: preset.initial,
+            texture: GridTexture::default(),
+            operation: HeatOperation::default(),
+            brush: HeatBrush::default(),
+            region_brush: RegionKind::default(),
+            region_start: None,
+            grab_offset: NVec2::zeros(),
+        };
+
+        simulation.sample();
+        simulation
+    }
+}
+
+impl HeatSimulation {
+    fn settings(state: &SimulationState) -> HeatSettings {
+        state
+            .settings
+            .specific
+            .as_heat_settings()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn refresh_maps(&mut self) {
+        if self.maps_key.0 != self.layout.regions || self.maps_key.1 != self.settings.diffusivity {
+            self.maps = HeatMaps::new(&self.grid, &self.layout, &self.settings);
+            self.maps_key = (self.layout.regions.clone(), self.settings.diffusivity);
+        }
+    }
+
+    // 지금 스텝의 탐침 온도를 기록한다.
+    fn sample(&mut self) {
+        let temperatures = self
+            .layout
+            .probes
+            .iter()
+            .map(|probe| {
+                self.field
+                    .value_at(&self.grid, probe.position)
+                    .unwrap_or(f64::NAN) as f32
+            })
+            .collect();
+
+        let sample = (self.field.time, temperatures);
+        match self.history.get_mut(self.field.step) {
+            Some(entry) => *entry = sample,
+            None => self.history.push(sample),
+        }
+    }
+
+    /// 그 스텝의 배치로 한 스텝 진행하고, 간격마다 격자를 저장한다.
+    fn advance(&mut self) {
+        self.layout = self.timeline.layout_at(self.field.step).clone();
+        self.refresh_maps();
+
+        self.field.advance(
+            &self.grid,
+            &self.layout,
+            &self.maps,
+            SIMULATION_TICK * self.settings.time_scale,
+        );
+
+        self.timeline.store(self.field.step, &self.field);
+        self.sample();
+    }
+
+    // 현재 배치를 타임라인의 `step`부터 쓴다. 그 뒤의 격자와 기록은 다시 계산해야 한다.
+    fn record(&mut self, step: usize) {
+        self.timeline.record(step, self.layout.clone());
+        self.history.truncate(step + 1);
+        self.sample();
+    }
+
+    fn move_target(&mut self, target: TargetId, anchor: NVec2) {
+        match target.group {
+            REGION_TARGET => {
+                if let Some(region) = self.layout.regions.get_mut(target.index) {
+                    let delta = anchor - region.center();
+                    region.min += delta;
+                    region.max += delta;
+                }
+            }
+            PROBE_TARGET => {
+                if let Some(probe) = self.layout.probes.get_mut(target.index) {
+                    probe.position = anchor;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn remove_target(&mut self, target: TargetId) {
+        match target.group {
+            REGION_TARGET if target.index < self.layout.regions.len() => {
+                self.layout.regions.remove(target.index);
+            }
+            PROBE_TARGET if target.index < self.layout.probes.len() => {
+                self.layout.probes.remove(target.index);
+            }
+            _ => {}
+        }
+    }
+
+    /// 지금까지 기록한 탐침 하나의 (시간, 온도)
+    fn probe_history(&self, index: usize) -> Vec<[f64; 2]> {
+        self.history
+            .iter()
+            .take(self.field.step + 1)
+            .filter_map(|(time, temperatures)| {
+                temperatures
+                    .get(index)
+                    .map(|temperature| [*time, *temperature as f64])
+            })
+            .collect()
+    }
+}
+
+impl Simulation for HeatSimulation {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let before = self.layout.clone();
+        let (min, max, mean) = self.field.stats();
+
+        egui::Grid::new("heat_summary").show(ui, |ui| {
+            ui.label("Grid");
+            ui.label(format!(
+                "{} x {} (cell {:.2})",
+                self.grid.width, self.grid.height, self.grid.cell
+            ));
+            ui.end_row();
+
+            ui.label("Substeps");
+            ui.label(format!(
+                "{}",
+                substeps(
+                    &self.grid,
+                    &self.maps,
+                    SIMULATION_TICK * self.settings.time_scale
+                )
+            ));
+            ui.end_row();
+
+            ui.label("Heat Time");
+            ui.label(format!("{:.2} s", self.field.time));
+            ui.end_row();
+
+            ui.label("Min / Max / Mean");
+            ui.label(format!("{:.2} / {:.2} / {:.2} °C", min, max, mean));
+            ui.end_row();
+
+            ui.label("Heat Source Power");
+            ui.label(format!("{:.2}", self.layout.source_power(&self.grid)));
+            ui.end_row();
+
+            if let Some(pointer) = state.pointer {
+                if let Some(temperature) = self
+                    .field
+                    .value_at(&self.grid, NVec2::new(pointer.x, pointer.y))
+                {
+                    ui.label("Pointer Temperature");
+                    ui.label(format!("{:.2} °C", temperature));
+                    ui.end_row();
+                }
+            }
+        });
+
+        if !self.layout.probes.is_empty() {
+            ui.label("Probe Temperature");
+            Plot::new("probe_history")
+                .height(160.0)
+                .legend(Legend::default())
+                .allow_drag(false)
+                .allow_zoom(false)
+                .allow_scroll(false)
+                .show(ui, |plot_ui| {
+                    for (index, probe) in self.layout.probes.iter().enumerate() {
+                        plot_ui.line(Line::new(self.probe_history(index)).name(&probe.name));
+                    }
+                });
+        }
+
+        ui.collapsing("Edges", |ui| {
+            for (edge, name) in self.layout.edges.iter_mut().zip(EDGE_NAMES) {
+                edge.ui(ui, name);
+            }
+        });
+
+        let mut remove = None;
+
+        for (i, region) in self.layout.regions.iter_mut().enumerate() {
+            ui.push_id(("region", i), |ui| {
+                CollapsingHeader::new(&region.name).show(ui, |ui| {
+                    region.kind.ui(ui);
+
+                    if ui.button("Remove this Region").clicked() {
+                        remove.replace(TargetId::new(REGION_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        for (i, probe) in self.layout.probes.iter().enumerate() {
+            ui.push_id(("probe", i), |ui| {
+                CollapsingHeader::new(&probe.name).show(ui, |ui| {
+                    if ui.button("Remove this Probe").clicked() {
+                        remove.replace(TargetId::new(PROBE_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        if ui.button("Clear Heat Sources").clicked() {
+            self.layout.sources.clear();
+        }
+
+        if let Some(target) = remove {
+            self.remove_target(target);
+        }
+
+        if before != self.layout {
+            self.record(state.current_step);
+        }
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operations");
+            ui.separator();
+            ui.horizontal(|ui| {
+                HEAT_OPERATION_ITER.iter().for_each(|operation| {
+                    ui.selectable_value(
+                        &mut self.operation,
+                        *operation,
+                        format!("{:?}", operation),
+                    );
+                });
+            });
+
+            if self.operation == HeatOperation::Paint {
+                ui.separator();
+                self.brush.ui(ui);
+            }
+
+            if self.operation == HeatOperation::AddRegion {
+                ui.separator();
+                self.region_brush.ui(ui);
+            }
+        });
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        msg: InputMessage,
+        response: Response,
+        _ctx: &egui::Context,
+        state: &mut SimulationState,
+    ) {
+        let Some(pointer_pos) = msg.pointer_pos else {
+            return;
+        };
+        let pointer = NVec2::new(pointer_pos.x, pointer_pos.y);
+
+        match self.operation {
+            HeatOperation::Navigate => {
+                if response.clicked() {
+                    plot.selected = plot.pick(pointer_pos);
+                }
+            }
+            HeatOperation::Move => {
+                if response.dragged() {
+                    if !plot.dragging_object {
+                        // 드래그 시작할 때
+                        plot.selected = plot.pick(pointer_pos);
+                        plot.dragging_object = plot.selected.is_some();
+
+                        if let Some(target) = &plot.selected {
+                            self.grab_offset = target.anchor - pointer;
+                        }
+                    } else if let Some(target) = plot.selected.as_ref().map(|target| target.id) {
+                        self.move_target(target, pointer + self.grab_offset);
+                        self.record(state.current_step);
+                    }
+                } else if plot.dragging_object {
+                    // 드래그가 끝났을 때
+                    plot.dragging_object = false;
+                }
+            }
+            HeatOperation::Paint => {
+                if response.dragged() || response.clicked() {
+                    // 칠하는 동안 플롯이 움직이지 않게 한다.
+                    plot.selected = None;
+                    plot.dragging_object = response.dragged();
+
+                    self.layout.paint(&self.grid, pointer, self.brush);
+                    self.record(state.current_step);
+                } else if plot.dragging_object {
+                    plot.dragging_object = false;
+                }
+            }
+            HeatOperation::AddRegion => {
+                if response.dragged() {
+                    // 드래그 시작점을 영역의 한 꼭짓점으로 쓴다.
+                    if self.region_start.is_none() {
+                        self.region_start = Some(pointer);
+                        plot.selected = None;
+                        plot.dragging_object = true;
+                    }
+                } else if let Some(start) = self.region_start.take() {
+                    plot.dragging_object = false;
+
+                    if start.x != pointer.x && start.y != pointer.y {
+                        self.layout.regions.push(HeatRegion::new(
+                            format!(
+                                "{} {}",
+                                self.region_brush.get_name(),
+                                self.layout.regions.len()
+                            ),
+                            start,
+                            pointer,
+                            self.region_brush,
+                        ));
+                        self.record(state.current_step);
+                    }
+                }
+            }
+            HeatOperation::AddProbe => {
+                if response.clicked() {
+                    self.layout.probes.push(Probe {
+                        name: format!("Probe {}", self.layout.probes.len()),
+                        position: pointer,
+                    });
+                    self.record(state.current_step);
+                }
+            }
+            HeatOperation::Remove => {
+                if response.clicked() {
+                    if let Some(target) = plot.pick(pointer_pos) {
+                        self.remove_target(target.id);
+
+                        plot.selected = None;
+                        self.record(state.current_step);
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, state: &mut SimulationState) {
+        puffin::profile_scope!("HeatSimulation::step");
+
+        self.settings = Self::settings(state);
+        self.advance();
+    }
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    fn pointer_edits(&self) -> bool {
+        self.operation != HeatOperation::Navigate
+    }
+
+    fn at_time_step(&mut self, step: usize) {
+        for _ in 0..self.timeline.rewind(step, &mut self.field) {
+            self.advance();
+        }
+
+        self.layout = self.timeline.layout_at(step).clone();
+    }
+
+    fn layout(&self, state: &SimulationState) -> Option<SessionLayout> {
+        Some(SessionLayout::Heat(HeatPreset {
+            grid: self.grid,
+            layout: self.layout.clone(),
+            settings: Self::settings(state),
+            initial: self.initial,
+        }))
+    }
+
+    fn plot_items(&mut self, ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        puffin::profile_scope!("HeatSimulation::plot_items");
+
+        self.settings = Self::settings(state);
+        self.refresh_maps();
+
+        let image = self
+            .field
+            .to_image(&self.grid, &self.layout, &self.settings);
+
+        let mut items = vec![self.texture.plot_item(ctx, "heat_field", &self.grid, image)];
+
+        for region in &self.layout.regions {
+            items.extend(region.draw());
+        }
+
+        for probe in &self.layout.probes {
+            items.extend(probe.draw());
+        }
+
+        // 그리고 있는 영역
+        if let (Some(start), Some(pointer)) = (self.region_start, state.pointer) {
+            let preview = HeatRegion::new(
+                "",
+                start,
+                NVec2::new(pointer.x, pointer.y),
+                self.region_brush,
+            );
+
+            items.push(
+                Polygon::new(
+                    preview
+                        .outline()
+                        .iter()
+                        .map(|p| [p.x, p.y])
+                        .collect::<Vec<_>>(),
+                )
+                .color(PlotColor::Selected.get_color())
+                .fill_alpha(0.1)
+                .into(),
+            );
+        }
+
+        items
+    }
+
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        let regions = self
+            .layout
+            .regions
+            .iter()
+            .enumerate()
+            .map(|(index, region)| PickTarget {
+                id: TargetId::new(REGION_TARGET, index),
+                name: region.name.clone(),
+                anchor: region.center(),
+                outline: region.outline(),
+            });
+
+        let probes = self
+            .layout
+            .probes
+            .iter()
+            .enumerate()
+            .map(|(index, probe)| PickTarget {
+                id: TargetId::new(PROBE_TARGET, index),
+                name: probe.name.clone(),
+                anchor: probe.position,
+                outline: probe.outline(),
+            });
+
+        regions.chain(probes).collect()
+    }
+
+    fn inspect(&self, target: TargetId, _state: &SimulationState) -> Vec<(String, String)> {
+        let point = |p: NVec2| format!("[{:.2}, {:.2}]", p.x, p.y);
+
+        match target.group {
+            REGION_TARGET => {
+                let Some(region) = self.layout.regions.get(target.index) else {
+                    return vec![];
+                };
+
+                let mut rows = vec![
+                    ("Type".to_string(), region.kind.get_name().to_string()),
+                    ("Min".into(), point(region.min)),
+                    ("Max".into(), point(region.max)),
+                ];
+
+                match region.kind {
+                    RegionKind::Material { diffusivity } => {
+                        rows.push(("Diffusivity".into(), format!("{:.2}", diffusivity)));
+                    }
+                    RegionKind::Fixed { temperature } => {
+                        rows.push(("Temperature".into(), format!("{:.2} °C", temperature)));
+                    }
+                }
+
+                if let Some(mean) = self
+                    .field
+                    .mean_where(&self.grid, |position| region.contains(position))
+                {
+                    rows.push(("Mean Temperature".into(), format!("{:.2} °C", mean)));
+                }
+
+                rows
+            }
+            PROBE_TARGET => {
+                let Some(probe) = self.layout.probes.get(target.index) else {
+                    return vec![];
+                };
+
+                let history = self.probe_history(target.index);
+                let mut rows = vec![("Position".to_string(), point(probe.position))];
+
+                if let Some(temperature) = self.field.value_at(&self.grid, probe.position) {
+                    rows.push(("Temperature".into(), format!("{:.2} °C", temperature)));
+                }
+
+                let (low, high) = history
+                    .iter()
+                    .fold((f64::MAX, f64::MIN), |(low, high), [_, t]| {
+                        (low.min(*t), high.max(*t))
+                    });
+                if low <= high {
+                    rows.push(("Min / Max".into(), format!("{:.2} / {:.2} °C", low, high)));
+                }
+
+                if let [.., [t0, a], [t1, b]] = history.as_slice() {
+                    if t1 > t0 {
+                        rows.push(("dT/dt".into(), format!("{:.3} °C/s", (b - a) / (t1 - t0))));
+                    }
+                }
+
+                rows
+            }
+            _ => vec![],
+        }
+    }
+}
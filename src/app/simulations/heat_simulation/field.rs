@@ -0,0 +1,52 @@
+use crate::app::simulations::grid::gradient_color;
+use crate::engine::grid::Grid;
+use crate::engine::heat::field::HeatField;
+use crate::engine::heat::layout::HeatLayout;
+use crate::engine::heat::sim_state::HeatSettings;
+use egui::{Color32, ColorImage};
+
+/// 온도 색: 남색 - 파랑 - 청록 - 노랑 - 빨강
+const THERMAL_STOPS: [[f32; 3]; 5] = [
+    [20.0, 30.0, 110.0],
+    [40.0, 110.0, 230.0],
+    [80.0, 210.0, 210.0],
+    [250.0, 220.0, 80.0],
+    [220.0, 50.0, 40.0],
+];
+
+impl HeatField {
+    pub fn to_image(
+        &self,
+        grid: &Grid,
+        layout: &HeatLayout,
+        settings: &HeatSettings,
+    ) -> ColorImage {
+        let (min, max) = (settings.min_temperature, settings.max_temperature);
+        let range = (max - min).max(1e-6);
+
+        grid.image(|i| {
+            let t = (self.temperature[i] as f64 - min) / range;
+            let color = gradient_color(&THERMAL_STOPS, t as f32);
+
+            match layout.sources.get(i) {
+                // 열원은 밝게, 냉각원은 어둡게 표시한다.
+                Some(q) if settings.show_sources && *q != 0.0 => {
+                    let target = if *q > 0.0 {
+                        Color32::WHITE
+                    } else {
+                        Color32::BLACK
+                    };
+
+                    blend(color, target, 0.3)
+                }
+                _ => color,
+            }
+        })
+    }
+}
+
+fn blend(a: Color32, b: Color32, ratio: f32) -> Color32 {
+    let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * ratio) as u8;
+
+    Color32::from_rgb(mix(a.r(), b.r()), mix(a.g(), b.g()), mix(a.b(), b.b()))
+}
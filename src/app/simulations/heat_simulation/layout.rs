@@ -0,0 +1,104 @@
+use crate::app::graphics::define::items::Polygon;
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::heat::layout::{EdgeCondition, HeatBrush, HeatRegion, Probe, RegionKind};
+use egui::{ComboBox, DragValue, Ui};
+
+impl RegionKind {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut is_fixed = matches!(self, RegionKind::Fixed { .. });
+
+        ComboBox::from_label("Region")
+            .selected_text(self.get_name())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut is_fixed, false, "Material");
+                ui.selectable_value(&mut is_fixed, true, "Fixed");
+            });
+
+        match (is_fixed, &mut *self) {
+            (true, RegionKind::Material { .. }) => *self = RegionKind::Fixed { temperature: 100.0 },
+            (false, RegionKind::Fixed { .. }) => *self = RegionKind::default(),
+            (_, RegionKind::Material { diffusivity }) => {
+                ui.add(
+                    DragValue::new(diffusivity)
+                        .speed(0.5)
+                        .clamp_range(0.1..=500.0)
+                        .prefix("α: "),
+                );
+            }
+            (_, RegionKind::Fixed { temperature }) => {
+                ui.add(DragValue::new(temperature).speed(0.5).suffix("°C"));
+            }
+        }
+    }
+}
+
+impl HeatRegion {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        let color = match self.kind {
+            RegionKind::Material { .. } => PlotColor::Boundary,
+            RegionKind::Fixed { .. } => PlotColor::FixedTemperature,
+        };
+
+        vec![Polygon::new(
+            self.outline()
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>(),
+        )
+        .color(color.get_color())
+        .fill_alpha(0.05)
+        .name(&self.name)
+        .into()]
+    }
+}
+
+impl EdgeCondition {
+    pub fn ui(&mut self, ui: &mut Ui, label: &str) {
+        ui.horizontal(|ui| {
+            let mut is_fixed = matches!(self, EdgeCondition::Fixed(_));
+            ui.checkbox(&mut is_fixed, format!("{} Fixed", label));
+
+            match (is_fixed, &mut *self) {
+                (true, EdgeCondition::Insulated) => *self = EdgeCondition::Fixed(20.0),
+                (false, EdgeCondition::Fixed(_)) => *self = EdgeCondition::Insulated,
+                (_, EdgeCondition::Fixed(temperature)) => {
+                    ui.add(DragValue::new(temperature).speed(0.5).suffix("°C"));
+                }
+                _ => {}
+            }
+        });
+    }
+}
+
+impl Probe {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        vec![Polygon::new(
+            self.outline()
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>(),
+        )
+        .color(PlotColor::Probe.get_color())
+        .fill_alpha(0.8)
+        .name(&self.name)
+        .into()]
+    }
+}
+
+impl HeatBrush {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            DragValue::new(&mut self.power)
+                .speed(0.5)
+                .clamp_range(-200.0..=200.0)
+                .suffix(" °C/s"),
+        );
+        ui.add(
+            DragValue::new(&mut self.radius)
+                .speed(0.1)
+                .clamp_range(1.0..=50.0)
+                .prefix("r: "),
+        );
+        ui.checkbox(&mut self.erase, "Erase");
+    }
+}
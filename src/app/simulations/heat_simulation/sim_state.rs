@@ -0,0 +1,38 @@
+use crate::engine::heat::sim_state::HeatSettings;
+use egui::{DragValue, Slider, Widget};
+
+impl HeatSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Heat Settings", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Diffusivity");
+                ui.add(
+                    DragValue::new(&mut self.diffusivity)
+                        .speed(0.1)
+                        .clamp_range(0.1..=500.0),
+                );
+            });
+
+            Slider::new(&mut self.time_scale, 1.0..=100.0)
+                .logarithmic(true)
+                .text("Time Scale")
+                .ui(ui);
+
+            ui.horizontal(|ui| {
+                ui.label("Colour Range");
+                ui.add(
+                    DragValue::new(&mut self.min_temperature)
+                        .speed(0.5)
+                        .suffix("°C"),
+                );
+                ui.add(
+                    DragValue::new(&mut self.max_temperature)
+                        .speed(0.5)
+                        .suffix("°C"),
+                );
+            });
+
+            ui.checkbox(&mut self.show_sources, "Show Heat Sources");
+        });
+    }
+}
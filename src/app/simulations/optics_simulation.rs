@@ -0,0 +1,613 @@
+pub mod element;
+pub mod light;
+pub mod ray;
+pub mod sim_state;
+
+use crate::app::graphics::define::items::Line;
+use crate::app::NVec2;
+
+use egui::{CollapsingHeader, Response, Ui};
+
+use crate::app::graphics::define::PlotItem;
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::session::SessionLayout;
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation, TargetId};
+
+use self::ray::wavelength_color;
+use crate::engine::keyframe::KeyframeTimeline;
+use crate::engine::optics::element::{ElementKind, OpticalElement};
+use crate::engine::optics::light::{LightSource, SourceKind, Spectrum};
+use crate::engine::optics::ray::{closest_point, RayPath};
+use crate::engine::optics::sim_state::OpticsSettings;
+use crate::engine::optics::template::OpticsPreset;
+
+/// `TargetId::group` 값
+const ELEMENT_TARGET: usize = 0;
+const SOURCE_TARGET: usize = 1;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpticsOperation {
+    #[default]
+    Navigate,
+    Move,
+    Rotate,
+    AddElement,
+    AddSource,
+    Remove,
+}
+
+const OPTICS_OPERATION_ITER: [OpticsOperation; 6] = [
+    OpticsOperation::Navigate,
+    OpticsOperation::Move,
+    OpticsOperation::Rotate,
+    OpticsOperation::AddElement,
+    OpticsOperation::AddSource,
+    OpticsOperation::Remove,
+];
+
+/// 소자 하나에 대해 렌즈 방정식으로 구한 상과 광선 추적으로 찾은 상.
+pub struct ImageReport {
+    pub source: String,
+    /// 물체 거리. 평행광이면 None (무한대)
+    pub object_distance: Option<f64>,
+    pub magnification: Option<f64>,
+    pub predicted: Option<NVec2>,
+    pub traced: Option<NVec2>,
+}
+
+/// 광원과 거울, 렌즈, 프리즘으로 이루어진 기하 광학 시뮬레이션.
+/// 광선은 매 프레임 현재 배치로 다시 추적하고, 타임라인에는 배치만 저장한다.
+#[derive(Clone)]
+pub struct OpticsSimulation {
+    pub elements: Vec<OpticalElement>,
+    pub sources: Vec<LightSource>,
+    /// 다시 계산할 것이 없으므로 배치만 남긴다.
+    timeline: KeyframeTimeline<(Vec<OpticalElement>, Vec<LightSource>), ()>,
+
+    pub operation: OpticsOperation,
+    pub element_brush: ElementKind,
+    pub source_brush: LightSource,
+    /// 드래그를 시작할 때 대상 위치와 포인터의 차이
+    grab_offset: NVec2,
+}
+
+impl From<OpticsPreset> for OpticsSimulation {
+    fn from(preset: OpticsPreset) -> Self {
+        Self {
+            timeline: KeyframeTimeline::new(
+                (preset.elements.clone(), preset.sources.clone()),
+                (),
+                1,
+            ),
+            elements: preset.elements,
+            sources: preset.sources,
+            operation: OpticsOperation::default(),
+            element_brush: ElementKind::default(),
+            source_brush: LightSource::default(),
+            grab_offset: NVec2::zeros(),
+        }
+    }
+}
+
+impl OpticsSimulation {
+    fn settings(state: &SimulationState) -> OpticsSettings {
+        state
+            .settings
+            .specific
+            .as_optics_settings()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn trace(&self, settings: &OpticsSettings) -> Vec<RayPath> {
+        let surfaces = self
+            .elements
+            .iter()
+            .enumerate()
+            .flat_map(|(index, element)| element.surfaces(index))
+            .collect::<Vec<_>>();
+
+        let mut paths = vec![];
+
+        for (index, source) in self.sources.iter().enumerate() {
+            for wavelength in source.spectrum.wavelengths() {
+                for (origin, direction) in source.rays() {
+                    paths.push(RayPath::trace(
+                        origin,
+                        direction,
+                        wavelength,
+                        index,
+                        &surfaces,
+                        settings.ambient_index,
+                        settings.max_bounces,
+                    ));
+                }
+            }
+        }
+
+        paths
+    }
+
+    // 현재 배치를 타임라인의 `step`부터 쓴다.
+    fn record(&mut self, step: usize) {
+        self.timeline
+            .record(step, (self.elements.clone(), self.sources.clone()));
+    }
+
+    fn target_pose_mut(&mut self, target: TargetId) -> Option<(&mut NVec2, &mut f64)> {
+        match target.group {
+            ELEMENT_TARGET => self
+                .elements
+                .get_mut(target.index)
+                .map(|element| (&mut element.position, &mut element.angle)),
+            SOURCE_TARGET => self
+                .sources
+                .get_mut(target.index)
+                .map(|source| (&mut source.position, &mut source.angle)),
+            _ => None,
+        }
+    }
+
+    /// 초점 거리가 있는 소자에 대해 광원마다 상의 위치를 구한다.
+    pub fn image_reports(&self, element_index: usize, paths: &[RayPath]) -> Vec<ImageReport> {
+        let element = &self.elements[element_index];
+        let Some(focal_length) = element.focal_length() else {
+            return vec![];
+        };
+
+        let axis = element.axis();
+        let lateral = NVec2::new(-axis.y, axis.x);
+
+        self.sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| {
+                let lines = paths
+                    .iter()
+                    .filter(|path| path.source == index)
+                    .filter_map(|path| path.exit_line(element_index))
+                    .collect::<Vec<_>>();
+
+                let (object_distance, magnification, predicted) = match source.kind {
+                    SourceKind::Point { .. } => {
+                        let relative = source.position - element.position;
+                        let (u, h) = (relative.dot(&axis), relative.dot(&lateral));
+                        let object_distance = u.abs();
+
+                        // 1/d_o + 1/d_i = 1/f, m = -d_i / d_o
+                        let power = 1.0 / focal_length - 1.0 / object_distance;
+                        let image = (power.abs() > 1e-9).then(|| {
+                            let image_distance = 1.0 / power;
+                            let magnification = -image_distance / object_distance;
+
+                            // 실상은 렌즈라면 반대편, 거울이라면 같은 편에 생긴다.
+                            let side = if element.is_mirror() {
+                                u.signum()
+                            } else {
+                                -u.signum()
+                            };
+
+                            (
+                                magnification,
+                                element.position
+                                    + axis * side * image_distance
+                                    + lateral * h * magnification,
+                            )
+                        });
+
+                        (
+                            Some(object_distance),
+                            image.map(|(m, _)| m),
+                            image.map(|(_, p)| p),
+                        )
+                    }
+                    SourceKind::Beam { .. } => {
+                        // 평행광은 초점면 위, 중심을 지나는 광선 위에 모인다.
+                        let direction = source.direction();
+                        let along = direction.dot(&axis);
+                        let slope = direction.dot(&lateral) / along.abs();
+                        let side = if element.is_mirror() {
+                            -along.signum()
+                        } else {
+                            along.signum()
+                        };
+
+                        let predicted = (along.abs() > 1e-9).then(|| {
+                            element.position
+                                + axis * side * focal_length
+                                + lateral * focal_length * slope
+                        });
+
+                        (None, None, predicted)
+                    }
+                };
+
+                ImageReport {
+                    source: source.name.clone(),
+                    object_distance,
+                    magnification,
+                    predicted,
+                    traced: closest_point(&lines),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Simulation for OpticsSimulation {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let paths = self.trace(&Self::settings(state));
+        let before = (self.elements.clone(), self.sources.clone());
+
+        egui::Grid::new("optics_summary").show(ui, |ui| {
+            ui.label("Rays");
+            ui.label(format!("{}", paths.len()));
+            ui.end_row();
+
+            ui.label("Total Internal Reflections");
+            ui.label(format!(
+                "{}",
+                paths
+                    .iter()
+                    .map(|path| path.total_internal_reflections)
+                    .sum::<usize>()
+            ));
+            ui.end_row();
+        });
+
+        let mut remove = None;
+
+        for (i, element) in self.elements.iter_mut().enumerate() {
+            ui.push_id(("element", i), |ui| {
+                CollapsingHeader::new(&element.name).show(ui, |ui| {
+                    element.kind.ui(ui);
+
+                    let mut degree = element.angle.to_degrees();
+                    ui.horizontal(|ui| {
+                        ui.label("Angle");
+                        if ui
+                            .add(egui::DragValue::new(&mut degree).suffix("°"))
+                            .changed()
+                        {
+                            element.angle = degree.to_radians();
+                        }
+                    });
+
+                    if ui.button("Remove this Element").clicked() {
+                        remove.replace(i);
+                    }
+                });
+            });
+        }
+
+        if let Some(index) = remove {
+            self.elements.remove(index);
+        }
+
+        let mut remove = None;
+
+        for (i, source) in self.sources.iter_mut().enumerate() {
+            ui.push_id(("source", i), |ui| {
+                CollapsingHeader::new(&source.name).show(ui, |ui| {
+                    source.ui(ui);
+
+                    let mut degree = source.angle.to_degrees();
+                    ui.horizontal(|ui| {
+                        ui.label("Angle");
+                        if ui
+                            .add(egui::DragValue::new(&mut degree).suffix("°"))
+                            .changed()
+                        {
+                            source.angle = degree.to_radians();
+                        }
+                    });
+
+                    if ui.button("Remove this Source").clicked() {
+                        remove.replace(i);
+                    }
+                });
+            });
+        }
+
+        if let Some(index) = remove {
+            self.sources.remove(index);
+        }
+
+        if before != (self.elements.clone(), self.sources.clone()) {
+            self.record(state.current_step);
+        }
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operations");
+            ui.separator();
+            ui.horizontal(|ui| {
+                OPTICS_OPERATION_ITER.iter().for_each(|operation| {
+                    ui.selectable_value(
+                        &mut self.operation,
+                        *operation,
+                        format!("{:?}", operation),
+                    );
+                });
+            });
+
+            if self.operation == OpticsOperation::AddElement {
+                ui.separator();
+                self.element_brush.ui(ui);
+            }
+
+            if self.operation == OpticsOperation::AddSource {
+                ui.separator();
+                self.source_brush.ui(ui);
+            }
+        });
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        msg: InputMessage,
+        response: Response,
+        _ctx: &egui::Context,
+        state: &mut SimulationState,
+    ) {
+        let Some(pointer_pos) = msg.pointer_pos else {
+            return;
+        };
+        let pointer = NVec2::new(pointer_pos.x, pointer_pos.y);
+
+        match self.operation {
+            OpticsOperation::Navigate => {
+                if response.clicked() {
+                    plot.selected = plot.pick(pointer_pos);
+                }
+            }
+            OpticsOperation::Move | OpticsOperation::Rotate => {
+                if response.dragged() {
+                    if !plot.dragging_object {
+                        // 드래그 시작할 때
+                        plot.selected = plot.pick(pointer_pos);
+                        plot.dragging_object = plot.selected.is_some();
+
+                        if let Some(target) = &plot.selected {
+                            self.grab_offset = target.anchor - pointer;
+                        }
+                    } else if let Some(target) = plot.selected.as_ref().map(|target| target.id) {
+                        let operation = self.operation;
+                        let grab_offset = self.grab_offset;
+
+                        if let Some((position, angle)) = self.target_pose_mut(target) {
+                            if operation == OpticsOperation::Move {
+                                *position = pointer + grab_offset;
+                            } else {
+                                let delta = pointer - *position;
+                                *angle = delta.y.atan2(delta.x);
+                            }
+                        }
+
+                        self.record(state.current_step);
+                    }
+                } else if plot.dragging_object {
+                    // 드래그가 끝났을 때
+                    plot.dragging_object = false;
+                }
+            }
+            OpticsOperation::AddElement => {
+                if response.clicked() {
+                    self.elements.push(OpticalElement::new(
+                        format!("{} {}", self.element_brush.get_name(), self.elements.len()),
+                        pointer,
+                        0.0,
+                        self.element_brush,
+                    ));
+                    self.record(state.current_step);
+                }
+            }
+            OpticsOperation::AddSource => {
+                if response.clicked() {
+                    self.sources.push(LightSource {
+                        name: format!("Light {}", self.sources.len()),
+                        position: pointer,
+                        ..self.source_brush.clone()
+                    });
+                    self.record(state.current_step);
+                }
+            }
+            OpticsOperation::Remove => {
+                if response.clicked() {
+                    if let Some(target) = plot.pick(pointer_pos) {
+                        match target.id.group {
+                            ELEMENT_TARGET => {
+                                self.elements.remove(target.id.index);
+                            }
+                            SOURCE_TARGET => {
+                                self.sources.remove(target.id.index);
+                            }
+                            _ => {}
+                        }
+
+                        plot.selected = None;
+                        self.record(state.current_step);
+                    }
+                }
+            }
+        }
+    }
+
+    // 배치는 바뀔 때만 남기므로 스텝마다 할 일이 없다.
+    fn step(&mut self, _state: &mut SimulationState) {}
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    fn pointer_edits(&self) -> bool {
+        self.operation != OpticsOperation::Navigate
+    }
+
+    fn at_time_step(&mut self, step: usize) {
+        (self.elements, self.sources) = self.timeline.layout_at(step).clone();
+    }
+
+    fn layout(&self, state: &SimulationState) -> Option<SessionLayout> {
+        Some(SessionLayout::Optics(OpticsPreset {
+            elements: self.elements.clone(),
+            sources: self.sources.clone(),
+            settings: Self::settings(state),
+        }))
+    }
+
+    fn plot_items(&mut self, _ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        let settings = Self::settings(state);
+        let mut items = vec![];
+
+        for element in &self.elements {
+            items.extend(element.draw(settings.show_focal_points));
+        }
+
+        for path in self.trace(&settings) {
+            items.push(
+                Line::new(path.points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>())
+                    .color(wavelength_color(path.wavelength))
+                    .name(&self.sources[path.source].name)
+                    .into(),
+            );
+        }
+
+        for source in &self.sources {
+            items.extend(source.draw());
+        }
+
+        items
+    }
+
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        let elements = self
+            .elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| PickTarget {
+                id: TargetId::new(ELEMENT_TARGET, index),
+                name: element.name.clone(),
+                anchor: element.position,
+                outline: element.outline(),
+            });
+
+        let sources = self
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| PickTarget {
+                id: TargetId::new(SOURCE_TARGET, index),
+                name: source.name.clone(),
+                anchor: source.position,
+                outline: source.outline(),
+            });
+
+        elements.chain(sources).collect()
+    }
+
+    fn inspect(&self, target: TargetId, state: &SimulationState) -> Vec<(String, String)> {
+        let paths = self.trace(&Self::settings(state));
+        let point = |p: Option<NVec2>| {
+            p.map_or("at infinity".to_string(), |p| {
+                format!("[{:.2}, {:.2}]", p.x, p.y)
+            })
+        };
+
+        match target.group {
+            ELEMENT_TARGET => {
+                let Some(element) = self.elements.get(target.index) else {
+                    return vec![];
+                };
+
+                let mut rows = vec![
+                    ("Type".to_string(), element.kind.get_name().to_string()),
+                    ("Position".into(), point(Some(element.position))),
+                    (
+                        "Angle".into(),
+                        format!("{:.1}°", element.angle.to_degrees()),
+                    ),
+                ];
+
+                if let Some(index) = element.index() {
+                    rows.push(("Refractive Index".into(), format!("{:.3}", index)));
+                }
+
+                if let Some(focal_length) = element.focal_length() {
+                    rows.push(("Focal Length".into(), format!("{:.3}", focal_length)));
+                }
+
+                for report in self.image_reports(target.index, &paths) {
+                    let name = report.source;
+
+                    rows.push((
+                        format!("{} d_o", name),
+                        report
+                            .object_distance
+                            .map_or("∞".to_string(), |d| format!("{:.3}", d)),
+                    ));
+
+                    if let Some(magnification) = report.magnification {
+                        rows.push((format!("{} m", name), format!("{:.3}", magnification)));
+                    }
+
+                    rows.push((format!("{} Lens Eq. Image", name), point(report.predicted)));
+                    rows.push((format!("{} Traced Image", name), point(report.traced)));
+
+                    if let (Some(predicted), Some(traced)) = (report.predicted, report.traced) {
+                        rows.push((
+                            format!("{} Difference", name),
+                            format!("{:.3}", (predicted - traced).norm()),
+                        ));
+                    }
+                }
+
+                rows
+            }
+            SOURCE_TARGET => {
+                let Some(source) = self.sources.get(target.index) else {
+                    return vec![];
+                };
+
+                let own_paths = paths
+                    .iter()
+                    .filter(|path| path.source == target.index)
+                    .collect::<Vec<_>>();
+
+                vec![
+                    ("Position".into(), point(Some(source.position))),
+                    ("Angle".into(), format!("{:.1}°", source.angle.to_degrees())),
+                    ("Rays".into(), format!("{}", own_paths.len())),
+                    (
+                        "Spectrum".into(),
+                        match source.spectrum {
+                            Spectrum::Mono(wavelength) => format!("{:.0} nm", wavelength),
+                            Spectrum::White => "White".into(),
+                        },
+                    ),
+                    (
+                        "Total Internal Reflections".into(),
+                        format!(
+                            "{}",
+                            own_paths
+                                .iter()
+                                .map(|path| path.total_internal_reflections)
+                                .sum::<usize>()
+                        ),
+                    ),
+                    (
+                        "Surface Hits".into(),
+                        format!(
+                            "{}",
+                            own_paths.iter().map(|path| path.hits.len()).sum::<usize>()
+                        ),
+                    ),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+}
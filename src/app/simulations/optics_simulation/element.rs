@@ -0,0 +1,156 @@
+use crate::app::graphics::define::items::{Line, Points, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::optics::element::{ElementKind, OpticalElement};
+use crate::engine::NVec2;
+use egui::{ComboBox, DragValue, Ui};
+
+impl ElementKind {
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut kind = self.get_name();
+
+        ComboBox::from_label("Element")
+            .selected_text(kind)
+            .show_ui(ui, |ui| {
+                for name in [
+                    "Plane Mirror",
+                    "Curved Mirror",
+                    "Thin Lens",
+                    "Thick Lens",
+                    "Prism",
+                ] {
+                    ui.selectable_value(&mut kind, name, name);
+                }
+            });
+
+        if kind != self.get_name() {
+            *self = Self::from_name(kind);
+        }
+
+        match self {
+            Self::PlaneMirror { length } => {
+                ui.add(
+                    DragValue::new(length)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("length: "),
+                );
+            }
+            Self::CurvedMirror { radius, aperture } => {
+                ui.add(DragValue::new(radius).speed(0.5).prefix("R: "));
+                ui.add(
+                    DragValue::new(aperture)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("aperture: "),
+                );
+            }
+            Self::ThinLens {
+                focal_length,
+                aperture,
+            } => {
+                ui.add(DragValue::new(focal_length).speed(0.5).prefix("f: "));
+                ui.add(
+                    DragValue::new(aperture)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("aperture: "),
+                );
+            }
+            Self::ThickLens {
+                radius,
+                thickness,
+                aperture,
+                index,
+            } => {
+                ui.add(
+                    DragValue::new(radius)
+                        .speed(0.5)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("R: "),
+                );
+                ui.add(
+                    DragValue::new(thickness)
+                        .speed(0.2)
+                        .clamp_range(0.0..=100.0)
+                        .prefix("d: "),
+                );
+                ui.add(
+                    DragValue::new(aperture)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("aperture: "),
+                );
+                ui.add(
+                    DragValue::new(index)
+                        .speed(0.01)
+                        .clamp_range(1.0..=3.0)
+                        .prefix("n: "),
+                );
+            }
+            Self::Prism { size, index } => {
+                ui.add(
+                    DragValue::new(size)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("size: "),
+                );
+                ui.add(
+                    DragValue::new(index)
+                        .speed(0.01)
+                        .clamp_range(1.0..=3.0)
+                        .prefix("n: "),
+                );
+            }
+        }
+    }
+}
+
+impl OpticalElement {
+    pub fn draw(&self, show_focal_points: bool) -> Vec<PlotItem> {
+        let to_plot = |points: Vec<NVec2>| points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>();
+
+        let mut items: Vec<PlotItem> = match self.kind {
+            ElementKind::PlaneMirror { .. } | ElementKind::CurvedMirror { .. } => self
+                .surfaces(0)
+                .iter()
+                .map(|surface| {
+                    Line::new(to_plot(surface.points()))
+                        .color(PlotColor::Mirror.get_color())
+                        .width(3.0)
+                        .name(&self.name)
+                        .into()
+                })
+                .collect(),
+            ElementKind::ThinLens { .. } => vec![Polygon::new(to_plot(self.outline()))
+                .color(PlotColor::Lens.get_color())
+                .fill_alpha(0.3)
+                .name(&self.name)
+                .into()],
+            ElementKind::ThickLens { .. } | ElementKind::Prism { .. } => {
+                vec![Polygon::new(to_plot(self.outline()))
+                    .color(PlotColor::Glass.get_color())
+                    .fill_alpha(0.2)
+                    .name(&self.name)
+                    .into()]
+            }
+        };
+
+        if let (true, Some(focal_length)) = (show_focal_points, self.focal_length()) {
+            let axis = self.axis();
+
+            let focal_points = if self.is_mirror() {
+                vec![self.position + axis * focal_length]
+            } else {
+                vec![
+                    self.position + axis * focal_length,
+                    self.position - axis * focal_length,
+                ]
+            };
+
+            items.push(
+                Points::new(to_plot(focal_points))
+                    .radius(3.0)
+                    .color(PlotColor::Lens.get_color())
+                    .name("focal point")
+                    .into(),
+            );
+        }
+
+        items
+    }
+}
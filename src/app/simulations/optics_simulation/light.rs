@@ -0,0 +1,72 @@
+use crate::app::graphics::define::items::Polygon;
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::optics::light::{LightSource, SourceKind, Spectrum};
+use egui::{ComboBox, DragValue, Ui};
+
+impl LightSource {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        vec![Polygon::new(
+            self.outline()
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>(),
+        )
+        .color(PlotColor::LightSource.get_color())
+        .fill_alpha(0.6)
+        .name(&self.name)
+        .into()]
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut is_beam = matches!(self.kind, SourceKind::Beam { .. });
+
+        ComboBox::from_label("Source")
+            .selected_text(if is_beam { "Beam" } else { "Point" })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut is_beam, true, "Beam");
+                ui.selectable_value(&mut is_beam, false, "Point");
+            });
+
+        match (is_beam, &mut self.kind) {
+            (true, SourceKind::Point { .. }) => self.kind = SourceKind::Beam { width: 20.0 },
+            (false, SourceKind::Beam { .. }) => self.kind = SourceKind::default(),
+            (_, SourceKind::Beam { width }) => {
+                ui.add(
+                    DragValue::new(width)
+                        .clamp_range(0.0..=400.0)
+                        .prefix("width: "),
+                );
+            }
+            (_, SourceKind::Point { spread }) => {
+                ui.add(
+                    DragValue::new(spread)
+                        .clamp_range(0.0..=360.0)
+                        .suffix("°")
+                        .prefix("spread: "),
+                );
+            }
+        }
+
+        ui.add(
+            DragValue::new(&mut self.rays)
+                .clamp_range(1..=64)
+                .prefix("rays: "),
+        );
+
+        let mut is_white = self.spectrum == Spectrum::White;
+        ui.checkbox(&mut is_white, "White Light");
+
+        match (is_white, &mut self.spectrum) {
+            (true, Spectrum::Mono(_)) => self.spectrum = Spectrum::White,
+            (false, Spectrum::White) => self.spectrum = Spectrum::Mono(550.0),
+            (_, Spectrum::Mono(wavelength)) => {
+                ui.add(
+                    DragValue::new(wavelength)
+                        .clamp_range(380.0..=750.0)
+                        .suffix(" nm"),
+                );
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use egui::Color32;
+
+/// 가시광선 파장을 대략적인 색으로 바꾼다.
+pub fn wavelength_color(wavelength: f64) -> Color32 {
+    let (r, g, b) = match wavelength {
+        w if w < 440.0 => ((440.0 - w) / 60.0, 0.0, 1.0),
+        w if w < 490.0 => (0.0, (w - 440.0) / 50.0, 1.0),
+        w if w < 510.0 => (0.0, 1.0, (510.0 - w) / 20.0),
+        w if w < 580.0 => ((w - 510.0) / 70.0, 1.0, 0.0),
+        w if w < 645.0 => (1.0, (645.0 - w) / 65.0, 0.0),
+        _ => (1.0, 0.0, 0.0),
+    };
+
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0) as u8;
+
+    Color32::from_rgb(channel(r), channel(g), channel(b))
+}
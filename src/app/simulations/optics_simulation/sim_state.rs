@@ -0,0 +1,23 @@
+use crate::engine::optics::sim_state::OpticsSettings;
+use egui::{DragValue, Slider, Widget};
+
+impl OpticsSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Optics Settings", |ui| {
+            Slider::new(&mut self.max_bounces, 1..=128)
+                .text("Max Bounces")
+                .ui(ui);
+
+            ui.horizontal(|ui| {
+                ui.label("Ambient Index");
+                ui.add(
+                    DragValue::new(&mut self.ambient_index)
+                        .speed(0.01)
+                        .clamp_range(1.0..=3.0),
+                );
+            });
+
+            ui.checkbox(&mut self.show_focal_points, "Focal Points");
+        });
+    }
+}
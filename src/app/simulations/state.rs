@@ -1,6 +1,13 @@
-use crate::app::simulations::classic_simulation::sim_state::CSimSettings;
+use crate::engine::circuit::sim_state::CircuitSettings;
+use crate::engine::classic::sim_state::CSimSettings;
+use crate::engine::fluid::sim_state::FluidSettings;
+use crate::engine::heat::sim_state::HeatSettings;
+use crate::engine::optics::sim_state::OpticsSettings;
+use crate::engine::wave::sim_state::WaveSettings;
+use crate::engine::{state, NVec2};
 use egui::plot::{PlotPoint, PlotUi};
 use paste::paste;
+use serde::{Deserialize, Serialize};
 
 macro_rules! cast {
     ($target: expr, $pat: path) => {{
@@ -23,6 +30,7 @@ macro_rules! cast_impl_inner {
                         cast!(self, $name::$variant)
                     }
 
+                    #[allow(dead_code)]
                     pub fn [<as_ $variant:snake _mut>](&mut self) -> Option<&mut $variant>{
                         cast!(self, $name::$variant)
                     }
@@ -46,9 +54,17 @@ macro_rules! cast_impl {
 #[derive(Clone, Copy, Debug)]
 pub struct BSimSettings {}
 
-cast_impl!(SpecificSimulationSettings, CSimSettings);
-
-#[derive(Clone, Debug)]
+cast_impl!(
+    SpecificSimulationSettings,
+    CSimSettings,
+    OpticsSettings,
+    WaveSettings,
+    HeatSettings,
+    FluidSettings,
+    CircuitSettings
+);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimulationSettings {
     pub grid: bool,
     pub specific: SpecificSimulationSettings,
@@ -80,9 +96,14 @@ impl SimulationSettings {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SpecificSimulationSettings {
     CSimSettings(CSimSettings),
+    OpticsSettings(OpticsSettings),
+    WaveSettings(WaveSettings),
+    HeatSettings(HeatSettings),
+    FluidSettings(FluidSettings),
+    CircuitSettings(CircuitSettings),
     None,
 }
 
@@ -99,62 +120,47 @@ impl SpecificSimulationSettings {
             Self::CSimSettings(settings) => {
                 settings.ui(ui);
             }
+            Self::OpticsSettings(settings) => {
+                settings.ui(ui);
+            }
+            Self::WaveSettings(settings) => {
+                settings.ui(ui);
+            }
+            Self::HeatSettings(settings) => {
+                settings.ui(ui);
+            }
+            Self::FluidSettings(settings) => {
+                settings.ui(ui);
+            }
+            Self::CircuitSettings(settings) => {
+                settings.ui(ui);
+            }
         }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct SimulationState {
-    pub settings: SimulationSettings,
-    pub(crate) pointer: Option<PlotPoint>,
-    pub(crate) time_mul: usize,
-    pub(crate) time: f64,
-    pub(crate) current_step: usize,
-    pub(crate) max_step: usize,
-    pub(crate) sim_started: bool,
-
-    pub(crate) zoom: f64,
-}
-
-impl Default for SimulationState {
-    fn default() -> Self {
-        Self {
-            pointer: None,
-            settings: Default::default(),
-            time: 0.0,
-            current_step: 0,
-            max_step: 0,
-            sim_started: false,
-            zoom: 1.0,
-            time_mul: 1,
-        }
-    }
-}
+/// 앱에서 쓰는 시뮬레이션 상태. 설정은 영역마다 다르다.
+pub type SimulationState = state::SimulationState<SimulationSettings>;
 
 impl SimulationState {
     pub(crate) fn update_simulation_state(&mut self, plot_ui: &mut PlotUi) {
-        let zoom = plot_ui.plot_bounds().width();
+        let bounds = plot_ui.plot_bounds();
+        let zoom = bounds.width();
         let pointer = plot_ui.pointer_coordinate();
 
-        self.zoom = zoom;
-        self.pointer = pointer;
-    }
+        let origin = plot_ui.screen_from_plot(PlotPoint::new(0.0, 0.0));
+        let unit = plot_ui.screen_from_plot(PlotPoint::new(1.0, 0.0));
 
-    pub fn is_sim_started(&self) -> bool {
-        self.sim_started
-    }
-
-    pub fn reset(&mut self) {
-        self.time = 0.0;
-        self.sim_started = false;
-        self.current_step = 0;
-        self.max_step = 0;
+        self.zoom = zoom;
+        self.view = [bounds.min(), bounds.max()].map(|[x, y]| NVec2::new(x, y));
+        self.pointer = pointer.map(|pointer| NVec2::new(pointer.x, pointer.y));
+        self.pixels_per_unit = (unit.x - origin.x) as f64;
     }
 
     pub fn inspection_ui(&mut self, ui: &mut egui::Ui) {
         ui.label(format!("Time: {:.2}", self.time));
         ui.label(format!("Step: {}", self.current_step));
         ui.label(format!("Max Step: {}", self.max_step));
-        ui.label(format!("Time Multiplier: {}", self.time_mul));
+        ui.label(format!("Speed: {}×", self.speed));
     }
 }
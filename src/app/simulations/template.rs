@@ -0,0 +1,167 @@
+use egui::Ui;
+use std::fmt::Debug;
+
+use crate::app::simulations::circuit_simulation::CircuitSimulation;
+use crate::app::simulations::classic_simulation::ClassicSimulation;
+use crate::app::simulations::fluid_simulation::FluidSimulation;
+use crate::app::simulations::heat_simulation::HeatSimulation;
+use crate::app::simulations::optics_simulation::OpticsSimulation;
+use crate::app::simulations::state::SpecificSimulationSettings;
+use crate::app::simulations::wave_simulation::WaveSimulation;
+use crate::app::simulations::Simulation;
+use crate::engine::circuit::template::{get_circuit_sim_list, CircuitTemplate};
+use crate::engine::classic::template::{get_cs_sim_list, CSTemplate};
+use crate::engine::fluid::template::{get_fluid_sim_list, FluidTemplate};
+use crate::engine::heat::template::{get_heat_sim_list, HeatTemplate};
+use crate::engine::optics::template::{get_optics_sim_list, OpticsTemplate};
+use crate::engine::wave::template::{get_wave_sim_list, WaveTemplate};
+
+/// 시작하기 전에 값을 입력받는 템플릿.
+pub trait SimulationInit: Debug {
+    fn ui(&mut self, ui: &mut Ui);
+
+    fn to_simulation_type(&self) -> SimulationType;
+}
+
+/// 모든 영역의 템플릿을 묶는다.
+#[derive(Clone, Debug)]
+pub enum SimulationType {
+    Classic(CSTemplate),
+    Optics(OpticsTemplate),
+    Wave(WaveTemplate),
+    Heat(HeatTemplate),
+    Fluid(FluidTemplate),
+    Circuit(CircuitTemplate),
+}
+
+/// 템플릿으로 만든 시뮬레이션과 그 설정.
+pub struct SimulationPreset {
+    pub simulation: Box<dyn Simulation>,
+    pub settings: SpecificSimulationSettings,
+}
+
+impl SimulationType {
+    pub fn get_name(&self) -> String {
+        match self {
+            SimulationType::Classic(template) => template.get_name(),
+            SimulationType::Optics(template) => template.get_name(),
+            SimulationType::Wave(template) => template.get_name(),
+            SimulationType::Heat(template) => template.get_name(),
+            SimulationType::Fluid(template) => template.get_name(),
+            SimulationType::Circuit(template) => template.get_name(),
+        }
+    }
+
+    pub fn get_description(&self) -> &str {
+        match self {
+            SimulationType::Classic(template) => template.get_description(),
+            _ => "",
+        }
+    }
+
+    pub fn get_domain(&self) -> &'static str {
+        match self {
+            SimulationType::Classic(_) => "Classic Mechanics",
+            SimulationType::Optics(_) => "Geometric Optics",
+            SimulationType::Wave(_) => "Waves",
+            SimulationType::Heat(_) => "Heat",
+            SimulationType::Fluid(_) => "Fluids",
+            SimulationType::Circuit(_) => "Circuits",
+        }
+    }
+
+    pub fn get_data(&self) -> Option<Box<dyn SimulationInit>> {
+        match self {
+            SimulationType::Classic(template) => template.get_data(),
+            SimulationType::Optics(_)
+            | SimulationType::Wave(_)
+            | SimulationType::Heat(_)
+            | SimulationType::Fluid(_)
+            | SimulationType::Circuit(_) => None,
+        }
+    }
+
+    pub fn build(self) -> SimulationPreset {
+        match self {
+            SimulationType::Classic(template) => {
+                let preset = template.get_preset_with_ui();
+                let settings = SpecificSimulationSettings::CSimSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(ClassicSimulation::from(preset)),
+                    settings,
+                }
+            }
+            SimulationType::Optics(template) => {
+                let preset = template.get_preset();
+                let settings = SpecificSimulationSettings::OpticsSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(OpticsSimulation::from(preset)),
+                    settings,
+                }
+            }
+            SimulationType::Wave(template) => {
+                let preset = template.get_preset();
+                let settings = SpecificSimulationSettings::WaveSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(WaveSimulation::from(preset)),
+                    settings,
+                }
+            }
+            SimulationType::Heat(template) => {
+                let preset = template.get_preset();
+                let settings = SpecificSimulationSettings::HeatSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(HeatSimulation::from(preset)),
+                    settings,
+                }
+            }
+            SimulationType::Fluid(template) => {
+                let preset = template.get_preset();
+                let settings = SpecificSimulationSettings::FluidSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(FluidSimulation::from(preset)),
+                    settings,
+                }
+            }
+            SimulationType::Circuit(template) => {
+                let preset = template.get_preset();
+                let settings = SpecificSimulationSettings::CircuitSettings(preset.settings.clone());
+
+                SimulationPreset {
+                    simulation: Box::new(CircuitSimulation::from(preset)),
+                    settings,
+                }
+            }
+        }
+    }
+}
+
+pub fn get_sim_list() -> Vec<SimulationType> {
+    let classic = get_cs_sim_list().into_iter().map(SimulationType::Classic);
+    let optics = get_optics_sim_list()
+        .into_iter()
+        .map(SimulationType::Optics);
+
+    let wave = get_wave_sim_list().into_iter().map(SimulationType::Wave);
+
+    let heat = get_heat_sim_list().into_iter().map(SimulationType::Heat);
+
+    let fluid = get_fluid_sim_list().into_iter().map(SimulationType::Fluid);
+
+    let circuit = get_circuit_sim_list()
+        .into_iter()
+        .map(SimulationType::Circuit);
+
+    classic
+        .chain(optics)
+        .chain(wave)
+        .chain(heat)
+        .chain(fluid)
+        .chain(circuit)
+        .collect()
+}
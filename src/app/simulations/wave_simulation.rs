@@ -0,0 +1,524 @@
+pub mod field;
+pub mod layout;
+pub mod sim_state;
+
+use crate::app::graphics::define::items::Line;
+use crate::app::NVec2;
+
+use egui::{CollapsingHeader, DragValue, Response, Ui};
+
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::app::graphics::plot::{InputMessage, PlotData};
+use crate::app::manager::SIMULATION_TICK;
+use crate::app::session::SessionLayout;
+use crate::app::simulations::grid::GridTexture;
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::{PickTarget, Simulation, TargetId};
+use crate::engine::grid::Grid;
+use crate::engine::keyframe::KeyframeTimeline;
+
+use crate::engine::wave::field::{courant, substeps, wall_mask, WaveField};
+use crate::engine::wave::layout::{Wall, WaveLayout, WaveSource, WaveSourceKind};
+use crate::engine::wave::sim_state::WaveSettings;
+use crate::engine::wave::template::WavePreset;
+
+/// `TargetId::group` 값
+const SOURCE_TARGET: usize = 0;
+const WALL_TARGET: usize = 1;
+
+/// 이 간격마다 격자 전체를 저장하고, 그 사이 스텝은 다시 계산한다.
+const KEYFRAME_INTERVAL: usize = 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaveOperation {
+    #[default]
+    Navigate,
+    Move,
+    AddSource,
+    AddWall,
+    Remove,
+}
+
+const WAVE_OPERATION_ITER: [WaveOperation; 5] = [
+    WaveOperation::Navigate,
+    WaveOperation::Move,
+    WaveOperation::AddSource,
+    WaveOperation::AddWall,
+    WaveOperation::Remove,
+];
+
+/// 격자 위에서 파동 방정식을 푸는 시뮬레이션. 파원과 벽, 틈으로 간섭과 회절을 보여준다.
+#[derive(Clone)]
+pub struct WaveSimulation {
+    pub grid: Grid,
+    pub layout: WaveLayout,
+    field: WaveField,
+    /// 벽이 차지하는 칸과 그걸 만든 벽들
+    mask: Vec<bool>,
+    mask_walls: Vec<Wall>,
+    timeline: KeyframeTimeline<WaveLayout, WaveField>,
+    /// 타임라인을 다시 계산할 때 쓸 마지막 설정
+    settings: WaveSettings,
+
+    texture: GridTexture,
+
+    pub operation: WaveOperation,
+    pub source_brush: WaveSource,
+    pub wall_thickness: f64,
+    /// 그리고 있는 벽의 시작점
+    wall_start: Option<NVec2>,
+    /// 드래그를 시작할 때 대상 위치와 포인터의 차이
+    grab_offset: NVec2,
+}
+
+impl From<WavePreset> for WaveSimulation {
+    fn from(preset: WavePreset) -> Self {
+        let field = WaveField::new(&preset.grid);
+
+        Self {
+            mask: wall_mask(&preset.grid, &preset.layout.walls),
+            mask_walls: preset.layout.walls.clone(),
+            timeline: KeyframeTimeline::new(
+                preset.layout.clone(),
+                field.clone(),
+                KEYFRAME_INTERVAL,
+            ),
+            grid: preset.grid,
+            layout: preset.layout,
+            field,
+            settings: preset.settings,
+            texture: GridTexture::default(),
+            operation: WaveOperation::default(),
+            source_brush: WaveSource::default(),
+            wall_thickness: 2.0,
+            wall_start: None,
+            grab_offset: NVec2::zeros(),
+        }
+    }
+}
+
+impl WaveSimulation {
+    fn settings(state: &SimulationState) -> WaveSettings {
+        state
+            .settings
+            .specific
+            .as_wave_settings()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn refresh_mask(&mut self) {
+        if self.mask_walls != self.layout.walls {
+            self.mask = wall_mask(&self.grid, &self.layout.walls);
+            self.mask_walls = self.layout.walls.clone();
+        }
+    }
+
+    /// 그 스텝의 배치로 한 스텝 진행하고, 간격마다 격자를 저장한다.
+    fn advance(&mut self) {
+        self.layout = self.timeline.layout_at(self.field.step).clone();
+        self.refresh_mask();
+
+        self.field.advance(
+            &self.grid,
+            &self.layout,
+            &self.mask,
+            &self.settings,
+            SIMULATION_TICK,
+        );
+
+        self.timeline.store(self.field.step, &self.field);
+    }
+
+    // 현재 배치를 타임라인의 `step`부터 쓴다. 그 뒤의 격자는 다시 계산해야 한다.
+    fn record(&mut self, step: usize) {
+        self.timeline.record(step, self.layout.clone());
+    }
+
+    fn move_target(&mut self, target: TargetId, anchor: NVec2) {
+        match target.group {
+            SOURCE_TARGET => {
+                if let Some(source) = self.layout.sources.get_mut(target.index) {
+                    source.position = anchor;
+                }
+            }
+            WALL_TARGET => {
+                if let Some(wall) = self.layout.walls.get_mut(target.index) {
+                    let delta = anchor - wall.center();
+                    wall.start += delta;
+                    wall.end += delta;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn remove_target(&mut self, target: TargetId) {
+        match target.group {
+            SOURCE_TARGET if target.index < self.layout.sources.len() => {
+                self.layout.sources.remove(target.index);
+            }
+            WALL_TARGET if target.index < self.layout.walls.len() => {
+                self.layout.walls.remove(target.index);
+            }
+            _ => {}
+        }
+    }
+
+    /// 색을 칠할 기준 진폭
+    fn amplitude(&self) -> f64 {
+        self.layout
+            .sources
+            .iter()
+            .fold(0.0, |max: f64, source| max.max(source.amplitude))
+    }
+}
+
+impl Simulation for WaveSimulation {
+    fn inspection_ui(&mut self, ui: &mut Ui, state: &SimulationState) {
+        let settings = Self::settings(state);
+        let before = self.layout.clone();
+
+        egui::Grid::new("wave_summary").show(ui, |ui| {
+            ui.label("Grid");
+            ui.label(format!(
+                "{} x {} (cell {:.2})",
+                self.grid.width, self.grid.height, self.grid.cell
+            ));
+            ui.end_row();
+
+            ui.label("Courant Number");
+            ui.label(format!(
+                "{:.3} ({} substeps)",
+                courant(&self.grid, &settings, SIMULATION_TICK),
+                substeps(&self.grid, &settings, SIMULATION_TICK)
+            ));
+            ui.end_row();
+
+            ui.label("Wave Time");
+            ui.label(format!("{:.3} s", self.field.time));
+            ui.end_row();
+
+            ui.label("Max Displacement");
+            ui.label(format!("{:.3}", self.field.max_displacement()));
+            ui.end_row();
+
+            ui.label("Keyframes");
+            ui.label(format!("{}", self.timeline.keyframe_count()));
+            ui.end_row();
+
+            if let Some(pointer) = state.pointer {
+                if let Some((value, intensity)) = self
+                    .field
+                    .value_at(&self.grid, NVec2::new(pointer.x, pointer.y))
+                {
+                    ui.label("Pointer Displacement");
+                    ui.label(format!("{:.3}", value));
+                    ui.end_row();
+
+                    ui.label("Pointer Intensity");
+                    ui.label(format!("{:.3}", intensity));
+                    ui.end_row();
+                }
+            }
+        });
+
+        let mut remove = None;
+
+        for (i, source) in self.layout.sources.iter_mut().enumerate() {
+            ui.push_id(("source", i), |ui| {
+                CollapsingHeader::new(&source.name).show(ui, |ui| {
+                    source.ui(ui);
+
+                    if ui.button("Remove this Source").clicked() {
+                        remove.replace(TargetId::new(SOURCE_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        for (i, wall) in self.layout.walls.iter_mut().enumerate() {
+            ui.push_id(("wall", i), |ui| {
+                CollapsingHeader::new(&wall.name).show(ui, |ui| {
+                    wall.ui(ui);
+
+                    if ui.button("Remove this Wall").clicked() {
+                        remove.replace(TargetId::new(WALL_TARGET, i));
+                    }
+                });
+            });
+        }
+
+        if let Some(target) = remove {
+            self.remove_target(target);
+        }
+
+        if before != self.layout {
+            self.record(state.current_step);
+        }
+    }
+
+    fn operation_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Operations");
+            ui.separator();
+            ui.horizontal(|ui| {
+                WAVE_OPERATION_ITER.iter().for_each(|operation| {
+                    ui.selectable_value(
+                        &mut self.operation,
+                        *operation,
+                        format!("{:?}", operation),
+                    );
+                });
+            });
+
+            if self.operation == WaveOperation::AddSource {
+                ui.separator();
+                self.source_brush.ui(ui);
+            }
+
+            if self.operation == WaveOperation::AddWall {
+                ui.separator();
+                ui.add(
+                    DragValue::new(&mut self.wall_thickness)
+                        .speed(0.1)
+                        .clamp_range(0.5..=20.0)
+                        .prefix("thickness: "),
+                );
+            }
+        });
+    }
+
+    fn input(
+        &mut self,
+        plot: &mut PlotData,
+        msg: InputMessage,
+        response: Response,
+        _ctx: &egui::Context,
+        state: &mut SimulationState,
+    ) {
+        let Some(pointer_pos) = msg.pointer_pos else {
+            return;
+        };
+        let pointer = NVec2::new(pointer_pos.x, pointer_pos.y);
+
+        match self.operation {
+            WaveOperation::Navigate => {
+                if response.clicked() {
+                    plot.selected = plot.pick(pointer_pos);
+                }
+            }
+            WaveOperation::Move => {
+                if response.dragged() {
+                    if !plot.dragging_object {
+                        // 드래그 시작할 때
+                        plot.selected = plot.pick(pointer_pos);
+                        plot.dragging_object = plot.selected.is_some();
+
+                        if let Some(target) = &plot.selected {
+                            self.grab_offset = target.anchor - pointer;
+                        }
+                    } else if let Some(target) = plot.selected.as_ref().map(|target| target.id) {
+                        self.move_target(target, pointer + self.grab_offset);
+                        self.record(state.current_step);
+                    }
+                } else if plot.dragging_object {
+                    // 드래그가 끝났을 때
+                    plot.dragging_object = false;
+                }
+            }
+            WaveOperation::AddSource => {
+                if response.clicked() {
+                    self.layout.sources.push(WaveSource {
+                        name: format!("Source {}", self.layout.sources.len()),
+                        position: pointer,
+                        ..self.source_brush.clone()
+                    });
+                    self.record(state.current_step);
+                }
+            }
+            WaveOperation::AddWall => {
+                if response.dragged() {
+                    // 드래그 시작점을 벽의 한쪽 끝으로 쓴다.
+                    if self.wall_start.is_none() {
+                        self.wall_start = Some(pointer);
+                        plot.selected = None;
+                        plot.dragging_object = true;
+                    }
+                } else if let Some(start) = self.wall_start.take() {
+                    plot.dragging_object = false;
+
+                    if start != pointer {
+                        self.layout.walls.push(Wall::new(
+                            format!("Wall {}", self.layout.walls.len()),
+                            start,
+                            pointer,
+                            self.wall_thickness,
+                        ));
+                        self.record(state.current_step);
+                    }
+                }
+            }
+            WaveOperation::Remove => {
+                if response.clicked() {
+                    if let Some(target) = plot.pick(pointer_pos) {
+                        self.remove_target(target.id);
+
+                        plot.selected = None;
+                        self.record(state.current_step);
+                    }
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, state: &mut SimulationState) {
+        puffin::profile_scope!("WaveSimulation::step");
+
+        self.settings = Self::settings(state);
+        self.advance();
+    }
+
+    fn snapshot(&self) -> Box<dyn Simulation> {
+        Box::new(self.clone())
+    }
+
+    fn pointer_edits(&self) -> bool {
+        self.operation != WaveOperation::Navigate
+    }
+
+    fn at_time_step(&mut self, step: usize) {
+        for _ in 0..self.timeline.rewind(step, &mut self.field) {
+            self.advance();
+        }
+
+        self.layout = self.timeline.layout_at(step).clone();
+    }
+
+    fn layout(&self, state: &SimulationState) -> Option<SessionLayout> {
+        Some(SessionLayout::Wave(WavePreset {
+            grid: self.grid,
+            layout: self.layout.clone(),
+            settings: Self::settings(state),
+        }))
+    }
+
+    fn plot_items(&mut self, ctx: &egui::Context, state: &SimulationState) -> Vec<PlotItem> {
+        puffin::profile_scope!("WaveSimulation::plot_items");
+
+        self.settings = Self::settings(state);
+        self.refresh_mask();
+
+        let image = self
+            .field
+            .to_image(&self.grid, &self.mask, &self.settings, self.amplitude());
+        let mut items = vec![self.texture.plot_item(ctx, "wave_field", &self.grid, image)];
+
+        for wall in &self.layout.walls {
+            items.extend(wall.draw());
+        }
+
+        for source in &self.layout.sources {
+            items.extend(source.draw());
+        }
+
+        // 그리고 있는 벽
+        if let (Some(start), Some(pointer)) = (self.wall_start, state.pointer) {
+            items.push(
+                Line::new(vec![[start.x, start.y], [pointer.x, pointer.y]])
+                    .color(PlotColor::Boundary.get_color())
+                    .width(self.wall_thickness as f32)
+                    .into(),
+            );
+        }
+
+        items
+    }
+
+    fn pick_targets(&self, _state: &SimulationState) -> Vec<PickTarget> {
+        let walls = self
+            .layout
+            .walls
+            .iter()
+            .enumerate()
+            .map(|(index, wall)| PickTarget {
+                id: TargetId::new(WALL_TARGET, index),
+                name: wall.name.clone(),
+                anchor: wall.center(),
+                outline: wall.outline(),
+            });
+
+        let sources = self
+            .layout
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| PickTarget {
+                id: TargetId::new(SOURCE_TARGET, index),
+                name: source.name.clone(),
+                anchor: source.position,
+                outline: source.outline(),
+            });
+
+        walls.chain(sources).collect()
+    }
+
+    fn inspect(&self, target: TargetId, state: &SimulationState) -> Vec<(String, String)> {
+        let settings = Self::settings(state);
+        let point = |p: NVec2| format!("[{:.2}, {:.2}]", p.x, p.y);
+
+        match target.group {
+            SOURCE_TARGET => {
+                let Some(source) = self.layout.sources.get(target.index) else {
+                    return vec![];
+                };
+
+                let mut rows = vec![
+                    (
+                        "Type".to_string(),
+                        match source.kind {
+                            WaveSourceKind::Point => "Point".to_string(),
+                            WaveSourceKind::Line { .. } => "Line".to_string(),
+                        },
+                    ),
+                    ("Position".into(), point(source.position)),
+                ];
+
+                if let WaveSourceKind::Line { length } = source.kind {
+                    rows.push(("Length".into(), format!("{:.2}", length)));
+                    rows.push(("Angle".into(), format!("{:.1}°", source.angle.to_degrees())));
+                }
+
+                rows.extend([
+                    ("Frequency".into(), format!("{:.3} Hz", source.frequency)),
+                    ("Period".into(), format!("{:.3} s", 1.0 / source.frequency)),
+                    (
+                        "Wavelength".into(),
+                        format!("{:.3}", settings.wave_speed / source.frequency),
+                    ),
+                    ("Amplitude".into(), format!("{:.3}", source.amplitude)),
+                    ("Phase".into(), format!("{:.1}°", source.phase.to_degrees())),
+                    (
+                        "Current Value".into(),
+                        format!("{:.3}", source.value(self.field.time)),
+                    ),
+                ]);
+
+                rows
+            }
+            WALL_TARGET => {
+                let Some(wall) = self.layout.walls.get(target.index) else {
+                    return vec![];
+                };
+
+                vec![
+                    ("Start".into(), point(wall.start)),
+                    ("End".into(), point(wall.end)),
+                    ("Length".into(), format!("{:.2}", wall.length())),
+                    ("Thickness".into(), format!("{:.2}", wall.thickness)),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::app::simulations::grid::gradient_color;
+use crate::engine::grid::Grid;
+use crate::engine::wave::field::WaveField;
+use crate::engine::wave::sim_state::{WaveSettings, WaveView};
+use egui::{Color32, ColorImage};
+
+const WALL_COLOR: Color32 = Color32::from_gray(170);
+
+/// 세기 색: 검정 - 보라 - 주황 - 노랑
+const INTENSITY_STOPS: [[f32; 3]; 4] = [
+    [0.0, 0.0, 0.0],
+    [110.0, 30.0, 150.0],
+    [240.0, 120.0, 30.0],
+    [255.0, 240.0, 160.0],
+];
+
+impl WaveField {
+    /// 변위나 세기를 색으로 칠한 이미지. `scale`은 파원의 진폭이다.
+    pub fn to_image(
+        &self,
+        grid: &Grid,
+        mask: &[bool],
+        settings: &WaveSettings,
+        scale: f64,
+    ) -> ColorImage {
+        let gain = (settings.contrast / scale.max(1e-6)) as f32;
+
+        grid.image(|i| {
+            if mask[i] {
+                return WALL_COLOR;
+            }
+
+            match settings.view {
+                WaveView::Displacement => displacement_color(self.current[i] * gain),
+                // 진폭이 A인 사인파의 평균 제곱은 A²/2. 약한 무늬도 보이도록 진폭으로 칠한다.
+                WaveView::Intensity => {
+                    gradient_color(&INTENSITY_STOPS, (self.intensity[i] * 2.0).sqrt() * gain)
+                }
+            }
+        })
+    }
+}
+
+/// 양수는 주황, 음수는 파랑
+fn displacement_color(value: f32) -> Color32 {
+    let v = value.clamp(-1.0, 1.0);
+    let channel = |weight: f32| (v.abs() * weight) as u8;
+
+    if v >= 0.0 {
+        Color32::from_rgb(channel(255.0), channel(130.0), channel(40.0))
+    } else {
+        Color32::from_rgb(channel(40.0), channel(140.0), channel(255.0))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::app::graphics::define::items::{Line, Polygon};
+use crate::app::graphics::define::{PlotColor, PlotItem};
+use crate::engine::wave::layout::{Wall, WaveSource, WaveSourceKind};
+use egui::{ComboBox, DragValue, Ui};
+
+impl WaveSource {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        let (a, b) = self.endpoints();
+
+        let item = match self.kind {
+            WaveSourceKind::Point => Polygon::new(
+                self.outline()
+                    .iter()
+                    .map(|p| [p.x, p.y])
+                    .collect::<Vec<_>>(),
+            )
+            .color(PlotColor::WaveSource.get_color())
+            .fill_alpha(0.8)
+            .name(&self.name)
+            .into(),
+            WaveSourceKind::Line { .. } => Line::new(vec![[a.x, a.y], [b.x, b.y]])
+                .color(PlotColor::WaveSource.get_color())
+                .width(3.0)
+                .name(&self.name)
+                .into(),
+        };
+
+        vec![item]
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let mut is_line = matches!(self.kind, WaveSourceKind::Line { .. });
+
+        ComboBox::from_label("Source")
+            .selected_text(if is_line { "Line" } else { "Point" })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut is_line, false, "Point");
+                ui.selectable_value(&mut is_line, true, "Line");
+            });
+
+        match (is_line, &mut self.kind) {
+            (true, WaveSourceKind::Point) => self.kind = WaveSourceKind::Line { length: 40.0 },
+            (false, WaveSourceKind::Line { .. }) => self.kind = WaveSourceKind::Point,
+            (_, WaveSourceKind::Line { length }) => {
+                ui.add(
+                    DragValue::new(length)
+                        .clamp_range(1.0..=400.0)
+                        .prefix("length: "),
+                );
+            }
+            _ => {}
+        }
+
+        ui.add(
+            DragValue::new(&mut self.frequency)
+                .speed(0.05)
+                .clamp_range(0.1..=20.0)
+                .suffix(" Hz"),
+        );
+        ui.add(
+            DragValue::new(&mut self.amplitude)
+                .speed(0.05)
+                .clamp_range(0.0..=10.0)
+                .prefix("A: "),
+        );
+
+        let mut degree = self.phase.to_degrees();
+        if ui
+            .add(DragValue::new(&mut degree).prefix("φ: ").suffix("°"))
+            .changed()
+        {
+            self.phase = degree.to_radians();
+        }
+    }
+}
+
+impl Wall {
+    pub fn draw(&self) -> Vec<PlotItem> {
+        vec![Polygon::new(
+            self.outline()
+                .iter()
+                .map(|p| [p.x, p.y])
+                .collect::<Vec<_>>(),
+        )
+        .color(PlotColor::Boundary.get_color())
+        .fill_alpha(0.8)
+        .name(&self.name)
+        .into()]
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.add(
+            DragValue::new(&mut self.thickness)
+                .speed(0.1)
+                .clamp_range(0.5..=20.0)
+                .prefix("thickness: "),
+        );
+    }
+}
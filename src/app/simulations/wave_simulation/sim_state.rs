@@ -0,0 +1,44 @@
+use crate::engine::wave::sim_state::{WaveSettings, WaveView};
+use egui::{ComboBox, DragValue, Slider, Widget};
+
+impl WaveSettings {
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.collapsing("Wave Settings", |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Wave Speed");
+                ui.add(
+                    DragValue::new(&mut self.wave_speed)
+                        .speed(0.5)
+                        .clamp_range(1.0..=200.0),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Damping");
+                ui.add(
+                    DragValue::new(&mut self.damping)
+                        .speed(0.01)
+                        .clamp_range(0.0..=10.0),
+                );
+            });
+
+            ui.checkbox(&mut self.absorbing_edges, "Absorbing Edges");
+
+            Slider::new(&mut self.substeps, 1..=8)
+                .text("Substeps")
+                .ui(ui);
+
+            ComboBox::from_label("View")
+                .selected_text(format!("{:?}", self.view))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.view, WaveView::Displacement, "Displacement");
+                    ui.selectable_value(&mut self.view, WaveView::Intensity, "Intensity");
+                });
+
+            Slider::new(&mut self.contrast, 0.1..=10.0)
+                .logarithmic(true)
+                .text("Contrast")
+                .ui(ui);
+        });
+    }
+}
@@ -0,0 +1,436 @@
+//! 새 스텝 계산. 네이티브에서는 작업 스레드가 시뮬레이션을 맡아 목표 스텝까지 쉬지 않고 계산하고,
+//! 화면은 작업 스레드가 보내 준 스냅샷을 그린다. 웹에서는 프레임 안에서 정해진 시간만큼 계산한다.
+
+use crate::app::simulations::state::SimulationState;
+use crate::app::simulations::Simulation;
+use crate::engine::SIMULATION_TICK;
+
+/// 계산을 맡긴 시뮬레이션과 상태. 돌려받을 때까지 UI 는 스냅샷만 쓸 수 있다.
+pub struct StepJob {
+    pub simulation: Box<dyn Simulation>,
+    pub state: SimulationState,
+    /// 이 스텝까지 계산한다.
+    pub target: usize,
+}
+
+impl StepJob {
+    pub fn new(simulation: Box<dyn Simulation>, state: SimulationState, target: usize) -> Self {
+        Self {
+            simulation,
+            state,
+            target,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.state.max_step >= self.target
+    }
+
+    // 기록 끝에 한 스텝을 더한다.
+    fn advance(&mut self) {
+        self.state.max_step += 1;
+        self.state.current_step = self.state.max_step;
+
+        self.simulation.step(&mut self.state);
+
+        // 지난 스텝을 다시 계산할 때와 같은 시각이 되도록 더하지 않고 곱한다.
+        self.state.time = SIMULATION_TICK * self.state.max_step as f64;
+    }
+}
+
+/// 작업 스레드에서 받아 온 시뮬레이션과 상태. 맡긴 것을 돌려받았거나 계산을 이어 가는 동안 그릴 스냅샷이다.
+pub struct Received {
+    pub simulation: Box<dyn Simulation>,
+    pub state: SimulationState,
+}
+
+impl From<StepJob> for Received {
+    fn from(job: StepJob) -> Self {
+        Self {
+            simulation: job.simulation,
+            state: job.state,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use worker::Stepper;
+
+#[cfg(target_arch = "wasm32")]
+pub use inline::Stepper;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod worker {
+    use std::panic;
+    use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+    use std::thread::{self, JoinHandle};
+
+    use super::{Received, StepJob};
+
+    enum Command {
+        Run(StepJob),
+        /// 목표 스텝을 늘린다.
+        Aim(usize),
+        /// 지난 스냅샷 뒤로 계산한 스텝이 있으면 스냅샷을 보낸다.
+        Snapshot,
+        /// 계산을 멈추고 맡은 것을 돌려준다.
+        Stop,
+    }
+
+    enum Reply {
+        Snapshot(Received),
+        Stopped(Option<StepJob>),
+    }
+
+    // 맡은 시뮬레이션을 목표 스텝까지 계산하면서 틈틈이 명령을 받는다. 할 일이 없으면 명령을 기다린다.
+    fn work(commands: Receiver<Command>, replies: Sender<Reply>) {
+        let mut job: Option<StepJob> = None;
+        // 스냅샷을 보내 달라고 했는지와 마지막으로 보낸 스냅샷의 스텝
+        let mut wanted = false;
+        let mut sent = None;
+
+        loop {
+            let command = if job.as_ref().map_or(false, |job| !job.is_done()) {
+                match commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            } else if wanted && job.is_some() && sent.is_none() {
+                None
+            } else {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => return,
+                }
+            };
+
+            match command {
+                Some(Command::Run(next)) => {
+                    job = Some(next);
+                    sent = None;
+                }
+                Some(Command::Aim(target)) => {
+                    if let Some(job) = job.as_mut() {
+                        job.target = job.target.max(target);
+                    }
+                }
+                Some(Command::Snapshot) => wanted = true,
+                Some(Command::Stop) => {
+                    wanted = false;
+                    if replies.send(Reply::Stopped(job.take())).is_err() {
+                        return;
+                    }
+                }
+                None => {}
+            }
+
+            let Some(job) = job.as_mut() else {
+                continue;
+            };
+
+            if !job.is_done() {
+                job.advance();
+            }
+
+            if wanted && sent != Some(job.state.max_step) {
+                wanted = false;
+                sent = Some(job.state.max_step);
+
+                let snapshot = Received {
+                    simulation: job.simulation.snapshot(),
+                    state: job.state.clone(),
+                };
+                if replies.send(Reply::Snapshot(snapshot)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    struct Worker {
+        commands: Sender<Command>,
+        replies: Receiver<Reply>,
+        handle: Option<JoinHandle<()>>,
+    }
+
+    impl Worker {
+        fn spawn() -> Self {
+            let (commands, command_receiver) = mpsc::channel();
+            let (reply_sender, replies) = mpsc::channel();
+
+            let handle = thread::Builder::new()
+                .name("stepper".to_string())
+                .spawn(move || work(command_receiver, reply_sender))
+                .expect("cannot spawn the stepping thread");
+
+            Self {
+                commands,
+                replies,
+                handle: Some(handle),
+            }
+        }
+
+        fn send(&mut self, command: Command) {
+            if self.commands.send(command).is_err() {
+                self.rethrow();
+            }
+        }
+
+        fn recv(&mut self) -> Reply {
+            match self.replies.recv() {
+                Ok(reply) => reply,
+                Err(_) => self.rethrow(),
+            }
+        }
+
+        // 시뮬레이션이 작업 스레드에서 패닉했으면 여기서 이어서 패닉한다.
+        fn rethrow(&mut self) -> ! {
+            match self.handle.take().map(JoinHandle::join) {
+                Some(Err(payload)) => panic::resume_unwind(payload),
+                _ => panic!("the stepping thread has stopped"),
+            }
+        }
+    }
+
+    /// 작업 스레드에 스텝 계산을 맡긴다. 스레드는 처음 맡길 때 만들고 계속 쓴다.
+    #[derive(Default)]
+    pub struct Stepper {
+        worker: Option<Worker>,
+        running: bool,
+        /// 이번에 맡긴 뒤로 스냅샷을 받았는지
+        shown: bool,
+    }
+
+    impl Stepper {
+        /// 시뮬레이션을 맡겨 두었는지. 맡겨 둔 동안 UI 가 가진 것은 스냅샷이다.
+        pub fn is_running(&self) -> bool {
+            self.running
+        }
+
+        /// `job`을 작업 스레드에 맡긴다. 돌려받을 때까지 목표 스텝을 향해 계속 계산한다.
+        pub fn start(&mut self, job: StepJob) {
+            debug_assert!(!self.running, "a step job is already running");
+
+            let worker = self.worker.get_or_insert_with(Worker::spawn);
+            worker.send(Command::Run(job));
+            worker.send(Command::Snapshot);
+            self.running = true;
+            self.shown = false;
+        }
+
+        /// 목표 스텝을 `target`까지 늘린다. 이미 더 멀리 잡혀 있으면 그대로 둔다.
+        pub fn aim(&mut self, target: usize) {
+            if let (true, Some(worker)) = (self.running, self.worker.as_mut()) {
+                worker.send(Command::Aim(target));
+            }
+        }
+
+        /// `take_back`이면 계산을 멈추고 맡긴 것을 돌려받는다. 계산 중인 스텝 하나가 끝날 때까지만 기다린다.
+        /// 아니면 지난번 뒤로 온 가장 새 스냅샷을 받는다. 맡긴 뒤로 받은 스냅샷이 없으면 첫 스냅샷을 기다린다.
+        pub fn receive(&mut self, take_back: bool) -> Option<Received> {
+            if !self.running {
+                return None;
+            }
+            let worker = self.worker.as_mut()?;
+
+            if take_back {
+                self.running = false;
+                worker.send(Command::Stop);
+
+                // 멈추기 전에 보낸 스냅샷은 버린다.
+                loop {
+                    if let Reply::Stopped(job) = worker.recv() {
+                        return job.map(Received::from);
+                    }
+                }
+            }
+
+            let mut latest = None;
+            if !self.shown {
+                if let Reply::Snapshot(snapshot) = worker.recv() {
+                    latest = Some(snapshot);
+                }
+            }
+            while let Ok(reply) = worker.replies.try_recv() {
+                if let Reply::Snapshot(snapshot) = reply {
+                    latest = Some(snapshot);
+                }
+            }
+
+            // 받을 때마다 다음 것을 하나만 청해서 스냅샷을 프레임마다 많아야 하나씩 만든다.
+            if latest.is_some() {
+                self.shown = true;
+                worker.send(Command::Snapshot);
+            }
+
+            latest
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::time::{Duration, Instant};
+
+        use egui::Response;
+
+        use super::*;
+        use crate::app::graphics::define::PlotItem;
+        use crate::app::graphics::plot::{InputMessage, PlotData};
+        use crate::app::simulations::state::SimulationState;
+        use crate::app::simulations::{Simulation, TargetId};
+
+        /// 스텝마다 수를 하나씩 세는 시뮬레이션. 센 수는 `inspect`로 본다.
+        #[derive(Clone, Default)]
+        struct Counter {
+            steps: usize,
+        }
+
+        impl Simulation for Counter {
+            fn input(
+                &mut self,
+                _plot: &mut PlotData,
+                _input_msg: InputMessage,
+                _response: Response,
+                _ctx: &egui::Context,
+                _state: &mut SimulationState,
+            ) {
+            }
+
+            fn step(&mut self, _state: &mut SimulationState) {
+                self.steps += 1;
+            }
+
+            fn snapshot(&self) -> Box<dyn Simulation> {
+                Box::new(self.clone())
+            }
+
+            fn at_time_step(&mut self, _step: usize) {}
+
+            fn plot_items(
+                &mut self,
+                _ctx: &egui::Context,
+                _state: &SimulationState,
+            ) -> Vec<PlotItem> {
+                vec![]
+            }
+
+            fn inspect(
+                &self,
+                _target: TargetId,
+                _state: &SimulationState,
+            ) -> Vec<(String, String)> {
+                vec![("Steps".to_string(), self.steps.to_string())]
+            }
+        }
+
+        fn counting(target: usize) -> StepJob {
+            StepJob::new(
+                Box::<Counter>::default(),
+                SimulationState::default(),
+                target,
+            )
+        }
+
+        fn recorded(received: &Received) -> usize {
+            let inspected = received
+                .simulation
+                .inspect(TargetId::new(0, 0), &received.state);
+
+            inspected[0].1.parse().unwrap()
+        }
+
+        #[test]
+        fn worker_keeps_stepping_while_a_snapshot_is_held() {
+            let mut stepper = Stepper::default();
+            stepper.start(counting(usize::MAX));
+
+            let held = stepper.receive(false).unwrap();
+            let seen = held.state.max_step;
+            assert!(stepper.is_running());
+
+            // 스냅샷을 들고 있는 동안 새 스냅샷이 더 나아간 스텝을 보여 줘야 한다.
+            let deadline = Instant::now() + Duration::from_secs(30);
+            let newer = loop {
+                assert!(Instant::now() < deadline, "the worker stopped at {}", seen);
+
+                match stepper.receive(false) {
+                    Some(newer) if newer.state.max_step > seen => break newer,
+                    _ => thread::sleep(Duration::from_millis(1)),
+                }
+            };
+
+            assert_eq!(held.state.max_step, seen);
+            assert_eq!(recorded(&held), seen);
+            assert_eq!(recorded(&newer), newer.state.max_step);
+
+            let returned = stepper.receive(true).unwrap();
+            assert!(returned.state.max_step >= newer.state.max_step);
+            assert!(!stepper.is_running());
+        }
+
+        #[test]
+        fn worker_stops_at_the_target() {
+            let mut stepper = Stepper::default();
+            stepper.start(counting(120));
+            stepper.aim(240);
+
+            let deadline = Instant::now() + Duration::from_secs(30);
+            while stepper
+                .receive(false)
+                .map_or(true, |snapshot| snapshot.state.max_step < 240)
+            {
+                assert!(Instant::now() < deadline);
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            let returned = stepper.receive(true).unwrap();
+            assert_eq!(returned.state.max_step, 240);
+            assert_eq!(recorded(&returned), 240);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod inline {
+    use instant::Instant;
+    use std::time::Duration;
+
+    use super::{Received, StepJob};
+
+    /// 한 프레임에서 새 스텝을 계산하는 데 쓸 시간. 넘기면 남은 스텝은 버리고 느리게 간다.
+    const STEP_BUDGET: Duration = Duration::from_millis(25);
+
+    /// 웹에는 스레드가 없으므로 받을 때 정해진 시간만큼 계산하고 맡긴 것을 그대로 돌려준다.
+    #[derive(Default)]
+    pub struct Stepper {
+        job: Option<StepJob>,
+    }
+
+    impl Stepper {
+        pub fn is_running(&self) -> bool {
+            self.job.is_some()
+        }
+
+        pub fn start(&mut self, job: StepJob) {
+            self.job = Some(job);
+        }
+
+        pub fn aim(&mut self, target: usize) {
+            if let Some(job) = self.job.as_mut() {
+                job.target = job.target.max(target);
+            }
+        }
+
+        pub fn receive(&mut self, _take_back: bool) -> Option<Received> {
+            let mut job = self.job.take()?;
+            let started = Instant::now();
+
+            while !job.is_done() && started.elapsed() < STEP_BUDGET {
+                job.advance();
+            }
+
+            Some(job.into())
+        }
+    }
+}
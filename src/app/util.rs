@@ -1,7 +1,5 @@
-
 use egui::util::History;
 
-
 pub struct FrameHistory {
     frame_times: History<f32>,
 }
@@ -34,4 +32,3 @@ impl FrameHistory {
         1.0 / self.frame_times.mean_time_interval().unwrap_or_default()
     }
 }
-
@@ -0,0 +1,243 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+//! 창 없이 시나리오를 돌리고 결과를 CSV나 JSON으로 남긴다.
+//!
+//! physics-cli <scenario> [--steps N] [--until COND] [--every K] [--format json|csv] [--output PATH]
+
+use physics::engine::classic::export::ClassicExport;
+use physics::engine::classic::scenario::Scenario;
+use physics::engine::classic::template::{get_cs_sim_list, CSTemplate};
+use physics::engine::classic::ClassicWorld;
+use physics::engine::state::SimulationState;
+use physics::engine::SIMULATION_TICK;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: physics-cli <scenario> [options]
+       physics-cli --list
+
+<scenario> is a built-in scenario name (see --list) or a scenario file.
+
+options:
+  --steps N          run at most N steps (default 2400)
+  --until COND       stop early: stamped | collision | time=SECONDS
+  --every K          keep every K-th step of the object timelines (default 1)
+  --format FORMAT    json | csv (default json)
+  --output PATH      json: file (default stdout), csv: directory (default .)";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StopCondition {
+    /// 모든 스탬프가 찍혔다.
+    Stamped,
+    /// 이번 스텝에 충돌이 있었다.
+    Collision,
+    Time(f64),
+}
+
+impl StopCondition {
+    fn parse(text: &str) -> Result<Self, String> {
+        match text.split_once('=') {
+            None if text == "stamped" => Ok(Self::Stamped),
+            None if text == "collision" => Ok(Self::Collision),
+            Some(("time", seconds)) => seconds
+                .parse()
+                .map(Self::Time)
+                .map_err(|_| format!("invalid time: {}", seconds)),
+            _ => Err(format!("unknown condition: {}", text)),
+        }
+    }
+
+    fn is_met(&self, world: &ClassicWorld, time: f64) -> bool {
+        match self {
+            Self::Stamped => {
+                !world.stamps.is_empty() && world.stamps.iter().all(|s| s.result().is_some())
+            }
+            Self::Collision => world
+                .events
+                .last()
+                .map_or(false, |events| events.iter().next().is_some()),
+            Self::Time(limit) => time >= *limit,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+struct Options {
+    scenario: String,
+    steps: usize,
+    until: Option<StopCondition>,
+    every: usize,
+    format: Format,
+    output: Option<PathBuf>,
+}
+
+/// JSON 출력. 내보낸 표 앞에 실행 정보를 적는다.
+#[derive(Serialize)]
+struct Run<'a> {
+    scenario: &'a str,
+    steps: usize,
+    time: f64,
+    stopped_by: Option<String>,
+    #[serde(flatten)]
+    export: &'a ClassicExport,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut scenario = None;
+    let mut options = Options {
+        scenario: String::new(),
+        steps: 2400,
+        until: None,
+        every: 1,
+        format: Format::Json,
+        output: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--list" => {
+                for template in get_cs_sim_list() {
+                    println!("{}", template.get_name());
+                }
+                return Ok(None);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(None);
+            }
+            "--steps" => {
+                options.steps = value()?.parse().map_err(|e| format!("--steps: {}", e))?;
+            }
+            "--until" => options.until = Some(StopCondition::parse(&value()?)?),
+            "--every" => {
+                options.every = value()?.parse().map_err(|e| format!("--every: {}", e))?;
+            }
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            "--output" => options.output = Some(PathBuf::from(value()?)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ => scenario = Some(arg),
+        }
+    }
+
+    options.scenario = scenario.ok_or("missing scenario")?;
+    Ok(Some(options))
+}
+
+/// 파일 경로면 시나리오 파일을 읽고, 아니면 기본 시나리오에서 이름으로 찾는다.
+fn find_scenario(name: &str) -> Result<CSTemplate, String> {
+    if Path::new(name).is_file() {
+        return std::fs::read_to_string(name)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Scenario::from_json(&text).map_err(|e| e.to_string()))
+            .map(|scenario| CSTemplate::Scenario(Box::new(scenario)))
+            .map_err(|e| format!("{}: {}", name, e));
+    }
+
+    get_cs_sim_list()
+        .into_iter()
+        .find(|template| template.get_name().eq_ignore_ascii_case(name))
+        .ok_or(format!("unknown scenario: {} (see --list)", name))
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let template = find_scenario(&options.scenario)?;
+
+    let preset = template.get_preset_with_ui();
+    let mut state = SimulationState {
+        settings: preset.settings.clone(),
+        sim_started: true,
+        ..SimulationState::default()
+    };
+    let mut world = ClassicWorld::from(preset);
+    let mut stopped_by = None;
+
+    while state.max_step < options.steps {
+        world.step(&mut state.settings, state.time);
+        state.max_step += 1;
+        state.current_step = state.max_step;
+        state.time = SIMULATION_TICK * state.max_step as f64;
+
+        if let Some(condition) = options.until.filter(|c| c.is_met(&world, state.time)) {
+            stopped_by = Some(format!("{:?}", condition));
+            break;
+        }
+    }
+
+    let export = ClassicExport::new(&world, options.every);
+
+    eprintln!(
+        "{}: {} steps ({:.3} s), {} samples, {} events, {} stamps",
+        options.scenario,
+        state.max_step,
+        state.time,
+        export.objects.len(),
+        export.events.len(),
+        export.stamps.len()
+    );
+
+    match options.format {
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&Run {
+                scenario: &options.scenario,
+                steps: state.max_step,
+                time: state.time,
+                stopped_by,
+                export: &export,
+            })
+            .map_err(|e| e.to_string())?;
+
+            match options.output {
+                Some(path) => {
+                    std::fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?
+                }
+                None => println!("{}", json),
+            }
+        }
+        Format::Csv => {
+            let directory = options.output.unwrap_or_else(|| PathBuf::from("."));
+            std::fs::create_dir_all(&directory)
+                .map_err(|e| format!("{}: {}", directory.display(), e))?;
+
+            for (file, csv) in [
+                ("objects.csv", export.objects_csv()),
+                ("events.csv", export.events_csv()),
+                ("stamps.csv", export.stamps_csv()),
+            ] {
+                let path = directory.join(file);
+                std::fs::write(&path, csv).map_err(|e| format!("{}: {}", path.display(), e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args(std::env::args().skip(1)).and_then(|options| match options {
+        Some(options) => run(options),
+        None => Ok(()),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("physics-cli: {}\n\n{}", message, USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
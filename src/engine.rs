@@ -0,0 +1,23 @@
+//! egui 없이 도는 시뮬레이션 핵심.
+//! 상태, 스텝, 이벤트, 템플릿이 여기에 있고 `app`은 그 위에서 그리기와 입력만 맡는다.
+
+pub mod chunked;
+pub mod circuit;
+pub mod classic;
+pub mod fluid;
+pub mod grid;
+pub mod heat;
+pub mod keyframe;
+pub mod optics;
+pub mod polygon;
+pub mod rng;
+pub mod state;
+pub mod wave;
+
+use nalgebra::Vector2;
+
+pub type Float = f64;
+pub type NVec2 = Vector2<Float>;
+
+/// 한 스텝의 시간 (초)
+pub const SIMULATION_TICK: f64 = 1.0 / 240.0;
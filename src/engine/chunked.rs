@@ -0,0 +1,300 @@
+//! 덩어리로 나눠 담는 벡터. 복사하면 다 찬 덩어리는 함께 쓰고 고칠 때만 그 덩어리를 복사한다.
+//! 스텝마다 늘어나는 기록을 시뮬레이션 사본끼리 나눠 쓰는 데 쓴다.
+
+use std::ops::Index;
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 한 덩어리의 길이
+const CHUNK: usize = 256;
+
+#[derive(Clone, Debug)]
+pub struct ChunkedVec<T> {
+    /// 다 찬 덩어리. 사본끼리 함께 쓴다.
+    chunks: Arc<Vec<Arc<Vec<T>>>>,
+    /// 아직 차지 않은 마지막 덩어리
+    tail: Vec<T>,
+    /// 첫 덩어리에서 버린 앞부분의 길이
+    skip: usize,
+}
+
+impl<T> Default for ChunkedVec<T> {
+    fn default() -> Self {
+        Self {
+            chunks: Arc::default(),
+            tail: vec![],
+            skip: 0,
+        }
+    }
+}
+
+impl<T: Clone> ChunkedVec<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len() * CHUNK + self.tail.len() - self.skip
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.tail.push(value);
+
+        if self.tail.len() == CHUNK {
+            let full = std::mem::replace(&mut self.tail, Vec::with_capacity(CHUNK));
+            Arc::make_mut(&mut self.chunks).push(Arc::new(full));
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let index = index + self.skip;
+        match self.chunks.get(index / CHUNK) {
+            Some(chunk) => chunk.get(index % CHUNK),
+            None => self.tail.get(index - self.chunks.len() * CHUNK),
+        }
+    }
+
+    /// 덩어리를 다른 사본과 함께 쓰고 있으면 그 덩어리만 복사한다.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let index = index + self.skip;
+        let full = self.chunks.len() * CHUNK;
+        if index >= full {
+            return self.tail.get_mut(index - full);
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+        Arc::make_mut(&mut chunks[index / CHUNK]).get_mut(index % CHUNK)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.range(0, self.len())
+    }
+
+    /// `from`부터 `to` 앞까지. 앞의 덩어리는 건너뛴다.
+    pub fn range(&self, from: usize, to: usize) -> impl Iterator<Item = &T> {
+        let to = to.min(self.len()) + self.skip;
+        let from = (from + self.skip).min(to);
+
+        self.chunks
+            .iter()
+            .map(|chunk| chunk.as_slice())
+            .chain(std::iter::once(self.tail.as_slice()))
+            .enumerate()
+            .skip(from / CHUNK)
+            .take_while(move |(index, _)| index * CHUNK < to)
+            .flat_map(move |(index, values)| {
+                let start = index * CHUNK;
+                let end = (to - start).min(values.len());
+
+                values[(from.max(start) - start).min(end)..end].iter()
+            })
+    }
+
+    /// 함께 쓰던 덩어리는 모두 복사한다.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        let skip = self.skip;
+
+        Arc::make_mut(&mut self.chunks)
+            .iter_mut()
+            .flat_map(|chunk| Arc::make_mut(chunk).iter_mut())
+            .chain(self.tail.iter_mut())
+            .skip(skip)
+    }
+
+    /// 처음 `len`개만 남긴다.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+
+        let end = len + self.skip;
+        let full = self.chunks.len() * CHUNK;
+        if end >= full {
+            self.tail.truncate(end - full);
+            return;
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+        self.tail = chunks[end / CHUNK][..end % CHUNK].to_vec();
+        chunks.truncate(end / CHUNK);
+        self.settle();
+    }
+
+    /// 앞에서 `count`개를 버린다.
+    pub fn drain_front(&mut self, count: usize) {
+        let skip = self.skip + count.min(self.len());
+        let whole = (skip / CHUNK).min(self.chunks.len());
+
+        if whole > 0 {
+            Arc::make_mut(&mut self.chunks).drain(..whole);
+        }
+        self.skip = skip - whole * CHUNK;
+        self.settle();
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let mut values = self.iter().cloned().collect::<Vec<_>>();
+        let removed = values.remove(index);
+        *self = Self::from(values);
+
+        removed
+    }
+
+    /// `pred`가 참인 앞부분의 길이. 앞부분에서만 참이어야 한다.
+    pub fn partition_point(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+
+        while low < high {
+            let middle = (low + high) / 2;
+            if pred(&self[middle]) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        low
+    }
+
+    // 다 찬 덩어리가 없으면 버린 앞부분을 마지막 덩어리에서 지운다.
+    fn settle(&mut self) {
+        if self.chunks.is_empty() && self.skip > 0 {
+            self.tail.drain(..self.skip);
+            self.skip = 0;
+        }
+    }
+}
+
+impl<T: Clone> Index<usize> for ChunkedVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of range")
+    }
+}
+
+impl<T: Clone> From<Vec<T>> for ChunkedVec<T> {
+    fn from(values: Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<T: Clone> FromIterator<T> for ChunkedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values = Self::new();
+        for value in iter {
+            values.push(value);
+        }
+        values
+    }
+}
+
+impl<T: Clone> Extend<T> for ChunkedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+// 저장 형식은 그냥 배열이다.
+impl<T: Clone + Serialize> Serialize for ChunkedVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for ChunkedVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(count: usize) -> ChunkedVec<usize> {
+        (0..count).collect()
+    }
+
+    #[test]
+    fn copies_share_full_chunks() {
+        let mut values = numbers(3 * CHUNK + 10);
+        let copy = values.clone();
+
+        assert!(Arc::ptr_eq(&values.chunks, &copy.chunks));
+
+        values.push(0);
+        *values.get_mut(5).unwrap() = 100;
+
+        assert_eq!(copy.len(), 3 * CHUNK + 10);
+        assert_eq!(copy[5], 5);
+        assert_eq!(values[5], 100);
+        assert!(Arc::ptr_eq(&values.chunks[1], &copy.chunks[1]));
+    }
+
+    #[test]
+    fn ranges_and_truncation_skip_dropped_values() {
+        let mut values = numbers(3 * CHUNK + 10);
+        values.drain_front(CHUNK + 7);
+
+        assert_eq!(values.len(), 2 * CHUNK + 3);
+        assert_eq!(values.first(), Some(&(CHUNK + 7)));
+        assert_eq!(values.last(), Some(&(3 * CHUNK + 9)));
+        assert_eq!(
+            values
+                .range(CHUNK - 9, CHUNK + 1)
+                .copied()
+                .collect::<Vec<_>>(),
+            (2 * CHUNK - 2..2 * CHUNK + 8).collect::<Vec<_>>()
+        );
+        assert!(values.iter().copied().eq(CHUNK + 7..3 * CHUNK + 10));
+
+        values.truncate(CHUNK);
+        assert!(values.iter().copied().eq(CHUNK + 7..2 * CHUNK + 7));
+        values.push(0);
+        assert_eq!(values.len(), CHUNK + 1);
+
+        values.drain_front(CHUNK);
+        assert!(values.iter().copied().eq([0]));
+        assert_eq!(values.partition_point(|value| *value < 1), 1);
+    }
+
+    #[test]
+    fn serializes_as_a_plain_list() {
+        let values = numbers(CHUNK + 1);
+        let json = serde_json::to_string(&values).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::to_string(&(0..=CHUNK).collect::<Vec<_>>()).unwrap()
+        );
+        assert!(serde_json::from_str::<ChunkedVec<usize>>(&json)
+            .unwrap()
+            .iter()
+            .copied()
+            .eq(0..=CHUNK));
+    }
+}
@@ -0,0 +1,6 @@
+//! 직류 회로. 격자 위 부품의 배치, 수정 절점 해석 풀이, 설정과 템플릿이 있다.
+
+pub mod component;
+pub mod sim_state;
+pub mod solver;
+pub mod template;
@@ -0,0 +1,174 @@
+use crate::engine::polygon::segment_outline;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+/// 격자 한 칸의 크기. 부품의 두 끝은 모두 격자점에 놓인다.
+pub const GRID: f64 = 10.0;
+
+/// 격자점의 정수 좌표. 끝이 같은 격자점에 놓인 부품끼리 이어진다.
+pub type GridPoint = (i64, i64);
+
+pub fn grid_point(p: NVec2) -> GridPoint {
+    ((p.x / GRID).round() as i64, (p.y / GRID).round() as i64)
+}
+
+/// 격자점의 위치
+pub fn grid_position((x, y): GridPoint) -> NVec2 {
+    NVec2::new(x as f64 * GRID, y as f64 * GRID)
+}
+
+/// 가장 가까운 격자점
+pub fn snap(p: NVec2) -> NVec2 {
+    grid_position(grid_point(p))
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ComponentKind {
+    Wire,
+    /// 끝점 쪽이 (+)극이다.
+    Battery {
+        voltage: f64,
+        internal_resistance: f64,
+    },
+    Resistor {
+        resistance: f64,
+    },
+    Capacitor {
+        capacitance: f64,
+        /// 처음 양 끝의 전압 (시작점 - 끝점)
+        initial_voltage: f64,
+    },
+    Switch {
+        closed: bool,
+    },
+    /// 정격 전력에서 가장 밝은 저항
+    Bulb {
+        resistance: f64,
+        rated_power: f64,
+    },
+}
+
+/// 부품 붓으로 고를 수 있는 부품들
+pub const COMPONENT_KINDS: [ComponentKind; 5] = [
+    ComponentKind::Battery {
+        voltage: 9.0,
+        internal_resistance: 0.0,
+    },
+    ComponentKind::Resistor { resistance: 100.0 },
+    ComponentKind::Capacitor {
+        capacitance: 1e-3,
+        initial_voltage: 0.0,
+    },
+    ComponentKind::Switch { closed: true },
+    ComponentKind::Bulb {
+        resistance: 12.0,
+        rated_power: 6.0,
+    },
+];
+
+impl ComponentKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ComponentKind::Wire => "Wire",
+            ComponentKind::Battery { .. } => "Battery",
+            ComponentKind::Resistor { .. } => "Resistor",
+            ComponentKind::Capacitor { .. } => "Capacitor",
+            ComponentKind::Switch { .. } => "Switch",
+            ComponentKind::Bulb { .. } => "Bulb",
+        }
+    }
+}
+
+/// 두 격자점 사이에 놓인 부품. 전류는 시작점에서 끝점으로 흐를 때 양수다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Component {
+    /// 배치가 바뀌어도 부품의 상태를 찾는 번호
+    pub id: usize,
+    pub name: String,
+    pub kind: ComponentKind,
+    pub start: NVec2,
+    pub end: NVec2,
+}
+
+impl Component {
+    pub fn center(&self) -> NVec2 {
+        (self.start + self.end) / 2.0
+    }
+
+    pub fn length(&self) -> f64 {
+        (self.end - self.start).norm()
+    }
+
+    /// 두 끝이 격자점에 남도록 옮긴다.
+    pub fn translate(&mut self, delta: NVec2) {
+        let start = snap(self.start + delta);
+        let delta = start - self.start;
+
+        self.start = start;
+        self.end += delta;
+    }
+
+    /// 두 끝을 바꾼다. 전지와 축전기의 극이 뒤집힌다.
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.start, &mut self.end);
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        segment_outline(self.start, self.end, 2.0)
+    }
+
+    /// 시작점에서 `distance`만큼 간 점
+    pub fn point_at(&self, distance: f64) -> NVec2 {
+        self.start + (self.end - self.start).normalize() * distance
+    }
+}
+
+/// 부품들의 배치. 타임라인에는 바뀐 스텝의 배치만 저장한다.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CircuitLayout {
+    pub components: Vec<Component>,
+    next_id: usize,
+}
+
+impl CircuitLayout {
+    /// 두 끝을 격자점에 맞춰 부품을 놓는다.
+    pub fn add(&mut self, name: impl Into<String>, kind: ComponentKind, start: NVec2, end: NVec2) {
+        self.components.push(Component {
+            id: self.next_id,
+            name: name.into(),
+            kind,
+            start: snap(start),
+            end: snap(end),
+        });
+        self.next_id += 1;
+    }
+
+    pub fn with_component(
+        mut self,
+        name: &str,
+        kind: ComponentKind,
+        start: [f64; 2],
+        end: [f64; 2],
+    ) -> Self {
+        self.add(
+            name,
+            kind,
+            NVec2::new(start[0], start[1]),
+            NVec2::new(end[0], end[1]),
+        );
+        self
+    }
+
+    /// 격자점마다 이어진 부품 끝의 수
+    pub fn degrees(&self) -> std::collections::BTreeMap<GridPoint, usize> {
+        let mut degrees = std::collections::BTreeMap::new();
+
+        for component in &self.components {
+            for end in [component.start, component.end] {
+                *degrees.entry(grid_point(end)).or_insert(0) += 1;
+            }
+        }
+
+        degrees
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CircuitSettings {
+    /// 한 스텝에 흐르는 회로 시간의 배율
+    pub(crate) time_scale: f64,
+    /// 전하 1 C이 흐를 때 전류 점이 움직이는 거리
+    pub(crate) current_scale: f64,
+    /// 격자점마다 전위를 적는다.
+    pub(crate) show_potentials: bool,
+}
+
+impl Default for CircuitSettings {
+    fn default() -> Self {
+        Self {
+            time_scale: 1.0,
+            current_scale: 100.0,
+            show_potentials: true,
+        }
+    }
+}
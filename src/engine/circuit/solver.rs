@@ -0,0 +1,444 @@
+use crate::engine::circuit::component::{
+    grid_point, CircuitLayout, Component, ComponentKind, GridPoint,
+};
+use crate::engine::keyframe::Stepped;
+use nalgebra::{DMatrix, DVector};
+use std::collections::BTreeMap;
+
+/// 전선, 닫힌 스위치, 이상적인 전지의 저항. 0이면 전선이 고리를 이룰 때 행렬이 특이해진다.
+const MIN_RESISTANCE: f64 = 1e-4;
+
+/// 어디에도 이어지지 않은 노드도 풀리도록 모든 노드와 접지 사이에 넣는 컨덕턴스
+const MIN_CONDUCTANCE: f64 = 1e-9;
+
+/// 전지에 이보다 센 전류가 흐르면 합선으로 본다. (A)
+pub const SHORT_CIRCUIT_CURRENT: f64 = 50.0;
+
+/// 수정 절점 해석(MNA)에서 부품 하나가 행렬에 들어가는 꼴
+enum Stamp {
+    Conductance(f64),
+    /// 컨덕턴스와 나란한 전류원. 전류원은 시작점에서 끝점으로 흐른다.
+    Companion {
+        conductance: f64,
+        current: f64,
+    },
+    /// 끝점 전위 - 시작점 전위 = `voltage` - `resistance` × 전류. 전류가 미지수로 추가된다.
+    Source {
+        voltage: f64,
+        resistance: f64,
+    },
+    Open,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComponentState {
+    /// 시작점 전위 - 끝점 전위
+    pub voltage: f64,
+    /// 시작점에서 끝점으로 흐르는 전류
+    pub current: f64,
+    /// 지금까지 흐른 전하. 전류를 나타내는 점을 이만큼 움직인다.
+    pub charge: f64,
+}
+
+struct Solution {
+    potentials: BTreeMap<GridPoint, f64>,
+    voltages: Vec<f64>,
+    currents: Vec<f64>,
+}
+
+/// 한 스텝의 회로. 축전기 전압과 흐른 전하를 부품 번호로 찾는다.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CircuitState {
+    pub step: usize,
+    pub time: f64,
+    pub components: BTreeMap<usize, ComponentState>,
+    /// 격자점마다 접지에 대한 전위
+    pub potentials: BTreeMap<GridPoint, f64>,
+    /// 풀지 못했으면 그 까닭. 전위와 전류는 그 전 스텝의 값을 쓴다.
+    pub error: Option<String>,
+}
+
+/// 첫 전지의 (-)극, 전지가 없으면 첫 부품의 시작점
+pub fn ground(layout: &CircuitLayout) -> Option<GridPoint> {
+    layout
+        .components
+        .iter()
+        .find(|component| matches!(component.kind, ComponentKind::Battery { .. }))
+        .or_else(|| layout.components.first())
+        .map(|component| grid_point(component.start))
+}
+
+impl Stepped for CircuitState {
+    fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl CircuitState {
+    pub fn new(layout: &CircuitLayout) -> Self {
+        let mut state = Self::default();
+        state.refresh(layout);
+        state
+    }
+
+    /// 지금 축전기 전압으로 전위와 전류만 다시 푼다. 배치를 고쳤을 때 쓴다.
+    pub fn refresh(&mut self, layout: &CircuitLayout) {
+        let solution = self.solve(layout, 0.0);
+        self.apply(layout, solution, 0.0);
+    }
+
+    pub fn advance(&mut self, layout: &CircuitLayout, dt: f64) {
+        let solution = self.solve(layout, dt);
+        self.apply(layout, solution, dt);
+
+        self.step += 1;
+        self.time += dt;
+    }
+
+    pub fn get(&self, component: &Component) -> ComponentState {
+        self.components
+            .get(&component.id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 축전기의 지금 전압. 처음 놓인 축전기는 처음 전압을 쓴다.
+    fn capacitor_voltage(&self, component: &Component) -> Option<f64> {
+        match component.kind {
+            ComponentKind::Capacitor {
+                initial_voltage, ..
+            } => Some(
+                self.components
+                    .get(&component.id)
+                    .map_or(initial_voltage, |state| state.voltage),
+            ),
+            _ => None,
+        }
+    }
+
+    // 축전기는 `dt`가 있으면 후진 오일러 등가 회로, 없으면 지금 전압의 전원으로 둔다.
+    fn stamp(&self, component: &Component, dt: f64) -> Stamp {
+        let wire = Stamp::Source {
+            voltage: 0.0,
+            resistance: MIN_RESISTANCE,
+        };
+
+        match component.kind {
+            ComponentKind::Wire | ComponentKind::Switch { closed: true } => wire,
+            ComponentKind::Switch { closed: false } => Stamp::Open,
+            ComponentKind::Battery {
+                voltage,
+                internal_resistance,
+            } => Stamp::Source {
+                voltage,
+                resistance: internal_resistance.max(MIN_RESISTANCE),
+            },
+            ComponentKind::Resistor { resistance } | ComponentKind::Bulb { resistance, .. } => {
+                Stamp::Conductance(1.0 / resistance.max(MIN_RESISTANCE))
+            }
+            ComponentKind::Capacitor { capacitance, .. } => {
+                let previous = self.capacitor_voltage(component).unwrap_or_default();
+
+                if dt > 0.0 {
+                    let conductance = capacitance / dt;
+                    Stamp::Companion {
+                        conductance,
+                        current: -conductance * previous,
+                    }
+                } else {
+                    Stamp::Source {
+                        voltage: -previous,
+                        resistance: MIN_RESISTANCE,
+                    }
+                }
+            }
+        }
+    }
+
+    fn solve(&self, layout: &CircuitLayout, dt: f64) -> Result<Solution, String> {
+        let ground = ground(layout).ok_or("The circuit has no components.")?;
+
+        // 접지를 뺀 노드마다 행 하나
+        let mut rows = BTreeMap::new();
+        for component in &layout.components {
+            for end in [component.start, component.end] {
+                let point = grid_point(end);
+                if point != ground && !rows.contains_key(&point) {
+                    rows.insert(point, rows.len());
+                }
+            }
+        }
+        let row = |point| rows.get(&grid_point(point)).copied();
+
+        let stamps = layout
+            .components
+            .iter()
+            .map(|component| self.stamp(component, dt))
+            .collect::<Vec<_>>();
+
+        let nodes = rows.len();
+        let sources = stamps
+            .iter()
+            .filter(|stamp| matches!(stamp, Stamp::Source { .. }))
+            .count();
+
+        let mut matrix = DMatrix::<f64>::zeros(nodes + sources, nodes + sources);
+        let mut rhs = DVector::<f64>::zeros(nodes + sources);
+        let mut source_rows = vec![None; stamps.len()];
+
+        for i in 0..nodes {
+            matrix[(i, i)] += MIN_CONDUCTANCE;
+        }
+
+        let mut next_source = nodes;
+        for (i, (component, stamp)) in layout.components.iter().zip(&stamps).enumerate() {
+            let (p, q) = (row(component.start), row(component.end));
+
+            match *stamp {
+                Stamp::Conductance(conductance) | Stamp::Companion { conductance, .. } => {
+                    if let Some(p) = p {
+                        matrix[(p, p)] += conductance;
+                    }
+                    if let Some(q) = q {
+                        matrix[(q, q)] += conductance;
+                    }
+                    if let (Some(p), Some(q)) = (p, q) {
+                        matrix[(p, q)] -= conductance;
+                        matrix[(q, p)] -= conductance;
+                    }
+
+                    if let Stamp::Companion { current, .. } = *stamp {
+                        if let Some(p) = p {
+                            rhs[p] -= current;
+                        }
+                        if let Some(q) = q {
+                            rhs[q] += current;
+                        }
+                    }
+                }
+                Stamp::Source {
+                    voltage,
+                    resistance,
+                } => {
+                    let k = next_source;
+                    next_source += 1;
+
+                    if let Some(p) = p {
+                        matrix[(p, k)] += 1.0;
+                        matrix[(k, p)] -= 1.0;
+                    }
+                    if let Some(q) = q {
+                        matrix[(q, k)] -= 1.0;
+                        matrix[(k, q)] += 1.0;
+                    }
+                    matrix[(k, k)] += resistance;
+                    rhs[k] = voltage;
+
+                    source_rows[i] = Some(k);
+                }
+                Stamp::Open => {}
+            }
+        }
+
+        let x = matrix
+            .lu()
+            .solve(&rhs)
+            .ok_or("The circuit equations are singular.")?;
+        // 값이 무한하거나 NaN인 부품이 있으면 행렬이 풀려도 전위가 숫자가 아니다.
+        if !x.iter().all(|value| value.is_finite()) {
+            return Err(
+                "The circuit has no finite solution. Check the component values.".to_string(),
+            );
+        }
+
+        let potential = |point| row(point).map_or(0.0, |i| x[i]);
+        let mut potentials = BTreeMap::from([(ground, 0.0)]);
+        potentials.extend(rows.iter().map(|(point, i)| (*point, x[*i])));
+
+        let voltages = layout
+            .components
+            .iter()
+            .map(|component| potential(component.start) - potential(component.end))
+            .collect::<Vec<_>>();
+
+        let currents = stamps
+            .iter()
+            .zip(&voltages)
+            .zip(&source_rows)
+            .map(|((stamp, voltage), source_row)| match *stamp {
+                Stamp::Conductance(conductance) => conductance * voltage,
+                Stamp::Companion {
+                    conductance,
+                    current,
+                } => conductance * voltage + current,
+                Stamp::Source { .. } => source_row.map_or(0.0, |k| x[k]),
+                Stamp::Open => 0.0,
+            })
+            .collect();
+
+        Ok(Solution {
+            potentials,
+            voltages,
+            currents,
+        })
+    }
+
+    fn apply(&mut self, layout: &CircuitLayout, solution: Result<Solution, String>, dt: f64) {
+        let solution = match solution {
+            Ok(solution) => solution,
+            Err(error) => {
+                self.error = Some(error);
+                self.components
+                    .values_mut()
+                    .for_each(|state| state.current = 0.0);
+                return;
+            }
+        };
+        self.error = None;
+
+        self.potentials = solution.potentials;
+        self.components = layout
+            .components
+            .iter()
+            .zip(solution.voltages.into_iter().zip(solution.currents))
+            .map(|(component, (voltage, current))| {
+                let previous = self.get(component);
+
+                // 전위만 다시 풀 때는 축전기 전압을 그대로 둔다.
+                let voltage = match self.capacitor_voltage(component) {
+                    Some(capacitor) if dt == 0.0 => capacitor,
+                    _ => voltage,
+                };
+
+                (
+                    component.id,
+                    ComponentState {
+                        voltage,
+                        current,
+                        charge: previous.charge + current * dt,
+                    },
+                )
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SIMULATION_TICK;
+
+    fn battery(voltage: f64) -> ComponentKind {
+        ComponentKind::Battery {
+            voltage,
+            internal_resistance: 0.0,
+        }
+    }
+
+    // 전지 위로 `first`, 오른쪽 아래로 `second`를 지나 전선으로 돌아오는 고리
+    fn series(voltage: f64, first: ComponentKind, second: ComponentKind) -> CircuitLayout {
+        CircuitLayout::default()
+            .with_component("Battery", battery(voltage), [0.0, 0.0], [0.0, 10.0])
+            .with_component("First", first, [0.0, 10.0], [10.0, 10.0])
+            .with_component("Second", second, [10.0, 10.0], [10.0, 0.0])
+            .with_component("Wire", ComponentKind::Wire, [10.0, 0.0], [0.0, 0.0])
+    }
+
+    #[test]
+    fn divider_follows_ohms_law() {
+        let layout = series(
+            12.0,
+            ComponentKind::Resistor { resistance: 100.0 },
+            ComponentKind::Resistor { resistance: 200.0 },
+        );
+        let state = CircuitState::new(&layout);
+        let [_, first, second, _] = [0, 1, 2, 3].map(|i| state.get(&layout.components[i]));
+
+        assert_eq!(state.error, None);
+        assert!((first.voltage - 4.0).abs() < 1e-4, "{}", first.voltage);
+        assert!((second.voltage - 8.0).abs() < 1e-4, "{}", second.voltage);
+        assert!((first.current - 0.04).abs() < 1e-6);
+        assert!((second.current - 0.04).abs() < 1e-6);
+    }
+
+    #[test]
+    fn capacitor_charges_exponentially() {
+        let (voltage, resistance, capacitance) = (10.0, 1000.0, 1e-3);
+        let layout = series(
+            voltage,
+            ComponentKind::Resistor { resistance },
+            ComponentKind::Capacitor {
+                capacitance,
+                initial_voltage: 0.0,
+            },
+        );
+        let capacitor = &layout.components[2];
+        let tau = resistance * capacitance;
+
+        let mut state = CircuitState::new(&layout);
+        while state.time < 3.0 * tau {
+            state.advance(&layout, SIMULATION_TICK);
+
+            // V(1 - e^(-t/RC)). 후진 오일러의 오차는 V·dt/RC 정도다.
+            let expected = voltage * (1.0 - (-state.time / tau).exp());
+            let actual = state.get(capacitor).voltage;
+            assert!(
+                (actual - expected).abs() < voltage * SIMULATION_TICK / tau,
+                "t {}: {} vs {}",
+                state.time,
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn floating_parts_are_solved_without_current() {
+        let layout = series(
+            12.0,
+            ComponentKind::Resistor { resistance: 100.0 },
+            ComponentKind::Resistor { resistance: 200.0 },
+        )
+        .with_component(
+            "Loose",
+            ComponentKind::Resistor { resistance: 50.0 },
+            [40.0, 40.0],
+            [60.0, 40.0],
+        );
+        let state = CircuitState::new(&layout);
+
+        assert_eq!(state.error, None);
+        assert!(state.potentials.values().all(|p| p.is_finite()));
+        assert_eq!(state.get(&layout.components[4]).current, 0.0);
+    }
+
+    #[test]
+    fn unsolvable_circuits_report_an_error() {
+        let resistor = ComponentKind::Resistor { resistance: 100.0 };
+
+        // 값이 무한한 전지
+        let layout = series(f64::INFINITY, resistor.clone(), resistor);
+        let state = CircuitState::new(&layout);
+        assert!(state.error.is_some());
+
+        // 음의 전기 용량이 접지와의 누설 컨덕턴스를 지워 행렬이 특이해진다.
+        let dt = 0.5;
+        let layout = CircuitLayout::default().with_component(
+            "Capacitor",
+            ComponentKind::Capacitor {
+                capacitance: -MIN_CONDUCTANCE * dt,
+                initial_voltage: 0.0,
+            },
+            [0.0, 0.0],
+            [10.0, 0.0],
+        );
+        let mut state = CircuitState::new(&layout);
+        state.advance(&layout, dt);
+        assert!(state.error.is_some());
+
+        for component in &layout.components {
+            let component = state.get(component);
+            assert!(component.voltage.is_finite() && component.current.is_finite());
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::engine::circuit::component::{CircuitLayout, ComponentKind};
+use crate::engine::circuit::sim_state::CircuitSettings;
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+pub enum CircuitTemplate {
+    SeriesParallelSim,
+    BulbBrightnessSim,
+    RcChargingSim,
+    RcDischargingSim,
+    /// 세션에 저장해 두었던 배치
+    Saved(Box<CircuitPreset>),
+}
+
+impl CircuitTemplate {
+    pub fn get_name(&self) -> String {
+        match self {
+            CircuitTemplate::Saved(_) => "Saved".to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    pub fn get_preset(self) -> CircuitPreset {
+        match self {
+            CircuitTemplate::SeriesParallelSim => series_parallel(),
+            CircuitTemplate::BulbBrightnessSim => bulb_brightness(),
+            CircuitTemplate::RcChargingSim => rc_charging(),
+            CircuitTemplate::RcDischargingSim => rc_discharging(),
+            CircuitTemplate::Saved(preset) => *preset,
+        }
+    }
+}
+
+pub fn get_circuit_sim_list() -> [CircuitTemplate; 4] {
+    [
+        CircuitTemplate::SeriesParallelSim,
+        CircuitTemplate::BulbBrightnessSim,
+        CircuitTemplate::RcChargingSim,
+        CircuitTemplate::RcDischargingSim,
+    ]
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CircuitPreset {
+    pub layout: CircuitLayout,
+    pub settings: CircuitSettings,
+}
+
+fn battery(voltage: f64) -> ComponentKind {
+    ComponentKind::Battery {
+        voltage,
+        internal_resistance: 0.0,
+    }
+}
+
+fn resistor(resistance: f64) -> ComponentKind {
+    ComponentKind::Resistor { resistance }
+}
+
+fn bulb(resistance: f64, rated_power: f64) -> ComponentKind {
+    ComponentKind::Bulb {
+        resistance,
+        rated_power,
+    }
+}
+
+// 100 Ω 뒤에 200 Ω과 300 Ω이 나란히 있다. 전체 저항은 220 Ω이다.
+fn series_parallel() -> CircuitPreset {
+    let layout = CircuitLayout::default()
+        .with_component("Battery", battery(9.0), [-40.0, -20.0], [-40.0, 20.0])
+        .with_component("Wire 1", ComponentKind::Wire, [-40.0, 20.0], [0.0, 20.0])
+        .with_component("R1", resistor(100.0), [0.0, 20.0], [40.0, 20.0])
+        .with_component("R2", resistor(200.0), [40.0, 20.0], [40.0, -20.0])
+        .with_component("Wire 2", ComponentKind::Wire, [40.0, 20.0], [70.0, 20.0])
+        .with_component("R3", resistor(300.0), [70.0, 20.0], [70.0, -20.0])
+        .with_component("Wire 3", ComponentKind::Wire, [70.0, -20.0], [40.0, -20.0])
+        .with_component("Wire 4", ComponentKind::Wire, [40.0, -20.0], [-40.0, -20.0]);
+
+    CircuitPreset {
+        layout,
+        settings: CircuitSettings {
+            current_scale: 400.0,
+            ..CircuitSettings::default()
+        },
+    }
+}
+
+// 홀로 있는 전구 A는 정격대로 켜지고, 직렬로 이은 B와 C는 4분의 1만큼 밝다.
+fn bulb_brightness() -> CircuitPreset {
+    let layout = CircuitLayout::default()
+        .with_component("Battery", battery(12.0), [-60.0, -20.0], [-60.0, 20.0])
+        .with_component(
+            "Switch",
+            ComponentKind::Switch { closed: true },
+            [-60.0, 20.0],
+            [-20.0, 20.0],
+        )
+        .with_component("Wire 1", ComponentKind::Wire, [-20.0, 20.0], [0.0, 20.0])
+        .with_component("Bulb A", bulb(12.0, 12.0), [0.0, 20.0], [0.0, -20.0])
+        .with_component("Wire 2", ComponentKind::Wire, [0.0, 20.0], [40.0, 20.0])
+        .with_component("Bulb B", bulb(12.0, 12.0), [40.0, 20.0], [40.0, 0.0])
+        .with_component("Bulb C", bulb(12.0, 12.0), [40.0, 0.0], [40.0, -20.0])
+        .with_component("Wire 3", ComponentKind::Wire, [40.0, -20.0], [0.0, -20.0])
+        .with_component("Wire 4", ComponentKind::Wire, [0.0, -20.0], [-60.0, -20.0]);
+
+    CircuitPreset {
+        layout,
+        settings: CircuitSettings {
+            current_scale: 15.0,
+            ..CircuitSettings::default()
+        },
+    }
+}
+
+// 1 kΩ과 1 mF, 시간 상수 1초로 충전된다.
+fn rc_charging() -> CircuitPreset {
+    let layout = CircuitLayout::default()
+        .with_component("Battery", battery(9.0), [-40.0, -20.0], [-40.0, 20.0])
+        .with_component(
+            "Switch",
+            ComponentKind::Switch { closed: true },
+            [-40.0, 20.0],
+            [-10.0, 20.0],
+        )
+        .with_component("R", resistor(1000.0), [-10.0, 20.0], [40.0, 20.0])
+        .with_component(
+            "C",
+            ComponentKind::Capacitor {
+                capacitance: 1e-3,
+                initial_voltage: 0.0,
+            },
+            [40.0, 20.0],
+            [40.0, -20.0],
+        )
+        .with_component("Wire", ComponentKind::Wire, [40.0, -20.0], [-40.0, -20.0]);
+
+    CircuitPreset {
+        layout,
+        settings: CircuitSettings {
+            current_scale: 2000.0,
+            ..CircuitSettings::default()
+        },
+    }
+}
+
+// 9 V로 충전된 10 mF이 스위치를 닫으면 100 Ω 전구로 방전된다. 시간 상수는 1초다.
+fn rc_discharging() -> CircuitPreset {
+    let layout = CircuitLayout::default()
+        .with_component(
+            "C",
+            ComponentKind::Capacitor {
+                capacitance: 1e-2,
+                initial_voltage: 9.0,
+            },
+            [-30.0, 20.0],
+            [-30.0, -20.0],
+        )
+        .with_component(
+            "Switch",
+            ComponentKind::Switch { closed: false },
+            [-30.0, 20.0],
+            [10.0, 20.0],
+        )
+        .with_component("Bulb", bulb(100.0, 0.81), [10.0, 20.0], [10.0, -20.0])
+        .with_component("Wire", ComponentKind::Wire, [10.0, -20.0], [-30.0, -20.0]);
+
+    CircuitPreset {
+        layout,
+        settings: CircuitSettings {
+            current_scale: 200.0,
+            ..CircuitSettings::default()
+        },
+    }
+}
@@ -0,0 +1,763 @@
+pub mod boundary;
+pub mod branch;
+pub mod chain;
+pub mod edit;
+pub mod event;
+pub mod export;
+pub mod object;
+pub mod probe;
+pub mod scenario;
+pub mod sim_state;
+pub mod soft_body;
+pub mod template;
+pub mod timeline;
+pub mod zone;
+
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::{NVec2, SIMULATION_TICK};
+
+use std::ops::RangeInclusive;
+
+use nalgebra::{vector, SMatrix};
+use serde::{Deserialize, Serialize};
+
+use self::boundary::WorldBoundary;
+use self::chain::OscillatorChain;
+use self::event::{CollisionEvent, SimulationEvent, SimulationEvents};
+use self::object::state::{CSObjectState, Collision};
+use self::sim_state::CSimSettings;
+use self::soft_body::SoftBody;
+use self::template::stamp::CSObjectStamp;
+use self::template::CSPreset;
+use self::timeline::{RemovedTimeline, Timeline, MEGABYTE};
+use self::zone::{FieldZone, ZoneEffect};
+pub use object::CSimObject;
+
+pub const GRAVITY: SMatrix<f64, 2, 1> = vector![0.0, -9.8];
+pub const ZERO_FORCE: SMatrix<f64, 2, 1> = vector![0.0, 0.0];
+
+#[repr(usize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum GlobalForceSlot {
+    Gravity = 0,
+    MAX = 1,
+}
+
+/// 모든 스텝에서 뺀 오브젝트. 되돌릴 때 원래 자리에 다시 넣는다.
+#[derive(Clone, Default)]
+pub struct RemovedObjects {
+    /// (원래 인덱스, 오브젝트). 인덱스가 작은 것부터 있다.
+    objects: Vec<(usize, CSimObject)>,
+    /// (스텝, 원래 인덱스로 적힌 이벤트)
+    events: Vec<(usize, SimulationEvent)>,
+    /// 빼기 전의 소프트 바디, 체인, 스탬프 범위
+    soft_bodies: Vec<SoftBody>,
+    chains: Vec<OscillatorChain>,
+    stamp_ranges: Vec<RangeInclusive<usize>>,
+    timeline: RemovedTimeline,
+}
+
+/// 어느 스텝에서 뺀 오브젝트. 그 앞의 기록은 세계에 남아 있으므로 빼기 전의 장면만 들고 있는다.
+#[derive(Clone, Default)]
+pub struct EndedObjects {
+    indices: Vec<usize>,
+    soft_bodies: Vec<SoftBody>,
+    chains: Vec<OscillatorChain>,
+}
+
+/// 고전 역학 세계. 오브젝트의 타임라인과 스텝마다 생긴 이벤트를 가진다.
+/// 스텝마다의 기록은 사본끼리 함께 쓰므로 복사해도 기록은 복사되지 않는다.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ClassicWorld {
+    pub objects: Vec<CSimObject>,
+    pub global_acc_list: Vec<NVec2>,
+    pub events: ChunkedVec<SimulationEvents>,
+    pub zones: Vec<FieldZone>,
+    pub soft_bodies: Vec<SoftBody>,
+    pub chains: Vec<OscillatorChain>,
+    pub wall_impulse: ChunkedVec<f64>,
+    pub stamps: Vec<CSObjectStamp>,
+    timeline: Timeline,
+}
+
+impl From<Vec<CSimObject>> for ClassicWorld {
+    fn from(object: Vec<CSimObject>) -> Self {
+        let mut global_acc_list = vec![ZERO_FORCE; GlobalForceSlot::MAX as usize];
+        global_acc_list[GlobalForceSlot::Gravity as usize] = GRAVITY;
+
+        ClassicWorld {
+            objects: object,
+            global_acc_list,
+            events: ChunkedVec::new(),
+            zones: vec![],
+            soft_bodies: vec![],
+            chains: vec![],
+            wall_impulse: ChunkedVec::new(),
+            stamps: vec![],
+            timeline: Timeline::default(),
+        }
+    }
+}
+
+impl From<CSPreset> for ClassicWorld {
+    fn from(preset: CSPreset) -> Self {
+        ClassicWorld::from(preset.simulation_objects)
+            .with_zones(preset.zones)
+            .with_soft_bodies(preset.soft_bodies)
+            .with_chains(preset.chains)
+            .with_stamps(preset.stamps)
+    }
+}
+
+impl ClassicWorld {
+    pub fn with_zones(mut self, zones: Vec<FieldZone>) -> Self {
+        self.zones = zones;
+        self
+    }
+
+    pub fn with_soft_bodies(mut self, soft_bodies: Vec<SoftBody>) -> Self {
+        self.soft_bodies = soft_bodies;
+        self
+    }
+
+    pub fn with_chains(mut self, chains: Vec<OscillatorChain>) -> Self {
+        self.chains = chains;
+        self
+    }
+
+    pub fn with_stamps(mut self, stamps: Vec<CSObjectStamp>) -> Self {
+        self.stamps = stamps;
+        self
+    }
+
+    pub fn zones_at(&self, pos: NVec2) -> impl Iterator<Item = &FieldZone> {
+        self.zones.iter().filter(move |zone| zone.contains(pos))
+    }
+
+    /// 소프트 바디에 속한 질점의 인덱스
+    pub fn soft_body_nodes(&self) -> Vec<usize> {
+        self.soft_bodies
+            .iter()
+            .flat_map(|body| body.nodes.iter().cloned())
+            .collect()
+    }
+
+    /// `time`에서 한 스텝 나아간다. 스탬프도 여기서 찍는다.
+    pub fn step(&mut self, settings: &mut CSimSettings, time: f64) {
+        puffin::profile_scope!("ClassicWorld::step");
+
+        //TODO: 이거 더 좋은 방법 없나?
+        if let Some(is_grav) = settings.gravity.get() {
+            if is_grav {
+                self.global_acc_list[GlobalForceSlot::Gravity as usize] = GRAVITY;
+            } else {
+                self.global_acc_list[GlobalForceSlot::Gravity as usize] = ZERO_FORCE;
+            }
+        }
+
+        let boundary = settings.boundary;
+        let keyed = self.record(&boundary);
+
+        // 지난 스텝의 위치와 속도는 기록에 남기고, 그 상태를 이번 스텝의 상태로 고친다.
+        for obj in self.objects.iter_mut().filter(|obj| !obj.is_ended()) {
+            obj.save_state();
+        }
+
+        let (event, wall_impulse) = self.advance(&boundary, time);
+
+        self.events.push(event);
+        self.wall_impulse.push(wall_impulse);
+        self.timeline.viewed = self.recorded_steps();
+
+        if keyed {
+            self.fit_budget(self.own_budget(settings.memory_budget.saturating_mul(MEGABYTE)));
+        }
+    }
+
+    // 모든 오브젝트를 한 스텝 계산한다. 생긴 이벤트와 벽이 받은 충격량을 돌려준다.
+    fn advance(&mut self, boundary: &WorldBoundary, time: f64) -> (SimulationEvents, f64) {
+        let mut event = SimulationEvents::default();
+        let boundary = *boundary;
+
+        let length = self.objects.len();
+        let mut wall_impulse = 0.0;
+
+        for body in self.soft_bodies.iter() {
+            body.apply_springs(&mut self.objects);
+        }
+
+        for chain in self.chains.iter() {
+            chain.apply_springs(&mut self.objects);
+        }
+
+        let next_time = time + SIMULATION_TICK;
+
+        let anchors = self
+            .soft_bodies
+            .iter()
+            .flat_map(|body| body.anchors.iter().cloned())
+            .collect::<Vec<_>>();
+
+        //물리 처리 부분
+        for (index, obj) in self.objects.iter_mut().enumerate() {
+            if obj.is_ended() {
+                continue;
+            }
+
+            if let Some(attached_fn) = &obj.attached() {
+                attached_fn(obj.live_state_mut());
+            }
+
+            let field_acc =
+                Self::field_acceleration(&obj.current_state(), &self.global_acc_list, &self.zones);
+
+            Self::physics(obj, field_acc);
+            wall_impulse += boundary.apply(obj.live_state_mut());
+
+            if let Some((_, anchor)) = anchors.iter().find(|(node, _)| *node == index) {
+                let state = obj.live_state_mut();
+                state.position = *anchor;
+                state.velocity = ZERO_FORCE;
+            }
+
+            for chain in self.chains.iter() {
+                chain.constrain(index, obj.live_state_mut(), next_time);
+            }
+
+            for stamp in self.stamps.iter_mut() {
+                stamp.get_data(&obj.current_state(), index, next_time);
+            }
+        }
+
+        //충돌 처리 부분
+        for i in 1..length + 1 {
+            let (_front, end) = self.objects.split_at_mut(i - 1);
+
+            let Some((obj, rest)) = end.split_first_mut() else {
+                panic!("Cannot Reach")
+            };
+
+            if obj.is_ended() {
+                continue;
+            }
+
+            for (j, obj2) in rest.iter_mut().enumerate() {
+                if obj2.is_ended() {
+                    continue;
+                }
+
+                if let Some(mut x) = Self::collision(obj, obj2, &boundary) {
+                    x.objects = (i - 1, i + j);
+                    event.add_event(x);
+                }
+            }
+        }
+
+        (event, wall_impulse)
+    }
+
+    /// 기록된 스텝 수
+    pub fn recorded_steps(&self) -> usize {
+        self.events.len()
+    }
+
+    /// `step` 스텝 뒤의 기록을 지우고 그 스텝으로 돌아간다. 그 뒤에 생긴 오브젝트는 뺀다.
+    /// 그 스텝을 보면서 고친 상태는 그대로 남는다.
+    pub fn truncate(&mut self, step: usize) {
+        if self.timeline.viewed != step {
+            self.at_time_step(step);
+        }
+
+        for obj in self.objects.iter_mut() {
+            obj.truncate(step);
+        }
+
+        self.events.truncate(step);
+        self.wall_impulse.truncate(step);
+        self.timeline.truncate(step);
+
+        let later = self
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, obj)| *obj.init_timestep() > step)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if !later.is_empty() {
+            self.remove_objects(later);
+        }
+
+        // 스탬프는 스텝이 끝난 시각으로 찍히므로 반 스텝의 여유를 둔다.
+        let time = SIMULATION_TICK * (step as f64 + 0.5);
+        for stamp in self.stamps.iter_mut() {
+            stamp.forget_after(time);
+        }
+    }
+
+    /// `from` 스텝 뒤를 지우고 `to` 스텝까지 지금 장면과 설정으로 다시 계산한다.
+    /// 드래그로 준 사용자 가속도는 기록된 대로 다시 주고, `before_step`은 그 뒤에 스텝마다 불린다.
+    pub fn recompute(
+        &mut self,
+        from: usize,
+        to: usize,
+        settings: &mut CSimSettings,
+        mut before_step: impl FnMut(&mut Self, usize),
+    ) {
+        let forces = self.timeline.user_forces_since(from);
+        self.truncate(from);
+
+        let apply_forces = |world: &mut Self, step: usize| {
+            for obj in world.objects.iter_mut() {
+                obj.set_user_force(ZERO_FORCE);
+            }
+            for force in forces.iter().filter(|force| force.step == step) {
+                if let Some(obj) = world.objects.get_mut(force.index) {
+                    obj.set_user_force(force.force);
+                }
+            }
+        };
+
+        for step in from..to {
+            apply_forces(self, step);
+            before_step(self, step);
+            self.step(settings, SIMULATION_TICK * step as f64);
+        }
+        apply_forces(self, to);
+    }
+
+    /// 오브젝트를 모든 스텝에서 뺀다. 뒤에 있던 오브젝트의 인덱스는 당겨지고,
+    /// 뺀 오브젝트가 관련된 이벤트와 소프트 바디, 체인의 질점도 함께 빠진다.
+    pub fn remove_objects(&mut self, indices: impl IntoIterator<Item = usize>) -> RemovedObjects {
+        let mut indices = indices
+            .into_iter()
+            .filter(|index| *index < self.objects.len())
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let map = |index: usize| match indices.binary_search(&index) {
+            Ok(_) => None,
+            Err(before) => Some(index - before),
+        };
+
+        let mut removed = RemovedObjects {
+            objects: vec![],
+            events: vec![],
+            soft_bodies: self.soft_bodies.clone(),
+            chains: self.chains.clone(),
+            stamp_ranges: self
+                .stamps
+                .iter()
+                .map(|stamp| stamp.range().clone())
+                .collect(),
+            timeline: self.remap_timeline(map),
+        };
+
+        for (step, events) in self.events.iter_mut().enumerate() {
+            removed
+                .events
+                .extend(events.remap(map).into_iter().map(|event| (step, event)));
+        }
+
+        for body in self.soft_bodies.iter_mut() {
+            body.remap(map);
+        }
+        self.soft_bodies.retain(|body| !body.nodes.is_empty());
+
+        for chain in self.chains.iter_mut() {
+            chain.remap(map);
+        }
+        self.chains.retain(|chain| !chain.nodes.is_empty());
+
+        for stamp in self.stamps.iter_mut() {
+            // 범위 안에서 빠진 만큼 끝이 당겨진다. 모두 빠지면 빈 범위가 된다.
+            let (start, end) = (*stamp.range().start(), *stamp.range().end());
+            let start = start - indices.partition_point(|index| *index < start);
+            let end = (end + 1).saturating_sub(indices.partition_point(|index| *index <= end));
+
+            stamp.set_range(if end > start {
+                start..=end - 1
+            } else {
+                RangeInclusive::new(1, 0)
+            });
+        }
+
+        for index in indices.iter().rev() {
+            removed.objects.push((*index, self.objects.remove(*index)));
+        }
+        removed.objects.reverse();
+
+        removed
+    }
+
+    /// 뺀 오브젝트를 원래 자리에 다시 넣는다. 빼고 나서 스텝이 지났으면
+    /// 다시 넣은 오브젝트는 그동안 마지막 상태 그대로 있었던 것으로 기록한다.
+    pub fn restore_objects(&mut self, removed: RemovedObjects) {
+        let indices = removed
+            .objects
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>();
+
+        let unmap = |index: usize| {
+            Some(indices.iter().fold(index, |index, removed| {
+                index + usize::from(index >= *removed)
+            }))
+        };
+
+        let last = self.recorded_steps();
+        for (index, mut obj) in removed.objects {
+            obj.hold_until(last);
+            self.objects.insert(index, obj);
+        }
+
+        for events in self.events.iter_mut() {
+            events.remap(unmap);
+        }
+        for (step, event) in removed.events {
+            if let Some(events) = self.events.get_mut(step) {
+                events.add_event(event);
+            }
+        }
+
+        self.soft_bodies = removed.soft_bodies;
+        self.chains = removed.chains;
+        self.restore_timeline(removed.timeline, unmap);
+
+        for (stamp, range) in self.stamps.iter_mut().zip(removed.stamp_ranges) {
+            stamp.set_range(range);
+        }
+    }
+
+    /// 오브젝트를 `step` 스텝에서 뺀다. 그 앞의 스텝에서는 그대로 보이고 인덱스도 바뀌지 않는다.
+    /// 소프트 바디는 질점이 모두 빠질 때만 남겨서 지난 스텝에 그린다. 체인에서는 빠진 질점을 뺀다.
+    pub fn end_objects(
+        &mut self,
+        indices: impl IntoIterator<Item = usize>,
+        step: usize,
+    ) -> EndedObjects {
+        let mut indices = indices
+            .into_iter()
+            .filter(|index| {
+                self.objects
+                    .get(*index)
+                    .map_or(false, |obj| !obj.is_ended())
+            })
+            .collect::<Vec<_>>();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let ended = EndedObjects {
+            indices: indices.clone(),
+            soft_bodies: self.soft_bodies.clone(),
+            chains: self.chains.clone(),
+        };
+
+        for index in indices.iter() {
+            self.objects[*index].end_at(step);
+        }
+
+        let objects = &self.objects;
+        let map = |index: usize| (!objects[index].is_ended()).then_some(index);
+
+        for body in self.soft_bodies.iter_mut() {
+            if body.nodes.iter().any(|node| map(*node).is_some()) {
+                body.remap(map);
+            }
+        }
+
+        for chain in self.chains.iter_mut() {
+            chain.remap(map);
+        }
+        self.chains.retain(|chain| !chain.nodes.is_empty());
+
+        self.timeline.invalidate();
+
+        ended
+    }
+
+    /// `step` 스텝에서 뺀 오브젝트를 다시 넣는다. 빼고 나서 스텝이 지났으면
+    /// 그동안 뺀 스텝의 상태 그대로 있었던 것으로 기록한다.
+    pub fn resume_objects(&mut self, ended: EndedObjects) {
+        let last = self.recorded_steps();
+
+        for index in ended.indices {
+            if let Some(obj) = self.objects.get_mut(index) {
+                obj.resume();
+                obj.hold_until(last);
+            }
+        }
+
+        self.soft_bodies = ended.soft_bodies;
+        self.chains = ended.chains;
+        self.timeline.invalidate();
+    }
+
+    fn collision(
+        obj: &mut CSimObject,
+        obj2: &mut CSimObject,
+        boundary: &WorldBoundary,
+    ) -> Option<CollisionEvent> {
+        let obj_state = obj.live_state_mut();
+        let obj2_state = obj2.live_state_mut();
+
+        // 주기 경계에서는 가장 가까운 이미지와 충돌을 검사한다.
+        let offset = boundary.image_offset(obj_state.position, obj2_state.position);
+
+        obj2_state.position += offset;
+        let contact = obj_state.contact(obj2_state);
+        obj2_state.position -= offset;
+
+        if let Some(contact) = contact {
+            obj_state.velocity += contact.obj1_velocity;
+            obj2_state.velocity += contact.obj2_velocity;
+            // obj_state.position += contact.penetration * contact.contact_normal;
+            // obj2_state.position += contact.penetration * -contact.contact_normal;
+
+            Some(contact)
+        } else {
+            None
+        }
+    }
+
+    /// `state`에 있는 오브젝트가 받는 가속도. 중력과 영역의 가속도에 오브젝트에 걸린 힘을 더한다.
+    pub fn total_acceleration(&self, state: &CSObjectState) -> NVec2 {
+        Self::field_acceleration(state, &self.global_acc_list, &self.zones) + state.acceleration()
+    }
+
+    // 물체가 들어있는 영역에 따라 전역 가속도를 덮어쓰거나 더한다.
+    fn field_acceleration(
+        state: &CSObjectState,
+        global_acc_list: &[NVec2],
+        zones: &[FieldZone],
+    ) -> NVec2 {
+        let mut global_acc_list = global_acc_list.to_vec();
+        let mut zone_acc = NVec2::zeros();
+
+        for zone in zones.iter().filter(|zone| zone.contains(state.position)) {
+            if let ZoneEffect::Gravity(gravity) = zone.effect {
+                global_acc_list[GlobalForceSlot::Gravity as usize] = gravity;
+            }
+
+            zone_acc += zone.acceleration(state.velocity, state.mass);
+        }
+
+        global_acc_list.iter().sum::<NVec2>() + zone_acc
+    }
+
+    fn physics(obj: &mut CSimObject, global_acc: NVec2) {
+        // Physics
+        let previous_state = obj.previous_state().unwrap_or(obj.current_state());
+        let state = obj.live_state_mut();
+
+        let dt = SIMULATION_TICK;
+
+        // ΣF
+        // ΣF = ma
+        // a = ΣF / m
+        // Δv = a * Δt
+        // Δp = ΣF * Δt
+        // Δs = v * Δt
+
+        {
+            let current_acc = state.acceleration();
+
+            let sum_acc = current_acc + global_acc; // Σa
+
+            let delta_a = current_acc - previous_state.acceleration();
+
+            let delta_v = sum_acc * dt; // 등가속도 운동에서의 보정.
+            let dv_error = (delta_a * dt) / 2.0;
+            let delta_v = delta_v + dv_error;
+
+            let v = state.velocity;
+
+            let delta_pos = v * dt;
+            let dpos_error = (delta_v * dt) / 2.0; // 등가속도 운동에서의 보정.
+            let delta_pos = delta_pos + dpos_error;
+            // Δs = v * Δt
+
+            state.last_velocity = state.velocity;
+            state.velocity += delta_v;
+            state.position += delta_pos
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::object::builder::CSimObjectBuilder;
+    use super::object::shape::{Circle, ObjectShape};
+    use super::*;
+
+    fn ball(position: NVec2, velocity: NVec2, mass: f64, radius: f64) -> CSimObject {
+        CSimObjectBuilder::new(CSObjectState {
+            position,
+            velocity,
+            mass,
+            shape: ObjectShape::Circle(Circle { radius }),
+            ..CSObjectState::default()
+        })
+        .build()
+    }
+
+    fn run(world: &mut ClassicWorld, settings: &mut CSimSettings, steps: usize) {
+        for step in 0..steps {
+            world.step(settings, SIMULATION_TICK * step as f64);
+        }
+    }
+
+    #[test]
+    fn projectile_follows_the_analytic_path() {
+        let (p0, v0) = (NVec2::new(-20.0, 5.0), NVec2::new(6.0, 15.0));
+        let mut world = ClassicWorld::from(vec![ball(p0, v0, 1.0, 1.0)]);
+        let mut settings = CSimSettings::default();
+
+        let steps = 480;
+        run(&mut world, &mut settings, steps);
+
+        let t = SIMULATION_TICK * steps as f64;
+        let state = world.objects[0].current_state();
+
+        // 등가속도 보정이 있으므로 적분 오차 없이 맞아야 한다.
+        let position = p0 + v0 * t + GRAVITY * t * t / 2.0;
+        let velocity = v0 + GRAVITY * t;
+        assert!(
+            (state.position - position).norm() < 1e-9,
+            "{}",
+            state.position
+        );
+        assert!(
+            (state.velocity - velocity).norm() < 1e-9,
+            "{}",
+            state.velocity
+        );
+    }
+
+    #[test]
+    fn elastic_collision_conserves_momentum_and_energy() {
+        let mut world = ClassicWorld::from(vec![
+            ball(NVec2::new(-5.0, 0.0), NVec2::new(8.0, 0.5), 2.0, 1.0),
+            ball(NVec2::new(5.0, 0.3), NVec2::new(-3.0, 0.0), 5.0, 1.5),
+        ]);
+        let mut settings = CSimSettings {
+            gravity: false.into(),
+            ..CSimSettings::default()
+        };
+        settings.gravity.changed();
+
+        let totals = |world: &ClassicWorld| {
+            world
+                .objects
+                .iter()
+                .fold((NVec2::zeros(), 0.0), |(p, e), obj| {
+                    let state = obj.current_state();
+                    (
+                        p + state.momentum(),
+                        e + state.mass * state.velocity.norm_squared() / 2.0,
+                    )
+                })
+        };
+        let (momentum, energy) = totals(&world);
+
+        run(&mut world, &mut settings, 480);
+
+        let collisions = world
+            .events
+            .iter()
+            .map(|events| events.iter().count())
+            .sum::<usize>();
+        assert_eq!(collisions, 1);
+
+        let (after_momentum, after_energy) = totals(&world);
+        assert!(
+            (after_momentum - momentum).norm() < 1e-9,
+            "{}",
+            after_momentum
+        );
+        assert!(
+            (after_energy - energy).abs() < 1e-9 * energy,
+            "{}",
+            after_energy
+        );
+    }
+
+    #[test]
+    fn ended_object_keeps_its_earlier_steps() {
+        let mut world = ClassicWorld::from(vec![
+            ball(NVec2::new(-20.0, 0.0), NVec2::new(2.0, 10.0), 1.0, 1.0),
+            ball(NVec2::new(20.0, 0.0), NVec2::new(-2.0, 10.0), 1.0, 1.0),
+        ]);
+        let mut settings = CSimSettings::default();
+        run(&mut world, &mut settings, 120);
+
+        world.at_time_step(60);
+        let earlier = world.objects[0].visible_state().unwrap().position;
+
+        world.at_time_step(120);
+        world.end_objects([0], 120);
+        run(&mut world, &mut settings, 120);
+
+        assert_eq!(world.objects.len(), 2);
+        assert_eq!(world.objects[0].last_timestep(), 120);
+        assert_eq!(world.objects[1].last_timestep(), 240);
+
+        world.at_time_step(60);
+        assert_eq!(world.objects[0].visible_state().unwrap().position, earlier);
+
+        for step in [120, 200, 240] {
+            world.at_time_step(step);
+            assert!(world.objects[0].visible_state().is_none(), "{}", step);
+            assert!(world.objects[1].visible_state().is_some(), "{}", step);
+        }
+    }
+
+    #[test]
+    fn resumed_object_continues_from_where_it_ended() {
+        let (p0, v0) = (NVec2::new(-20.0, 5.0), NVec2::new(6.0, 15.0));
+        let mut world = ClassicWorld::from(vec![ball(p0, v0, 1.0, 1.0)]);
+        let mut settings = CSimSettings::default();
+        run(&mut world, &mut settings, 240);
+
+        let ended = world.end_objects([0], 240);
+        world.resume_objects(ended);
+        run(&mut world, &mut settings, 240);
+
+        let t = SIMULATION_TICK * 480.0;
+        let position = p0 + v0 * t + GRAVITY * t * t / 2.0;
+        let state = world.objects[0].current_state();
+        assert!(
+            (state.position - position).norm() < 1e-9,
+            "{}",
+            state.position
+        );
+        assert!(!world.objects[0].is_ended());
+    }
+
+    #[test]
+    fn copy_keeps_its_steps_while_the_original_goes_on() {
+        let mut world = ClassicWorld::from(vec![ball(
+            NVec2::new(-20.0, 5.0),
+            NVec2::new(6.0, 15.0),
+            1.0,
+            1.0,
+        )]);
+        let mut settings = CSimSettings::default();
+        run(&mut world, &mut settings, 1000);
+
+        let copy = world.clone();
+        let position = copy.objects[0].current_state().position;
+        let recorded = copy.objects[0].track().get(700);
+
+        world.truncate(500);
+        world.objects[0].current_state_mut().velocity = NVec2::zeros();
+        run(&mut world, &mut settings, 700);
+
+        assert_eq!(world.recorded_steps(), 1200);
+        assert_ne!(world.objects[0].track().get(700), recorded);
+        assert_eq!(copy.recorded_steps(), 1000);
+        assert_eq!(copy.objects[0].track().get(700), recorded);
+        assert_eq!(copy.objects[0].current_state().position, position);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BoundaryMode {
+    #[default]
+    Open,
+    /// 한쪽 끝을 넘어간 물체가 반대쪽 끝에서 다시 나온다.
+    Periodic,
+    /// 벽에 부딪힌 물체가 탄성 반사된다.
+    Wall,
+}
+
+/// 시뮬레이션 세계의 경계. 축마다 따로 동작을 정할 수 있다.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorldBoundary {
+    pub min: NVec2,
+    pub max: NVec2,
+    pub x: BoundaryMode,
+    pub y: BoundaryMode,
+}
+
+impl Default for WorldBoundary {
+    fn default() -> Self {
+        Self {
+            min: NVec2::new(-100.0, -100.0),
+            max: NVec2::new(100.0, 100.0),
+            x: BoundaryMode::Open,
+            y: BoundaryMode::Open,
+        }
+    }
+}
+
+impl WorldBoundary {
+    pub fn periodic(min: NVec2, max: NVec2) -> Self {
+        Self {
+            min,
+            max,
+            x: BoundaryMode::Periodic,
+            y: BoundaryMode::Periodic,
+        }
+    }
+
+    pub fn walled(min: NVec2, max: NVec2) -> Self {
+        Self {
+            min,
+            max,
+            x: BoundaryMode::Wall,
+            y: BoundaryMode::Wall,
+        }
+    }
+
+    pub fn size(&self) -> NVec2 {
+        self.max - self.min
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.x == BoundaryMode::Open && self.y == BoundaryMode::Open
+    }
+
+    pub fn has_walls(&self) -> bool {
+        self.x == BoundaryMode::Wall || self.y == BoundaryMode::Wall
+    }
+
+    /// 벽의 전체 길이. 2차원에서 압력은 단위 길이당 힘이다.
+    pub fn wall_length(&self) -> f64 {
+        let size = self.size();
+        let mut length = 0.0;
+
+        if self.x == BoundaryMode::Wall {
+            length += 2.0 * size.y;
+        }
+        if self.y == BoundaryMode::Wall {
+            length += 2.0 * size.x;
+        }
+
+        length
+    }
+
+    fn axes(&self) -> [BoundaryMode; 2] {
+        [self.x, self.y]
+    }
+
+    /// 물체에 경계 조건을 적용하고, 벽이 받은 충격량의 크기를 반환한다.
+    pub fn apply(&self, state: &mut CSObjectState) -> f64 {
+        self.wrap(&mut state.position);
+
+        let extent = state.shape.half_extent();
+        let mut impulse = 0.0;
+
+        for (axis, mode) in self.axes().into_iter().enumerate() {
+            if mode != BoundaryMode::Wall {
+                continue;
+            }
+
+            let low = self.min[axis] + extent[axis];
+            let high = (self.max[axis] - extent[axis]).max(low);
+
+            let position = state.position[axis];
+            let velocity = state.velocity[axis];
+
+            if (position < low && velocity < 0.0) || (position > high && velocity > 0.0) {
+                state.velocity[axis] = -velocity;
+                impulse += 2.0 * state.mass * velocity.abs();
+            }
+
+            state.position[axis] = position.clamp(low, high);
+        }
+
+        impulse
+    }
+
+    /// 주기 경계를 넘어간 위치를 반대편으로 옮긴다. 옮겼다면 true.
+    pub fn wrap(&self, position: &mut NVec2) -> bool {
+        let size = self.size();
+        let mut wrapped = false;
+
+        for (axis, mode) in self.axes().into_iter().enumerate() {
+            if mode == BoundaryMode::Periodic && size[axis] > 0.0 {
+                let local = position[axis] - self.min[axis];
+
+                if !(0.0..size[axis]).contains(&local) {
+                    position[axis] = self.min[axis] + local.rem_euclid(size[axis]);
+                    wrapped = true;
+                }
+            }
+        }
+
+        wrapped
+    }
+
+    /// `to`에 더하면 `from`에서 가장 가까운 주기 이미지가 되는 오프셋. (minimum image convention)
+    pub fn image_offset(&self, from: NVec2, to: NVec2) -> NVec2 {
+        let size = self.size();
+        let mut offset = NVec2::zeros();
+
+        for (axis, mode) in self.axes().into_iter().enumerate() {
+            if mode == BoundaryMode::Periodic && size[axis] > 0.0 {
+                let delta = to[axis] - from[axis];
+                offset[axis] = -size[axis] * (delta / size[axis]).round();
+            }
+        }
+
+        offset
+    }
+
+    /// 연속된 두 위치 사이에서 주기 경계를 건너뛰었는지 확인한다.
+    pub fn is_jump(&self, from: NVec2, to: NVec2) -> bool {
+        let size = self.size();
+
+        self.axes().into_iter().enumerate().any(|(axis, mode)| {
+            mode == BoundaryMode::Periodic && (to[axis] - from[axis]).abs() > size[axis] / 2.0
+        })
+    }
+}
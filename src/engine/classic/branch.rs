@@ -0,0 +1,252 @@
+//! 지난 스텝을 고치면 그 뒤에 기록된 스텝은 잘려서 갈래로 남는다.
+//! 갈래는 지금 타임라인과 나란히 보거나 지금 타임라인과 맞바꿀 수 있다.
+
+use crate::engine::classic::edit::SceneEdit;
+use crate::engine::classic::sim_state::CSimSettings;
+use crate::engine::classic::ClassicWorld;
+
+/// 갈래는 메모리 예산을 이 수로 나눈 만큼까지만 쓴다.
+const BRANCH_SHARE: usize = 2;
+
+#[derive(Clone)]
+pub struct Branch {
+    pub id: usize,
+    /// 갈라진 스텝. 이 스텝 앞까지는 지금 타임라인과 같다.
+    pub step: usize,
+    pub world: ClassicWorld,
+}
+
+#[derive(Clone, Default)]
+pub struct Branches {
+    branches: Vec<Branch>,
+    next_id: usize,
+    /// 지금 타임라인과 나란히 그리는 갈래
+    pub compared: Option<usize>,
+    /// 마지막으로 잰 갈래의 메모리. 갈래가 바뀌면 다시 잰다.
+    usage: Option<usize>,
+}
+
+impl Branches {
+    pub fn iter(&self) -> impl Iterator<Item = &Branch> {
+        self.branches.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// 갈래가 쓰는 메모리의 바이트 수
+    pub fn usage(&mut self) -> usize {
+        *self.usage.get_or_insert_with(|| {
+            self.branches
+                .iter()
+                .map(|branch| branch.world.usage().total())
+                .sum()
+        })
+    }
+
+    /// 갈래가 `budget` 바이트를 넘으면 갈래마다 오래된 구간의 기록을 버린다.
+    /// 그래도 넘으면 나란히 보지 않는 갈래부터 오래된 것을 뺀다.
+    pub fn fit_budget(&mut self, budget: usize) {
+        if self.usage() <= budget {
+            return;
+        }
+
+        let share = budget / self.branches.len().max(1);
+        for branch in self.branches.iter_mut() {
+            branch.world.fit_budget(share);
+        }
+        self.usage = None;
+
+        while self.usage() > budget {
+            let oldest = self
+                .branches
+                .iter()
+                .find(|branch| Some(branch.id) != self.compared)
+                .or(self.branches.first())
+                .map(|branch| branch.id);
+
+            match oldest {
+                Some(id) => self.remove(id),
+                None => break,
+            }
+        }
+    }
+
+    /// 갈래와 `world`가 함께 `budget` 바이트를 쓰게 한다. 갈래를 예산의 절반 안으로 줄이고,
+    /// 남은 만큼을 `world`가 스텝마다 맞추도록 갈래가 쓰는 바이트를 알려 둔다.
+    pub fn share_budget(&mut self, world: &mut ClassicWorld, budget: usize) {
+        self.fit_budget(budget / BRANCH_SHARE);
+        world.reserve(self.usage());
+    }
+
+    pub fn compared(&self) -> Option<&Branch> {
+        let id = self.compared?;
+        self.branches.iter().find(|branch| branch.id == id)
+    }
+
+    /// `world`를 `step` 스텝에서 자른다. 잘린 스텝은 `edits`를 하기 전의 모습으로 갈래에 남는다.
+    /// `edits`는 `world`에 이미 적용된 편집이다.
+    pub fn fork(
+        &mut self,
+        world: &mut ClassicWorld,
+        step: usize,
+        edits: &[SceneEdit],
+        settings: &mut CSimSettings,
+    ) -> usize {
+        let mut original = world.clone();
+        for edit in edits.iter().rev() {
+            match edit {
+                // 고친 상태는 보고 있는 스텝에만 있으므로 다시 보면 사라진다.
+                SceneEdit::EditObject { index, before, .. } => {
+                    if let Some(obj) = original.objects.get_mut(*index) {
+                        obj.set_attached(before.attached);
+                    }
+                }
+                _ => edit.clone().undo(&mut original, settings),
+            }
+        }
+        original.at_time_step(step);
+
+        world.truncate(step);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.branches.push(Branch {
+            id,
+            step,
+            world: original,
+        });
+        self.compared = Some(id);
+        self.usage = None;
+
+        id
+    }
+
+    /// 갈래와 지금 세계를 맞바꾼다. 갈래가 없으면 false.
+    pub fn switch(&mut self, id: usize, world: &mut ClassicWorld) -> bool {
+        match self.branches.iter_mut().find(|branch| branch.id == id) {
+            Some(branch) => {
+                std::mem::swap(&mut branch.world, world);
+                self.usage = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        self.branches.retain(|branch| branch.id != id);
+        self.usage = None;
+
+        if self.compared == Some(id) {
+            self.compared = None;
+        }
+    }
+
+    /// 나란히 보는 갈래도 `step` 스텝을 보게 한다.
+    pub fn at_time_step(&mut self, step: usize) {
+        let Some(id) = self.compared else {
+            return;
+        };
+
+        if let Some(branch) = self.branches.iter_mut().find(|branch| branch.id == id) {
+            if branch.world.viewed_step() != step {
+                branch.world.at_time_step(step);
+                self.usage = None;
+            }
+        }
+    }
+
+    /// `step` 스텝에서 같은 인덱스의 오브젝트끼리 가장 멀리 떨어진 것과 그 거리.
+    /// `world`는 이미 그 스텝을 보고 있어야 한다.
+    pub fn gap(&mut self, world: &ClassicWorld, step: usize) -> Option<(usize, f64)> {
+        self.at_time_step(step);
+        let branch = self.compared()?;
+
+        world
+            .objects
+            .iter()
+            .zip(branch.world.objects.iter())
+            .enumerate()
+            .filter_map(|(index, (obj, other))| {
+                let distance =
+                    (obj.visible_state()?.position - other.visible_state()?.position).norm();
+                Some((index, distance))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::classic::object::builder::CSimObjectBuilder;
+    use crate::engine::classic::object::state::CSObjectState;
+    use crate::engine::{NVec2, SIMULATION_TICK};
+
+    fn run(world: &mut ClassicWorld, settings: &mut CSimSettings, steps: usize) {
+        let from = world.recorded_steps();
+        for step in from..from + steps {
+            world.step(settings, SIMULATION_TICK * step as f64);
+        }
+    }
+
+    // 600 스텝마다 300 스텝 앞에서 갈라진 네 갈래
+    fn forked() -> (ClassicWorld, Branches, CSimSettings) {
+        let mut world = ClassicWorld::from(
+            (0..8)
+                .map(|i| {
+                    CSimObjectBuilder::new(CSObjectState {
+                        position: NVec2::new(i as f64 * 3.0, 0.0),
+                        velocity: NVec2::new(1.0, 20.0),
+                        ..CSObjectState::default()
+                    })
+                    .build()
+                })
+                .collect::<Vec<_>>(),
+        );
+        let mut settings = CSimSettings::default();
+        let mut branches = Branches::default();
+
+        for _ in 0..4 {
+            run(&mut world, &mut settings, 600);
+            let step = world.recorded_steps() - 300;
+            world.at_time_step(step);
+            branches.fork(&mut world, step, &[], &mut settings);
+        }
+
+        (world, branches, settings)
+    }
+
+    #[test]
+    fn branches_fit_in_half_the_budget() {
+        let (mut world, mut branches, _) = forked();
+        let compared = branches.compared;
+
+        let budget = branches.usage();
+        branches.share_budget(&mut world, budget);
+
+        let usage = branches.usage();
+        assert!(usage <= budget / 2, "{} > {}", usage, budget / 2);
+        assert_eq!(branches.compared, compared);
+        assert!(branches.compared().is_some());
+        assert_eq!(world.own_budget(budget), budget - usage);
+    }
+
+    #[test]
+    fn oldest_branches_are_dropped_first() {
+        let (mut world, mut branches, _) = forked();
+        let compared = branches.compared;
+
+        let budget = branches.usage() / 8;
+        branches.share_budget(&mut world, budget);
+
+        let ids = branches.iter().map(|branch| branch.id).collect::<Vec<_>>();
+        assert!(ids.len() < 4);
+        // 나란히 보는 마지막 갈래와 그 앞의 새 갈래만 남는다.
+        assert_eq!(ids, (4 - ids.len()..4).collect::<Vec<_>>());
+        assert_eq!(branches.compared, compared);
+        assert!(branches.usage() <= budget / 2);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::engine::classic::object::builder::CSimObjectBuilder;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::classic::object::state::{CSObjectState, ForceIndex};
+use crate::engine::classic::CSimObject;
+use crate::engine::NVec2;
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChainEnd {
+    /// 벽에 스프링으로 연결되어 있다.
+    #[default]
+    Fixed,
+    /// 아무것에도 연결되지 않았다.
+    Free,
+}
+
+/// 첫 번째 질점을 y = A sin(2πft) 로 흔든다.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChainDriver {
+    pub frequency: f64,
+    pub amplitude: f64,
+}
+
+impl ChainDriver {
+    pub fn displacement(&self, time: f64) -> (f64, f64) {
+        let omega = TAU * self.frequency;
+
+        (
+            self.amplitude * (omega * time).sin(),
+            self.amplitude * omega * (omega * time).cos(),
+        )
+    }
+}
+
+/// 한 줄로 놓인 질점들이 이웃과 스프링으로 연결된 결합 진동자.
+/// 질점은 x 방향으로 고정되어 있고 y 방향(횡파)으로만 움직인다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OscillatorChain {
+    pub nodes: Vec<usize>,
+    pub equilibrium: Vec<NVec2>,
+    pub stiffness: f64,
+    pub mass: f64,
+    pub left: ChainEnd,
+    pub right: ChainEnd,
+    pub driver: Option<ChainDriver>,
+}
+
+/// 정규 모드 하나. `shape`는 정규화된 모드 벡터.
+pub struct NormalMode {
+    pub angular_frequency: f64,
+    pub shape: DVector<f64>,
+}
+
+impl OscillatorChain {
+    /// 질점 오브젝트를 만들고, `first_index`부터 오브젝트 목록에 추가된다고 보고 체인을 만든다.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        count: usize,
+        spacing: f64,
+        origin: NVec2,
+        stiffness: f64,
+        mass: f64,
+        (left, right): (ChainEnd, ChainEnd),
+        driver: Option<ChainDriver>,
+        first_index: usize,
+    ) -> (Self, Vec<CSimObject>) {
+        let equilibrium = (0..count)
+            .map(|i| origin + NVec2::new(spacing * (i + 1) as f64, 0.0))
+            .collect::<Vec<_>>();
+
+        let objects = equilibrium
+            .iter()
+            .map(|position| {
+                CSimObjectBuilder::new(CSObjectState {
+                    position: *position,
+                    mass,
+                    ..CSObjectState::default()
+                })
+                .shape(ObjectShape::circle(spacing / 4.0))
+                .build()
+            })
+            .collect();
+
+        let chain = Self {
+            nodes: (first_index..first_index + count).collect(),
+            equilibrium,
+            stiffness,
+            mass,
+            left,
+            right,
+            driver,
+        };
+
+        (chain, objects)
+    }
+
+    /// 질점 인덱스를 `map`으로 바꾼다. 없어진 질점은 평형 위치와 함께 빠지고 이웃끼리 이어진다.
+    pub fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        let (nodes, equilibrium) = self
+            .nodes
+            .iter()
+            .zip(&self.equilibrium)
+            .filter_map(|(node, equilibrium)| Some((map(*node)?, *equilibrium)))
+            .unzip();
+
+        self.nodes = nodes;
+        self.equilibrium = equilibrium;
+    }
+
+    pub fn spacing(&self) -> f64 {
+        match self.equilibrium.as_slice() {
+            [first, second, ..] => (second - first).norm(),
+            _ => 0.0,
+        }
+    }
+
+    /// 질점마다 평형 위치에서 y 방향으로 벗어난 거리
+    pub fn displacements(states: &[CSObjectState], equilibrium: &[NVec2]) -> Vec<f64> {
+        states
+            .iter()
+            .zip(equilibrium)
+            .map(|(state, eq)| state.position.y - eq.y)
+            .collect()
+    }
+
+    /// 이웃과의 스프링 가속도를 `ForceIndex::Spring` 자리에 기록한다.
+    /// a_i = k/m (y_{i+1} - 2y_i + y_{i-1})
+    pub fn apply_springs(&self, objects: &mut [CSimObject]) {
+        let states = self
+            .nodes
+            .iter()
+            .map(|node| objects[*node].current_state())
+            .collect::<Vec<_>>();
+        let y = Self::displacements(&states, &self.equilibrium);
+        let n = y.len();
+
+        for i in 0..n {
+            let mut force = 0.0;
+
+            if i > 0 {
+                force += y[i - 1] - y[i];
+            } else if self.left == ChainEnd::Fixed {
+                force -= y[i];
+            }
+
+            if i + 1 < n {
+                force += y[i + 1] - y[i];
+            } else if self.right == ChainEnd::Fixed {
+                force -= y[i];
+            }
+
+            objects[self.nodes[i]].live_state_mut().acc_list[ForceIndex::Spring as usize] =
+                NVec2::new(0.0, self.stiffness * force / self.mass);
+        }
+    }
+
+    /// 질점을 x 방향으로 고정하고, 구동 질점의 위치를 정해준다.
+    pub fn constrain(&self, index: usize, state: &mut CSObjectState, time: f64) {
+        let Some(i) = self.nodes.iter().position(|node| *node == index) else {
+            return;
+        };
+
+        let equilibrium = self.equilibrium[i];
+        state.position.x = equilibrium.x;
+        state.velocity.x = 0.0;
+
+        if i == 0 {
+            if let Some(driver) = self.driver {
+                let (y, vy) = driver.displacement(time);
+                state.position.y = equilibrium.y + y;
+                state.velocity.y = vy;
+            }
+        }
+    }
+
+    /// 구동되는 첫 질점을 제외한 나머지 질점의 정규 모드. 낮은 진동수부터 정렬된다.
+    pub fn normal_modes(&self) -> Vec<NormalMode> {
+        let offset = usize::from(self.driver.is_some());
+        let n = self.nodes.len().saturating_sub(offset);
+
+        if n == 0 {
+            return vec![];
+        }
+
+        // 구동 질점은 움직이는 고정단처럼 보인다.
+        let left_fixed = self.driver.is_some() || self.left == ChainEnd::Fixed;
+        let right_fixed = self.right == ChainEnd::Fixed;
+
+        let mut matrix = DMatrix::<f64>::zeros(n, n);
+        for i in 0..n {
+            let mut diagonal = 0.0;
+
+            if i > 0 {
+                diagonal += 1.0;
+                matrix[(i, i - 1)] = -1.0;
+            } else if left_fixed {
+                diagonal += 1.0;
+            }
+
+            if i + 1 < n {
+                diagonal += 1.0;
+                matrix[(i, i + 1)] = -1.0;
+            } else if right_fixed {
+                diagonal += 1.0;
+            }
+
+            matrix[(i, i)] = diagonal;
+        }
+
+        let matrix = matrix * (self.stiffness / self.mass);
+        let eigen = matrix.symmetric_eigen();
+
+        let mut modes = eigen
+            .eigenvalues
+            .iter()
+            .zip(eigen.eigenvectors.column_iter())
+            .map(|(value, vector)| NormalMode {
+                angular_frequency: value.max(0.0).sqrt(),
+                shape: vector.into_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        modes.sort_by(|a, b| a.angular_frequency.total_cmp(&b.angular_frequency));
+        modes
+    }
+}
@@ -0,0 +1,428 @@
+//! 장면을 바꾸는 편집. 편집마다 되돌리는 법과 다시 하는 법을 안다.
+//! 편집은 쌓인 순서의 반대로만 되돌려지므로 편집이 기억하는 인덱스는 되돌릴 때에도 맞다.
+
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::object::AttachedFn;
+use crate::engine::classic::sim_state::CSimSettings;
+use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+use crate::engine::classic::{CSimObject, ClassicWorld, EndedObjects, RemovedObjects, ZERO_FORCE};
+use crate::engine::NVec2;
+
+/// 편집으로 바뀌는 오브젝트의 값
+#[derive(Clone)]
+pub struct ObjectValues {
+    pub state: CSObjectState,
+    pub attached: Option<AttachedFn>,
+}
+
+impl ObjectValues {
+    /// 오브젝트가 지금 보고 있는 스텝의 값
+    pub fn of(obj: &CSimObject) -> Self {
+        Self {
+            state: obj.current_state(),
+            attached: *obj.attached(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum SceneEdit {
+    /// 오브젝트 하나를 붙였다. 되돌린 동안에는 뺀 오브젝트를 들고 있는다.
+    AddObject {
+        index: usize,
+        removed: RemovedObjects,
+    },
+    /// 소프트 바디와 그 질점들을 붙였다.
+    AddSoftBody {
+        name: String,
+        nodes: Vec<usize>,
+        removed: RemovedObjects,
+    },
+    /// 오브젝트를 `step` 스텝에서 뺐다. 그 앞의 스텝에는 남는다. 소프트 바디는 질점을 모두 뺀다.
+    RemoveObjects {
+        name: String,
+        indices: Vec<usize>,
+        step: usize,
+        ended: EndedObjects,
+    },
+    /// `step` 스텝에서 오브젝트의 상태를 고쳤다. 부착 함수는 모든 스텝에 걸친다.
+    EditObject {
+        index: usize,
+        step: usize,
+        before: ObjectValues,
+        after: ObjectValues,
+    },
+    AddZone(FieldZone),
+    RemoveZone {
+        index: usize,
+        zone: FieldZone,
+    },
+    EditZone {
+        index: usize,
+        name: String,
+        before: ZoneEffect,
+        after: ZoneEffect,
+    },
+    /// 드래그로 `from` 스텝부터 스텝마다 준 사용자 가속도.
+    /// 되돌리거나 다시 하면 `from` 뒤의 타임라인을 다시 계산한다.
+    Force {
+        name: String,
+        targets: Vec<usize>,
+        from: usize,
+        forces: Vec<NVec2>,
+    },
+}
+
+impl SceneEdit {
+    pub fn label(&self) -> String {
+        match self {
+            Self::AddObject { index, .. } => format!("Add Object {}", index),
+            Self::AddSoftBody { name, .. } => format!("Add {}", name),
+            Self::RemoveObjects { name, .. } => format!("Remove {}", name),
+            Self::EditObject { index, step, .. } => {
+                format!("Edit Object {} at step {}", index, step)
+            }
+            Self::AddZone(zone) => format!("Add {}", zone.name),
+            Self::RemoveZone { zone, .. } => format!("Remove {}", zone.name),
+            Self::EditZone { name, .. } => format!("Edit {}", name),
+            Self::Force {
+                name, from, forces, ..
+            } => format!("Force on {} ({}..{})", name, from, from + forces.len()),
+        }
+    }
+
+    /// 값을 끌고 있는 동안 생긴 같은 편집을 하나로 합친다. 합치지 못하면 그대로 돌려준다.
+    pub fn merge(&mut self, next: SceneEdit) -> Option<SceneEdit> {
+        match (self, next) {
+            (
+                Self::EditZone { index, after, .. },
+                Self::EditZone {
+                    index: next_index,
+                    after: next_after,
+                    ..
+                },
+            ) if *index == next_index => {
+                *after = next_after;
+                None
+            }
+            (
+                Self::EditObject {
+                    index, step, after, ..
+                },
+                Self::EditObject {
+                    index: next_index,
+                    step: next_step,
+                    after: next_after,
+                    ..
+                },
+            ) if *index == next_index && *step == next_step => {
+                *after = next_after;
+                None
+            }
+            (_, next) => Some(next),
+        }
+    }
+
+    pub fn undo(&mut self, world: &mut ClassicWorld, settings: &mut CSimSettings) {
+        match self {
+            Self::AddObject { index, removed } => {
+                *removed = world.remove_objects([*index]);
+            }
+            Self::AddSoftBody { nodes, removed, .. } => {
+                *removed = world.remove_objects(nodes.iter().cloned());
+            }
+            Self::RemoveObjects { ended, .. } => {
+                world.resume_objects(std::mem::take(ended));
+            }
+            Self::EditObject {
+                index,
+                step,
+                before,
+                ..
+            } => set_state(world, *index, *step, before, settings),
+            Self::AddZone(_) => {
+                world.zones.pop();
+            }
+            Self::RemoveZone { index, zone } => {
+                world.zones.insert(*index, zone.clone());
+            }
+            Self::EditZone { index, before, .. } => {
+                if let Some(zone) = world.zones.get_mut(*index) {
+                    zone.effect = *before;
+                }
+            }
+            Self::Force { .. } => self.replay_force(world, settings, false),
+        }
+    }
+
+    pub fn redo(&mut self, world: &mut ClassicWorld, settings: &mut CSimSettings) {
+        match self {
+            Self::AddObject { removed, .. } | Self::AddSoftBody { removed, .. } => {
+                world.restore_objects(std::mem::take(removed));
+            }
+            Self::RemoveObjects {
+                indices,
+                step,
+                ended,
+                ..
+            } => {
+                *ended = world.end_objects(indices.iter().cloned(), *step);
+            }
+            Self::EditObject {
+                index, step, after, ..
+            } => set_state(world, *index, *step, after, settings),
+            Self::AddZone(zone) => {
+                world.zones.push(zone.clone());
+            }
+            Self::RemoveZone { index, .. } => {
+                world.zones.remove(*index);
+            }
+            Self::EditZone { index, after, .. } => {
+                if let Some(zone) = world.zones.get_mut(*index) {
+                    zone.effect = *after;
+                }
+            }
+            Self::Force { .. } => self.replay_force(world, settings, true),
+        }
+    }
+
+    // 드래그한 힘을 주거나 빼고 지금까지 기록된 스텝을 다시 계산한다.
+    fn replay_force(&self, world: &mut ClassicWorld, settings: &mut CSimSettings, apply: bool) {
+        let Self::Force {
+            targets,
+            from,
+            forces,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        let set_force = |world: &mut ClassicWorld, force: NVec2| {
+            for target in targets {
+                if let Some(obj) = world.objects.get_mut(*target) {
+                    obj.set_user_force(force);
+                }
+            }
+        };
+
+        let to = world.recorded_steps();
+        world.recompute(*from, to, settings, |world, step| {
+            if let Some(force) = forces.get(step - from) {
+                set_force(world, if apply { *force } else { ZERO_FORCE });
+            }
+        });
+    }
+}
+
+// 기록된 스텝의 값을 바꾼다. 지난 스텝이면 그 뒤는 바꾼 값에서 다시 계산한다.
+// 보고 있는 스텝은 부르는 쪽이 다시 맞춘다.
+fn set_state(
+    world: &mut ClassicWorld,
+    index: usize,
+    step: usize,
+    values: &ObjectValues,
+    settings: &mut CSimSettings,
+) {
+    world.at_time_step(step);
+
+    let Some(obj) = world.objects.get_mut(index).filter(|obj| !*obj.hide()) else {
+        return;
+    };
+    obj.set_attached(values.attached);
+    *obj.current_state_mut() = values.state.clone();
+
+    let to = world.recorded_steps();
+    if step < to {
+        world.recompute(step, to, settings, |_, _| {});
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::vector;
+
+    use super::*;
+    use crate::engine::classic::object::builder::CSimObjectBuilder;
+    use crate::engine::classic::object::perpendicular;
+    use crate::engine::classic::template::init::IdealGasSimInit;
+    use crate::engine::classic::template::CSTemplate;
+    use crate::engine::SIMULATION_TICK;
+
+    const STEPS: usize = 200;
+
+    fn recorded() -> (ClassicWorld, CSimSettings) {
+        let preset = CSTemplate::IdealGasSimInit(IdealGasSimInit {
+            count: 20,
+            ..IdealGasSimInit::default()
+        })
+        .get_preset_with_ui();
+        let mut settings = preset.settings.clone();
+        let mut world = ClassicWorld::from(preset);
+
+        for step in 0..STEPS {
+            world.step(&mut settings, SIMULATION_TICK * step as f64);
+        }
+        (world, settings)
+    }
+
+    // 기록된 모든 스텝에서 보이는 오브젝트의 위치, 속도와 부착 함수, 그리고 영역
+    type Snapshot = (Vec<Vec<Option<(NVec2, NVec2)>>>, Vec<bool>, Vec<FieldZone>);
+
+    fn snapshot(world: &mut ClassicWorld) -> Snapshot {
+        let steps = (0..=world.recorded_steps())
+            .map(|step| {
+                world.at_time_step(step);
+                world
+                    .objects
+                    .iter()
+                    .map(|obj| {
+                        obj.visible_state()
+                            .map(|state| (state.position, state.velocity))
+                    })
+                    .collect()
+            })
+            .collect();
+        let attached = world
+            .objects
+            .iter()
+            .map(|obj| obj.attached().is_some())
+            .collect();
+
+        (steps, attached, world.zones.clone())
+    }
+
+    // 이미 적용된 편집을 되돌리고 다시 하면서 앞뒤 모습이 그대로인지 본다.
+    fn round_trip(
+        world: &mut ClassicWorld,
+        settings: &mut CSimSettings,
+        before: &Snapshot,
+        mut edit: SceneEdit,
+    ) {
+        let after = snapshot(world);
+        assert!(after != *before, "{} changed nothing", edit.label());
+
+        for _ in 0..2 {
+            edit.undo(world, settings);
+            assert!(snapshot(world) == *before, "undo {}", edit.label());
+
+            edit.redo(world, settings);
+            assert!(snapshot(world) == after, "redo {}", edit.label());
+        }
+    }
+
+    #[test]
+    fn object_edits_round_trip() {
+        let (mut world, mut settings) = recorded();
+
+        // 드래그한 힘. 다시 계산하면 그 뒤에 생긴 오브젝트는 빠지므로 지난 스텝의 편집을 먼저 한다.
+        // 앱에서는 지난 스텝을 고치기 전에 그 뒤를 갈래로 잘라 낸다.
+        let before = snapshot(&mut world);
+        let mut edit = SceneEdit::Force {
+            name: "Object 0".to_string(),
+            targets: vec![0],
+            from: 50,
+            forces: vec![vector![0.0, 500.0]; 30],
+        };
+        edit.redo(&mut world, &mut settings);
+        round_trip(&mut world, &mut settings, &before, edit);
+
+        // 지난 스텝의 속도와 부착 함수를 고친다. 부착 함수는 그 스텝부터 다시 계산된다.
+        let before = snapshot(&mut world);
+        world.at_time_step(80);
+        let values = ObjectValues::of(&world.objects[7]);
+        let mut changed = values.clone();
+        changed.state.velocity = vector![30.0, -10.0];
+        changed.attached = Some(perpendicular);
+        let mut edit = SceneEdit::EditObject {
+            index: 7,
+            step: 80,
+            before: values,
+            after: changed,
+        };
+        edit.redo(&mut world, &mut settings);
+        round_trip(&mut world, &mut settings, &before, edit);
+
+        // 보고 있는 마지막 스텝에 붙인다.
+        let before = snapshot(&mut world);
+        world.at_time_step(STEPS);
+        let index = world.objects.len();
+        world.objects.push(
+            CSimObjectBuilder::new(CSObjectState {
+                position: vector![1.0, 2.0],
+                ..CSObjectState::default()
+            })
+            .at(STEPS)
+            .build(),
+        );
+        let edit = SceneEdit::AddObject {
+            index,
+            removed: Default::default(),
+        };
+        round_trip(&mut world, &mut settings, &before, edit);
+
+        // 지난 스텝에서 뺀다.
+        let before = snapshot(&mut world);
+        let ended = world.end_objects([3, 4], 120);
+        let edit = SceneEdit::RemoveObjects {
+            name: "Objects".to_string(),
+            indices: vec![3, 4],
+            step: 120,
+            ended,
+        };
+        round_trip(&mut world, &mut settings, &before, edit);
+    }
+
+    #[test]
+    fn zone_edits_round_trip() {
+        let (mut world, mut settings) = recorded();
+        let zone = FieldZone::polygon(
+            "Zone",
+            vec![vector![-5.0, -5.0], vector![5.0, -5.0], vector![0.0, 5.0]],
+            ZoneEffect::Drag(1.0),
+        );
+
+        let before = snapshot(&mut world);
+        world.zones.push(zone.clone());
+        round_trip(&mut world, &mut settings, &before, SceneEdit::AddZone(zone));
+
+        let before = snapshot(&mut world);
+        world.zones[0].effect = ZoneEffect::Drag(3.0);
+        let edit = SceneEdit::EditZone {
+            index: 0,
+            name: "Zone".to_string(),
+            before: ZoneEffect::Drag(1.0),
+            after: ZoneEffect::Drag(3.0),
+        };
+        round_trip(&mut world, &mut settings, &before, edit);
+
+        let before = snapshot(&mut world);
+        let zone = world.zones.remove(0);
+        round_trip(
+            &mut world,
+            &mut settings,
+            &before,
+            SceneEdit::RemoveZone { index: 0, zone },
+        );
+    }
+
+    #[test]
+    fn edits_merge_only_with_the_same_target() {
+        let zone_edit = |index, effect| SceneEdit::EditZone {
+            index,
+            name: "Zone".to_string(),
+            before: ZoneEffect::Drag(0.0),
+            after: effect,
+        };
+
+        let mut edit = zone_edit(0, ZoneEffect::Drag(1.0));
+        assert!(edit.merge(zone_edit(0, ZoneEffect::Drag(2.0))).is_none());
+        assert!(edit.merge(zone_edit(1, ZoneEffect::Drag(3.0))).is_some());
+
+        let SceneEdit::EditZone { before, after, .. } = edit else {
+            unreachable!();
+        };
+        assert_eq!(before, ZoneEffect::Drag(0.0));
+        assert_eq!(after, ZoneEffect::Drag(2.0));
+    }
+}
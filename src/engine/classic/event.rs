@@ -0,0 +1,78 @@
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SimulationEvents(Vec<SimulationEvent>);
+
+impl SimulationEvents {
+    #[allow(dead_code)]
+    pub fn add_events(&mut self, events: Vec<impl Into<SimulationEvent>>) {
+        self.0.extend(events.into_iter().map(|x| x.into()));
+    }
+
+    pub fn add_event(&mut self, event: impl Into<SimulationEvent>) {
+        self.0.push(event.into());
+    }
+
+    /// 오브젝트 인덱스를 `map`으로 바꾼다. 없어진 오브젝트가 관련된 이벤트는 원래 인덱스 그대로 빼낸다.
+    pub fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) -> Vec<SimulationEvent> {
+        let mut removed = vec![];
+
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .filter_map(|event| {
+                let remapped = event.remapped(&map);
+                if remapped.is_none() {
+                    removed.push(event);
+                }
+                remapped
+            })
+            .collect();
+
+        removed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SimulationEvent> {
+        self.0.iter()
+    }
+}
+
+impl From<CollisionEvent> for SimulationEvent {
+    fn from(event: CollisionEvent) -> Self {
+        SimulationEvent::Collision(event)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum SimulationEvent {
+    Collision(CollisionEvent),
+}
+
+impl SimulationEvent {
+    fn remapped(&self, map: impl Fn(usize) -> Option<usize>) -> Option<Self> {
+        match self {
+            SimulationEvent::Collision(collision) => {
+                let objects = (map(collision.objects.0)?, map(collision.objects.1)?);
+
+                Some(SimulationEvent::Collision(CollisionEvent {
+                    objects,
+                    ..collision.clone()
+                }))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CollisionEvent {
+    /// 부딪힌 두 오브젝트의 인덱스. `ClassicWorld`가 채운다.
+    pub objects: (usize, usize),
+    pub contact_point: NVec2,
+    pub contact_normal: NVec2,
+    pub obj1_velocity: NVec2,
+    pub obj2_velocity: NVec2,
+    pub obj1_state: CSObjectState,
+    pub obj2_state: CSObjectState,
+    pub penetration: f64,
+}
@@ -0,0 +1,269 @@
+use crate::engine::classic::event::SimulationEvent;
+use crate::engine::classic::ClassicWorld;
+use crate::engine::{NVec2, SIMULATION_TICK};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// 오브젝트 하나의 한 스텝
+#[derive(Clone, Debug, Serialize)]
+pub struct ObjectSample {
+    pub object: usize,
+    pub step: usize,
+    pub time: f64,
+    pub position: [f64; 2],
+    pub velocity: [f64; 2],
+    pub acceleration: [f64; 2],
+    pub momentum: [f64; 2],
+    pub kinetic_energy: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EventRecord {
+    pub step: usize,
+    pub time: f64,
+    pub kind: &'static str,
+    pub objects: [usize; 2],
+    pub contact_point: [f64; 2],
+    pub contact_normal: [f64; 2],
+    pub penetration: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StampRecord {
+    pub label: String,
+    pub name: String,
+    pub time: f64,
+    pub position: [f64; 2],
+    pub velocity: [f64; 2],
+}
+
+/// 기록된 타임라인, 이벤트, 스탬프를 표로 펼친 것
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ClassicExport {
+    pub objects: Vec<ObjectSample>,
+    pub events: Vec<EventRecord>,
+    pub stamps: Vec<StampRecord>,
+}
+
+fn pair(v: NVec2) -> [f64; 2] {
+    [v.x, v.y]
+}
+
+// 쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감싼다.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+impl ClassicExport {
+    /// `every` 스텝마다 오브젝트 상태를 뽑는다. 이벤트와 스탬프는 모두 담는다.
+    pub fn new(world: &ClassicWorld, every: usize) -> Self {
+        let every = every.max(1);
+
+        let mut objects = vec![];
+        let mut events = vec![];
+
+        // 지난 스텝의 상태와 버린 이벤트는 키프레임에서 다시 계산해서 얻는다.
+        world.replay(|step, states, step_events| {
+            if step % every == 0 {
+                objects.extend(states.iter().enumerate().filter_map(|(object, state)| {
+                    let state = state.as_ref()?;
+
+                    Some(ObjectSample {
+                        object,
+                        step,
+                        time: step as f64 * SIMULATION_TICK,
+                        position: pair(state.position),
+                        velocity: pair(state.velocity),
+                        acceleration: pair(world.total_acceleration(state)),
+                        momentum: pair(state.momentum()),
+                        kinetic_energy: 0.5 * state.mass * state.velocity.norm_squared(),
+                    })
+                }));
+            }
+
+            // 스텝을 계산할 때 생긴 이벤트는 다음 스텝에서 생긴 것이다.
+            let step = step + 1;
+            events.extend(step_events.into_iter().flat_map(|e| e.iter()).map(
+                |event| match event {
+                    SimulationEvent::Collision(collision) => EventRecord {
+                        step,
+                        time: step as f64 * SIMULATION_TICK,
+                        kind: "collision",
+                        objects: [collision.objects.0, collision.objects.1],
+                        contact_point: pair(collision.contact_point),
+                        contact_normal: pair(collision.contact_normal),
+                        penetration: collision.penetration,
+                    },
+                },
+            ));
+        });
+
+        // 오브젝트마다 스텝 순서로 모은다.
+        objects.sort_by_key(|sample| sample.object);
+
+        let stamps = world
+            .stamps
+            .iter()
+            .filter_map(|stamp| stamp.result())
+            .map(|result| StampRecord {
+                label: result.label.clone(),
+                name: result.name.clone(),
+                time: result.time,
+                position: pair(result.state.position),
+                velocity: pair(result.state.velocity),
+            })
+            .collect();
+
+        Self {
+            objects,
+            events,
+            stamps,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn objects_csv(&self) -> String {
+        let mut csv = String::from("object,step,time,x,y,vx,vy,ax,ay,px,py,kinetic_energy\n");
+
+        for s in &self.objects {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                s.object,
+                s.step,
+                s.time,
+                s.position[0],
+                s.position[1],
+                s.velocity[0],
+                s.velocity[1],
+                s.acceleration[0],
+                s.acceleration[1],
+                s.momentum[0],
+                s.momentum[1],
+                s.kinetic_energy
+            );
+        }
+
+        csv
+    }
+
+    pub fn events_csv(&self) -> String {
+        let mut csv = String::from(
+            "step,time,kind,object_a,object_b,contact_x,contact_y,normal_x,normal_y,penetration\n",
+        );
+
+        for e in &self.events {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{}",
+                e.step,
+                e.time,
+                e.kind,
+                e.objects[0],
+                e.objects[1],
+                e.contact_point[0],
+                e.contact_point[1],
+                e.contact_normal[0],
+                e.contact_normal[1],
+                e.penetration
+            );
+        }
+
+        csv
+    }
+
+    pub fn stamps_csv(&self) -> String {
+        let mut csv = String::from("label,name,time,x,y,vx,vy\n");
+
+        for s in &self.stamps {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                csv_field(&s.label),
+                csv_field(&s.name),
+                s.time,
+                s.position[0],
+                s.position[1],
+                s.velocity[0],
+                s.velocity[1]
+            );
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::classic::object::builder::CSimObjectBuilder;
+    use crate::engine::classic::object::state::CSObjectState;
+    use crate::engine::classic::sim_state::CSimSettings;
+    use crate::engine::classic::GRAVITY;
+
+    fn projectile() -> ClassicWorld {
+        let mut world = ClassicWorld::from(vec![CSimObjectBuilder::new(CSObjectState {
+            velocity: NVec2::new(4.0, 12.0),
+            ..CSObjectState::default()
+        })
+        .build()]);
+        let mut settings = CSimSettings::default();
+
+        for step in 0..240 {
+            world.step(&mut settings, SIMULATION_TICK * step as f64);
+        }
+
+        world
+    }
+
+    fn assert_gravity(acceleration: [f64; 2]) {
+        assert!(acceleration[0].abs() < 1e-9, "{:?}", acceleration);
+        assert!(
+            (acceleration[1] - GRAVITY.y).abs() < 1e-9,
+            "{:?}",
+            acceleration
+        );
+    }
+
+    #[test]
+    fn csv_acceleration_includes_gravity() {
+        let csv = ClassicExport::new(&projectile(), 10).objects_csv();
+        let mut lines = csv.lines();
+
+        let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+        let column = |name: &str| header.iter().position(|h| *h == name).unwrap();
+        let (ax, ay) = (column("ax"), column("ay"));
+
+        let mut rows = 0;
+        for line in lines {
+            let fields = line.split(',').collect::<Vec<_>>();
+            assert_gravity([fields[ax].parse().unwrap(), fields[ay].parse().unwrap()]);
+            rows += 1;
+        }
+        assert_eq!(rows, 25);
+    }
+
+    #[test]
+    fn json_acceleration_includes_gravity() {
+        let json = ClassicExport::new(&projectile(), 10).to_json().unwrap();
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+
+        let objects = value["objects"].as_array().unwrap();
+        assert_eq!(objects.len(), 25);
+
+        for sample in objects {
+            let acceleration = &sample["acceleration"];
+            assert_gravity([
+                acceleration[0].as_f64().unwrap(),
+                acceleration[1].as_f64().unwrap(),
+            ]);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+pub mod builder;
+pub mod shape;
+pub mod state;
+pub mod track;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::NVec2;
+
+use state::{CSObjectState, ForceIndex};
+use track::StateTrack;
+
+pub type AttachedFn = fn(&mut CSObjectState);
+
+/// 시나리오 파일에 이름으로 적을 수 있는 부착 함수
+pub const NAMED_ATTACHED: [(&str, AttachedFn); 1] = [("Perpendicular", perpendicular)];
+
+/// 속도에 수직인 가속도를 준다. 원운동을 만든다.
+pub fn perpendicular(state: &mut CSObjectState) {
+    state.acc_list[ForceIndex::Attached as usize] = NVec2::new(state.velocity.y, -state.velocity.x);
+}
+
+pub fn attached_by_name(name: &str) -> Option<AttachedFn> {
+    NAMED_ATTACHED
+        .iter()
+        .find(|(attached_name, _)| *attached_name == name)
+        .map(|(_, attached)| *attached)
+}
+
+pub fn attached_name(attached: AttachedFn) -> Option<&'static str> {
+    NAMED_ATTACHED
+        .iter()
+        .find(|(_, named)| *named as usize == attached as usize)
+        .map(|(name, _)| *name)
+}
+
+// 부착 함수는 이름으로 저장한다. 이름이 없는 함수는 저장되지 않는다.
+mod attached_serde {
+    use super::{attached_by_name, attached_name, AttachedFn};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        attached: &Option<AttachedFn>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        attached.and_then(attached_name).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<AttachedFn>, D::Error> {
+        let name = Option::<String>::deserialize(deserializer)?;
+
+        Ok(name.as_deref().and_then(attached_by_name))
+    }
+}
+
+/// 키프레임 스텝의 온전한 상태. 그 뒤의 스텝은 여기서부터 다시 계산한다.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObjectKeyframe {
+    pub step: usize,
+    pub state: CSObjectState,
+}
+
+/// 시뮬레이션 오브젝트. 지난 스텝은 위치와 속도만 남기고, 나머지 값은 키프레임에서 다시 계산한다.
+#[derive(Default, Clone, Getters, Serialize, Deserialize)]
+pub struct CSimObject {
+    /// 마지막 스텝 앞까지의 위치와 속도
+    #[getset(get = "pub")]
+    track: StateTrack,
+    #[getset(get = "pub")]
+    keyframes: ChunkedVec<ObjectKeyframe>,
+    /// 기록된 마지막 스텝의 상태. 다음 스텝은 이 상태에서 계산한다.
+    live: CSObjectState,
+    /// 계산하고 있는 스텝을 시작할 때의 상태
+    #[serde(skip)]
+    previous: Option<CSObjectState>,
+    /// 지난 스텝을 볼 때의 상태. 세계가 키프레임에서 다시 계산해서 채운다.
+    #[serde(skip)]
+    view: Option<CSObjectState>,
+    /// 마지막 키프레임 뒤에 상태를 고쳤는지. 고쳤으면 다음 스텝에서 키프레임을 남긴다.
+    #[serde(skip)]
+    edited: bool,
+    #[getset(get = "pub")]
+    init_timestep: usize,
+    /// 뺀 스텝. 이 스텝부터는 계산하지 않고 보이지 않는다. 그 앞의 기록은 남는다.
+    #[getset(get = "pub")]
+    #[serde(default)]
+    end_timestep: Option<usize>,
+    timestep: usize,
+
+    #[getset(get = "pub")]
+    hide: bool,
+    #[getset(get = "pub")]
+    #[serde(with = "attached_serde", default)]
+    attached: Option<AttachedFn>,
+}
+
+impl CSimObject {
+    /// `step` 스텝의 `state`에서 다시 계산하는, 기록이 없는 복사본
+    pub fn replica(&self, step: usize, state: CSObjectState) -> Self {
+        Self {
+            track: StateTrack::new(step),
+            keyframes: ChunkedVec::new(),
+            live: state,
+            previous: None,
+            view: None,
+            edited: false,
+            init_timestep: step,
+            end_timestep: None,
+            timestep: step,
+            hide: false,
+            attached: self.attached,
+        }
+    }
+
+    /// 기록된 마지막 스텝
+    pub fn last_timestep(&self) -> usize {
+        self.track.end()
+    }
+
+    /// 마지막 스텝의 위치와 속도를 기록하고 다음 스텝을 계산하기 시작한다.
+    pub fn save_state(&mut self) {
+        self.track.push(self.live.position, self.live.velocity);
+        self.previous = Some(self.live.clone());
+        self.view = None;
+        self.timestep = self.last_timestep();
+        self.hide = false;
+    }
+
+    /// `step` 스텝에서 뺀다. 그 스텝부터는 더 기록하지 않는다.
+    pub fn end_at(&mut self, step: usize) {
+        self.end_timestep = Some(step);
+        self.hide = self.timestep >= step;
+    }
+
+    /// 뺀 것을 되돌린다. 뺀 동안 지난 스텝은 `hold_until`로 채운다.
+    pub fn resume(&mut self) {
+        self.end_timestep = None;
+        self.edited = true;
+    }
+
+    /// 이미 빠진 오브젝트인지
+    pub fn is_ended(&self) -> bool {
+        self.end_timestep.is_some()
+    }
+
+    /// `step` 스텝에서 이미 빠졌는지
+    pub fn is_ended_at(&self, step: usize) -> bool {
+        self.end_timestep.map_or(false, |end| step >= end)
+    }
+
+    /// 마지막 상태 그대로 `step` 스텝까지 기록을 채운다.
+    pub fn hold_until(&mut self, step: usize) {
+        while self.last_timestep() < step {
+            self.track.push(self.live.position, self.live.velocity);
+            self.edited = true;
+        }
+    }
+
+    /// 마지막 스텝의 상태를 키프레임으로 남긴다.
+    pub fn save_keyframe(&mut self) {
+        self.keyframes.push(ObjectKeyframe {
+            step: self.last_timestep(),
+            state: self.live.clone(),
+        });
+        self.edited = false;
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.edited
+    }
+
+    pub fn keyframe_at(&self, step: usize) -> Option<&CSObjectState> {
+        let index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.step < step);
+
+        self.keyframes
+            .get(index)
+            .filter(|keyframe| keyframe.step == step)
+            .map(|keyframe| &keyframe.state)
+    }
+
+    pub fn remove_keyframe(&mut self, step: usize) {
+        let index = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.step < step);
+
+        if self
+            .keyframes
+            .get(index)
+            .map_or(false, |keyframe| keyframe.step == step)
+        {
+            self.keyframes.remove(index);
+        }
+    }
+
+    /// `step` 스텝 앞의 위치와 속도를 버린다. 키프레임은 남는다.
+    pub fn forget_before(&mut self, step: usize) {
+        self.track.forget_before(step);
+    }
+
+    /// `timestep` 스텝 뒤의 기록을 지우고 그 스텝으로 돌아간다.
+    /// 그 스텝의 상태는 세계가 미리 채워 둔 것을 쓴다.
+    pub fn truncate(&mut self, timestep: usize) {
+        if timestep < self.init_timestep || timestep >= self.last_timestep() {
+            return;
+        }
+
+        if self.end_timestep.map_or(false, |end| end > timestep) {
+            self.end_timestep = None;
+        }
+
+        self.live = self.current_state();
+        self.track.truncate(timestep);
+        self.keyframes.truncate(
+            self.keyframes
+                .partition_point(|keyframe| keyframe.step < timestep),
+        );
+        self.previous = None;
+        self.view = None;
+        self.edited = true;
+        self.timestep = timestep;
+        self.hide = false;
+    }
+
+    /// 부착 함수를 바꾼다. 지금 상태에 남은 부착 가속도는 지운다.
+    pub fn set_attached(&mut self, attached: Option<AttachedFn>) {
+        self.attached = attached;
+
+        if !self.hide {
+            self.current_state_mut().acc_list[ForceIndex::Attached as usize] = NVec2::zeros();
+        }
+    }
+
+    pub fn local_timestep(&self, timestep: usize) -> Option<usize> {
+        timestep.checked_sub(self.init_timestep)
+    }
+
+    /// `timestep` 스텝을 본다. 지난 스텝이면 세계가 `set_view`로 상태를 채운다.
+    pub fn at_timestep(&mut self, timestep: usize) {
+        self.view = None;
+        self.timestep = timestep;
+        self.hide = timestep < self.init_timestep
+            || timestep > self.last_timestep()
+            || self.is_ended_at(timestep);
+    }
+
+    pub fn set_view(&mut self, state: CSObjectState) {
+        self.view = Some(state);
+    }
+
+    /// 보고 있는 스텝의 상태
+    pub fn current_state(&self) -> CSObjectState {
+        if self.timestep >= self.last_timestep() {
+            return self.live.clone();
+        }
+
+        self.view
+            .clone()
+            .unwrap_or_else(|| self.recorded_state(self.timestep))
+    }
+
+    /// 보고 있는 스텝의 상태를 고친다. 고친 상태는 다음 스텝에서 키프레임으로 남는다.
+    pub fn current_state_mut(&mut self) -> &mut CSObjectState {
+        self.edited = true;
+        self.state_mut()
+    }
+
+    /// 사용자가 준 가속도를 바꾼다. 세계가 스텝마다 기록하므로 고친 것으로 보지 않는다.
+    pub fn set_user_force(&mut self, force: NVec2) {
+        self.state_mut().acc_list[ForceIndex::UserInteraction as usize] = force;
+    }
+
+    /// 계산하고 있는 스텝의 상태. 스텝 안에서 바꾸는 값은 편집이 아니다.
+    pub fn live_state_mut(&mut self) -> &mut CSObjectState {
+        &mut self.live
+    }
+
+    /// 기록된 마지막 스텝의 상태
+    pub fn live_state(&self) -> &CSObjectState {
+        &self.live
+    }
+
+    pub fn previous_state(&self) -> Option<CSObjectState> {
+        self.previous.clone()
+    }
+
+    /// 보이면 보고 있는 스텝의 상태
+    pub fn visible_state(&self) -> Option<CSObjectState> {
+        (!self.hide).then(|| self.current_state())
+    }
+
+    /// 처음 나왔을 때의 상태
+    pub fn initial_state(&self) -> CSObjectState {
+        self.keyframes
+            .first()
+            .filter(|keyframe| keyframe.step == self.init_timestep)
+            .map(|keyframe| keyframe.state.clone())
+            .unwrap_or_else(|| self.recorded_state(self.init_timestep))
+    }
+
+    // 마지막 상태에 `step` 스텝에 기록된 위치와 속도를 넣은 상태
+    fn recorded_state(&self, step: usize) -> CSObjectState {
+        let mut state = self.live.clone();
+
+        if let Some((position, velocity)) = self.track.get(step) {
+            state.position = position;
+            state.velocity = velocity;
+        }
+
+        state
+    }
+
+    fn state_mut(&mut self) -> &mut CSObjectState {
+        if self.timestep >= self.last_timestep() {
+            return &mut self.live;
+        }
+
+        if self.view.is_none() {
+            self.view = Some(self.recorded_state(self.timestep));
+        }
+
+        self.view.as_mut().unwrap()
+    }
+}
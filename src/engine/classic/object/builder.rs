@@ -1,7 +1,9 @@
-use crate::app::simulations::classic_simulation::object::shape::ObjectShape;
-use crate::app::simulations::classic_simulation::object::state::CSObjectState;
-use crate::app::simulations::classic_simulation::object::AttachedFn;
-use crate::app::simulations::classic_simulation::CSimObject;
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::object::track::StateTrack;
+use crate::engine::classic::object::AttachedFn;
+use crate::engine::classic::CSimObject;
 
 pub struct CSimObjectBuilder {
     init_state: Option<CSObjectState>,
@@ -37,9 +39,22 @@ impl CSimObjectBuilder {
 
     pub fn build(self) -> CSimObject {
         let init_timestep = self.init_timestep.unwrap_or(0);
+        let mut init_state = self.init_state.unwrap_or_default();
+
+        if let Some(shape) = self.shape {
+            init_state.shape = shape;
+        }
+
         CSimObject {
-            state_timeline: vec![self.init_state.unwrap_or_default()],
+            track: StateTrack::new(init_timestep),
+            keyframes: ChunkedVec::new(),
+            live: init_state,
+            previous: None,
+            view: None,
+            // 처음 상태는 첫 스텝에서 키프레임으로 남는다.
+            edited: true,
             init_timestep,
+            end_timestep: None,
             timestep: init_timestep,
             hide: false,
             attached: self.attached,
@@ -1,14 +1,13 @@
-use crate::app::NVec2;
-use egui::plot::PlotPoints;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
 use std::f64::consts::TAU;
 
 pub trait Shape {
     fn get_points(&self) -> Vec<[f64; 2]>;
-    fn get_plot_points(&self, pos: NVec2) -> PlotPoints;
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ObjectShape {
     Circle(Circle),
     Rect(Rect),
@@ -37,15 +36,16 @@ impl ObjectShape {
         }
     }
 
-    pub fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
+    // 중심에서 각 축 방향으로 모양이 차지하는 거리.
+    pub fn half_extent(&self) -> NVec2 {
         match self {
-            Self::Circle(circle) => circle.get_plot_points(pos),
-            Self::Rect(rect) => rect.get_plot_points(pos),
+            Self::Circle(circle) => NVec2::repeat(circle.radius),
+            Self::Rect(rect) => NVec2::new(rect.width / 2.0, rect.height / 2.0),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Circle {
     pub radius: f64,
 }
@@ -62,18 +62,10 @@ impl Shape for Circle {
     fn get_points(&self) -> Vec<[f64; 2]> {
         self._get_points(Self::DEFAULT_RESOLUTION)
     }
-
-    fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
-        PlotPoints::from_parametric_callback(
-            move |t| (t.sin() * self.radius + pos.x, t.cos() * self.radius + pos.y),
-            0.0..TAU,
-            Self::DEFAULT_RESOLUTION as usize,
-        )
-    }
 }
 
 impl Circle {
-    const DEFAULT_RESOLUTION: u64 = 50;
+    pub const DEFAULT_RESOLUTION: u64 = 50;
     const DEFAULT_RADIUS: f64 = 10.0;
 
     fn _get_points(&self, resolution: u64) -> Vec<[f64; 2]> {
@@ -97,7 +89,7 @@ impl Circle {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Rect {
     pub width: f64,
     pub height: f64,
@@ -114,19 +106,4 @@ impl Shape for Rect {
             [-width / 2.0, height / 2.0],
         ]
     }
-
-    fn get_plot_points(&self, pos: NVec2) -> PlotPoints {
-        let width = self.width;
-        let height = self.height;
-        vec![
-            (-width / 2.0, -height / 2.0),
-            (width / 2.0, -height / 2.0),
-            (width / 2.0, height / 2.0),
-            (-width / 2.0, height / 2.0),
-        ]
-        .into_iter()
-        .map(|e| [e.0 + pos.x, e.1 + pos.y])
-        .collect::<Vec<_>>()
-        .into()
-    }
 }
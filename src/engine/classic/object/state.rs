@@ -1,8 +1,8 @@
-use crate::app::simulations::classic_simulation::event::CollisionEvent;
-use crate::app::simulations::classic_simulation::object::shape::ObjectShape;
-use crate::app::NVec2;
+use crate::engine::classic::event::CollisionEvent;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::NVec2;
 use nalgebra::vector;
-
+use serde::{Deserialize, Serialize};
 
 pub trait ListAdd<Rhs = Self> {
     type Output;
@@ -17,18 +17,23 @@ impl ListAdd for [f64; 2] {
     }
 }
 
+/// 반발 계수. 1.0 이면 완전 탄성 충돌.
+pub const RESTITUTION: f64 = 1.0;
+
 pub trait Collision {
     fn contact(&self, ops: &CSObjectState) -> Option<CollisionEvent>;
 }
 
 #[repr(usize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ForceIndex {
     Attached = 0,
     UserInteraction = 1,
-    MAX = 2,
+    Spring = 2,
+    MAX = 3,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CSObjectState {
     pub position: NVec2,
     pub velocity: NVec2,
@@ -53,24 +58,24 @@ impl Collision for CSObjectState {
                         delta_pos.normalize()
                     };
 
-                    let contact_point = self.position - contact_normal * circle.radius;
+                    // 이미 멀어지는 중이면 충돌로 보지 않는다.
+                    let approach_speed = (self.velocity - ops.velocity).dot(&contact_normal);
 
-                    //
-                    let scale1 = contact_normal.yx().dot(&self.momentum())
-                        / contact_normal.yx().dot(&contact_normal);
+                    if approach_speed >= 0.0 {
+                        return None;
+                    }
 
-                    let scale2 = contact_normal.yx().dot(&ops.momentum())
-                        / contact_normal.yx().dot(&contact_normal);
-
-                    dbg!(scale1, scale2);
+                    let contact_point = self.position - contact_normal * circle.radius;
 
-                    let obj1_scale = scale2.abs();
-                    let obj2_scale = scale1.abs();
+                    // 탄성 충돌 충격량 J = -(1 + e)(v_rel · n) / (1/m1 + 1/m2)
+                    let impulse =
+                        -(1.0 + RESTITUTION) * approach_speed / (1.0 / self.mass + 1.0 / ops.mass);
 
-                    let obj1_velocity = contact_normal * (obj1_scale / self.mass);
-                    let obj2_velocity = (-contact_normal) * (obj2_scale / ops.mass);
+                    let obj1_velocity = contact_normal * (impulse / self.mass);
+                    let obj2_velocity = (-contact_normal) * (impulse / ops.mass);
 
                     Some(CollisionEvent {
+                        objects: (0, 0),
                         contact_point,
                         contact_normal,
                         penetration,
@@ -106,6 +111,7 @@ impl CSObjectState {
         self.acc_list.iter().sum::<NVec2>()
     }
 
+    #[allow(dead_code)]
     pub fn scale(&self) -> f64 {
         5.0 + (self.mass / 4.0)
     }
@@ -124,10 +130,13 @@ impl Default for CSObjectState {
     }
 }
 
+#[allow(dead_code)]
+#[derive(Default)]
 pub struct CSObjectStateBuilder {
     state: CSObjectState,
 }
 
+#[allow(dead_code)]
 impl CSObjectStateBuilder {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,81 @@
+//! 스텝마다 남기는 위치와 속도. 나머지 값은 키프레임에서 다시 계산한다.
+
+use std::mem::size_of;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::NVec2;
+
+/// 오브젝트가 생긴 스텝부터 센 스텝마다의 위치와 속도.
+/// 오래된 기록은 앞에서부터 버릴 수 있다. 사본끼리 기록을 함께 쓴다.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct StateTrack {
+    /// 남아 있는 첫 기록의 스텝
+    start: usize,
+    positions: ChunkedVec<NVec2>,
+    velocities: ChunkedVec<NVec2>,
+}
+
+impl StateTrack {
+    /// `start` 스텝부터 기록한다.
+    pub fn new(start: usize) -> Self {
+        Self {
+            start,
+            ..Self::default()
+        }
+    }
+
+    /// 남아 있는 첫 기록의 스텝
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// 기록된 마지막 스텝의 다음 스텝
+    pub fn end(&self) -> usize {
+        self.start + self.positions.len()
+    }
+
+    pub fn push(&mut self, position: NVec2, velocity: NVec2) {
+        self.positions.push(position);
+        self.velocities.push(velocity);
+    }
+
+    /// `step` 스텝의 위치와 속도. 버렸거나 아직 없으면 None.
+    pub fn get(&self, step: usize) -> Option<(NVec2, NVec2)> {
+        let index = step.checked_sub(self.start)?;
+
+        Some((*self.positions.get(index)?, *self.velocities.get(index)?))
+    }
+
+    /// `from` 스텝부터 `to` 스텝 앞까지 남아 있는 위치
+    pub fn positions(&self, from: usize, to: usize) -> impl Iterator<Item = &NVec2> {
+        let from = from.clamp(self.start, self.end()) - self.start;
+        let to = to.clamp(self.start, self.end()) - self.start;
+
+        self.positions.range(from, to)
+    }
+
+    /// `step` 스텝부터의 기록을 지운다. 그 앞을 버렸으면 `step` 스텝부터 다시 기록한다.
+    pub fn truncate(&mut self, step: usize) {
+        let len = step.saturating_sub(self.start);
+
+        self.positions.truncate(len);
+        self.velocities.truncate(len);
+        self.start = self.start.min(step);
+    }
+
+    /// `step` 스텝 앞의 기록을 버린다.
+    pub fn forget_before(&mut self, step: usize) {
+        let count = step.saturating_sub(self.start).min(self.positions.len());
+
+        self.positions.drain_front(count);
+        self.velocities.drain_front(count);
+        self.start += count;
+    }
+
+    /// 기록이 차지하는 바이트 수
+    pub fn bytes(&self) -> usize {
+        (self.positions.len() + self.velocities.len()) * size_of::<NVec2>()
+    }
+}
@@ -0,0 +1,130 @@
+use crate::engine::chunked::ChunkedVec;
+use crate::engine::classic::boundary::WorldBoundary;
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::SIMULATION_TICK;
+
+/// 압력을 구할 때 평균을 내는 스텝 수.
+pub const PRESSURE_WINDOW: usize = 240;
+
+/// 벽으로 둘러싸인 기체의 압력, 온도 측정값. 볼츠만 상수는 1로 둔다.
+pub struct GasProbe {
+    pub count: usize,
+    pub area: f64,
+    pub pressure: f64,
+    pub temperature: f64,
+    pub mean_mass: f64,
+    pub speeds: Vec<f64>,
+}
+
+impl GasProbe {
+    pub fn measure(
+        states: &[CSObjectState],
+        wall_impulse: &ChunkedVec<f64>,
+        boundary: &WorldBoundary,
+        timestep: usize,
+    ) -> Self {
+        let count = states.len();
+        let size = boundary.size();
+        let area = size.x * size.y;
+
+        // P = ΣJ / (Δt · L)
+        let end = timestep.min(wall_impulse.len());
+        let start = end.saturating_sub(PRESSURE_WINDOW);
+        let duration = (end - start) as f64 * SIMULATION_TICK;
+        let impulse: f64 = wall_impulse.range(start, end).sum();
+
+        let pressure = if duration > 0.0 && boundary.wall_length() > 0.0 {
+            impulse / (duration * boundary.wall_length())
+        } else {
+            0.0
+        };
+
+        // 2차원 이상기체: <½mv²> = kT
+        let kinetic: f64 = states
+            .iter()
+            .map(|state| 0.5 * state.mass * state.velocity.norm_squared())
+            .sum();
+
+        let temperature = if count > 0 {
+            kinetic / count as f64
+        } else {
+            0.0
+        };
+
+        let mean_mass = if count > 0 {
+            states.iter().map(|state| state.mass).sum::<f64>() / count as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            count,
+            area,
+            pressure,
+            temperature,
+            mean_mass,
+            speeds: states.iter().map(|state| state.velocity.norm()).collect(),
+        }
+    }
+
+    /// PV / NkT. 이상기체라면 1에 가깝다. 입자가 없거나 온도가 0이면 정할 수 없어 None.
+    pub fn compressibility(&self) -> Option<f64> {
+        if self.count == 0 || self.temperature <= 0.0 {
+            return None;
+        }
+
+        Some(self.pressure * self.area / (self.count as f64 * self.temperature))
+    }
+
+    /// 2차원 맥스웰-볼츠만 속력 분포 f(v) = (mv / kT) exp(-mv² / 2kT)
+    pub fn maxwell_boltzmann(&self, speed: f64) -> f64 {
+        let m = self.mean_mass;
+        let kt = self.temperature;
+
+        (m * speed / kt) * (-m * speed * speed / (2.0 * kt)).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::NVec2;
+
+    fn boundary() -> WorldBoundary {
+        WorldBoundary::walled(NVec2::new(-10.0, -10.0), NVec2::new(10.0, 10.0))
+    }
+
+    #[test]
+    fn empty_region_has_no_compressibility() {
+        let probe = GasProbe::measure(&[], &vec![1.0; 10].into(), &boundary(), 10);
+
+        assert_eq!(probe.count, 0);
+        assert_eq!(probe.temperature, 0.0);
+        assert_eq!(probe.compressibility(), None);
+    }
+
+    #[test]
+    fn resting_gas_has_no_compressibility() {
+        let states = vec![CSObjectState::default(); 4];
+        let probe = GasProbe::measure(&states, &vec![0.0; 10].into(), &boundary(), 10);
+
+        assert_eq!(probe.temperature, 0.0);
+        assert_eq!(probe.compressibility(), None);
+    }
+
+    #[test]
+    fn moving_gas_has_finite_compressibility() {
+        let states = vec![
+            CSObjectState {
+                mass: 1.0,
+                velocity: NVec2::new(3.0, 4.0),
+                ..CSObjectState::default()
+            };
+            4
+        ];
+        let probe = GasProbe::measure(&states, &vec![1.0; 10].into(), &boundary(), 10);
+
+        assert!((probe.temperature - 12.5).abs() < 1e-12);
+        assert!(probe.compressibility().unwrap().is_finite());
+    }
+}
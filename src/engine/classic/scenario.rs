@@ -0,0 +1,516 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::engine::classic::boundary::WorldBoundary;
+use crate::engine::classic::chain::OscillatorChain;
+use crate::engine::classic::object::builder::CSimObjectBuilder;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::classic::object::state::{CSObjectState, ForceIndex};
+use crate::engine::classic::object::{attached_by_name, attached_name};
+use crate::engine::classic::sim_state::{CSimSettings, PlotViewFilter};
+use crate::engine::classic::soft_body::SoftBody;
+use crate::engine::classic::template::stamp::CSObjectStamp;
+use crate::engine::classic::template::CSPreset;
+use crate::engine::classic::zone::FieldZone;
+use crate::engine::classic::ClassicWorld;
+use crate::engine::NVec2;
+
+/// 시나리오 파일 형식의 버전. 예전 파일을 그대로 읽을 수 없게 바뀌면 올린다.
+pub const SCENARIO_VERSION: u32 = 1;
+
+/// 파일로 주고받는 고전 역학 시나리오.
+/// 물체는 시작 상태만 담고, 스탬프는 찍히기 전 조건만 담는다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scenario {
+    pub version: u32,
+    pub name: String,
+    /// 문제 설명처럼 시나리오와 함께 보여줄 글
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub settings: ScenarioSettings,
+    #[serde(default)]
+    pub objects: Vec<ScenarioObject>,
+    #[serde(default)]
+    pub zones: Vec<FieldZone>,
+    #[serde(default)]
+    pub soft_bodies: Vec<SoftBody>,
+    #[serde(default)]
+    pub chains: Vec<OscillatorChain>,
+    #[serde(default)]
+    pub stamps: Vec<CSObjectStamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioSettings {
+    pub gravity: bool,
+    pub boundary: WorldBoundary,
+    pub plot_filter: PlotViewFilter,
+}
+
+impl Default for ScenarioSettings {
+    fn default() -> Self {
+        Self {
+            gravity: true,
+            boundary: WorldBoundary::default(),
+            plot_filter: PlotViewFilter::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScenarioObject {
+    pub position: NVec2,
+    #[serde(default)]
+    pub velocity: NVec2,
+    #[serde(default = "default_mass")]
+    pub mass: f64,
+    #[serde(default)]
+    pub shape: ObjectShape,
+    /// 물체가 계속 받는 가속도
+    #[serde(default)]
+    pub acceleration: NVec2,
+    /// `NAMED_ATTACHED`에 있는 부착 함수의 이름
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attached: Option<String>,
+}
+
+fn default_mass() -> f64 {
+    CSObjectState::default().mass
+}
+
+/// 시나리오 파일을 읽지 못한 까닭
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScenarioError {
+    /// JSON 형식이 틀렸다.
+    Parse(String),
+    /// 이 프로그램보다 새 버전의 파일이다.
+    TooNew(u32),
+    /// 질량이 0 이하이거나 유한한 수가 아니다.
+    NonPositiveMass {
+        object: usize,
+        mass: f64,
+    },
+    /// 위치, 속도, 가속도에 NaN이나 무한대가 있다.
+    NotFinite {
+        object: usize,
+        field: &'static str,
+    },
+    /// 소프트 바디, 체인, 스탬프가 없는 물체를 가리킨다.
+    ObjectOutOfRange {
+        owner: String,
+        index: usize,
+        count: usize,
+    },
+    /// 체인의 질점과 평형 위치의 개수가 다르다.
+    ChainLength {
+        nodes: usize,
+        equilibrium: usize,
+    },
+    UnknownAttached(String),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "{}", error),
+            Self::TooNew(version) => write!(
+                f,
+                "scenario version {} is newer than this program ({})",
+                version, SCENARIO_VERSION
+            ),
+            Self::NonPositiveMass { object, mass } => {
+                write!(f, "object {} has a non-positive mass ({})", object, mass)
+            }
+            Self::NotFinite { object, field } => {
+                write!(f, "object {} has a non-finite {}", object, field)
+            }
+            Self::ObjectOutOfRange {
+                owner,
+                index,
+                count,
+            } => write!(
+                f,
+                "{} refers to object {}, but there are only {} objects",
+                owner, index, count
+            ),
+            Self::ChainLength { nodes, equilibrium } => write!(
+                f,
+                "chain has {} nodes but {} equilibrium positions",
+                nodes, equilibrium
+            ),
+            Self::UnknownAttached(name) => write!(f, "unknown attached function: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Scenario {
+    /// 세계가 보고 있는 스텝을 시나리오로 만든다. 아직 나오지 않은 물체는 처음 상태로 넣고,
+    /// 이미 뺀 물체는 넣지 않는다.
+    pub fn from_world(
+        name: impl Into<String>,
+        world: &ClassicWorld,
+        settings: &CSimSettings,
+    ) -> Self {
+        let step = world.viewed_step();
+        let ended = world
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, obj)| obj.is_ended_at(step))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        // 뺀 물체를 가리키는 인덱스는 모든 스텝에서 빼는 것과 같이 당긴다.
+        let trimmed = (!ended.is_empty()).then(|| {
+            let mut copy = world.clone();
+            copy.remove_objects(ended);
+            copy
+        });
+        let world = trimmed.as_ref().unwrap_or(world);
+
+        let objects = world
+            .objects
+            .iter()
+            .map(|obj| {
+                let state = obj.visible_state().unwrap_or_else(|| obj.initial_state());
+
+                ScenarioObject {
+                    position: state.position,
+                    velocity: state.velocity,
+                    mass: state.mass,
+                    shape: state.shape,
+                    acceleration: state.acc_list[ForceIndex::Attached as usize]
+                        + state.acc_list[ForceIndex::UserInteraction as usize],
+                    attached: obj
+                        .attached()
+                        .and_then(attached_name)
+                        .map(|name| name.to_string()),
+                }
+            })
+            .collect();
+
+        let stamps = world
+            .stamps
+            .iter()
+            .map(|stamp| CSObjectStamp::new(&stamp.label, stamp.condition, stamp.range().clone()))
+            .collect();
+
+        Self {
+            version: SCENARIO_VERSION,
+            name: name.into(),
+            description: String::new(),
+            settings: ScenarioSettings {
+                gravity: settings.gravity.value(),
+                boundary: settings.boundary,
+                plot_filter: settings.plot_filter,
+            },
+            objects,
+            zones: world.zones.clone(),
+            soft_bodies: world.soft_bodies.clone(),
+            chains: world.chains.clone(),
+            stamps,
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ScenarioError> {
+        let scenario: Self =
+            serde_json::from_str(text).map_err(|e| ScenarioError::Parse(e.to_string()))?;
+
+        if scenario.version > SCENARIO_VERSION {
+            return Err(ScenarioError::TooNew(scenario.version));
+        }
+
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    // 손으로 고친 파일이 스텝 중에 패닉을 내거나 NaN을 퍼뜨리지 않도록 값과 인덱스를 확인한다.
+    fn validate(&self) -> Result<(), ScenarioError> {
+        for (index, object) in self.objects.iter().enumerate() {
+            if !object.mass.is_finite() || object.mass <= 0.0 {
+                return Err(ScenarioError::NonPositiveMass {
+                    object: index,
+                    mass: object.mass,
+                });
+            }
+
+            let vectors = [
+                ("position", object.position),
+                ("velocity", object.velocity),
+                ("acceleration", object.acceleration),
+            ];
+            for (field, vector) in vectors {
+                if !vector.iter().all(|x| x.is_finite()) {
+                    return Err(ScenarioError::NotFinite {
+                        object: index,
+                        field,
+                    });
+                }
+            }
+
+            if let Some(name) = &object.attached {
+                if attached_by_name(name).is_none() {
+                    return Err(ScenarioError::UnknownAttached(name.clone()));
+                }
+            }
+        }
+
+        let count = self.objects.len();
+        let check = |owner: &str, index: usize| {
+            if index < count {
+                Ok(())
+            } else {
+                Err(ScenarioError::ObjectOutOfRange {
+                    owner: owner.to_string(),
+                    index,
+                    count,
+                })
+            }
+        };
+
+        for body in &self.soft_bodies {
+            let indices = body
+                .nodes
+                .iter()
+                .chain(body.outline.iter())
+                .chain(body.anchors.iter().map(|(node, _)| node))
+                .chain(
+                    body.springs
+                        .iter()
+                        .flat_map(|spring| [&spring.a, &spring.b]),
+                );
+
+            for index in indices {
+                check(&format!("soft body \"{}\"", body.name), *index)?;
+            }
+        }
+
+        for chain in &self.chains {
+            if chain.nodes.len() != chain.equilibrium.len() {
+                return Err(ScenarioError::ChainLength {
+                    nodes: chain.nodes.len(),
+                    equilibrium: chain.equilibrium.len(),
+                });
+            }
+
+            for index in &chain.nodes {
+                check("chain", *index)?;
+            }
+        }
+
+        // 모든 물체가 빠져 빈 범위가 된 스탬프는 그대로 둔다.
+        for stamp in self.stamps.iter().filter(|stamp| !stamp.range().is_empty()) {
+            check(&format!("stamp \"{}\"", stamp.label), *stamp.range().end())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_preset(self) -> CSPreset {
+        let simulation_objects = self
+            .objects
+            .into_iter()
+            .map(|object| {
+                let mut state = CSObjectState {
+                    position: object.position,
+                    velocity: object.velocity,
+                    mass: object.mass,
+                    ..CSObjectState::default()
+                };
+                state.acc_list[ForceIndex::Attached as usize] = object.acceleration;
+
+                let builder = CSimObjectBuilder::new(state).shape(object.shape);
+
+                match object.attached.as_deref().and_then(attached_by_name) {
+                    Some(attached) => builder.attached(attached),
+                    None => builder,
+                }
+                .build()
+            })
+            .collect();
+
+        let mut settings = CSimSettings {
+            gravity: self.settings.gravity.into(),
+            boundary: self.settings.boundary,
+            plot_filter: self.settings.plot_filter,
+            ..CSimSettings::default()
+        };
+        settings.gravity.changed();
+
+        CSPreset {
+            simulation_objects,
+            stamps: self.stamps,
+            zones: self.zones,
+            soft_bodies: self.soft_bodies,
+            chains: self.chains,
+            settings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::classic::chain::ChainEnd;
+    use crate::engine::classic::template::stamp::{Axis, StampCondition};
+    use std::ops::RangeInclusive;
+
+    fn object(x: f64) -> ScenarioObject {
+        ScenarioObject {
+            position: NVec2::new(x, 0.0),
+            velocity: NVec2::new(1.0, 0.0),
+            mass: 1.0,
+            shape: ObjectShape::default(),
+            acceleration: NVec2::zeros(),
+            attached: None,
+        }
+    }
+
+    fn two_balls() -> Scenario {
+        Scenario {
+            version: SCENARIO_VERSION,
+            name: "two balls".to_string(),
+            description: String::new(),
+            settings: ScenarioSettings::default(),
+            objects: vec![object(-10.0), object(10.0)],
+            zones: vec![],
+            soft_bodies: vec![],
+            chains: vec![],
+            stamps: vec![],
+        }
+    }
+
+    fn stamp(range: RangeInclusive<usize>) -> CSObjectStamp {
+        let condition = StampCondition::PositionAbove {
+            axis: Axis::X,
+            value: 0.0,
+        };
+
+        CSObjectStamp::new("crossed", condition, range)
+    }
+
+    #[test]
+    fn valid_scenario_survives_a_round_trip() {
+        let mut scenario = two_balls();
+        scenario.stamps.push(stamp(0..=1));
+
+        let text = scenario.to_json().unwrap();
+        let loaded = Scenario::from_json(&text).unwrap();
+
+        assert_eq!(loaded.objects.len(), 2);
+        assert_eq!(loaded.stamps[0].range(), &(0..=1));
+    }
+
+    #[test]
+    fn rejects_non_positive_mass() {
+        for mass in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let mut scenario = two_balls();
+            scenario.objects[1].mass = mass;
+
+            assert!(
+                matches!(
+                    scenario.validate(),
+                    Err(ScenarioError::NonPositiveMass { object: 1, .. })
+                ),
+                "mass {}",
+                mass
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_non_finite_position() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut scenario = two_balls();
+            scenario.objects[0].position.y = value;
+
+            assert_eq!(
+                scenario.validate(),
+                Err(ScenarioError::NotFinite {
+                    object: 0,
+                    field: "position"
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_non_finite_velocity() {
+        let mut scenario = two_balls();
+        scenario.objects[1].velocity.x = f64::NAN;
+
+        assert_eq!(
+            scenario.validate(),
+            Err(ScenarioError::NotFinite {
+                object: 1,
+                field: "velocity"
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_stamp_beyond_the_objects() {
+        let mut scenario = two_balls();
+        scenario.stamps.push(stamp(0..=2));
+
+        assert_eq!(
+            scenario.validate(),
+            Err(ScenarioError::ObjectOutOfRange {
+                owner: "stamp \"crossed\"".to_string(),
+                index: 2,
+                count: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn accepts_stamp_whose_objects_were_all_removed() {
+        let mut scenario = two_balls();
+        scenario.stamps.push(stamp(RangeInclusive::new(1, 0)));
+
+        assert_eq!(scenario.validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_chain_node_beyond_the_objects() {
+        let mut scenario = two_balls();
+        scenario.chains.push(OscillatorChain {
+            nodes: vec![1, 2],
+            equilibrium: vec![NVec2::zeros(); 2],
+            stiffness: 10.0,
+            mass: 1.0,
+            left: ChainEnd::Fixed,
+            right: ChainEnd::Fixed,
+            driver: None,
+        });
+
+        assert_eq!(
+            scenario.validate(),
+            Err(ScenarioError::ObjectOutOfRange {
+                owner: "chain".to_string(),
+                index: 2,
+                count: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_a_newer_version() {
+        let mut scenario = two_balls();
+        scenario.version = SCENARIO_VERSION + 1;
+        let text = scenario.to_json().unwrap();
+
+        assert_eq!(
+            Scenario::from_json(&text).unwrap_err(),
+            ScenarioError::TooNew(SCENARIO_VERSION + 1)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::engine::classic::boundary::WorldBoundary;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChangeNotifier<T: Debug + Clone + Copy + PartialEq> {
+    value: T,
+    /// 읽어 온 값은 다시 적용되어야 하므로 바뀐 것으로 본다.
+    #[serde(skip, default = "pending")]
+    changed: bool,
+}
+
+fn pending() -> bool {
+    true
+}
+
+impl<T: Debug + Clone + Copy + PartialEq> From<T> for ChangeNotifier<T> {
+    fn from(value: T) -> Self {
+        Self {
+            value,
+            changed: false,
+        }
+    }
+}
+
+impl<T: Debug + Clone + Copy + PartialEq> ChangeNotifier<T> {
+    pub fn get(&mut self) -> Option<T> {
+        if self.changed {
+            self.changed = false;
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    pub fn changed(&mut self) {
+        self.changed = true;
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CSimSettings {
+    pub plot_filter: PlotViewFilter,
+    pub gravity: ChangeNotifier<bool>,
+    pub boundary: WorldBoundary,
+    /// 타임라인이 쓸 수 있는 메모리 (MB)
+    #[serde(default = "default_memory_budget")]
+    pub memory_budget: usize,
+}
+
+pub const DEFAULT_MEMORY_BUDGET: usize = 256;
+
+fn default_memory_budget() -> usize {
+    DEFAULT_MEMORY_BUDGET
+}
+
+impl Default for CSimSettings {
+    fn default() -> Self {
+        Self {
+            plot_filter: PlotViewFilter::default(),
+            gravity: true.into(),
+            boundary: WorldBoundary::default(),
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlotViewFilter {
+    pub acceleration: bool,
+    pub sigma_force: bool,
+    pub velocity: bool,
+    pub trace: bool,
+    pub text: bool,
+    pub stamp: bool,
+}
+
+impl Default for PlotViewFilter {
+    fn default() -> Self {
+        Self {
+            acceleration: true,
+            sigma_force: false,
+            velocity: true,
+            trace: true,
+            text: false,
+            stamp: true,
+        }
+    }
+}
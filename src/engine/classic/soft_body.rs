@@ -0,0 +1,269 @@
+use crate::engine::classic::object::builder::CSimObjectBuilder;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::classic::object::state::{CSObjectState, ForceIndex};
+use crate::engine::classic::CSimObject;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+/// 두 질점을 잇는 감쇠 스프링.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spring {
+    pub a: usize,
+    pub b: usize,
+    pub rest_length: f64,
+    pub stiffness: f64,
+    pub damping: f64,
+}
+
+impl Spring {
+    // a가 받는 힘. b는 반대 방향의 힘을 받는다.
+    fn force(&self, a: &CSObjectState, b: &CSObjectState) -> NVec2 {
+        let delta = b.position - a.position;
+        let length = delta.norm();
+
+        if length == 0.0 {
+            return NVec2::zeros();
+        }
+
+        let direction = delta / length;
+        let stretch = length - self.rest_length;
+        let closing_speed = (b.velocity - a.velocity).dot(&direction);
+
+        // F = k(|d| - L0)d̂ + c((v_b - v_a)·d̂)d̂
+        direction * (self.stiffness * stretch + self.damping * closing_speed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoftBodyKind {
+    /// 격자 + 대각선 스프링. 젤리처럼 모양을 유지한다.
+    Grid { columns: usize, rows: usize },
+    /// 원형으로 놓인 질점과 중심을 잇는 바퀴살 스프링.
+    Ring { nodes: usize },
+    /// 대각선 스프링이 없는 격자. 윗줄이 고정된다.
+    Cloth { columns: usize, rows: usize },
+}
+
+impl Default for SoftBodyKind {
+    fn default() -> Self {
+        Self::Grid {
+            columns: 4,
+            rows: 4,
+        }
+    }
+}
+
+impl SoftBodyKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Grid { .. } => "Grid",
+            Self::Ring { .. } => "Ring",
+            Self::Cloth { .. } => "Cloth",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoftBodyParams {
+    pub kind: SoftBodyKind,
+    pub size: f64,
+    pub node_mass: f64,
+    pub node_radius: f64,
+    pub stiffness: f64,
+    pub damping: f64,
+}
+
+impl Default for SoftBodyParams {
+    fn default() -> Self {
+        Self {
+            kind: SoftBodyKind::default(),
+            size: 30.0,
+            node_mass: 1.0,
+            node_radius: 1.5,
+            stiffness: 200.0,
+            damping: 2.0,
+        }
+    }
+}
+
+/// 스프링으로 연결된 여러 질점을 한 물체처럼 다룬다.
+/// 질점은 `ClassicSimulation::objects` 안의 `CSimObject`이고 여기에는 그 인덱스만 저장한다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SoftBody {
+    pub name: String,
+    pub nodes: Vec<usize>,
+    pub springs: Vec<Spring>,
+    /// 외곽선을 이루는 질점 인덱스 (순서대로)
+    pub outline: Vec<usize>,
+    /// 제자리에 고정된 질점과 그 위치
+    pub anchors: Vec<(usize, NVec2)>,
+}
+
+impl SoftBody {
+    /// 질점 인덱스를 `map`으로 바꾼다. 없어진 질점에 걸린 스프링도 뺀다.
+    pub fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        self.nodes = self.nodes.iter().filter_map(|node| map(*node)).collect();
+        self.outline = self.outline.iter().filter_map(|node| map(*node)).collect();
+        self.anchors = self
+            .anchors
+            .iter()
+            .filter_map(|(node, position)| Some((map(*node)?, *position)))
+            .collect();
+        self.springs = self
+            .springs
+            .iter()
+            .filter_map(|spring| {
+                Some(Spring {
+                    a: map(spring.a)?,
+                    b: map(spring.b)?,
+                    ..*spring
+                })
+            })
+            .collect();
+    }
+
+    /// 질점 오브젝트를 만들고, `first_index`부터 오브젝트 목록에 추가된다고 보고 몸체를 만든다.
+    pub fn build(
+        name: impl Into<String>,
+        params: SoftBodyParams,
+        center: NVec2,
+        first_index: usize,
+        init_timestep: usize,
+    ) -> (Self, Vec<CSimObject>) {
+        let mut positions = vec![];
+        let mut links = vec![];
+        let mut outline = vec![];
+        let mut pinned = vec![];
+
+        match params.kind {
+            SoftBodyKind::Grid { columns, rows } | SoftBodyKind::Cloth { columns, rows } => {
+                let is_cloth = matches!(params.kind, SoftBodyKind::Cloth { .. });
+                let spacing = params.size / (columns.max(rows) - 1) as f64;
+                let origin =
+                    center - NVec2::new((columns - 1) as f64, (rows - 1) as f64) * spacing / 2.0;
+                let index = |column: usize, row: usize| row * columns + column;
+
+                for row in 0..rows {
+                    for column in 0..columns {
+                        positions.push(origin + NVec2::new(column as f64, row as f64) * spacing);
+                    }
+                }
+
+                for row in 0..rows {
+                    for column in 0..columns {
+                        if column + 1 < columns {
+                            links.push((index(column, row), index(column + 1, row)));
+                        }
+                        if row + 1 < rows {
+                            links.push((index(column, row), index(column, row + 1)));
+                        }
+                        if !is_cloth && column + 1 < columns && row + 1 < rows {
+                            links.push((index(column, row), index(column + 1, row + 1)));
+                            links.push((index(column + 1, row), index(column, row + 1)));
+                        }
+                    }
+                }
+
+                outline.extend((0..columns).map(|column| index(column, 0)));
+                outline.extend((1..rows).map(|row| index(columns - 1, row)));
+                outline.extend((0..columns - 1).rev().map(|column| index(column, rows - 1)));
+                outline.extend((1..rows - 1).rev().map(|row| index(0, row)));
+
+                if is_cloth {
+                    pinned.extend((0..columns).map(|column| index(column, rows - 1)));
+                }
+            }
+            SoftBodyKind::Ring { nodes } => {
+                let radius = params.size / 2.0;
+
+                for i in 0..nodes {
+                    let angle = TAU * i as f64 / nodes as f64;
+                    positions.push(center + NVec2::new(angle.cos(), angle.sin()) * radius);
+
+                    links.push((i, (i + 1) % nodes));
+                    links.push((i, (i + 2) % nodes));
+                    links.push((i, nodes));
+                }
+
+                positions.push(center);
+                outline.extend(0..nodes);
+            }
+        }
+
+        let objects = positions
+            .iter()
+            .map(|position| {
+                CSimObjectBuilder::new(CSObjectState {
+                    position: *position,
+                    mass: params.node_mass,
+                    ..CSObjectState::default()
+                })
+                .shape(ObjectShape::circle(params.node_radius))
+                .at(init_timestep)
+                .build()
+            })
+            .collect::<Vec<_>>();
+
+        let springs = links
+            .into_iter()
+            .map(|(a, b)| Spring {
+                a: first_index + a,
+                b: first_index + b,
+                rest_length: (positions[b] - positions[a]).norm(),
+                stiffness: params.stiffness,
+                damping: params.damping,
+            })
+            .collect();
+
+        let body = Self {
+            name: name.into(),
+            nodes: (first_index..first_index + positions.len()).collect(),
+            springs,
+            outline: outline.into_iter().map(|i| first_index + i).collect(),
+            anchors: pinned
+                .into_iter()
+                .map(|i| (first_index + i, positions[i]))
+                .collect(),
+        };
+
+        (body, objects)
+    }
+
+    /// 스프링 가속도를 질점의 `ForceIndex::Spring` 자리에 기록한다.
+    pub fn apply_springs(&self, objects: &mut [CSimObject]) {
+        for node in &self.nodes {
+            objects[*node].live_state_mut().acc_list[ForceIndex::Spring as usize] = NVec2::zeros();
+        }
+
+        for spring in &self.springs {
+            let a = objects[spring.a].current_state();
+            let b = objects[spring.b].current_state();
+            let force = spring.force(&a, &b);
+
+            objects[spring.a].live_state_mut().acc_list[ForceIndex::Spring as usize] +=
+                force / a.mass;
+            objects[spring.b].live_state_mut().acc_list[ForceIndex::Spring as usize] -=
+                force / b.mass;
+        }
+    }
+
+    pub fn is_visible(&self, objects: &[CSimObject]) -> bool {
+        self.nodes.iter().all(|node| !*objects[*node].hide())
+    }
+
+    pub fn outline_points(&self, objects: &[CSimObject]) -> Vec<NVec2> {
+        self.outline
+            .iter()
+            .map(|node| objects[*node].current_state().position)
+            .collect()
+    }
+
+    pub fn center(&self, objects: &[CSimObject]) -> NVec2 {
+        self.nodes
+            .iter()
+            .map(|node| objects[*node].current_state().position)
+            .sum::<NVec2>()
+            / self.nodes.len().max(1) as f64
+    }
+}
@@ -0,0 +1,400 @@
+use nalgebra::Vector2;
+
+use crate::engine::classic::boundary::WorldBoundary;
+use crate::engine::classic::chain::{ChainDriver, OscillatorChain};
+use crate::engine::classic::object::builder::CSimObjectBuilder;
+use crate::engine::classic::object::perpendicular;
+use crate::engine::classic::object::shape::ObjectShape;
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::classic::scenario::Scenario;
+use crate::engine::classic::sim_state::{CSimSettings, PlotViewFilter};
+use crate::engine::classic::soft_body::{SoftBody, SoftBodyKind, SoftBodyParams};
+use crate::engine::classic::template::init::{
+    BasicSimInitObjData, IdealGasSimInit, OscillatorChainSimInit, ThetaThrowSimInit,
+};
+use crate::engine::classic::template::stamp::{Axis, CSObjectStamp, StampCondition};
+use crate::engine::classic::zone::{FieldZone, ZoneEffect};
+use crate::engine::classic::CSimObject;
+use crate::engine::rng::XorShiftRng;
+use crate::engine::NVec2;
+
+pub mod init;
+pub mod stamp;
+
+#[derive(Clone, Debug)]
+pub enum CSTemplate {
+    ThetaThrowSimInit(ThetaThrowSimInit),
+    DefaultSim,
+    CircleSim,
+    ZoneSim,
+    PeriodicSim,
+    IdealGasSimInit(IdealGasSimInit),
+    SoftBodySim,
+    OscillatorChainSimInit(OscillatorChainSimInit),
+    /// 파일에서 읽은 시나리오
+    Scenario(Box<Scenario>),
+}
+
+impl CSTemplate {
+    pub fn get_name(&self) -> String {
+        if let CSTemplate::Scenario(scenario) = self {
+            return scenario.name.clone();
+        }
+
+        format!("{:?}", self).split('(').collect::<Vec<&str>>()[0].to_string()
+    }
+
+    pub fn get_description(&self) -> &str {
+        match self {
+            CSTemplate::Scenario(scenario) => &scenario.description,
+            _ => "",
+        }
+    }
+
+    pub fn get_preset_with_ui(self) -> CSPreset {
+        match self {
+            CSTemplate::DefaultSim => default_sim(),
+            CSTemplate::ThetaThrowSimInit(init) => theta_throw(init),
+            CSTemplate::CircleSim => circle_sim(),
+            CSTemplate::ZoneSim => zone_sim(),
+            CSTemplate::PeriodicSim => periodic_sim(),
+            CSTemplate::IdealGasSimInit(init) => ideal_gas(init),
+            CSTemplate::SoftBodySim => soft_body_sim(),
+            CSTemplate::OscillatorChainSimInit(init) => oscillator_chain(init),
+            CSTemplate::Scenario(scenario) => scenario.into_preset(),
+        }
+    }
+}
+
+pub fn get_cs_sim_list() -> [CSTemplate; 8] {
+    [
+        CSTemplate::ThetaThrowSimInit(ThetaThrowSimInit {
+            objects: vec![
+                BasicSimInitObjData {
+                    mass: 5.0,
+                    theta: 30.0,
+                    start_velocity_mul: 20.0,
+                },
+                BasicSimInitObjData {
+                    mass: 5.0,
+                    theta: 60.0,
+                    start_velocity_mul: 20.0,
+                },
+                BasicSimInitObjData {
+                    mass: 5.0,
+                    theta: 15.0,
+                    start_velocity_mul: 20.0,
+                },
+                BasicSimInitObjData {
+                    mass: 5.0,
+                    theta: 75.0,
+                    start_velocity_mul: 20.0,
+                },
+            ],
+        }),
+        CSTemplate::DefaultSim,
+        CSTemplate::CircleSim,
+        CSTemplate::ZoneSim,
+        CSTemplate::PeriodicSim,
+        CSTemplate::IdealGasSimInit(IdealGasSimInit::default()),
+        CSTemplate::SoftBodySim,
+        CSTemplate::OscillatorChainSimInit(OscillatorChainSimInit::default()),
+    ]
+}
+
+#[derive(Default)]
+pub struct CSPreset {
+    pub simulation_objects: Vec<CSimObject>,
+    pub stamps: Vec<CSObjectStamp>,
+    pub zones: Vec<FieldZone>,
+    pub soft_bodies: Vec<SoftBody>,
+    pub chains: Vec<OscillatorChain>,
+    pub settings: CSimSettings,
+}
+
+fn theta_throw(data: ThetaThrowSimInit) -> CSPreset {
+    // value have any item
+    // let force = value.theta * 5.0;
+    // force_list.push(force) // how to?
+    let objects = data
+        .objects
+        .iter()
+        .map(|obj| {
+            let velocity = obj.theta.to_radians().sin_cos();
+            let velocity = Vector2::new(velocity.0, velocity.1) * obj.start_velocity_mul;
+
+            CSimObjectBuilder::new(CSObjectState {
+                velocity,
+                mass: obj.mass,
+                ..CSObjectState::default()
+            })
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    let stamp = CSObjectStamp::new(
+        "WHEN pos < 0.1",
+        StampCondition::PositionBelow {
+            axis: Axis::Y,
+            value: 0.0,
+        },
+        0..=0,
+    );
+
+    CSPreset {
+        simulation_objects: objects,
+        stamps: vec![stamp],
+        ..CSPreset::default()
+    }
+}
+
+fn circle_sim() -> CSPreset {
+    let mass = 5.0;
+
+    let sim = [5.0]
+        .iter()
+        .map(|x| {
+            CSimObjectBuilder::new(CSObjectState {
+                velocity: NVec2::new(*x, *x),
+
+                mass,
+                position: NVec2::new(1.0, 0.0),
+
+                ..CSObjectState::default()
+            })
+            .attached(perpendicular)
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    CSPreset {
+        simulation_objects: sim,
+
+        ..CSPreset::default()
+    }
+}
+
+fn zone_sim() -> CSPreset {
+    let objects = [0.0, 10.0, 20.0, 30.0]
+        .iter()
+        .map(|speed| {
+            CSimObjectBuilder::new(CSObjectState {
+                position: NVec2::new(-90.0, 60.0),
+                velocity: NVec2::new(*speed, 0.0),
+                mass: 5.0,
+                ..CSObjectState::default()
+            })
+            .shape(ObjectShape::circle(3.0))
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    let zones = vec![
+        FieldZone::rect(
+            "Low Gravity",
+            NVec2::new(-100.0, 0.0),
+            NVec2::new(0.0, 80.0),
+            ZoneEffect::Gravity(NVec2::new(0.0, -1.6)),
+        ),
+        FieldZone::rect(
+            "Conveyor",
+            NVec2::new(0.0, -20.0),
+            NVec2::new(100.0, 0.0),
+            ZoneEffect::Conveyor {
+                velocity: NVec2::new(-20.0, 0.0),
+                grip: 3.0,
+            },
+        ),
+        FieldZone::rect(
+            "Water",
+            NVec2::new(-100.0, -100.0),
+            NVec2::new(100.0, -20.0),
+            ZoneEffect::Drag(10.0),
+        ),
+    ];
+
+    CSPreset {
+        simulation_objects: objects,
+        zones,
+        ..CSPreset::default()
+    }
+}
+
+fn periodic_sim() -> CSPreset {
+    let objects = (0..12)
+        .map(|i| {
+            let angle = (i as f64) * 2.4;
+            let position = NVec2::new((i % 4) as f64 * 25.0 - 37.5, (i / 4) as f64 * 25.0 - 25.0);
+
+            CSimObjectBuilder::new(CSObjectState {
+                position,
+                velocity: NVec2::new(angle.cos(), angle.sin()) * 30.0,
+                mass: 5.0,
+                ..CSObjectState::default()
+            })
+            .shape(ObjectShape::circle(3.0))
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    let mut settings = CSimSettings {
+        gravity: false.into(),
+        boundary: WorldBoundary::periodic(NVec2::new(-50.0, -50.0), NVec2::new(50.0, 50.0)),
+        ..CSimSettings::default()
+    };
+    settings.gravity.changed();
+
+    CSPreset {
+        simulation_objects: objects,
+        settings,
+        ..CSPreset::default()
+    }
+}
+
+fn ideal_gas(data: IdealGasSimInit) -> CSPreset {
+    const HALF_SIZE: f64 = 50.0;
+
+    let mut rng = XorShiftRng::new(data.seed);
+
+    // 겹치지 않도록 격자 위에 놓고, 속력은 같게 방향만 무작위로 준다.
+    let columns = (data.count as f64).sqrt().ceil() as usize;
+    let spacing = (2.0 * HALF_SIZE) / columns as f64;
+
+    let objects = (0..data.count)
+        .map(|i| {
+            let position = NVec2::new(
+                -HALF_SIZE + spacing * ((i % columns) as f64 + 0.5),
+                -HALF_SIZE + spacing * ((i / columns) as f64 + 0.5),
+            );
+
+            let angle = rng.range(0.0, std::f64::consts::TAU);
+
+            CSimObjectBuilder::new(CSObjectState {
+                position,
+                velocity: NVec2::new(angle.cos(), angle.sin()) * data.speed,
+                mass: data.mass,
+                ..CSObjectState::default()
+            })
+            .shape(ObjectShape::circle(data.radius.min(spacing / 2.0)))
+            .build()
+        })
+        .collect::<Vec<_>>();
+
+    let mut settings = CSimSettings {
+        gravity: false.into(),
+        boundary: WorldBoundary::walled(
+            NVec2::new(-HALF_SIZE, -HALF_SIZE),
+            NVec2::new(HALF_SIZE, HALF_SIZE),
+        ),
+        plot_filter: PlotViewFilter {
+            acceleration: false,
+            velocity: false,
+            trace: false,
+            ..PlotViewFilter::default()
+        },
+        ..CSimSettings::default()
+    };
+    settings.gravity.changed();
+
+    CSPreset {
+        simulation_objects: objects,
+        settings,
+        ..CSPreset::default()
+    }
+}
+
+fn soft_body_sim() -> CSPreset {
+    let bodies = [
+        ("Jelly", NVec2::new(-60.0, 0.0), SoftBodyParams::default()),
+        (
+            "Ball",
+            NVec2::new(0.0, 20.0),
+            SoftBodyParams {
+                kind: SoftBodyKind::Ring { nodes: 16 },
+                ..SoftBodyParams::default()
+            },
+        ),
+        (
+            "Cloth",
+            NVec2::new(60.0, 50.0),
+            SoftBodyParams {
+                kind: SoftBodyKind::Cloth {
+                    columns: 8,
+                    rows: 6,
+                },
+                size: 50.0,
+                stiffness: 400.0,
+                ..SoftBodyParams::default()
+            },
+        ),
+    ];
+
+    let mut objects = vec![];
+    let mut soft_bodies = vec![];
+
+    for (name, center, params) in bodies {
+        let (body, nodes) = SoftBody::build(name, params, center, objects.len(), 0);
+        objects.extend(nodes);
+        soft_bodies.push(body);
+    }
+
+    CSPreset {
+        simulation_objects: objects,
+        soft_bodies,
+        settings: CSimSettings {
+            boundary: WorldBoundary::walled(NVec2::new(-100.0, -100.0), NVec2::new(100.0, 100.0)),
+            plot_filter: PlotViewFilter {
+                acceleration: false,
+                velocity: false,
+                trace: false,
+                ..PlotViewFilter::default()
+            },
+            ..CSimSettings::default()
+        },
+        ..CSPreset::default()
+    }
+}
+
+fn oscillator_chain(data: OscillatorChainSimInit) -> CSPreset {
+    const LENGTH: f64 = 150.0;
+
+    let spacing = LENGTH / (data.count + 1) as f64;
+    let driver = data.driven.then_some(ChainDriver {
+        frequency: data.frequency,
+        amplitude: data.amplitude,
+    });
+
+    let (chain, objects) = OscillatorChain::build(
+        data.count,
+        spacing,
+        NVec2::new(-LENGTH / 2.0, 0.0),
+        data.stiffness,
+        data.mass,
+        (data.left, data.right),
+        driver,
+        0,
+    );
+
+    let mut settings = CSimSettings {
+        gravity: false.into(),
+        plot_filter: PlotViewFilter {
+            acceleration: false,
+            velocity: false,
+            trace: false,
+            ..PlotViewFilter::default()
+        },
+        ..CSimSettings::default()
+    };
+    settings.gravity.changed();
+
+    CSPreset {
+        simulation_objects: objects,
+        chains: vec![chain],
+        settings,
+        ..CSPreset::default()
+    }
+}
+
+fn default_sim() -> CSPreset {
+    CSPreset::default()
+}
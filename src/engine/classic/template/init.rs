@@ -0,0 +1,61 @@
+use crate::engine::classic::chain::ChainEnd;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BasicSimInitObjData {
+    pub theta: f64,
+    pub start_velocity_mul: f64,
+    pub mass: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ThetaThrowSimInit {
+    pub objects: Vec<BasicSimInitObjData>,
+}
+
+#[derive(Clone, Debug)]
+pub struct IdealGasSimInit {
+    pub count: usize,
+    pub speed: f64,
+    pub radius: f64,
+    pub mass: f64,
+    pub seed: u64,
+}
+
+impl Default for IdealGasSimInit {
+    fn default() -> Self {
+        Self {
+            count: 200,
+            speed: 30.0,
+            radius: 1.0,
+            mass: 1.0,
+            seed: 8965,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OscillatorChainSimInit {
+    pub count: usize,
+    pub stiffness: f64,
+    pub mass: f64,
+    pub left: ChainEnd,
+    pub right: ChainEnd,
+    pub driven: bool,
+    pub frequency: f64,
+    pub amplitude: f64,
+}
+
+impl Default for OscillatorChainSimInit {
+    fn default() -> Self {
+        Self {
+            count: 20,
+            stiffness: 100.0,
+            mass: 1.0,
+            left: ChainEnd::Fixed,
+            right: ChainEnd::Fixed,
+            driven: true,
+            frequency: 0.5,
+            amplitude: 2.0,
+        }
+    }
+}
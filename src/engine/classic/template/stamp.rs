@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+use crate::engine::classic::object::state::CSObjectState;
+use crate::engine::NVec2;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CSObjectStampResult {
+    pub label: String,
+    pub name: String,
+    pub time: f64,
+    pub state: CSObjectState,
+}
+
+impl Default for CSObjectStampResult {
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            name: String::new(),
+            time: f64::NAN,
+            state: CSObjectState::default(),
+        }
+    }
+}
+
+impl CSObjectStampResult {
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn state(mut self, state: CSObjectState) -> Self {
+        self.state = state;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+}
+
+/// 위치나 속도의 성분
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// 스탬프가 찍히는 조건. 파일에 적을 수 있도록 값으로 나타낸다.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StampCondition {
+    /// 위치 성분이 값보다 작아졌다.
+    PositionBelow { axis: Axis, value: f64 },
+    /// 위치 성분이 값보다 커졌다.
+    PositionAbove { axis: Axis, value: f64 },
+    /// 속도 성분이 값보다 작아졌다. 0 이면 최고점을 잡는다.
+    VelocityBelow { axis: Axis, value: f64 },
+    /// 속도 성분이 값보다 커졌다.
+    VelocityAbove { axis: Axis, value: f64 },
+    /// 시간이 지났다.
+    TimeAfter(f64),
+}
+
+impl StampCondition {
+    pub fn is_met(&self, state: &CSObjectState, time: f64) -> bool {
+        let component = |vector: NVec2, axis: &Axis| match axis {
+            Axis::X => vector.x,
+            Axis::Y => vector.y,
+        };
+
+        match self {
+            Self::PositionBelow { axis, value } => component(state.position, axis) < *value,
+            Self::PositionAbove { axis, value } => component(state.position, axis) > *value,
+            Self::VelocityBelow { axis, value } => component(state.velocity, axis) < *value,
+            Self::VelocityAbove { axis, value } => component(state.velocity, axis) > *value,
+            Self::TimeAfter(after) => time >= *after,
+        }
+    }
+}
+
+/// `objects` 범위의 물체 중 하나가 조건을 처음 만족한 순간을 기록한다.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CSObjectStamp {
+    pub label: String,
+    pub condition: StampCondition,
+    #[serde(rename = "objects")]
+    stamp_range: RangeInclusive<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<CSObjectStampResult>,
+}
+
+impl CSObjectStamp {
+    pub fn new(
+        label: impl Into<String>,
+        condition: StampCondition,
+        range: impl Into<RangeInclusive<usize>>,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            condition,
+            stamp_range: range.into(),
+            result: None,
+        }
+    }
+
+    pub fn covers(&self, obj_index: usize) -> bool {
+        self.stamp_range.contains(&obj_index)
+    }
+
+    pub fn range(&self) -> &RangeInclusive<usize> {
+        &self.stamp_range
+    }
+
+    /// 오브젝트가 빠지거나 다시 들어와서 인덱스가 바뀌었을 때 쓴다.
+    pub fn set_range(&mut self, range: RangeInclusive<usize>) {
+        self.stamp_range = range;
+    }
+
+    /// 이미 찍혔다면 그 결과
+    pub fn result(&self) -> Option<&CSObjectStampResult> {
+        self.result.as_ref()
+    }
+
+    /// `time` 뒤에 찍힌 결과를 지운다. 타임라인을 다시 계산할 때 쓴다.
+    pub fn forget_after(&mut self, time: f64) {
+        if self
+            .result
+            .as_ref()
+            .map_or(false, |result| result.time > time)
+        {
+            self.result = None;
+        }
+    }
+
+    /// 아직 찍히지 않았다면 `obj_state`로 조건을 확인한다.
+    pub fn get_data(
+        &mut self,
+        obj_state: &CSObjectState,
+        obj_index: usize,
+        time: f64,
+    ) -> Option<CSObjectStampResult> {
+        if !self.stamp_range.contains(&obj_index) {
+            return None;
+        }
+
+        if self.result.is_none() && self.condition.is_met(obj_state, time) {
+            self.result = Some(
+                CSObjectStampResult::default()
+                    .label(self.label.clone())
+                    .state(obj_state.clone())
+                    .time(time),
+            );
+        }
+
+        self.result.clone()
+    }
+}
@@ -0,0 +1,852 @@
+//! 기록된 스텝을 적은 메모리로 남긴다.
+//! 스텝마다 위치와 속도, 이벤트만 남기고 온전한 상태는 키프레임에서만 남긴다.
+//! 지난 스텝의 나머지 값은 그 앞의 키프레임에서 다시 계산한다.
+
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::classic::boundary::WorldBoundary;
+use crate::engine::classic::chain::OscillatorChain;
+use crate::engine::classic::event::{SimulationEvent, SimulationEvents};
+use crate::engine::classic::object::state::{CSObjectState, ForceIndex};
+use crate::engine::classic::object::ObjectKeyframe;
+use crate::engine::classic::soft_body::SoftBody;
+use crate::engine::classic::zone::FieldZone;
+use crate::engine::classic::{ClassicWorld, ZERO_FORCE};
+use crate::engine::{NVec2, SIMULATION_TICK};
+
+/// 장면이 그대로일 때 키프레임을 남기는 간격
+pub const KEYFRAME_INTERVAL: usize = 60;
+
+/// 다시 계산한 구간에서 들고 있는 스텝 수
+const REPLAY_WINDOW: usize = 2 * KEYFRAME_INTERVAL;
+
+/// 한꺼번에 들고 있는 다시 계산한 구간의 수
+const MAX_REPLAYS: usize = 2;
+
+pub const MEGABYTE: usize = 1024 * 1024;
+
+/// 키프레임에서 다시 계산할 때 쓰는 장면. 장면이 바뀔 때만 새로 남긴다.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Scene {
+    global_acc_list: Vec<NVec2>,
+    zones: Vec<FieldZone>,
+    soft_bodies: Vec<SoftBody>,
+    chains: Vec<OscillatorChain>,
+    boundary: WorldBoundary,
+}
+
+impl Scene {
+    fn of(world: &ClassicWorld, boundary: &WorldBoundary) -> Self {
+        Self {
+            global_acc_list: world.global_acc_list.clone(),
+            zones: world.zones.clone(),
+            soft_bodies: world.soft_bodies.clone(),
+            chains: world.chains.clone(),
+            boundary: *boundary,
+        }
+    }
+
+    fn matches(&self, world: &ClassicWorld, boundary: &WorldBoundary) -> bool {
+        self.global_acc_list == world.global_acc_list
+            && self.zones == world.zones
+            && self.soft_bodies == world.soft_bodies
+            && self.chains == world.chains
+            && self.boundary == *boundary
+    }
+
+    /// 질점 인덱스를 `map`으로 바꾼다. 질점이 모두 빠진 소프트 바디와 체인은 뺀다.
+    fn remap(&mut self, map: impl Fn(usize) -> Option<usize>) {
+        for body in self.soft_bodies.iter_mut() {
+            body.remap(&map);
+        }
+        self.soft_bodies.retain(|body| !body.nodes.is_empty());
+
+        for chain in self.chains.iter_mut() {
+            chain.remap(&map);
+        }
+        self.chains.retain(|chain| !chain.nodes.is_empty());
+    }
+
+    fn bytes(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .soft_bodies
+                .iter()
+                .map(|body| {
+                    body.springs.len() * size_of::<crate::engine::classic::soft_body::Spring>()
+                        + (body.nodes.len() + body.outline.len()) * size_of::<usize>()
+                })
+                .sum::<usize>()
+            + self
+                .chains
+                .iter()
+                .map(|chain| chain.nodes.len() * (size_of::<usize>() + size_of::<NVec2>()))
+                .sum::<usize>()
+            + self
+                .zones
+                .iter()
+                .map(|zone| zone.points.len() * size_of::<NVec2>())
+                .sum::<usize>()
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub step: usize,
+    /// `Timeline::scenes`의 인덱스
+    scene: usize,
+    /// 간격을 채우려고 남긴 키프레임. 메모리가 모자라면 솎아 낸다.
+    periodic: bool,
+}
+
+/// 드래그로 준 사용자 가속도. 다시 계산할 때 스텝마다 다시 준다.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct UserForce {
+    pub step: usize,
+    pub index: usize,
+    pub force: NVec2,
+}
+
+/// 지금 타임라인이 쓰는 메모리의 바이트 수
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimelineUsage {
+    /// 스텝마다 남긴 위치, 속도와 벽이 받은 충격량
+    pub records: usize,
+    /// 키프레임과 장면, 사용자 가속도
+    pub keyframes: usize,
+    pub events: usize,
+    /// 다시 계산해 둔 구간
+    pub replays: usize,
+}
+
+impl TimelineUsage {
+    pub fn total(&self) -> usize {
+        self.records + self.keyframes + self.events + self.replays
+    }
+}
+
+fn pending() -> bool {
+    true
+}
+
+/// 세계의 키프레임과 다시 계산에 필요한 기록
+#[derive(Default, Serialize, Deserialize)]
+pub struct Timeline {
+    keyframes: Vec<Keyframe>,
+    scenes: Vec<Scene>,
+    /// 스텝 순서대로 있다.
+    user_forces: Vec<UserForce>,
+    /// 이 스텝 앞의 위치, 속도와 이벤트는 메모리를 아끼려고 버렸다.
+    detail_from: usize,
+    /// 다음 스텝에서 키프레임을 남긴다. 읽어 온 세계도 키프레임부터 남긴다.
+    #[serde(skip, default = "pending")]
+    pending: bool,
+    /// 보고 있는 스텝
+    #[serde(skip)]
+    pub(super) viewed: usize,
+    #[serde(skip)]
+    replays: Vec<Replay>,
+    /// 세계 밖에서 같은 예산을 쓰는 바이트 수. 스텝마다 예산을 맞출 때 빼고 맞춘다.
+    #[serde(skip)]
+    reserved: usize,
+}
+
+// 다시 계산해 둔 구간은 세계를 통째로 들고 있으므로 복사하지 않는다. 필요하면 다시 계산한다.
+impl Clone for Timeline {
+    fn clone(&self) -> Self {
+        Self {
+            keyframes: self.keyframes.clone(),
+            scenes: self.scenes.clone(),
+            user_forces: self.user_forces.clone(),
+            detail_from: self.detail_from,
+            pending: self.pending,
+            viewed: self.viewed,
+            replays: vec![],
+            reserved: self.reserved,
+        }
+    }
+}
+
+impl Timeline {
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn detail_from(&self) -> usize {
+        self.detail_from
+    }
+
+    /// `step` 스텝이 속한 구간, 즉 그 스텝 앞의 마지막 키프레임의 인덱스
+    fn segment_of(&self, step: usize) -> Option<usize> {
+        self.keyframes
+            .partition_point(|keyframe| keyframe.step <= step)
+            .checked_sub(1)
+    }
+
+    fn user_forces_at(&self, step: usize) -> &[UserForce] {
+        let start = self.user_forces.partition_point(|force| force.step < step);
+        let end = self.user_forces.partition_point(|force| force.step <= step);
+
+        &self.user_forces[start..end]
+    }
+
+    pub(super) fn user_forces_since(&self, step: usize) -> Vec<UserForce> {
+        let start = self.user_forces.partition_point(|force| force.step < step);
+
+        self.user_forces[start..].to_vec()
+    }
+
+    /// 장면이 바뀌었다. 다음 스텝에서 키프레임을 남기고 다시 계산해 둔 구간은 버린다.
+    pub(super) fn invalidate(&mut self) {
+        self.pending = true;
+        self.replays.clear();
+    }
+
+    /// `step` 스텝부터의 기록을 지운다. 다음 스텝에서 키프레임을 남긴다.
+    pub(super) fn truncate(&mut self, step: usize) {
+        self.keyframes.retain(|keyframe| keyframe.step < step);
+        self.scenes.truncate(
+            self.keyframes
+                .last()
+                .map_or(0, |keyframe| keyframe.scene + 1),
+        );
+        self.user_forces.retain(|force| force.step < step);
+        self.detail_from = self.detail_from.min(step);
+        self.pending = true;
+        self.replays.clear();
+    }
+}
+
+/// 오브젝트를 빼면서 바뀐 키프레임 기록. 되돌릴 때 다시 넣는다.
+#[derive(Clone, Default)]
+pub struct RemovedTimeline {
+    scenes: Vec<Scene>,
+    user_forces: Vec<UserForce>,
+}
+
+// 한 구간을 키프레임에서 다시 계산하는 세계. 최근 스텝의 상태와 이벤트를 들고 있는다.
+struct Replay {
+    /// 다시 계산을 시작한 키프레임의 스텝
+    keyframe: usize,
+    /// 구간이 끝나는 스텝
+    end: usize,
+    world: ClassicWorld,
+    boundary: WorldBoundary,
+    /// 다시 계산하는 세계의 오브젝트가 원래 세계에서 가진 인덱스
+    present: Vec<usize>,
+    /// 다시 계산하는 세계가 있는 스텝
+    step: usize,
+    /// `states`와 `events`의 첫 스텝
+    from: usize,
+    /// 원래 세계의 인덱스로 찾는 스텝마다의 상태
+    states: VecDeque<Vec<Option<CSObjectState>>>,
+    events: VecDeque<SimulationEvents>,
+}
+
+impl Replay {
+    fn new(world: &ClassicWorld, segment: usize) -> Self {
+        let keyframe = world.timeline.keyframes[segment];
+        let scene = &world.timeline.scenes[keyframe.scene];
+        let end = world
+            .timeline
+            .keyframes
+            .get(segment + 1)
+            .map_or(world.recorded_steps(), |next| next.step);
+
+        let mut present = vec![];
+        let mut objects = vec![];
+        for (index, obj) in world.objects.iter().enumerate() {
+            if let Some(state) = obj.keyframe_at(keyframe.step) {
+                present.push(index);
+                objects.push(obj.replica(keyframe.step, state.clone()));
+            }
+        }
+
+        let mut scene = scene.clone();
+        scene.remap(|index| present.binary_search(&index).ok());
+
+        let mut replayed = ClassicWorld::from(objects)
+            .with_zones(scene.zones)
+            .with_soft_bodies(scene.soft_bodies)
+            .with_chains(scene.chains);
+        replayed.global_acc_list = scene.global_acc_list;
+
+        Self {
+            keyframe: keyframe.step,
+            end,
+            world: replayed,
+            boundary: scene.boundary,
+            present,
+            step: keyframe.step,
+            from: keyframe.step,
+            states: VecDeque::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    fn contains(&self, step: usize) -> bool {
+        (self.from..self.step).contains(&step)
+    }
+
+    /// 한 스텝 나아간다. 구간이 끝났으면 false.
+    fn advance(&mut self, timeline: &Timeline, count: usize, window: usize) -> bool {
+        if self.step >= self.end {
+            return false;
+        }
+
+        for obj in self.world.objects.iter_mut() {
+            obj.set_user_force(ZERO_FORCE);
+        }
+        for force in timeline.user_forces_at(self.step) {
+            if let Ok(index) = self.present.binary_search(&force.index) {
+                self.world.objects[index].set_user_force(force.force);
+            }
+        }
+
+        let mut states = vec![None; count];
+        for (obj, index) in self.world.objects.iter().zip(self.present.iter()) {
+            if let Some(state) = states.get_mut(*index) {
+                *state = Some(obj.current_state());
+            }
+        }
+
+        for obj in self.world.objects.iter_mut() {
+            obj.save_state();
+        }
+
+        let time = SIMULATION_TICK * self.step as f64;
+        let (mut events, _) = self.world.advance(&self.boundary, time);
+        events.remap(|index| self.present.get(index).copied());
+
+        self.states.push_back(states);
+        self.events.push_back(events);
+        self.step += 1;
+
+        while self.states.len() > window {
+            self.states.pop_front();
+            self.events.pop_front();
+            self.from += 1;
+        }
+
+        true
+    }
+
+    fn bytes(&self) -> usize {
+        let states = self
+            .states
+            .iter()
+            .flatten()
+            .map(|state| state.as_ref().map_or(0, state_bytes) + size_of::<Option<()>>())
+            .sum::<usize>();
+
+        states + self.events.iter().map(events_bytes).sum::<usize>()
+    }
+}
+
+fn state_bytes(state: &CSObjectState) -> usize {
+    size_of::<CSObjectState>() + state.acc_list.len() * size_of::<NVec2>()
+}
+
+fn keyframe_bytes(keyframe: &ObjectKeyframe) -> usize {
+    size_of::<ObjectKeyframe>() + keyframe.state.acc_list.len() * size_of::<NVec2>()
+}
+
+fn events_bytes(events: &SimulationEvents) -> usize {
+    size_of::<SimulationEvents>() + events.iter().count() * size_of::<SimulationEvent>()
+}
+
+impl ClassicWorld {
+    pub fn timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    /// 한 스텝을 계산하기 전에 지금 스텝을 기록한다. 키프레임을 남겼으면 true.
+    pub(super) fn record(&mut self, boundary: &WorldBoundary) -> bool {
+        let step = self.recorded_steps();
+        let last = self.timeline.keyframes.last().copied();
+
+        let scene_changed = last.map_or(true, |keyframe| {
+            !self.timeline.scenes[keyframe.scene].matches(self, boundary)
+        });
+        let forced = self.timeline.pending
+            || scene_changed
+            || self
+                .objects
+                .iter()
+                .any(|obj| obj.is_edited() && !obj.is_ended());
+        let periodic = last.map_or(true, |keyframe| step >= keyframe.step + KEYFRAME_INTERVAL);
+
+        let keyed = forced || periodic;
+        if keyed {
+            if scene_changed {
+                let scene = Scene::of(self, boundary);
+                self.timeline.scenes.push(scene);
+            }
+
+            self.timeline.keyframes.push(Keyframe {
+                step,
+                scene: self.timeline.scenes.len() - 1,
+                periodic: !forced,
+            });
+
+            for obj in self.objects.iter_mut().filter(|obj| !obj.is_ended()) {
+                obj.save_keyframe();
+            }
+
+            self.timeline.pending = false;
+        }
+
+        for (index, obj) in self.objects.iter().enumerate() {
+            if obj.is_ended() {
+                continue;
+            }
+
+            let force = obj.live_state().acc_list[ForceIndex::UserInteraction as usize];
+
+            if force != ZERO_FORCE {
+                self.timeline
+                    .user_forces
+                    .push(UserForce { step, index, force });
+            }
+        }
+
+        keyed
+    }
+
+    /// `step` 스텝에서 오브젝트에 드래그로 준 사용자 가속도
+    pub fn user_force(&self, step: usize, index: usize) -> NVec2 {
+        self.timeline
+            .user_forces_at(step)
+            .iter()
+            .find(|force| force.index == index)
+            .map_or(ZERO_FORCE, |force| force.force)
+    }
+
+    /// 보고 있는 스텝
+    pub fn viewed_step(&self) -> usize {
+        self.timeline.viewed
+    }
+
+    /// `step` 스텝을 본다. 지난 스텝이면 앞의 키프레임에서 다시 계산한 상태에
+    /// 기록된 위치와 속도를 넣는다.
+    pub fn at_time_step(&mut self, step: usize) {
+        self.timeline.viewed = step;
+
+        for obj in self.objects.iter_mut() {
+            obj.at_timestep(step);
+        }
+
+        // 그 스텝으로 오게 한 이벤트가 버려졌으면 그것도 다시 계산해 둔다.
+        if let Some(before) = step.checked_sub(1) {
+            if before < self.timeline.detail_from {
+                self.replay_to(before);
+            }
+        }
+
+        let Some(replay) = self.replay_to(step) else {
+            return;
+        };
+
+        let states =
+            &self.timeline.replays[replay].states[step - self.timeline.replays[replay].from];
+        let forces = self.timeline.user_forces_at(step);
+
+        for (index, obj) in self.objects.iter_mut().enumerate() {
+            if *obj.hide() || step >= obj.last_timestep() {
+                continue;
+            }
+
+            let mut state = states
+                .get(index)
+                .cloned()
+                .flatten()
+                .unwrap_or_else(|| obj.current_state());
+
+            if let Some((position, velocity)) = obj.track().get(step) {
+                state.position = position;
+                state.velocity = velocity;
+            }
+            state.acc_list[ForceIndex::UserInteraction as usize] = forces
+                .iter()
+                .find(|force| force.index == index)
+                .map_or(ZERO_FORCE, |force| force.force);
+
+            obj.set_view(state);
+        }
+    }
+
+    /// `index` 스텝을 계산할 때 생긴 이벤트. 즉 `index + 1` 스텝에서 생긴 이벤트다.
+    /// 버린 이벤트는 다시 계산해 둔 것이 있을 때만 돌려준다.
+    pub fn events_at(&self, index: usize) -> Option<&SimulationEvents> {
+        if index >= self.timeline.detail_from {
+            return self.events.get(index);
+        }
+
+        self.timeline
+            .replays
+            .iter()
+            .find(|replay| replay.contains(index))
+            .map(|replay| &replay.events[index - replay.from])
+    }
+
+    // `step` 스텝까지 다시 계산한 구간의 인덱스. 마지막 스텝이면 다시 계산하지 않는다.
+    fn replay_to(&mut self, step: usize) -> Option<usize> {
+        if step >= self.recorded_steps() {
+            return None;
+        }
+
+        let segment = self.timeline.segment_of(step)?;
+        let keyframe = self.timeline.keyframes[segment].step;
+
+        let found = self.timeline.replays.iter().position(|replay| {
+            replay.keyframe == keyframe && replay.from <= step && step < replay.end
+        });
+
+        let index = match found {
+            Some(index) => index,
+            None => {
+                let replay = Replay::new(self, segment);
+                if self.timeline.replays.len() >= MAX_REPLAYS {
+                    self.timeline.replays.remove(0);
+                }
+                self.timeline.replays.push(replay);
+                self.timeline.replays.len() - 1
+            }
+        };
+
+        // 가장 최근에 쓴 구간이 맨 뒤에 오도록 꺼냈다가 다시 넣는다.
+        let count = self.objects.len();
+        let mut replay = self.timeline.replays.remove(index);
+        while !replay.contains(step) {
+            if !replay.advance(&self.timeline, count, REPLAY_WINDOW) {
+                return None;
+            }
+        }
+        self.timeline.replays.push(replay);
+
+        Some(self.timeline.replays.len() - 1)
+    }
+
+    /// 처음부터 마지막 스텝까지 스텝마다 모든 오브젝트의 상태와 그 스텝을 계산할 때 생긴 이벤트를 넘긴다.
+    /// 아직 나오지 않았거나 빠진 오브젝트는 None이다. 마지막 스텝에는 이벤트가 없다.
+    pub fn replay(
+        &self,
+        mut f: impl FnMut(usize, &[Option<CSObjectState>], Option<&SimulationEvents>),
+    ) {
+        let count = self.objects.len();
+
+        for segment in 0..self.timeline.keyframes.len() {
+            let mut replay = Replay::new(self, segment);
+
+            while replay.advance(&self.timeline, count, 1) {
+                let step = replay.step - 1;
+                let mut states = replay.states.pop_back().unwrap_or_default();
+                let replayed = replay.events.pop_back().unwrap_or_default();
+
+                for (state, obj) in states.iter_mut().zip(self.objects.iter()) {
+                    if let (Some(state), Some((position, velocity))) =
+                        (state.as_mut(), obj.track().get(step))
+                    {
+                        state.position = position;
+                        state.velocity = velocity;
+                    }
+                }
+
+                let events = if step >= self.timeline.detail_from {
+                    &self.events[step]
+                } else {
+                    &replayed
+                };
+                f(step, &states, Some(events));
+            }
+        }
+
+        let states = self
+            .objects
+            .iter()
+            .map(|obj| (!obj.is_ended()).then(|| obj.live_state().clone()))
+            .collect::<Vec<_>>();
+        f(self.recorded_steps(), &states, None);
+    }
+
+    /// 타임라인이 쓰는 메모리
+    pub fn usage(&self) -> TimelineUsage {
+        let records = self
+            .objects
+            .iter()
+            .map(|obj| obj.track().bytes())
+            .sum::<usize>()
+            + self.wall_impulse.len() * size_of::<f64>();
+
+        let keyframes = self
+            .objects
+            .iter()
+            .flat_map(|obj| obj.keyframes().iter())
+            .map(keyframe_bytes)
+            .sum::<usize>()
+            + self.timeline.keyframes.len() * size_of::<Keyframe>()
+            + self.timeline.scenes.iter().map(Scene::bytes).sum::<usize>()
+            + self.timeline.user_forces.len() * size_of::<UserForce>();
+
+        TimelineUsage {
+            records,
+            keyframes,
+            events: self.events.iter().map(events_bytes).sum(),
+            replays: self.timeline.replays.iter().map(Replay::bytes).sum(),
+        }
+    }
+
+    /// 갈래처럼 세계 밖에서 같은 예산을 쓰는 바이트 수를 알려 둔다.
+    pub fn reserve(&mut self, bytes: usize) {
+        self.timeline.reserved = bytes;
+    }
+
+    /// 스텝마다 맞추는 예산에서 세계 밖에서 쓰는 바이트를 뺀 것
+    pub(super) fn own_budget(&self, budget: usize) -> usize {
+        budget.saturating_sub(self.timeline.reserved)
+    }
+
+    /// 쓰는 메모리가 `budget` 바이트를 넘으면 오래된 구간의 위치, 속도와 이벤트를 버린다.
+    /// 그래도 넘으면 오래된 구간의 키프레임을 솎아 낸다. 마지막 구간은 그대로 둔다.
+    pub fn fit_budget(&mut self, budget: usize) {
+        let mut usage = self.usage().total();
+
+        while usage > budget {
+            let freed = match self.forget_oldest_segment() {
+                Some(freed) => freed,
+                None => match self.thin_keyframes() {
+                    Some(freed) => freed,
+                    None => break,
+                },
+            };
+
+            usage = usage.saturating_sub(freed);
+        }
+    }
+
+    // 자세한 기록이 남은 가장 오래된 구간의 위치, 속도와 이벤트를 버리고 버린 바이트 수를 돌려준다.
+    fn forget_oldest_segment(&mut self) -> Option<usize> {
+        let from = self.timeline.detail_from;
+        let to = self
+            .timeline
+            .keyframes
+            .iter()
+            .find(|keyframe| keyframe.step > from)?
+            .step;
+
+        let mut freed = 0;
+
+        for obj in self.objects.iter_mut() {
+            let before = obj.track().bytes();
+            obj.forget_before(to);
+            freed += before - obj.track().bytes();
+        }
+
+        let end = to.min(self.events.len());
+        for step in from.min(end)..end {
+            if let Some(events) = self.events.get_mut(step) {
+                freed += events_bytes(events) - size_of::<SimulationEvents>();
+                *events = SimulationEvents::default();
+            }
+        }
+
+        self.timeline.detail_from = to;
+
+        Some(freed)
+    }
+
+    // 자세한 기록을 버린 구간에서 간격을 채우려고 남긴 키프레임 하나를 뺀다.
+    // 합쳐진 구간이 가장 짧아지는 것을 골라 남은 키프레임이 고르게 퍼지게 한다.
+    fn thin_keyframes(&mut self) -> Option<usize> {
+        let keyframes = &self.timeline.keyframes;
+        let detail_from = self.timeline.detail_from;
+
+        let index = (1..keyframes.len().saturating_sub(1))
+            .filter(|i| keyframes[*i].periodic && keyframes[*i].step < detail_from)
+            .min_by_key(|i| keyframes[i + 1].step - keyframes[i - 1].step)?;
+
+        let step = self.timeline.keyframes.remove(index).step;
+        let mut freed = size_of::<Keyframe>();
+
+        for obj in self.objects.iter_mut() {
+            if let Some(state) = obj.keyframe_at(step) {
+                freed += size_of::<ObjectKeyframe>() + state.acc_list.len() * size_of::<NVec2>();
+                obj.remove_keyframe(step);
+            }
+        }
+
+        self.timeline.replays.clear();
+
+        Some(freed)
+    }
+
+    /// 오브젝트를 빼면서 장면과 사용자 가속도의 인덱스를 `map`으로 바꾼다.
+    pub(super) fn remap_timeline(
+        &mut self,
+        map: impl Fn(usize) -> Option<usize>,
+    ) -> RemovedTimeline {
+        let removed = RemovedTimeline {
+            scenes: self.timeline.scenes.clone(),
+            user_forces: self.timeline.user_forces.clone(),
+        };
+
+        for scene in self.timeline.scenes.iter_mut() {
+            scene.remap(&map);
+        }
+
+        self.timeline
+            .user_forces
+            .retain_mut(|force| match map(force.index) {
+                Some(index) => {
+                    force.index = index;
+                    true
+                }
+                None => false,
+            });
+
+        self.timeline.pending = true;
+        self.timeline.replays.clear();
+
+        removed
+    }
+
+    /// 뺀 오브젝트를 다시 넣으면서 장면과 사용자 가속도를 돌려놓는다.
+    /// 빼고 나서 생긴 장면은 `unmap`으로 인덱스만 바꾼다.
+    pub(super) fn restore_timeline(
+        &mut self,
+        removed: RemovedTimeline,
+        unmap: impl Fn(usize) -> Option<usize>,
+    ) {
+        let kept = removed.scenes.len().min(self.timeline.scenes.len());
+
+        for scene in self.timeline.scenes[kept..].iter_mut() {
+            scene.remap(&unmap);
+        }
+        self.timeline
+            .scenes
+            .splice(..kept, removed.scenes.into_iter().take(kept));
+
+        let since = removed.user_forces.last().map_or(0, |force| force.step + 1);
+        let mut later = self
+            .timeline
+            .user_forces
+            .iter()
+            .filter(|force| force.step >= since)
+            .copied()
+            .collect::<Vec<_>>();
+        for force in later.iter_mut() {
+            force.index = unmap(force.index).unwrap_or(force.index);
+        }
+
+        self.timeline.user_forces = removed.user_forces;
+        self.timeline.user_forces.extend(later);
+
+        self.timeline.pending = true;
+        self.timeline.replays.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::classic::template::init::IdealGasSimInit;
+    use crate::engine::classic::template::CSTemplate;
+
+    const STEPS: usize = 300;
+
+    // 스텝마다 모든 오브젝트의 위치, 속도와 그 스텝으로 오게 한 이벤트
+    type Snapshot = (Vec<(NVec2, NVec2)>, String);
+
+    fn recorded(template: CSTemplate) -> ClassicWorld {
+        let preset = template.get_preset_with_ui();
+        let mut settings = preset.settings.clone();
+        let mut world = ClassicWorld::from(preset);
+
+        for step in 0..STEPS {
+            world.step(&mut settings, SIMULATION_TICK * step as f64);
+        }
+        world
+    }
+
+    fn snapshot(world: &mut ClassicWorld, step: usize) -> Snapshot {
+        world.at_time_step(step);
+
+        let states = world
+            .objects
+            .iter()
+            .map(|obj| {
+                let state = obj.current_state();
+                (state.position, state.velocity)
+            })
+            .collect();
+        let events = step
+            .checked_sub(1)
+            .and_then(|index| world.events_at(index))
+            .map(|events| serde_json::to_string(events).unwrap())
+            .unwrap_or_default();
+
+        (states, events)
+    }
+
+    // 기록을 예산에 맞춰 버린 뒤에도 모든 스텝이 버리기 전과 똑같이 다시 계산되어야 한다.
+    fn trimmed_steps_are_recomputed_exactly(template: CSTemplate) {
+        let mut world = recorded(template);
+        let before = (0..=STEPS)
+            .map(|step| snapshot(&mut world, step))
+            .collect::<Vec<_>>();
+        let keyframes = world.timeline().keyframes().len();
+        let usage = world.usage().records;
+
+        world.fit_budget(0);
+
+        assert!(world.timeline().detail_from() > 0);
+        assert!(world.timeline().keyframes().len() < keyframes);
+        assert!(world.usage().records < usage);
+
+        for (step, expected) in before.iter().enumerate() {
+            assert!(snapshot(&mut world, step) == *expected, "step {}", step);
+        }
+
+        // 거꾸로 보아도 다시 계산한 구간을 잘못 쓰지 않는다.
+        for (step, expected) in before.iter().enumerate().rev().step_by(7) {
+            assert!(snapshot(&mut world, step) == *expected, "step {}", step);
+        }
+
+        world.replay(|step, states, events| {
+            for (state, expected) in states.iter().zip(&before[step].0) {
+                if let Some(state) = state {
+                    assert_eq!((state.position, state.velocity), *expected, "step {}", step);
+                }
+            }
+            if let (Some(events), Some((_, expected))) = (events, before.get(step + 1)) {
+                assert_eq!(
+                    serde_json::to_string(events).unwrap(),
+                    *expected,
+                    "step {}",
+                    step
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn trimmed_gas_is_recomputed_exactly() {
+        trimmed_steps_are_recomputed_exactly(CSTemplate::IdealGasSimInit(IdealGasSimInit {
+            count: 60,
+            ..IdealGasSimInit::default()
+        }));
+    }
+
+    #[test]
+    fn trimmed_soft_body_is_recomputed_exactly() {
+        trimmed_steps_are_recomputed_exactly(CSTemplate::SoftBodySim);
+    }
+
+    #[test]
+    fn trimmed_periodic_motion_is_recomputed_exactly() {
+        trimmed_steps_are_recomputed_exactly(CSTemplate::PeriodicSim);
+    }
+}
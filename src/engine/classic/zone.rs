@@ -0,0 +1,84 @@
+use crate::engine::polygon::is_inside;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+/// 영역 안에서만 적용되는 물리 효과.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ZoneEffect {
+    /// 전역 중력 대신 이 가속도를 사용한다.
+    Gravity(NVec2),
+    /// 점성 유체 저항. F = -bv
+    Drag(f64),
+    /// 컨베이어 벨트처럼 표면 속도로 끌어당긴다. a = grip * (v_surface - v)
+    Conveyor { velocity: NVec2, grip: f64 },
+}
+
+impl Default for ZoneEffect {
+    fn default() -> Self {
+        Self::Drag(1.0)
+    }
+}
+
+impl ZoneEffect {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Gravity(_) => "Gravity",
+            Self::Drag(_) => "Drag",
+            Self::Conveyor { .. } => "Conveyor",
+        }
+    }
+}
+
+/// 사각형 또는 다각형 모양의 국소 물리 영역.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldZone {
+    pub name: String,
+    pub points: Vec<NVec2>,
+    pub effect: ZoneEffect,
+}
+
+impl FieldZone {
+    pub fn polygon(name: impl Into<String>, points: Vec<NVec2>, effect: ZoneEffect) -> Self {
+        Self {
+            name: name.into(),
+            points,
+            effect,
+        }
+    }
+
+    pub fn rect(
+        name: impl Into<String>,
+        corner1: NVec2,
+        corner2: NVec2,
+        effect: ZoneEffect,
+    ) -> Self {
+        let min = corner1.inf(&corner2);
+        let max = corner1.sup(&corner2);
+
+        Self::polygon(
+            name,
+            vec![min, NVec2::new(max.x, min.y), max, NVec2::new(min.x, max.y)],
+            effect,
+        )
+    }
+
+    pub fn contains(&self, pos: NVec2) -> bool {
+        is_inside(pos, &self.points)
+    }
+
+    pub fn center(&self) -> NVec2 {
+        self.points.iter().sum::<NVec2>() / self.points.len().max(1) as f64
+    }
+
+    /// 영역 안의 물체가 받는 가속도. 중력 영역은 전역 중력을 대체하므로 여기서 제외한다.
+    pub fn acceleration(&self, velocity: NVec2, mass: f64) -> NVec2 {
+        match self.effect {
+            ZoneEffect::Gravity(_) => NVec2::zeros(),
+            ZoneEffect::Drag(coefficient) => -velocity * coefficient / mass,
+            ZoneEffect::Conveyor {
+                velocity: surface,
+                grip,
+            } => (surface - velocity) * grip,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! SPH로 푸는 액체. 벽과 그릇의 배치, 설정, 템플릿과 입자 스텝이 있다.
+
+pub mod layout;
+pub mod sim_state;
+pub mod sph;
+pub mod template;
@@ -0,0 +1,108 @@
+use crate::engine::polygon::{polyline_outline, segment_outline};
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+/// 움직이지 않는 벽. 두께가 있는 꺾은선이고, 열린 그릇도 이걸로 만든다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Solid {
+    pub name: String,
+    pub points: Vec<NVec2>,
+    pub thickness: f64,
+}
+
+impl Solid {
+    pub fn wall(name: impl Into<String>, start: NVec2, end: NVec2, thickness: f64) -> Self {
+        Self {
+            name: name.into(),
+            points: vec![start, end],
+            thickness,
+        }
+    }
+
+    /// 위가 열린 그릇. 두 점이 만드는 직사각형의 왼쪽, 아래, 오른쪽 면이다.
+    pub fn container(name: impl Into<String>, a: NVec2, b: NVec2, thickness: f64) -> Self {
+        let (min, max) = (a.inf(&b), a.sup(&b));
+
+        Self {
+            name: name.into(),
+            points: vec![NVec2::new(min.x, max.y), min, NVec2::new(max.x, min.y), max],
+            thickness,
+        }
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = (NVec2, NVec2)> + '_ {
+        self.points.windows(2).map(|pair| (pair[0], pair[1]))
+    }
+
+    /// 꼭짓점들의 가운데
+    pub fn center(&self) -> NVec2 {
+        self.points.iter().sum::<NVec2>() / self.points.len().max(1) as f64
+    }
+
+    pub fn translate(&mut self, delta: NVec2) {
+        self.points.iter_mut().for_each(|p| *p += delta);
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        polyline_outline(&self.points, self.thickness / 2.0)
+    }
+}
+
+/// 시작 시간부터 `duration`동안 `angle` 방향으로 유체를 뿜는 관.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Emitter {
+    pub name: String,
+    pub position: NVec2,
+    pub angle: f64,
+    /// 뿜는 속력 (단위/s)
+    pub speed: f64,
+    /// 관의 폭
+    pub width: f64,
+    /// 뿜기 시작하는 시간 (s)
+    pub start: f64,
+    pub duration: f64,
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self {
+            name: "Emitter".to_string(),
+            position: NVec2::zeros(),
+            angle: -std::f64::consts::FRAC_PI_2,
+            speed: 30.0,
+            width: 8.0,
+            start: 0.0,
+            duration: 5.0,
+        }
+    }
+}
+
+impl Emitter {
+    pub fn direction(&self) -> NVec2 {
+        NVec2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    /// 관 입구의 양 끝
+    pub fn endpoints(&self) -> (NVec2, NVec2) {
+        let direction = self.direction();
+        let half = NVec2::new(-direction.y, direction.x) * self.width / 2.0;
+
+        (self.position - half, self.position + half)
+    }
+
+    pub fn is_active(&self, time: f64) -> bool {
+        (self.start..self.start + self.duration).contains(&time)
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        let (a, b) = self.endpoints();
+        segment_outline(a, b, 2.0)
+    }
+}
+
+/// 벽과 관의 배치. 타임라인에는 바뀐 스텝의 배치만 저장한다.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FluidLayout {
+    pub solids: Vec<Solid>,
+    pub emitters: Vec<Emitter>,
+}
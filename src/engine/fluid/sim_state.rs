@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// 입자를 칠할 값
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FluidView {
+    #[default]
+    Speed,
+    Density,
+    Pressure,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FluidSettings {
+    /// 압력이 0이 되는 밀도
+    pub(crate) rest_density: f64,
+    /// 밀도가 늘어난 만큼 압력이 오르는 정도. 음속의 제곱과 같다.
+    pub(crate) stiffness: f64,
+    /// 동점성 계수 (단위²/s)
+    pub(crate) viscosity: f64,
+    pub(crate) gravity: f64,
+    pub(crate) view: FluidView,
+}
+
+impl Default for FluidSettings {
+    fn default() -> Self {
+        Self {
+            rest_density: 1000.0,
+            stiffness: 4e5,
+            viscosity: 2.0,
+            gravity: 100.0,
+            view: FluidView::default(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use crate::engine::fluid::layout::{Emitter, FluidLayout, Solid};
+use crate::engine::fluid::sim_state::FluidSettings;
+use crate::engine::fluid::sph::FluidTank;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+pub enum FluidTemplate {
+    DamBreakSim,
+    ViscousDamBreakSim,
+    PouringSim,
+    CascadeSim,
+    /// 세션에 저장해 두었던 배치
+    Saved(Box<FluidPreset>),
+}
+
+impl FluidTemplate {
+    pub fn get_name(&self) -> String {
+        match self {
+            FluidTemplate::Saved(_) => "Saved".to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    pub fn get_preset(self) -> FluidPreset {
+        match self {
+            FluidTemplate::DamBreakSim => dam_break(),
+            FluidTemplate::ViscousDamBreakSim => viscous_dam_break(),
+            FluidTemplate::PouringSim => pouring(),
+            FluidTemplate::CascadeSim => cascade(),
+            FluidTemplate::Saved(preset) => *preset,
+        }
+    }
+}
+
+pub fn get_fluid_sim_list() -> [FluidTemplate; 4] {
+    [
+        FluidTemplate::DamBreakSim,
+        FluidTemplate::ViscousDamBreakSim,
+        FluidTemplate::PouringSim,
+        FluidTemplate::CascadeSim,
+    ]
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FluidPreset {
+    pub tank: FluidTank,
+    pub layout: FluidLayout,
+    pub settings: FluidSettings,
+    /// 처음 입자들의 위치
+    pub particles: Vec<NVec2>,
+}
+
+impl Default for FluidPreset {
+    fn default() -> Self {
+        let settings = FluidSettings::default();
+
+        Self {
+            tank: FluidTank::new(
+                NVec2::new(-80.0, -70.0),
+                NVec2::new(80.0, 70.0),
+                2.0,
+                settings.rest_density,
+            ),
+            layout: FluidLayout::default(),
+            settings,
+            particles: vec![],
+        }
+    }
+}
+
+// 왼쪽에 쌓인 물기둥이 무너진다.
+fn dam_break() -> FluidPreset {
+    let preset = FluidPreset::default();
+    let particles = preset
+        .tank
+        .lattice(NVec2::new(-80.0, -70.0), NVec2::new(-20.0, 20.0));
+
+    FluidPreset {
+        particles,
+        ..preset
+    }
+}
+
+// 꿀처럼 끈적한 물기둥이 가운데의 턱을 넘는다.
+fn viscous_dam_break() -> FluidPreset {
+    let preset = FluidPreset::default();
+    let particles = preset
+        .tank
+        .lattice(NVec2::new(-80.0, -70.0), NVec2::new(-30.0, 10.0));
+
+    FluidPreset {
+        particles,
+        layout: FluidLayout {
+            solids: vec![Solid::wall(
+                "Step",
+                NVec2::new(20.0, -70.0),
+                NVec2::new(20.0, -55.0),
+                4.0,
+            )],
+            emitters: vec![],
+        },
+        settings: FluidSettings {
+            viscosity: 150.0,
+            ..preset.settings
+        },
+        ..preset
+    }
+}
+
+// 관에서 나온 물이 그릇을 채우고 넘친다.
+fn pouring() -> FluidPreset {
+    FluidPreset {
+        layout: FluidLayout {
+            solids: vec![Solid::container(
+                "Cup",
+                NVec2::new(-10.0, -50.0),
+                NVec2::new(40.0, -10.0),
+                2.0,
+            )],
+            emitters: vec![Emitter {
+                name: "Tap".to_string(),
+                position: NVec2::new(15.0, 50.0),
+                duration: 10.0,
+                ..Emitter::default()
+            }],
+        },
+        ..FluidPreset::default()
+    }
+}
+
+// 비탈을 따라 흘러내린 물이 바닥의 그릇에 모인다.
+fn cascade() -> FluidPreset {
+    FluidPreset {
+        layout: FluidLayout {
+            solids: vec![
+                Solid::wall(
+                    "Upper Ramp",
+                    NVec2::new(-80.0, 40.0),
+                    NVec2::new(20.0, 28.0),
+                    2.0,
+                ),
+                Solid::wall(
+                    "Lower Ramp",
+                    NVec2::new(80.0, 5.0),
+                    NVec2::new(-30.0, -25.0),
+                    2.0,
+                ),
+                Solid::container(
+                    "Basin",
+                    NVec2::new(-76.0, -68.0),
+                    NVec2::new(-24.0, -40.0),
+                    2.0,
+                ),
+            ],
+            emitters: vec![Emitter {
+                name: "Spring".to_string(),
+                position: NVec2::new(-65.0, 60.0),
+                speed: 20.0,
+                duration: 12.0,
+                ..Emitter::default()
+            }],
+        },
+        ..FluidPreset::default()
+    }
+}
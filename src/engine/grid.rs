@@ -0,0 +1,77 @@
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+/// 플롯 좌표 `min`에서 시작해 한 칸의 크기가 `cell`인 직사각형 격자.
+/// 칸 `(x, y)`의 값은 `y * width + x`에 저장한다.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Grid {
+    pub min: NVec2,
+    pub cell: f64,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Grid {
+    pub fn new(min: NVec2, max: NVec2, cell: f64) -> Self {
+        let size = (max - min) / cell;
+
+        Self {
+            min,
+            cell,
+            width: (size.x.round() as usize).max(1),
+            height: (size.y.round() as usize).max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// `new`는 가로세로를 1칸 이상으로 만들므로 비어 있지 않다.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn size(&self) -> NVec2 {
+        NVec2::new(self.width as f64, self.height as f64) * self.cell
+    }
+
+    pub fn center(&self) -> NVec2 {
+        self.min + self.size() / 2.0
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// 칸 중심의 좌표
+    pub fn position(&self, x: usize, y: usize) -> NVec2 {
+        self.min + NVec2::new(x as f64 + 0.5, y as f64 + 0.5) * self.cell
+    }
+
+    pub fn cell_of(&self, point: NVec2) -> Option<usize> {
+        let local = (point - self.min) / self.cell;
+
+        if local.x < 0.0 || local.y < 0.0 {
+            return None;
+        }
+
+        let (x, y) = (local.x as usize, local.y as usize);
+        (x < self.width && y < self.height).then(|| self.index(x, y))
+    }
+
+    /// 중심이 `condition`을 만족하는 칸들
+    pub fn cells_where(&self, condition: impl Fn(NVec2) -> bool) -> Vec<usize> {
+        let mut cells = vec![];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if condition(self.position(x, y)) {
+                    cells.push(self.index(x, y));
+                }
+            }
+        }
+
+        cells
+    }
+}
@@ -0,0 +1,6 @@
+//! 판 위의 열 전도. 격자 위 온도, 영역과 탐침의 배치, 설정과 템플릿이 있다.
+
+pub mod field;
+pub mod layout;
+pub mod sim_state;
+pub mod template;
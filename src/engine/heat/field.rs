@@ -0,0 +1,303 @@
+use crate::engine::grid::Grid;
+use crate::engine::heat::layout::{EdgeCondition, HeatLayout, RegionKind};
+use crate::engine::heat::sim_state::HeatSettings;
+use crate::engine::keyframe::Stepped;
+use crate::engine::NVec2;
+
+/// 양해법이 안정하려면 dt·α/h² 가 이보다 작아야 한다.
+/// 이웃 네 면에 조화 평균 α가 최대 2α까지 들어가므로 1/8로 잡는다.
+const MAX_STABILITY: f64 = 1.0 / 8.0;
+
+/// 배치와 설정으로 만든 칸마다의 물성. 배치가 바뀔 때만 다시 만든다.
+#[derive(Clone, Debug, Default)]
+pub struct HeatMaps {
+    pub diffusivity: Vec<f32>,
+    /// 온도가 고정된 칸
+    pub fixed: Vec<Option<f32>>,
+}
+
+impl HeatMaps {
+    /// 나중에 놓인 영역이 앞의 영역을 덮는다.
+    pub fn new(grid: &Grid, layout: &HeatLayout, settings: &HeatSettings) -> Self {
+        let mut maps = Self {
+            diffusivity: vec![settings.diffusivity as f32; grid.len()],
+            fixed: vec![None; grid.len()],
+        };
+
+        for region in &layout.regions {
+            for cell in grid.cells_where(|position| region.contains(position)) {
+                match region.kind {
+                    RegionKind::Material { diffusivity } => {
+                        maps.diffusivity[cell] = diffusivity as f32;
+                        maps.fixed[cell] = None;
+                    }
+                    RegionKind::Fixed { temperature } => {
+                        maps.fixed[cell] = Some(temperature as f32);
+                    }
+                }
+            }
+        }
+
+        maps
+    }
+
+    pub fn max_diffusivity(&self) -> f64 {
+        self.diffusivity.iter().fold(0.0f32, |max, a| max.max(*a)) as f64
+    }
+}
+
+/// 스텝 하나를 몇 번에 나눠 계산해야 안정한지
+pub fn substeps(grid: &Grid, maps: &HeatMaps, dt: f64) -> usize {
+    let stability = dt * maps.max_diffusivity() / (grid.cell * grid.cell);
+
+    ((stability / MAX_STABILITY).ceil() as usize).max(1)
+}
+
+/// 어느 스텝에서의 격자 위 온도 (°C).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatField {
+    pub step: usize,
+    pub time: f64,
+    pub temperature: Vec<f32>,
+}
+
+impl Stepped for HeatField {
+    fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl HeatField {
+    pub fn new(grid: &Grid, initial: f64) -> Self {
+        Self {
+            step: 0,
+            time: 0.0,
+            temperature: vec![initial as f32; grid.len()],
+        }
+    }
+
+    /// 열방정식 T_t = ∇·(α∇T) + q 를 FTCS로 `dt`만큼 진행한다.
+    /// 칸 사이의 α는 조화 평균을 써서 서로 다른 물질의 경계에서도 열 흐름이 이어지게 한다.
+    pub fn advance(&mut self, grid: &Grid, layout: &HeatLayout, maps: &HeatMaps, dt: f64) {
+        let substeps = substeps(grid, maps, dt);
+        let dt = dt / substeps as f64;
+        let ratio = (dt / (grid.cell * grid.cell)) as f32;
+
+        let (width, height) = (grid.width, grid.height);
+        let alpha = &maps.diffusivity;
+        let mut next = self.temperature.clone();
+
+        for _ in 0..substeps {
+            let temperature = &self.temperature;
+
+            for y in 0..height {
+                for x in 0..width {
+                    let i = y * width + x;
+
+                    if let Some(fixed) = maps.fixed[i] {
+                        next[i] = fixed;
+                        continue;
+                    }
+
+                    let (a, t) = (alpha[i], temperature[i]);
+
+                    // 왼쪽, 오른쪽, 아래, 위 이웃. `then_some`은 값을 먼저 계산하므로
+                    // 가장자리에서 빼기가 넘치지 않게 한다.
+                    let neighbours = [
+                        (x > 0).then_some(i.wrapping_sub(1)),
+                        (x + 1 < width).then_some(i + 1),
+                        (y > 0).then_some(i.wrapping_sub(width)),
+                        (y + 1 < height).then_some(i + width),
+                    ];
+
+                    let mut flux = 0.0;
+                    for (neighbour, edge) in neighbours.iter().zip(&layout.edges) {
+                        match (neighbour, edge) {
+                            (Some(j), _) => {
+                                let b = alpha[*j];
+                                flux += 2.0 * a * b / (a + b) * (temperature[*j] - t);
+                            }
+                            // 가장자리는 칸 중심에서 반 칸 떨어져 있다.
+                            (None, EdgeCondition::Fixed(outside)) => {
+                                flux += 2.0 * a * (*outside as f32 - t);
+                            }
+                            (None, EdgeCondition::Insulated) => {}
+                        }
+                    }
+
+                    let source = layout.sources.get(i).copied().unwrap_or(0.0);
+                    next[i] = t + ratio * flux + dt as f32 * source;
+                }
+            }
+
+            std::mem::swap(&mut self.temperature, &mut next);
+            self.time += dt;
+        }
+
+        self.step += 1;
+    }
+
+    pub fn value_at(&self, grid: &Grid, point: NVec2) -> Option<f64> {
+        grid.cell_of(point)
+            .map(|cell| self.temperature[cell] as f64)
+    }
+
+    /// (최저, 최고, 평균)
+    pub fn stats(&self) -> (f64, f64, f64) {
+        let (min, max, sum) = self
+            .temperature
+            .iter()
+            .fold((f32::MAX, f32::MIN, 0.0f64), |(min, max, sum), t| {
+                (min.min(*t), max.max(*t), sum + *t as f64)
+            });
+
+        (
+            min as f64,
+            max as f64,
+            sum / self.temperature.len().max(1) as f64,
+        )
+    }
+
+    /// 중심이 `contains`를 만족하는 칸들의 평균 온도
+    pub fn mean_where(&self, grid: &Grid, contains: impl Fn(NVec2) -> bool) -> Option<f64> {
+        let cells = grid.cells_where(contains);
+
+        (!cells.is_empty()).then(|| {
+            cells
+                .iter()
+                .map(|cell| self.temperature[*cell] as f64)
+                .sum::<f64>()
+                / cells.len() as f64
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::heat::layout::HeatRegion;
+
+    fn settings(diffusivity: f64) -> HeatSettings {
+        HeatSettings {
+            diffusivity,
+            ..HeatSettings::default()
+        }
+    }
+
+    fn with_shape(grid: &Grid, shape: impl Fn(NVec2) -> f64) -> HeatField {
+        let mut field = HeatField::new(grid, 0.0);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                field.temperature[grid.index(x, y)] = shape(grid.position(x, y)) as f32;
+            }
+        }
+        field
+    }
+
+    // erfc의 근사 (Abramowitz & Stegun 7.1.26, 오차 1.5e-7)
+    fn erfc(x: f64) -> f64 {
+        let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+        let poly = t
+            * (0.254829592
+                + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+        let erfc = poly * (-x * x).exp();
+
+        if x >= 0.0 {
+            erfc
+        } else {
+            2.0 - erfc
+        }
+    }
+
+    #[test]
+    fn substeps_keep_the_scheme_stable() {
+        let grid = Grid::new(NVec2::zeros(), NVec2::new(30.0, 30.0), 1.0);
+        let layout = HeatLayout::default();
+
+        for diffusivity in [1.0, 50.0, 600.0] {
+            let maps = HeatMaps::new(&grid, &layout, &settings(diffusivity));
+            let dt = 0.05;
+            let stability =
+                dt / substeps(&grid, &maps, dt) as f64 * diffusivity / (grid.cell * grid.cell);
+            assert!(stability <= MAX_STABILITY);
+
+            // 양해법이 안정하면 처음의 최고, 최저 온도를 넘지 않는다.
+            let mut field = with_shape(&grid, |p| {
+                if (p.x + p.y) as usize % 2 == 0 {
+                    100.0
+                } else {
+                    0.0
+                }
+            });
+            for _ in 0..50 {
+                field.advance(&grid, &layout, &maps, dt);
+            }
+
+            let (min, max, _) = field.stats();
+            assert!(
+                min >= -1e-3 && max <= 100.0 + 1e-3,
+                "{}: {} {}",
+                diffusivity,
+                min,
+                max
+            );
+        }
+    }
+
+    #[test]
+    fn insulated_plate_keeps_its_heat() {
+        let grid = Grid::new(NVec2::zeros(), NVec2::new(40.0, 30.0), 1.0);
+        let layout = HeatLayout {
+            // 서로 다른 물질의 경계에서도 열이 새지 않아야 한다.
+            regions: vec![HeatRegion::new(
+                "Copper",
+                NVec2::new(10.0, 5.0),
+                NVec2::new(25.0, 20.0),
+                RegionKind::Material { diffusivity: 110.0 },
+            )],
+            ..HeatLayout::default()
+        };
+        let maps = HeatMaps::new(&grid, &layout, &settings(20.0));
+
+        let mut field = with_shape(&grid, |p| if p.x < 12.0 { 90.0 } else { 10.0 });
+        let (_, _, start) = field.stats();
+
+        for _ in 0..200 {
+            field.advance(&grid, &layout, &maps, 0.05);
+        }
+
+        let (min, max, mean) = field.stats();
+        assert!((mean - start).abs() < 1e-3 * start, "{} vs {}", mean, start);
+        // 충분히 지나면 고르게 섞인다.
+        assert!(max - min < 80.0);
+    }
+
+    #[test]
+    fn step_diffuses_like_the_error_function() {
+        // 한 줄짜리 긴 막대. 가장자리가 멀어서 무한한 막대처럼 볼 수 있다.
+        let grid = Grid::new(NVec2::new(-100.0, 0.0), NVec2::new(100.0, 1.0), 1.0);
+        let layout = HeatLayout::default();
+        let diffusivity = 20.0;
+        let maps = HeatMaps::new(&grid, &layout, &settings(diffusivity));
+
+        let mut field = with_shape(&grid, |p| if p.x < 0.0 { 100.0 } else { 0.0 });
+        for _ in 0..100 {
+            field.advance(&grid, &layout, &maps, 0.05);
+        }
+
+        // T = T₀/2 · erfc(x / 2√(αt))
+        let spread = 2.0 * (diffusivity * field.time).sqrt();
+        // 칸 중심에서 비교한다. 처음의 경계는 두 칸 사이의 x = 0이다.
+        for x in [-29.5, -9.5, -1.5, 0.5, 4.5, 14.5, 39.5] {
+            let expected = 50.0 * erfc(x / spread);
+            let actual = field.value_at(&grid, NVec2::new(x, 0.5)).unwrap();
+            assert!(
+                (actual - expected).abs() < 0.5,
+                "x {}: {} vs {}",
+                x,
+                actual,
+                expected
+            );
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::engine::grid::Grid;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RegionKind {
+    /// 열확산 계수가 다른 물질
+    Material { diffusivity: f64 },
+    /// 온도가 고정된 열판
+    Fixed { temperature: f64 },
+}
+
+impl Default for RegionKind {
+    fn default() -> Self {
+        Self::Material { diffusivity: 100.0 }
+    }
+}
+
+impl RegionKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            RegionKind::Material { .. } => "Material",
+            RegionKind::Fixed { .. } => "Fixed",
+        }
+    }
+}
+
+/// 축에 나란한 직사각형 영역.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeatRegion {
+    pub name: String,
+    pub min: NVec2,
+    pub max: NVec2,
+    pub kind: RegionKind,
+}
+
+impl HeatRegion {
+    pub fn new(name: impl Into<String>, a: NVec2, b: NVec2, kind: RegionKind) -> Self {
+        Self {
+            name: name.into(),
+            min: a.inf(&b),
+            max: a.sup(&b),
+            kind,
+        }
+    }
+
+    pub fn center(&self) -> NVec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn contains(&self, point: NVec2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        vec![
+            self.min,
+            NVec2::new(self.max.x, self.min.y),
+            self.max,
+            NVec2::new(self.min.x, self.max.y),
+        ]
+    }
+}
+
+/// 가장자리의 경계 조건
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EdgeCondition {
+    /// 열이 드나들지 않는다.
+    Insulated,
+    /// 바깥 온도가 고정되어 있다.
+    Fixed(f64),
+}
+
+/// 온도를 기록하는 지점.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Probe {
+    pub name: String,
+    pub position: NVec2,
+}
+
+impl Probe {
+    pub fn outline(&self) -> Vec<NVec2> {
+        const HALF_SIZE: f64 = 2.0;
+
+        [(-1.0, 0.0), (0.0, -1.0), (1.0, 0.0), (0.0, 1.0)]
+            .iter()
+            .map(|(x, y)| self.position + NVec2::new(*x, *y) * HALF_SIZE)
+            .collect()
+    }
+}
+
+/// 마우스로 열원을 칠하는 붓.
+#[derive(Clone, Copy, Debug)]
+pub struct HeatBrush {
+    /// 칠한 칸의 온도가 오르는 빠르기 (°C/s). 음수면 식힌다.
+    pub power: f64,
+    pub radius: f64,
+    pub erase: bool,
+}
+
+impl Default for HeatBrush {
+    fn default() -> Self {
+        Self {
+            power: 20.0,
+            radius: 6.0,
+            erase: false,
+        }
+    }
+}
+
+/// 가장자리 순서
+pub const EDGE_NAMES: [&str; 4] = ["Left", "Right", "Bottom", "Top"];
+
+/// 영역, 탐침, 경계 조건, 칠한 열원. 타임라인에는 바뀐 스텝의 배치만 저장한다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeatLayout {
+    pub regions: Vec<HeatRegion>,
+    pub probes: Vec<Probe>,
+    /// 왼쪽, 오른쪽, 아래, 위
+    pub edges: [EdgeCondition; 4],
+    /// 칸마다의 열원 (°C/s). 칠한 적이 없으면 비어 있다.
+    pub sources: Vec<f32>,
+}
+
+impl Default for HeatLayout {
+    fn default() -> Self {
+        Self {
+            regions: vec![],
+            probes: vec![],
+            edges: [EdgeCondition::Insulated; 4],
+            sources: vec![],
+        }
+    }
+}
+
+impl HeatLayout {
+    /// `center`에서 `radius` 안의 칸을 `brush`로 칠한다.
+    pub fn paint(&mut self, grid: &Grid, center: NVec2, brush: HeatBrush) {
+        if self.sources.len() != grid.len() {
+            self.sources = vec![0.0; grid.len()];
+        }
+
+        for cell in grid.cells_where(|position| (position - center).norm() <= brush.radius) {
+            self.sources[cell] = if brush.erase { 0.0 } else { brush.power as f32 };
+        }
+    }
+
+    /// 열원 전체의 세기 (°C·단위²/s)
+    pub fn source_power(&self, grid: &Grid) -> f64 {
+        self.sources.iter().map(|q| *q as f64).sum::<f64>() * grid.cell * grid.cell
+    }
+}
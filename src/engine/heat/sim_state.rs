@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HeatSettings {
+    /// 영역 밖 매질의 열확산 계수 (단위²/s)
+    pub(crate) diffusivity: f64,
+    /// 스텝 하나에 흐르는 시간의 배율. 열은 천천히 퍼지므로 빨리 돌린다.
+    pub(crate) time_scale: f64,
+    /// 색을 칠할 온도 범위
+    pub(crate) min_temperature: f64,
+    pub(crate) max_temperature: f64,
+    /// 열원을 칠한 칸을 표시한다.
+    pub(crate) show_sources: bool,
+}
+
+impl Default for HeatSettings {
+    fn default() -> Self {
+        Self {
+            diffusivity: 20.0,
+            time_scale: 20.0,
+            min_temperature: 0.0,
+            max_temperature: 100.0,
+            show_sources: true,
+        }
+    }
+}
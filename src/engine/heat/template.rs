@@ -0,0 +1,189 @@
+use crate::engine::grid::Grid;
+use crate::engine::heat::layout::{
+    EdgeCondition, HeatBrush, HeatLayout, HeatRegion, Probe, RegionKind,
+};
+use crate::engine::heat::sim_state::HeatSettings;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+pub enum HeatTemplate {
+    HotPlateSim,
+    CompositeWallSim,
+    HeatSourceSim,
+    CoolingFinSim,
+    /// 세션에 저장해 두었던 배치
+    Saved(Box<HeatPreset>),
+}
+
+impl HeatTemplate {
+    pub fn get_name(&self) -> String {
+        match self {
+            HeatTemplate::Saved(_) => "Saved".to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    pub fn get_preset(self) -> HeatPreset {
+        match self {
+            HeatTemplate::HotPlateSim => hot_plate(),
+            HeatTemplate::CompositeWallSim => composite_wall(),
+            HeatTemplate::HeatSourceSim => heat_source(),
+            HeatTemplate::CoolingFinSim => cooling_fin(),
+            HeatTemplate::Saved(preset) => *preset,
+        }
+    }
+}
+
+pub fn get_heat_sim_list() -> [HeatTemplate; 4] {
+    [
+        HeatTemplate::HotPlateSim,
+        HeatTemplate::CompositeWallSim,
+        HeatTemplate::HeatSourceSim,
+        HeatTemplate::CoolingFinSim,
+    ]
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HeatPreset {
+    pub grid: Grid,
+    pub layout: HeatLayout,
+    pub settings: HeatSettings,
+    /// 처음 온도 (°C)
+    pub initial: f64,
+}
+
+impl Default for HeatPreset {
+    fn default() -> Self {
+        Self {
+            grid: Grid::new(NVec2::new(-100.0, -100.0), NVec2::new(100.0, 100.0), 2.0),
+            layout: HeatLayout::default(),
+            settings: HeatSettings::default(),
+            initial: 20.0,
+        }
+    }
+}
+
+fn probes(points: &[(f64, f64)]) -> Vec<Probe> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| Probe {
+            name: format!("Probe {}", i),
+            position: NVec2::new(*x, *y),
+        })
+        .collect()
+}
+
+fn hot_plate() -> HeatPreset {
+    HeatPreset {
+        layout: HeatLayout {
+            regions: vec![HeatRegion::new(
+                "Hot Plate",
+                NVec2::new(-15.0, -15.0),
+                NVec2::new(15.0, 15.0),
+                RegionKind::Fixed { temperature: 100.0 },
+            )],
+            probes: probes(&[(25.0, 0.0), (45.0, 0.0), (65.0, 0.0)]),
+            edges: [EdgeCondition::Fixed(20.0); 4],
+            ..HeatLayout::default()
+        },
+        ..HeatPreset::default()
+    }
+}
+
+// 정상 상태에서 온도는 각 층 안에서 직선이고, 기울기는 열확산 계수에 반비례한다.
+fn composite_wall() -> HeatPreset {
+    HeatPreset {
+        layout: HeatLayout {
+            regions: vec![
+                HeatRegion::new(
+                    "Copper",
+                    NVec2::new(-60.0, -100.0),
+                    NVec2::new(-20.0, 100.0),
+                    RegionKind::Material { diffusivity: 120.0 },
+                ),
+                HeatRegion::new(
+                    "Insulation",
+                    NVec2::new(20.0, -100.0),
+                    NVec2::new(40.0, 100.0),
+                    RegionKind::Material { diffusivity: 4.0 },
+                ),
+            ],
+            probes: probes(&[
+                (-80.0, 0.0),
+                (-40.0, 0.0),
+                (0.0, 0.0),
+                (30.0, 0.0),
+                (70.0, 0.0),
+            ]),
+            edges: [
+                EdgeCondition::Fixed(100.0),
+                EdgeCondition::Fixed(0.0),
+                EdgeCondition::Insulated,
+                EdgeCondition::Insulated,
+            ],
+            ..HeatLayout::default()
+        },
+        initial: 0.0,
+        ..HeatPreset::default()
+    }
+}
+
+// 단열된 상자 안의 열원. 평균 온도가 일정한 빠르기로 오른다.
+fn heat_source() -> HeatPreset {
+    let preset = HeatPreset::default();
+    let mut layout = HeatLayout {
+        regions: vec![HeatRegion::new(
+            "Metal Bar",
+            NVec2::new(-80.0, -4.0),
+            NVec2::new(80.0, 4.0),
+            RegionKind::Material { diffusivity: 120.0 },
+        )],
+        probes: probes(&[(0.0, 0.0), (60.0, 0.0), (0.0, 60.0)]),
+        ..HeatLayout::default()
+    };
+
+    layout.paint(
+        &preset.grid,
+        NVec2::zeros(),
+        HeatBrush {
+            power: 10.0,
+            radius: 10.0,
+            erase: false,
+        },
+    );
+
+    HeatPreset { layout, ..preset }
+}
+
+fn cooling_fin() -> HeatPreset {
+    HeatPreset {
+        layout: HeatLayout {
+            regions: vec![
+                HeatRegion::new(
+                    "Fin",
+                    NVec2::new(-80.0, -5.0),
+                    NVec2::new(40.0, 5.0),
+                    RegionKind::Material { diffusivity: 120.0 },
+                ),
+                HeatRegion::new(
+                    "Heater",
+                    NVec2::new(-100.0, -30.0),
+                    NVec2::new(-80.0, 30.0),
+                    RegionKind::Fixed { temperature: 90.0 },
+                ),
+            ],
+            probes: probes(&[(-60.0, 0.0), (-20.0, 0.0), (20.0, 0.0), (-20.0, 30.0)]),
+            edges: [
+                EdgeCondition::Insulated,
+                EdgeCondition::Fixed(20.0),
+                EdgeCondition::Fixed(20.0),
+                EdgeCondition::Fixed(20.0),
+            ],
+            ..HeatLayout::default()
+        },
+        ..HeatPreset::default()
+    }
+}
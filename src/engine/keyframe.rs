@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 저장본이 계산된 스텝
+pub trait Stepped {
+    fn step(&self) -> usize;
+}
+
+/// 격자 시뮬레이션의 타임라인. 배치는 바뀐 스텝에만, 격자는 `interval` 스텝마다 저장하고
+/// 그 사이 스텝은 저장본에서 다시 계산한다. 저장본은 사본끼리 함께 쓴다.
+#[derive(Clone, Debug)]
+pub struct KeyframeTimeline<L, F> {
+    /// 배치가 바뀐 스텝과 그때의 배치
+    layouts: BTreeMap<usize, Arc<L>>,
+    keyframes: BTreeMap<usize, Arc<F>>,
+    interval: usize,
+}
+
+impl<L: Clone, F: Clone> KeyframeTimeline<L, F> {
+    pub fn new(layout: L, field: F, interval: usize) -> Self {
+        Self {
+            layouts: BTreeMap::from([(0, Arc::new(layout))]),
+            keyframes: BTreeMap::from([(0, Arc::new(field))]),
+            interval: interval.max(1),
+        }
+    }
+
+    /// `step`에서 쓰는 배치
+    pub fn layout_at(&self, step: usize) -> &L {
+        self.layouts
+            .range(..=step)
+            .next_back()
+            .map(|(_, layout)| layout.as_ref())
+            .expect("step 0 always has a layout")
+    }
+
+    /// `step`부터 `layout`을 쓴다. 그 뒤에 저장한 배치와 격자는 버린다.
+    pub fn record(&mut self, step: usize, layout: L) {
+        self.layouts.retain(|changed, _| *changed < step);
+        self.layouts.insert(step, Arc::new(layout));
+        self.keyframes.retain(|keyframe, _| *keyframe <= step);
+    }
+
+    /// `step`의 격자를 직접 고쳤을 때 그 격자를 저장하고, 그 뒤에 저장한 배치와 격자는 버린다.
+    pub fn edit(&mut self, step: usize, field: &F) {
+        self.layouts.retain(|changed, _| *changed <= step);
+        self.keyframes.retain(|keyframe, _| *keyframe < step);
+        self.keyframes.insert(step, Arc::new(field.clone()));
+    }
+
+    /// 간격에 맞는 스텝이면 격자를 저장한다.
+    pub fn store(&mut self, step: usize, field: &F) {
+        if step % self.interval == 0 {
+            self.keyframes
+                .entry(step)
+                .or_insert_with(|| Arc::new(field.clone()));
+        }
+    }
+
+    /// `step` 이전에서 가장 가까운 저장본
+    pub fn keyframe_before(&self, step: usize) -> (usize, &F) {
+        self.keyframes
+            .range(..=step)
+            .next_back()
+            .map(|(keyframe, field)| (*keyframe, field.as_ref()))
+            .expect("step 0 always has a keyframe")
+    }
+
+    pub fn keyframe_count(&self) -> usize {
+        self.keyframes.len()
+    }
+}
+
+impl<L: Clone, F: Clone + Stepped> KeyframeTimeline<L, F> {
+    /// `step`을 보려고 `field`를 되돌린다. 지금 것에서 이어서 계산할 수 없으면 가장 가까운
+    /// 저장본으로 바꾼다. 더 계산해야 하는 스텝 수를 돌려준다.
+    pub fn rewind(&self, step: usize, field: &mut F) -> usize {
+        let (keyframe, saved) = self.keyframe_before(step);
+
+        if field.step() > step || field.step() < keyframe {
+            *field = saved.clone();
+        }
+
+        step - field.step()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editing_a_past_step_drops_what_came_after() {
+        let mut timeline = KeyframeTimeline::new("a", 0, 10);
+        for step in 1..=40 {
+            timeline.store(step, &step);
+        }
+        timeline.record(20, "b");
+        timeline.record(30, "c");
+
+        timeline.record(15, "d");
+
+        assert_eq!(*timeline.layout_at(17), "d");
+        assert_eq!(*timeline.layout_at(35), "d");
+        assert_eq!(*timeline.layout_at(10), "a");
+        assert_eq!(timeline.keyframe_before(40), (10, &10));
+
+        timeline.edit(12, &120);
+
+        assert_eq!(*timeline.layout_at(35), "a");
+        assert_eq!(timeline.keyframe_before(40), (12, &120));
+    }
+}
@@ -0,0 +1,7 @@
+//! 기하 광학. 거울, 렌즈, 프리즘과 광원의 배치, 광선 추적, 설정과 템플릿이 있다.
+
+pub mod element;
+pub mod light;
+pub mod ray;
+pub mod sim_state;
+pub mod template;
@@ -0,0 +1,463 @@
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+const ARC_RESOLUTION: usize = 32;
+
+/// 광선이 면에 닿았을 때의 동작.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interaction {
+    Mirror,
+    /// 굴절률 `index`인 매질의 경계. 면의 법선은 매질 바깥을 향한다.
+    Refract {
+        index: f64,
+    },
+    /// 이상적인 얇은 렌즈. 광선의 높이에 비례해 꺾는다.
+    ThinLens {
+        center: NVec2,
+        axis: NVec2,
+        focal_length: f64,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum SurfaceShape {
+    /// 법선은 (b - a)를 시계 방향으로 90도 돌린 방향이다.
+    Segment { a: NVec2, b: NVec2 },
+    /// `center`에서 `mid` 방향으로 ±`half_angle` 만큼 펼쳐진 호. 법선은 중심에서 멀어지는 방향이다.
+    Arc {
+        center: NVec2,
+        radius: f64,
+        mid: NVec2,
+        half_angle: f64,
+    },
+}
+
+/// 광선과 부딪히는 면 하나.
+#[derive(Clone, Copy, Debug)]
+pub struct Surface {
+    pub shape: SurfaceShape,
+    pub interaction: Interaction,
+    /// 이 면을 만든 소자의 인덱스
+    pub element: usize,
+}
+
+pub struct SurfaceHit {
+    pub distance: f64,
+    pub point: NVec2,
+    pub normal: NVec2,
+}
+
+fn perp(v: NVec2) -> NVec2 {
+    NVec2::new(-v.y, v.x)
+}
+
+fn cross(a: NVec2, b: NVec2) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+impl Surface {
+    /// 시작점에서 `min_distance`보다 먼 곳에서 가장 먼저 만나는 점.
+    pub fn intersect(
+        &self,
+        origin: NVec2,
+        direction: NVec2,
+        min_distance: f64,
+    ) -> Option<SurfaceHit> {
+        match self.shape {
+            SurfaceShape::Segment { a, b } => {
+                let edge = b - a;
+                let denominator = cross(direction, edge);
+
+                if denominator.abs() < f64::EPSILON {
+                    return None;
+                }
+
+                let t = cross(a - origin, edge) / denominator;
+                let s = cross(a - origin, direction) / denominator;
+
+                if t > min_distance && (0.0..=1.0).contains(&s) {
+                    Some(SurfaceHit {
+                        distance: t,
+                        point: origin + direction * t,
+                        normal: -perp(edge).normalize(),
+                    })
+                } else {
+                    None
+                }
+            }
+            SurfaceShape::Arc {
+                center,
+                radius,
+                mid,
+                half_angle,
+            } => {
+                // |o + td - c|² = r²
+                let offset = origin - center;
+                let b = offset.dot(&direction);
+                let c = offset.norm_squared() - radius * radius;
+                let discriminant = b * b - c;
+
+                if discriminant < 0.0 {
+                    return None;
+                }
+
+                let root = discriminant.sqrt();
+
+                [-b - root, -b + root]
+                    .into_iter()
+                    .filter(|t| *t > min_distance)
+                    .map(|t| (t, origin + direction * t))
+                    .find(|(_, point)| {
+                        let radial = point - center;
+                        cross(mid, radial).atan2(mid.dot(&radial)).abs() <= half_angle
+                    })
+                    .map(|(t, point)| SurfaceHit {
+                        distance: t,
+                        point,
+                        normal: (point - center) / radius,
+                    })
+            }
+        }
+    }
+
+    pub fn points(&self) -> Vec<NVec2> {
+        match self.shape {
+            SurfaceShape::Segment { a, b } => vec![a, b],
+            SurfaceShape::Arc {
+                center,
+                radius,
+                mid,
+                half_angle,
+            } => arc_points(center, radius, mid, half_angle),
+        }
+    }
+}
+
+fn arc_points(center: NVec2, radius: f64, mid: NVec2, half_angle: f64) -> Vec<NVec2> {
+    let mid_angle = mid.y.atan2(mid.x);
+
+    (0..=ARC_RESOLUTION)
+        .map(|i| {
+            let angle =
+                mid_angle - half_angle + 2.0 * half_angle * i as f64 / ARC_RESOLUTION as f64;
+            center + NVec2::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+// 닫힌 도형의 변. 법선이 `inside`의 반대쪽을 향하도록 순서를 맞춘다.
+fn solid_segment(a: NVec2, b: NVec2, inside: NVec2) -> SurfaceShape {
+    if (-perp(b - a)).dot(&(a - inside)) >= 0.0 {
+        SurfaceShape::Segment { a, b }
+    } else {
+        SurfaceShape::Segment { a: b, b: a }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ElementKind {
+    PlaneMirror {
+        length: f64,
+    },
+    /// `radius` > 0 이면 오목 거울, < 0 이면 볼록 거울
+    CurvedMirror {
+        radius: f64,
+        aperture: f64,
+    },
+    /// `focal_length` > 0 이면 볼록 렌즈, < 0 이면 오목 렌즈
+    ThinLens {
+        focal_length: f64,
+        aperture: f64,
+    },
+    /// 양면의 곡률 반지름이 같은 양볼록 렌즈
+    ThickLens {
+        radius: f64,
+        thickness: f64,
+        aperture: f64,
+        index: f64,
+    },
+    /// 정삼각형 프리즘
+    Prism {
+        size: f64,
+        index: f64,
+    },
+}
+
+impl Default for ElementKind {
+    fn default() -> Self {
+        Self::ThinLens {
+            focal_length: 25.0,
+            aperture: 40.0,
+        }
+    }
+}
+
+impl ElementKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::PlaneMirror { .. } => "Plane Mirror",
+            Self::CurvedMirror { .. } => "Curved Mirror",
+            Self::ThinLens { .. } => "Thin Lens",
+            Self::ThickLens { .. } => "Thick Lens",
+            Self::Prism { .. } => "Prism",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Plane Mirror" => Self::PlaneMirror { length: 40.0 },
+            "Curved Mirror" => Self::CurvedMirror {
+                radius: 60.0,
+                aperture: 40.0,
+            },
+            "Thick Lens" => Self::ThickLens {
+                radius: 40.0,
+                thickness: 12.0,
+                aperture: 36.0,
+                index: 1.5,
+            },
+            "Prism" => Self::Prism {
+                size: 40.0,
+                index: 1.5,
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// 광학 소자. `angle`은 광축(거울은 바라보는 방향)의 각도이다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpticalElement {
+    pub name: String,
+    pub position: NVec2,
+    pub angle: f64,
+    pub kind: ElementKind,
+}
+
+impl OpticalElement {
+    pub fn new(name: impl Into<String>, position: NVec2, angle: f64, kind: ElementKind) -> Self {
+        Self {
+            name: name.into(),
+            position,
+            angle,
+            kind,
+        }
+    }
+
+    pub fn axis(&self) -> NVec2 {
+        NVec2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    // 광축 좌표 (u: 광축 방향, v: 옆 방향)를 월드 좌표로 바꾼다.
+    fn local(&self, u: f64, v: f64) -> NVec2 {
+        let axis = self.axis();
+        self.position + axis * u + perp(axis) * v
+    }
+
+    pub fn is_mirror(&self) -> bool {
+        matches!(
+            self.kind,
+            ElementKind::PlaneMirror { .. } | ElementKind::CurvedMirror { .. }
+        )
+    }
+
+    /// 초점 거리. 평면 거울과 프리즘은 None
+    pub fn focal_length(&self) -> Option<f64> {
+        match self.kind {
+            ElementKind::CurvedMirror { radius, .. } => Some(radius / 2.0),
+            ElementKind::ThinLens { focal_length, .. } => Some(focal_length),
+            ElementKind::ThickLens { index, .. } => {
+                let (radius, thickness) = self.thick_lens_shape();
+
+                // 렌즈 제작자 공식, R1 = R, R2 = -R
+                // 1/f = (n-1) [2/R - (n-1)d / (nR²)]
+                let power = (index - 1.0)
+                    * (2.0 / radius - (index - 1.0) * thickness / (index * radius * radius));
+
+                Some(1.0 / power)
+            }
+            ElementKind::PlaneMirror { .. } | ElementKind::Prism { .. } => None,
+        }
+    }
+
+    pub fn index(&self) -> Option<f64> {
+        match self.kind {
+            ElementKind::ThickLens { index, .. } | ElementKind::Prism { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    // 가장자리 두께가 음수가 되지 않도록 맞춘 (반지름, 중심 두께)
+    fn thick_lens_shape(&self) -> (f64, f64) {
+        let ElementKind::ThickLens {
+            radius,
+            thickness,
+            aperture,
+            ..
+        } = self.kind
+        else {
+            return (0.0, 0.0);
+        };
+
+        let radius = radius.max(aperture / 2.0);
+        let sag = radius - (radius * radius - aperture * aperture / 4.0).sqrt();
+
+        (radius, thickness.max(2.0 * sag))
+    }
+
+    fn prism_vertices(&self) -> [NVec2; 3] {
+        let ElementKind::Prism { size, .. } = self.kind else {
+            return [self.position; 3];
+        };
+
+        let circumradius = size / 3f64.sqrt();
+
+        [90f64, 210.0, 330.0].map(|degree| {
+            let angle = self.angle + degree.to_radians();
+            self.position + NVec2::new(angle.cos(), angle.sin()) * circumradius
+        })
+    }
+
+    /// 광선 추적에 쓰는 면들. `element`는 이 소자의 인덱스.
+    pub fn surfaces(&self, element: usize) -> Vec<Surface> {
+        let axis = self.axis();
+
+        let shapes = match self.kind {
+            ElementKind::PlaneMirror { length } => {
+                vec![(
+                    SurfaceShape::Segment {
+                        a: self.local(0.0, -length / 2.0),
+                        b: self.local(0.0, length / 2.0),
+                    },
+                    Interaction::Mirror,
+                )]
+            }
+            ElementKind::CurvedMirror { radius, aperture } => {
+                let half_angle = (aperture / 2.0 / radius.abs()).min(1.0).asin();
+
+                vec![(
+                    SurfaceShape::Arc {
+                        center: self.position + axis * radius,
+                        radius: radius.abs(),
+                        mid: -axis * radius.signum(),
+                        half_angle,
+                    },
+                    Interaction::Mirror,
+                )]
+            }
+            ElementKind::ThinLens {
+                focal_length,
+                aperture,
+            } => {
+                vec![(
+                    SurfaceShape::Segment {
+                        a: self.local(0.0, -aperture / 2.0),
+                        b: self.local(0.0, aperture / 2.0),
+                    },
+                    Interaction::ThinLens {
+                        center: self.position,
+                        axis,
+                        focal_length,
+                    },
+                )]
+            }
+            ElementKind::ThickLens {
+                aperture, index, ..
+            } => {
+                let (radius, thickness) = self.thick_lens_shape();
+                let half = aperture / 2.0;
+                let half_angle = (half / radius).min(1.0).asin();
+                let sag = radius - (radius * radius - half * half).sqrt();
+                let edge = thickness / 2.0 - sag;
+                let interaction = Interaction::Refract { index };
+
+                let mut shapes = vec![
+                    (
+                        SurfaceShape::Arc {
+                            center: self.local(-thickness / 2.0 + radius, 0.0),
+                            radius,
+                            mid: -axis,
+                            half_angle,
+                        },
+                        interaction,
+                    ),
+                    (
+                        SurfaceShape::Arc {
+                            center: self.local(thickness / 2.0 - radius, 0.0),
+                            radius,
+                            mid: axis,
+                            half_angle,
+                        },
+                        interaction,
+                    ),
+                ];
+
+                if edge > 0.0 {
+                    for v in [-half, half] {
+                        shapes.push((
+                            solid_segment(self.local(-edge, v), self.local(edge, v), self.position),
+                            interaction,
+                        ));
+                    }
+                }
+
+                shapes
+            }
+            ElementKind::Prism { index, .. } => {
+                let [a, b, c] = self.prism_vertices();
+
+                [(a, b), (b, c), (c, a)]
+                    .into_iter()
+                    .map(|(p, q)| {
+                        (
+                            solid_segment(p, q, self.position),
+                            Interaction::Refract { index },
+                        )
+                    })
+                    .collect()
+            }
+        };
+
+        shapes
+            .into_iter()
+            .map(|(shape, interaction)| Surface {
+                shape,
+                interaction,
+                element,
+            })
+            .collect()
+    }
+
+    /// 선택, 그리기에 쓰는 외곽선
+    pub fn outline(&self) -> Vec<NVec2> {
+        const HALF_WIDTH: f64 = 1.5;
+
+        match self.kind {
+            ElementKind::PlaneMirror { length: extent }
+            | ElementKind::ThinLens {
+                aperture: extent, ..
+            } => vec![
+                self.local(-HALF_WIDTH, -extent / 2.0),
+                self.local(HALF_WIDTH, -extent / 2.0),
+                self.local(HALF_WIDTH, extent / 2.0),
+                self.local(-HALF_WIDTH, extent / 2.0),
+            ],
+            ElementKind::CurvedMirror { .. } => {
+                let arc = self.surfaces(0)[0].points();
+                let axis = self.axis();
+
+                arc.iter()
+                    .map(|p| p + axis * HALF_WIDTH)
+                    .chain(arc.iter().rev().map(|p| p - axis * HALF_WIDTH))
+                    .collect()
+            }
+            ElementKind::ThickLens { .. } => {
+                let surfaces = self.surfaces(0);
+                let mut points = surfaces[0].points();
+                points.extend(surfaces[1].points());
+                points
+            }
+            ElementKind::Prism { .. } => self.prism_vertices().to_vec(),
+        }
+    }
+}
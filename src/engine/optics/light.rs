@@ -0,0 +1,115 @@
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+
+/// 백색광을 나눌 파장들 (nm)
+pub const WHITE_WAVELENGTHS: [f64; 7] = [400.0, 450.0, 500.0, 550.0, 600.0, 650.0, 700.0];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SourceKind {
+    /// 폭이 `width`인 평행광
+    Beam { width: f64 },
+    /// `spread`도 만큼 퍼지는 점광원
+    Point { spread: f64 },
+}
+
+impl Default for SourceKind {
+    fn default() -> Self {
+        Self::Point { spread: 30.0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Spectrum {
+    /// 단색광, 파장 (nm)
+    Mono(f64),
+    White,
+}
+
+impl Spectrum {
+    pub fn wavelengths(&self) -> Vec<f64> {
+        match self {
+            Spectrum::Mono(wavelength) => vec![*wavelength],
+            Spectrum::White => WHITE_WAVELENGTHS.to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LightSource {
+    pub name: String,
+    pub position: NVec2,
+    pub angle: f64,
+    pub kind: SourceKind,
+    pub rays: usize,
+    pub spectrum: Spectrum,
+}
+
+impl Default for LightSource {
+    fn default() -> Self {
+        Self {
+            name: "Light".to_string(),
+            position: NVec2::zeros(),
+            angle: 0.0,
+            kind: SourceKind::default(),
+            rays: 9,
+            spectrum: Spectrum::Mono(550.0),
+        }
+    }
+}
+
+impl LightSource {
+    pub fn direction(&self) -> NVec2 {
+        NVec2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    /// 광원에서 나가는 광선들의 (시작점, 방향)
+    pub fn rays(&self) -> Vec<(NVec2, NVec2)> {
+        let count = self.rays.max(1);
+        // 광선이 하나면 가운데로 보낸다.
+        let ratio = |i: usize| {
+            if count == 1 {
+                0.0
+            } else {
+                i as f64 / (count - 1) as f64 - 0.5
+            }
+        };
+
+        let direction = self.direction();
+
+        match self.kind {
+            SourceKind::Beam { width } => {
+                let lateral = NVec2::new(-direction.y, direction.x);
+
+                (0..count)
+                    .map(|i| (self.position + lateral * width * ratio(i), direction))
+                    .collect()
+            }
+            SourceKind::Point { spread } => (0..count)
+                .map(|i| {
+                    let angle = self.angle + spread.to_radians() * ratio(i);
+                    (self.position, NVec2::new(angle.cos(), angle.sin()))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        const HALF_SIZE: f64 = 2.5;
+
+        let direction = self.direction();
+        let lateral = NVec2::new(-direction.y, direction.x);
+
+        let half_width = match self.kind {
+            SourceKind::Beam { width } => (width / 2.0).max(HALF_SIZE),
+            SourceKind::Point { .. } => HALF_SIZE,
+        };
+
+        vec![
+            self.position - direction * HALF_SIZE * 2.0 - lateral * half_width,
+            self.position - lateral * half_width,
+            self.position + direction * HALF_SIZE,
+            self.position + lateral * half_width,
+            self.position - direction * HALF_SIZE * 2.0 + lateral * half_width,
+        ]
+    }
+}
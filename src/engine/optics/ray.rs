@@ -0,0 +1,286 @@
+use crate::engine::optics::element::{Interaction, Surface, SurfaceHit};
+use crate::engine::NVec2;
+use nalgebra::Matrix2;
+
+/// 아무것에도 닿지 않은 광선을 그릴 길이
+pub const RAY_LENGTH: f64 = 1000.0;
+
+const MIN_DISTANCE: f64 = 1e-6;
+
+/// 코시 분산식 n(λ) = n_d + B (1/λ² - 1/λ_d²) 의 B (µm²)
+const CAUCHY_B: f64 = 0.0042;
+const D_LINE: f64 = 0.5893;
+
+/// 소자의 굴절률은 나트륨 D선 기준이다. 다른 파장에서의 굴절률.
+pub fn dispersed_index(index: f64, wavelength: f64) -> f64 {
+    let wavelength = wavelength / 1000.0;
+    index + CAUCHY_B * (1.0 / (wavelength * wavelength) - 1.0 / (D_LINE * D_LINE))
+}
+
+pub fn reflect(direction: NVec2, normal: NVec2) -> NVec2 {
+    direction - normal * 2.0 * direction.dot(&normal)
+}
+
+/// 스넬의 법칙. `normal`은 굴절률 `inside`인 매질 바깥을 향한다. 전반사라면 None
+pub fn refract(direction: NVec2, normal: NVec2, outside: f64, inside: f64) -> Option<NVec2> {
+    let (normal, eta) = if direction.dot(&normal) < 0.0 {
+        (normal, outside / inside)
+    } else {
+        (-normal, inside / outside)
+    };
+
+    let cos_i = -normal.dot(&direction);
+    let k = 1.0 - eta * eta * (1.0 - cos_i * cos_i);
+
+    if k < 0.0 {
+        None
+    } else {
+        Some(direction * eta + normal * (eta * cos_i - k.sqrt()))
+    }
+}
+
+/// 얇은 렌즈를 지나는 광선. 광축 기준 기울기가 h/f 만큼 바뀐다.
+pub fn thin_lens(
+    direction: NVec2,
+    point: NVec2,
+    center: NVec2,
+    axis: NVec2,
+    focal_length: f64,
+) -> NVec2 {
+    let lateral = NVec2::new(-axis.y, axis.x);
+    let height = (point - center).dot(&lateral);
+
+    let along = direction.dot(&axis);
+    let slope = direction.dot(&lateral) / along.abs() - height / focal_length;
+
+    (axis * along.signum() + lateral * slope).normalize()
+}
+
+/// 광선 하나가 지나간 경로.
+#[derive(Clone, Debug)]
+pub struct RayPath {
+    pub points: Vec<NVec2>,
+    /// `points[i + 1]`에서 부딪힌 소자의 인덱스
+    pub hits: Vec<usize>,
+    pub wavelength: f64,
+    pub source: usize,
+    pub total_internal_reflections: usize,
+}
+
+impl RayPath {
+    pub fn trace(
+        origin: NVec2,
+        direction: NVec2,
+        wavelength: f64,
+        source: usize,
+        surfaces: &[Surface],
+        ambient_index: f64,
+        max_bounces: usize,
+    ) -> Self {
+        let mut path = Self {
+            points: vec![origin],
+            hits: vec![],
+            wavelength,
+            source,
+            total_internal_reflections: 0,
+        };
+
+        let mut origin = origin;
+        let mut direction = direction.normalize();
+
+        for _ in 0..max_bounces {
+            let nearest = surfaces
+                .iter()
+                .filter_map(|surface| {
+                    surface
+                        .intersect(origin, direction, MIN_DISTANCE)
+                        .map(|hit| (surface, hit))
+                })
+                .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance));
+
+            let Some((surface, SurfaceHit { point, normal, .. })) = nearest else {
+                path.points.push(origin + direction * RAY_LENGTH);
+                return path;
+            };
+
+            path.points.push(point);
+            path.hits.push(surface.element);
+
+            direction = match surface.interaction {
+                Interaction::Mirror => reflect(direction, normal),
+                Interaction::Refract { index } => {
+                    let index = dispersed_index(index, wavelength);
+
+                    refract(direction, normal, ambient_index, index).unwrap_or_else(|| {
+                        path.total_internal_reflections += 1;
+                        reflect(direction, normal)
+                    })
+                }
+                Interaction::ThinLens {
+                    center,
+                    axis,
+                    focal_length,
+                } => thin_lens(direction, point, center, axis, focal_length),
+            };
+            origin = point;
+        }
+
+        path
+    }
+
+    /// 소자를 처음 빠져나온 뒤의 직선 (시작점, 방향)
+    pub fn exit_line(&self, element: usize) -> Option<(NVec2, NVec2)> {
+        let first = self.hits.iter().position(|hit| *hit == element)?;
+        let last = first
+            + self.hits[first..]
+                .iter()
+                .take_while(|hit| **hit == element)
+                .count()
+            - 1;
+
+        let start = *self.points.get(last + 1)?;
+        let end = *self.points.get(last + 2)?;
+
+        Some((start, (end - start).normalize()))
+    }
+}
+
+/// 여러 직선에 가장 가까운 점 (최소제곱). 직선이 거의 평행하면 None
+pub fn closest_point(lines: &[(NVec2, NVec2)]) -> Option<NVec2> {
+    if lines.len() < 2 {
+        return None;
+    }
+
+    let mut matrix = Matrix2::zeros();
+    let mut rhs = NVec2::zeros();
+
+    for (point, direction) in lines {
+        // 직선에 수직인 성분만 남기는 사영 행렬 I - ddᵀ
+        let projection = Matrix2::identity() - direction * direction.transpose();
+        matrix += projection;
+        rhs += projection * point;
+    }
+
+    if matrix.determinant().abs() < 1e-6 * (lines.len() * lines.len()) as f64 {
+        return None;
+    }
+
+    matrix.try_inverse().map(|inverse| inverse * rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::optics::element::{ElementKind, OpticalElement};
+    use crate::engine::optics::light::{LightSource, SourceKind};
+    use crate::engine::optics::template::OpticsTemplate;
+
+    fn trace_all(elements: &[OpticalElement], sources: &[LightSource]) -> Vec<RayPath> {
+        let surfaces = elements
+            .iter()
+            .enumerate()
+            .flat_map(|(index, element)| element.surfaces(index))
+            .collect::<Vec<_>>();
+
+        sources
+            .iter()
+            .enumerate()
+            .flat_map(|(index, source)| {
+                let surfaces = &surfaces;
+                source.rays().into_iter().map(move |(origin, direction)| {
+                    RayPath::trace(origin, direction, 589.3, index, surfaces, 1.0, 32)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn thin_lens_rays_cross_at_the_lens_equation_image() {
+        let (focal_length, distance, height) = (25.0, 60.0, 10.0);
+        let lens = OpticalElement::new(
+            "Lens",
+            NVec2::zeros(),
+            0.0,
+            ElementKind::ThinLens {
+                focal_length,
+                aperture: 80.0,
+            },
+        );
+        let source = LightSource {
+            position: NVec2::new(-distance, height),
+            kind: SourceKind::Point { spread: 30.0 },
+            ..LightSource::default()
+        };
+
+        let lines = trace_all(&[lens], &[source])
+            .iter()
+            .filter_map(|path| path.exit_line(0))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 9);
+
+        // 1/f = 1/a + 1/b, 배율 -b/a
+        let image_distance = 1.0 / (1.0 / focal_length - 1.0 / distance);
+        let image = NVec2::new(image_distance, -height * image_distance / distance);
+
+        let traced = closest_point(&lines).unwrap();
+        assert!((traced - image).norm() < 1e-9, "{} vs {}", traced, image);
+    }
+
+    #[test]
+    fn light_is_totally_reflected_past_the_critical_angle() {
+        let (glass, air) = (1.5f64, 1.0);
+        let critical = (air / glass).asin();
+        // 유리 안에서 바깥(+y)으로 나가는 면. 법선은 바깥을 향한다.
+        let normal = NVec2::new(0.0, 1.0);
+        let ray = |angle: f64| NVec2::new(angle.sin(), angle.cos());
+
+        let below = refract(ray(critical - 0.01), normal, air, glass).unwrap();
+        let sine = (critical - 0.01).sin() * glass;
+        assert!((below.x - sine).abs() < 1e-12);
+        assert!(below.y > 0.0);
+
+        assert!(refract(ray(critical + 0.01), normal, air, glass).is_none());
+
+        // 템플릿의 레이저는 프리즘의 아랫면에서 전반사된다.
+        let preset = OpticsTemplate::PrismSim.get_preset();
+        let paths = trace_all(&preset.elements[1..], &preset.sources[1..]);
+        assert!(paths.iter().all(|path| path.total_internal_reflections > 0));
+    }
+
+    #[test]
+    fn thick_lens_rays_stay_finite() {
+        let preset = OpticsTemplate::ThickLensSim.get_preset();
+        let mut sources = preset.sources.clone();
+        // 가장자리를 스치거나 옆면에 닿는 광선까지 넣는다.
+        sources.push(LightSource {
+            position: NVec2::new(-80.0, 0.0),
+            kind: SourceKind::Beam { width: 60.0 },
+            rays: 61,
+            ..LightSource::default()
+        });
+
+        for (radius, thickness) in [(40.0, 12.0), (18.0, 0.0), (18.0, 40.0), (1000.0, 1.0)] {
+            let mut lens = preset.elements[0].clone();
+            if let ElementKind::ThickLens {
+                radius: r,
+                thickness: t,
+                ..
+            } = &mut lens.kind
+            {
+                (*r, *t) = (radius, thickness);
+            }
+
+            for path in trace_all(&[lens], &sources) {
+                assert!(
+                    path.points
+                        .iter()
+                        .all(|point| point.x.is_finite() && point.y.is_finite()),
+                    "radius {} thickness {}",
+                    radius,
+                    thickness
+                );
+                assert!(path.points.len() <= 34);
+            }
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpticsSettings {
+    pub(crate) max_bounces: usize,
+    /// 주변 매질의 굴절률 (공기 1.0, 물 1.33)
+    pub(crate) ambient_index: f64,
+    pub(crate) show_focal_points: bool,
+}
+
+impl Default for OpticsSettings {
+    fn default() -> Self {
+        Self {
+            max_bounces: 32,
+            ambient_index: 1.0,
+            show_focal_points: true,
+        }
+    }
+}
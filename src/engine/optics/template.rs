@@ -0,0 +1,206 @@
+use crate::engine::optics::element::{ElementKind, OpticalElement};
+use crate::engine::optics::light::{LightSource, SourceKind, Spectrum};
+use crate::engine::optics::sim_state::OpticsSettings;
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+pub enum OpticsTemplate {
+    ThinLensSim,
+    CurvedMirrorSim,
+    ThickLensSim,
+    PrismSim,
+    /// 세션에 저장해 두었던 배치
+    Saved(Box<OpticsPreset>),
+}
+
+impl OpticsTemplate {
+    pub fn get_name(&self) -> String {
+        match self {
+            OpticsTemplate::Saved(_) => "Saved".to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    pub fn get_preset(self) -> OpticsPreset {
+        match self {
+            OpticsTemplate::ThinLensSim => thin_lens(),
+            OpticsTemplate::CurvedMirrorSim => curved_mirror(),
+            OpticsTemplate::ThickLensSim => thick_lens(),
+            OpticsTemplate::PrismSim => prism(),
+            OpticsTemplate::Saved(preset) => *preset,
+        }
+    }
+}
+
+pub fn get_optics_sim_list() -> [OpticsTemplate; 4] {
+    [
+        OpticsTemplate::ThinLensSim,
+        OpticsTemplate::CurvedMirrorSim,
+        OpticsTemplate::ThickLensSim,
+        OpticsTemplate::PrismSim,
+    ]
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OpticsPreset {
+    pub elements: Vec<OpticalElement>,
+    pub sources: Vec<LightSource>,
+    pub settings: OpticsSettings,
+}
+
+fn thin_lens() -> OpticsPreset {
+    OpticsPreset {
+        elements: vec![
+            OpticalElement::new(
+                "Convex Lens",
+                NVec2::zeros(),
+                0.0,
+                ElementKind::ThinLens {
+                    focal_length: 25.0,
+                    aperture: 40.0,
+                },
+            ),
+            OpticalElement::new(
+                "Concave Lens",
+                NVec2::new(60.0, -50.0),
+                0.0,
+                ElementKind::ThinLens {
+                    focal_length: -20.0,
+                    aperture: 30.0,
+                },
+            ),
+        ],
+        sources: vec![
+            LightSource {
+                name: "Object".to_string(),
+                position: NVec2::new(-60.0, 10.0),
+                kind: SourceKind::Point { spread: 30.0 },
+                ..LightSource::default()
+            },
+            LightSource {
+                name: "Beam".to_string(),
+                position: NVec2::new(0.0, -50.0),
+                kind: SourceKind::Beam { width: 20.0 },
+                rays: 7,
+                spectrum: Spectrum::Mono(650.0),
+                ..LightSource::default()
+            },
+        ],
+        ..OpticsPreset::default()
+    }
+}
+
+fn curved_mirror() -> OpticsPreset {
+    OpticsPreset {
+        elements: vec![OpticalElement::new(
+            "Concave Mirror",
+            NVec2::new(50.0, 0.0),
+            PI,
+            ElementKind::CurvedMirror {
+                radius: 60.0,
+                aperture: 50.0,
+            },
+        )],
+        sources: vec![
+            LightSource {
+                name: "Beam".to_string(),
+                position: NVec2::new(-80.0, 0.0),
+                kind: SourceKind::Beam { width: 30.0 },
+                spectrum: Spectrum::Mono(650.0),
+                ..LightSource::default()
+            },
+            LightSource {
+                name: "Object".to_string(),
+                position: NVec2::new(-30.0, 8.0),
+                kind: SourceKind::Point { spread: 24.0 },
+                rays: 7,
+                ..LightSource::default()
+            },
+        ],
+        ..OpticsPreset::default()
+    }
+}
+
+fn thick_lens() -> OpticsPreset {
+    OpticsPreset {
+        elements: vec![OpticalElement::new(
+            "Biconvex Lens",
+            NVec2::zeros(),
+            0.0,
+            ElementKind::ThickLens {
+                radius: 40.0,
+                thickness: 12.0,
+                aperture: 36.0,
+                index: 1.5,
+            },
+        )],
+        sources: vec![
+            LightSource {
+                name: "Beam".to_string(),
+                position: NVec2::new(-80.0, 0.0),
+                kind: SourceKind::Beam { width: 30.0 },
+                rays: 11,
+                spectrum: Spectrum::Mono(650.0),
+                ..LightSource::default()
+            },
+            LightSource {
+                name: "Object".to_string(),
+                position: NVec2::new(-70.0, 6.0),
+                // 렌즈 중심을 향하게 한다.
+                angle: (-6f64).atan2(70.0),
+                kind: SourceKind::Point { spread: 16.0 },
+                rays: 7,
+                ..LightSource::default()
+            },
+        ],
+        ..OpticsPreset::default()
+    }
+}
+
+fn prism() -> OpticsPreset {
+    OpticsPreset {
+        elements: vec![
+            OpticalElement::new(
+                "Dispersion Prism",
+                NVec2::new(0.0, 40.0),
+                0.0,
+                ElementKind::Prism {
+                    size: 40.0,
+                    index: 1.5,
+                },
+            ),
+            OpticalElement::new(
+                "Reflecting Prism",
+                NVec2::new(0.0, -70.0),
+                0.0,
+                ElementKind::Prism {
+                    size: 40.0,
+                    index: 1.5,
+                },
+            ),
+        ],
+        sources: vec![
+            LightSource {
+                name: "White Light".to_string(),
+                position: NVec2::new(-70.0, 35.0),
+                angle: 10f64.to_radians(),
+                kind: SourceKind::Beam { width: 0.0 },
+                rays: 1,
+                spectrum: Spectrum::White,
+            },
+            // 왼쪽 면에 수직으로 들어가 아랫면에서 전반사된다.
+            LightSource {
+                name: "Laser".to_string(),
+                position: NVec2::new(-49.6, -52.9),
+                angle: (-30f64).to_radians(),
+                kind: SourceKind::Beam { width: 4.0 },
+                rays: 3,
+                spectrum: Spectrum::Mono(650.0),
+            },
+        ],
+        ..OpticsPreset::default()
+    }
+}
@@ -0,0 +1,91 @@
+use crate::engine::NVec2;
+
+/// 점이 다각형 안에 있는지. 반직선과 변이 만나는 횟수로 판단한다.
+pub fn is_inside(pos: NVec2, shape_points: &[NVec2]) -> bool {
+    let mut contact = 0;
+    let closing_edge = shape_points.last().zip(shape_points.first());
+
+    for (p1, p2) in shape_points
+        .windows(2)
+        .map(|x| (&x[0], &x[1]))
+        .chain(closing_edge)
+    {
+        if (pos.y > p1.y) != (pos.y > p2.y) {
+            let at_x = (p2.x - p1.x) * (pos.y - p1.y) / (p2.y - p1.y) + p1.x;
+
+            if at_x > pos.x {
+                contact += 1;
+            }
+        }
+    }
+    contact % 2 > 0
+}
+
+/// 선분을 `half_width`만큼 부풀린 사각형. 길이가 0이면 정사각형이 된다.
+pub fn segment_outline(a: NVec2, b: NVec2, half_width: f64) -> Vec<NVec2> {
+    let along = b - a;
+    let direction = if along.norm() > 0.0 {
+        along.normalize()
+    } else {
+        NVec2::x()
+    };
+    let lateral = NVec2::new(-direction.y, direction.x) * half_width;
+    let direction = direction * half_width;
+
+    vec![
+        a - direction - lateral,
+        b + direction - lateral,
+        b + direction + lateral,
+        a - direction + lateral,
+    ]
+}
+
+/// 선분 `a`-`b` 위에서 `point`와 가장 가까운 점
+pub fn closest_on_segment(a: NVec2, b: NVec2, point: NVec2) -> NVec2 {
+    let along = b - a;
+    let t = if along.norm_squared() > 0.0 {
+        ((point - a).dot(&along) / along.norm_squared()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    a + along * t
+}
+
+/// 꺾은선을 `half_width`만큼 부풀린 띠. 오목할 수 있으므로 고르기에만 쓴다.
+pub fn polyline_outline(points: &[NVec2], half_width: f64) -> Vec<NVec2> {
+    if points.len() < 3 {
+        let a = points.first().copied().unwrap_or_else(NVec2::zeros);
+        return segment_outline(a, points.last().copied().unwrap_or(a), half_width);
+    }
+
+    let normals = points
+        .windows(2)
+        .map(|pair| {
+            let along = (pair[1] - pair[0])
+                .try_normalize(0.0)
+                .unwrap_or_else(NVec2::x);
+            NVec2::new(-along.y, along.x)
+        })
+        .collect::<Vec<_>>();
+
+    // 꼭짓점에서는 양쪽 선분의 법선을 평균 내고, 꺾인 만큼 늘린다.
+    let offsets = (0..points.len())
+        .map(|i| {
+            let before = normals[i.saturating_sub(1)];
+            let after = normals[i.min(normals.len() - 1)];
+            let normal = (before + after).try_normalize(0.0).unwrap_or(after);
+
+            normal * half_width / normal.dot(&after).max(0.3)
+        })
+        .collect::<Vec<_>>();
+
+    let left = points.iter().zip(&offsets).map(|(p, offset)| p + offset);
+    let right = points
+        .iter()
+        .zip(&offsets)
+        .rev()
+        .map(|(p, offset)| p - offset);
+
+    left.chain(right).collect()
+}
@@ -0,0 +1,51 @@
+use crate::engine::NVec2;
+
+/// 시뮬레이션의 시간과 스텝. 설정의 타입은 쓰는 쪽이 정한다.
+#[derive(Clone, Debug)]
+pub struct SimulationState<S> {
+    pub settings: S,
+    /// 플롯 좌표로 나타낸 포인터 위치
+    pub pointer: Option<NVec2>,
+    /// 재생 속도. 1이면 실제 시간과 같은 빠르기로 흐른다.
+    pub speed: f64,
+    pub time: f64,
+    pub current_step: usize,
+    pub max_step: usize,
+    pub sim_started: bool,
+
+    pub zoom: f64,
+    /// 플롯에 보이는 영역의 가장 작은 좌표와 가장 큰 좌표
+    pub view: [NVec2; 2],
+    /// 플롯의 한 단위가 화면에서 몇 점인지
+    pub pixels_per_unit: f64,
+}
+
+impl<S: Default> Default for SimulationState<S> {
+    fn default() -> Self {
+        Self {
+            pointer: None,
+            settings: Default::default(),
+            time: 0.0,
+            current_step: 0,
+            max_step: 0,
+            sim_started: false,
+            zoom: 1.0,
+            view: [NVec2::new(-100.0, -100.0), NVec2::new(100.0, 100.0)],
+            pixels_per_unit: 1.0,
+            speed: 1.0,
+        }
+    }
+}
+
+impl<S> SimulationState<S> {
+    pub fn is_sim_started(&self) -> bool {
+        self.sim_started
+    }
+
+    pub fn reset(&mut self) {
+        self.time = 0.0;
+        self.sim_started = false;
+        self.current_step = 0;
+        self.max_step = 0;
+    }
+}
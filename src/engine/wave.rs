@@ -0,0 +1,6 @@
+//! 물결통의 파동. 격자 위 변위, 파원과 벽의 배치, 설정과 템플릿이 있다.
+
+pub mod field;
+pub mod layout;
+pub mod sim_state;
+pub mod template;
@@ -0,0 +1,352 @@
+use crate::engine::grid::Grid;
+use crate::engine::keyframe::Stepped;
+use crate::engine::wave::layout::{Wall, WaveLayout, WaveSourceKind};
+use crate::engine::wave::sim_state::WaveSettings;
+use crate::engine::NVec2;
+
+/// 이보다 큰 쿠랑 수에서는 양해법이 발산한다. (2차원에서 1/√2)
+const MAX_COURANT: f64 = 0.7;
+
+/// 가장자리 흡수층의 두께 (칸)
+const SPONGE_CELLS: usize = 16;
+/// 흡수층 가장 바깥의 감쇠 계수 (1/s)
+const SPONGE_STRENGTH: f64 = 60.0;
+
+/// 세기를 평균낼 시간 (s)
+const INTENSITY_TIME: f64 = 1.0;
+
+/// 벽이 차지하는 칸
+pub fn wall_mask(grid: &Grid, walls: &[Wall]) -> Vec<bool> {
+    let mut mask = vec![false; grid.len()];
+
+    for cell in grid.cells_where(|position| {
+        walls
+            .iter()
+            .any(|wall| wall.distance(position) <= (wall.thickness + grid.cell) / 2.0)
+    }) {
+        mask[cell] = true;
+    }
+
+    mask
+}
+
+/// 파원이 차지하는 칸과 그 파원의 인덱스
+fn source_cells(grid: &Grid, layout: &WaveLayout) -> Vec<(usize, usize)> {
+    let mut cells = vec![];
+
+    for (index, source) in layout.sources.iter().enumerate() {
+        match source.kind {
+            WaveSourceKind::Point => {
+                cells.extend(grid.cell_of(source.position).map(|cell| (cell, index)));
+            }
+            WaveSourceKind::Line { length } => {
+                let (a, b) = source.endpoints();
+                let samples = (length / grid.cell * 2.0).ceil().max(1.0) as usize;
+
+                for i in 0..=samples {
+                    let point = a + (b - a) * (i as f64 / samples as f64);
+                    if let Some(cell) = grid.cell_of(point) {
+                        if !cells.contains(&(cell, index)) {
+                            cells.push((cell, index));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    cells
+}
+
+/// 칸마다의 감쇠 계수
+fn damping(grid: &Grid, settings: &WaveSettings) -> Vec<f64> {
+    let mut damping = vec![settings.damping; grid.len()];
+
+    if settings.absorbing_edges {
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let edge = x.min(y).min(grid.width - 1 - x).min(grid.height - 1 - y);
+
+                if edge < SPONGE_CELLS {
+                    let depth = (SPONGE_CELLS - edge) as f64 / SPONGE_CELLS as f64;
+                    damping[grid.index(x, y)] += SPONGE_STRENGTH * depth * depth;
+                }
+            }
+        }
+    }
+
+    damping
+}
+
+/// 스텝 하나를 몇 번에 나눠 계산할지. 설정값보다 적으면 안정 조건에 맞춰 늘린다.
+pub fn substeps(grid: &Grid, settings: &WaveSettings, dt: f64) -> usize {
+    let required = (settings.wave_speed * dt / (grid.cell * MAX_COURANT)).ceil() as usize;
+
+    settings.substeps.max(required).max(1)
+}
+
+pub fn courant(grid: &Grid, settings: &WaveSettings, dt: f64) -> f64 {
+    settings.wave_speed * dt / (substeps(grid, settings, dt) as f64 * grid.cell)
+}
+
+/// 어느 스텝에서의 격자 위 변위.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveField {
+    pub step: usize,
+    pub time: f64,
+    pub current: Vec<f32>,
+    pub previous: Vec<f32>,
+    /// 변위 제곱의 이동 평균
+    pub intensity: Vec<f32>,
+}
+
+impl Stepped for WaveField {
+    fn step(&self) -> usize {
+        self.step
+    }
+}
+
+impl WaveField {
+    pub fn new(grid: &Grid) -> Self {
+        Self {
+            step: 0,
+            time: 0.0,
+            current: vec![0.0; grid.len()],
+            previous: vec![0.0; grid.len()],
+            intensity: vec![0.0; grid.len()],
+        }
+    }
+
+    /// 파동 방정식 u_tt = c²∇²u - γu_t + s 를 leapfrog로 `dt`만큼 진행한다.
+    /// 벽은 변위가 0인 경계다. 파원은 변위를 더하기만 해서 돌아온 파동을 반사하지 않는다.
+    pub fn advance(
+        &mut self,
+        grid: &Grid,
+        layout: &WaveLayout,
+        mask: &[bool],
+        settings: &WaveSettings,
+        dt: f64,
+    ) {
+        let substeps = substeps(grid, settings, dt);
+        let dt = dt / substeps as f64;
+
+        let courant2 = (settings.wave_speed * dt / grid.cell).powi(2) as f32;
+        let damping = damping(grid, settings)
+            .into_iter()
+            .map(|gamma| (gamma * dt / 2.0) as f32)
+            .collect::<Vec<_>>();
+        let sources = source_cells(grid, layout);
+        // 선 파원이 한 칸에 q를 더하면 u = (h / 2c·dt²)∫q dt 인 평면파가 나간다.
+        // 진폭이 A이고 치우침이 없도록 q = 2c·dt²/h · d(A sin(ωt))/dt 를 더한다.
+        let source_gain = 2.0 * settings.wave_speed * dt * dt / grid.cell;
+        let averaging = (dt / INTENSITY_TIME) as f32;
+
+        let (width, height) = (grid.width, grid.height);
+
+        for _ in 0..substeps {
+            // 다음 변위는 같은 칸의 이전 변위만 쓰므로 `previous`에 바로 덮어쓴다.
+            for y in 0..height {
+                for x in 0..width {
+                    let i = y * width + x;
+
+                    if mask[i] {
+                        self.previous[i] = 0.0;
+                        continue;
+                    }
+
+                    let u = self.current[i];
+                    let left = if x > 0 { self.current[i - 1] } else { 0.0 };
+                    let right = if x + 1 < width {
+                        self.current[i + 1]
+                    } else {
+                        0.0
+                    };
+                    let down = if y > 0 { self.current[i - width] } else { 0.0 };
+                    let up = if y + 1 < height {
+                        self.current[i + width]
+                    } else {
+                        0.0
+                    };
+
+                    let laplacian = left + right + down + up - 4.0 * u;
+                    let g = damping[i];
+
+                    self.previous[i] =
+                        (2.0 * u - (1.0 - g) * self.previous[i] + courant2 * laplacian) / (1.0 + g);
+                }
+            }
+
+            std::mem::swap(&mut self.current, &mut self.previous);
+            self.time += dt;
+
+            for (cell, index) in &sources {
+                if !mask[*cell] {
+                    self.current[*cell] +=
+                        (source_gain * layout.sources[*index].rate(self.time)) as f32;
+                }
+            }
+
+            for (intensity, u) in self.intensity.iter_mut().zip(&self.current) {
+                *intensity += (u * u - *intensity) * averaging;
+            }
+        }
+
+        self.step += 1;
+    }
+
+    pub fn value_at(&self, grid: &Grid, point: NVec2) -> Option<(f64, f64)> {
+        grid.cell_of(point)
+            .map(|cell| (self.current[cell] as f64, self.intensity[cell] as f64))
+    }
+
+    pub fn max_displacement(&self) -> f64 {
+        self.current.iter().fold(0.0f32, |max, u| max.max(u.abs())) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SIMULATION_TICK;
+    use std::f64::consts::PI;
+
+    fn square(cells: usize) -> Grid {
+        Grid::new(NVec2::zeros(), NVec2::new(cells as f64, cells as f64), 1.0)
+    }
+
+    // 감쇠와 흡수층이 없어 가장자리에서 변위가 0인 매질
+    fn still_medium(wave_speed: f64) -> WaveSettings {
+        WaveSettings {
+            wave_speed,
+            damping: 0.0,
+            absorbing_edges: false,
+            substeps: 1,
+            ..WaveSettings::default()
+        }
+    }
+
+    fn with_shape(grid: &Grid, shape: impl Fn(NVec2) -> f64) -> WaveField {
+        let mut field = WaveField::new(grid);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let value = shape(grid.position(x, y)) as f32;
+                field.current[grid.index(x, y)] = value;
+                field.previous[grid.index(x, y)] = value;
+            }
+        }
+        field
+    }
+
+    // leapfrog가 그대로 지키는 이산 에너지. 운동 에너지와, 이웃 칸 차이를 앞뒤 변위로 곱한 퍼텐셜 에너지.
+    // 가장자리 바깥은 변위가 0인 칸으로 본다.
+    fn energy(field: &WaveField, grid: &Grid, settings: &WaveSettings) -> f64 {
+        let dt = SIMULATION_TICK / substeps(grid, settings, SIMULATION_TICK) as f64;
+        let (now, before) = (&field.current, &field.previous);
+        let value = |u: &[f32], x: isize, y: isize| {
+            let inside =
+                (0..grid.width as isize).contains(&x) && (0..grid.height as isize).contains(&y);
+            if inside {
+                u[grid.index(x as usize, y as usize)] as f64
+            } else {
+                0.0
+            }
+        };
+
+        let mut kinetic = 0.0;
+        let mut potential = 0.0;
+        for y in -1..grid.height as isize {
+            for x in -1..grid.width as isize {
+                let u = (value(now, x, y), value(before, x, y));
+                if x >= 0 && y >= 0 {
+                    kinetic += ((u.0 - u.1) / dt).powi(2);
+                }
+                for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                    let v = (value(now, nx, ny), value(before, nx, ny));
+                    potential += (u.0 - v.0) * (u.1 - v.1);
+                }
+            }
+        }
+
+        (kinetic + (settings.wave_speed / grid.cell).powi(2) * potential) / 2.0
+    }
+
+    #[test]
+    fn substeps_keep_the_courant_number_stable() {
+        let grid = square(32);
+        let layout = WaveLayout::default();
+        let mask = vec![false; grid.len()];
+
+        for wave_speed in [10.0, 60.0, 400.0, 3000.0] {
+            let settings = still_medium(wave_speed);
+            assert!(courant(&grid, &settings, SIMULATION_TICK) <= MAX_COURANT);
+
+            let mut field =
+                with_shape(&grid, |p| (-(p - grid.center()).norm_squared() / 8.0).exp());
+            for _ in 0..120 {
+                field.advance(&grid, &layout, &mask, &settings, SIMULATION_TICK);
+            }
+
+            assert!(field.current.iter().all(|u| u.is_finite()));
+            assert!(field.max_displacement() < 1.0, "{}", wave_speed);
+        }
+    }
+
+    #[test]
+    fn energy_is_conserved_without_damping() {
+        let grid = square(40);
+        let settings = still_medium(60.0);
+        let layout = WaveLayout::default();
+        let mask = vec![false; grid.len()];
+
+        let mut field = with_shape(&grid, |p| {
+            (-(p - NVec2::new(15.0, 22.0)).norm_squared() / 10.0).exp()
+        });
+        field.advance(&grid, &layout, &mask, &settings, SIMULATION_TICK);
+        let start = energy(&field, &grid, &settings);
+
+        for _ in 0..600 {
+            field.advance(&grid, &layout, &mask, &settings, SIMULATION_TICK);
+
+            let now = energy(&field, &grid, &settings);
+            assert!((now - start).abs() < 1e-3 * start, "{} vs {}", now, start);
+        }
+    }
+
+    #[test]
+    fn standing_wave_has_the_analytic_period() {
+        let grid = square(40);
+        let settings = still_medium(60.0);
+        let layout = WaveLayout::default();
+        let mask = vec![false; grid.len()];
+
+        // 가장자리 바깥 칸에서 0이 되는 (1, 1) 모드. 한 변의 길이는 41칸이다.
+        let length = (grid.width + 1) as f64 * grid.cell;
+        let mut field = with_shape(&grid, |p| {
+            (PI * (p.x + 0.5) / length).sin() * (PI * (p.y + 0.5) / length).sin()
+        });
+        let period = 2.0 * length / (settings.wave_speed * 2f64.sqrt());
+
+        let center = grid.cell_of(grid.center()).unwrap();
+        let mut crossings = vec![];
+        let mut last = field.current[center] as f64;
+
+        while field.time < 3.0 * period {
+            field.advance(&grid, &layout, &mask, &settings, SIMULATION_TICK);
+
+            let now = field.current[center] as f64;
+            if last.signum() != now.signum() {
+                crossings.push(field.time - SIMULATION_TICK * now / (now - last));
+            }
+            last = now;
+        }
+
+        assert_eq!(crossings.len(), 6);
+        let measured = (crossings[5] - crossings[1]) / 2.0;
+        assert!(
+            (measured - period).abs() < 0.01 * period,
+            "{} vs {}",
+            measured,
+            period
+        );
+    }
+}
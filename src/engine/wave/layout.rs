@@ -0,0 +1,151 @@
+use crate::engine::polygon::{closest_on_segment, segment_outline};
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WaveSourceKind {
+    /// 한 점에서 원형으로 퍼지는 파원
+    Point,
+    /// 길이가 `length`인 선 파원. 평면파를 만든다.
+    Line { length: f64 },
+}
+
+impl Default for WaveSourceKind {
+    fn default() -> Self {
+        Self::Point
+    }
+}
+
+/// A sin(2πft + φ)로 진동하며 진폭이 A인 파동을 내보내는 파원.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveSource {
+    pub name: String,
+    pub position: NVec2,
+    pub angle: f64,
+    pub kind: WaveSourceKind,
+    /// 진동수 (Hz)
+    pub frequency: f64,
+    pub amplitude: f64,
+    /// 위상 (rad)
+    pub phase: f64,
+}
+
+impl Default for WaveSource {
+    fn default() -> Self {
+        Self {
+            name: "Source".to_string(),
+            position: NVec2::zeros(),
+            angle: 0.0,
+            kind: WaveSourceKind::default(),
+            frequency: 3.0,
+            amplitude: 1.0,
+            phase: 0.0,
+        }
+    }
+}
+
+impl WaveSource {
+    pub fn value(&self, time: f64) -> f64 {
+        self.amplitude * (TAU * self.frequency * time + self.phase).sin()
+    }
+
+    /// `value`의 시간 미분
+    pub fn rate(&self, time: f64) -> f64 {
+        let omega = TAU * self.frequency;
+
+        self.amplitude * omega * (omega * time + self.phase).cos()
+    }
+
+    /// 선 파원의 양 끝점. 점 파원이면 같은 점 두 개
+    pub fn endpoints(&self) -> (NVec2, NVec2) {
+        match self.kind {
+            WaveSourceKind::Point => (self.position, self.position),
+            WaveSourceKind::Line { length } => {
+                let half = NVec2::new(self.angle.cos(), self.angle.sin()) * length / 2.0;
+                (self.position - half, self.position + half)
+            }
+        }
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        const HALF_SIZE: f64 = 2.0;
+
+        let (a, b) = self.endpoints();
+        segment_outline(a, b, HALF_SIZE)
+    }
+}
+
+/// 파동을 반사하는 벽. 선분에 두께를 준 모양이다.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Wall {
+    pub name: String,
+    pub start: NVec2,
+    pub end: NVec2,
+    pub thickness: f64,
+}
+
+impl Wall {
+    pub fn new(name: impl Into<String>, start: NVec2, end: NVec2, thickness: f64) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            end,
+            thickness,
+        }
+    }
+
+    /// `x = x` 위에 `(중심 y, 폭)`의 틈들을 남기고 `y_range`를 막는 벽들.
+    pub fn barrier_with_slits(
+        name: &str,
+        x: f64,
+        y_range: (f64, f64),
+        slits: &[(f64, f64)],
+        thickness: f64,
+    ) -> Vec<Self> {
+        let mut edges = vec![y_range.0];
+        for (center, width) in slits {
+            edges.push(center - width / 2.0);
+            edges.push(center + width / 2.0);
+        }
+        edges.push(y_range.1);
+
+        edges
+            .chunks(2)
+            .filter(|pair| pair[1] > pair[0])
+            .enumerate()
+            .map(|(i, pair)| {
+                Self::new(
+                    format!("{} {}", name, i),
+                    NVec2::new(x, pair[0]),
+                    NVec2::new(x, pair[1]),
+                    thickness,
+                )
+            })
+            .collect()
+    }
+
+    pub fn center(&self) -> NVec2 {
+        (self.start + self.end) / 2.0
+    }
+
+    pub fn length(&self) -> f64 {
+        (self.end - self.start).norm()
+    }
+
+    /// 벽의 중심선에서 `point`까지의 거리
+    pub fn distance(&self, point: NVec2) -> f64 {
+        (point - closest_on_segment(self.start, self.end, point)).norm()
+    }
+
+    pub fn outline(&self) -> Vec<NVec2> {
+        segment_outline(self.start, self.end, self.thickness / 2.0)
+    }
+}
+
+/// 파원과 벽의 배치. 타임라인에는 바뀐 스텝의 배치만 저장한다.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaveLayout {
+    pub sources: Vec<WaveSource>,
+    pub walls: Vec<Wall>,
+}
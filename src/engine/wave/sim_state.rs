@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// 플롯에 그릴 값
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaveView {
+    /// 순간 변위
+    #[default]
+    Displacement,
+    /// 시간 평균한 세기. 간섭 무늬가 잘 보인다.
+    Intensity,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WaveSettings {
+    /// 파동의 속력 (단위/s)
+    pub(crate) wave_speed: f64,
+    /// 매질 전체의 감쇠 계수 (1/s)
+    pub(crate) damping: f64,
+    /// 가장자리에서 파동을 흡수해 반사를 없앤다.
+    pub(crate) absorbing_edges: bool,
+    /// 스텝 하나를 나눠서 계산하는 횟수
+    pub(crate) substeps: usize,
+    pub(crate) view: WaveView,
+    /// 색을 칠할 때 곱하는 값
+    pub(crate) contrast: f64,
+}
+
+impl Default for WaveSettings {
+    fn default() -> Self {
+        Self {
+            wave_speed: 60.0,
+            damping: 0.0,
+            absorbing_edges: true,
+            substeps: 2,
+            view: WaveView::default(),
+            contrast: 1.0,
+        }
+    }
+}
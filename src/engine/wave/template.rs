@@ -0,0 +1,155 @@
+use crate::engine::grid::Grid;
+use crate::engine::wave::layout::{Wall, WaveLayout, WaveSource, WaveSourceKind};
+use crate::engine::wave::sim_state::{WaveSettings, WaveView};
+use crate::engine::NVec2;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_2;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Debug)]
+pub enum WaveTemplate {
+    TwoSourceSim,
+    SingleSlitSim,
+    DoubleSlitSim,
+    ReflectionSim,
+    /// 세션에 저장해 두었던 배치
+    Saved(Box<WavePreset>),
+}
+
+impl WaveTemplate {
+    pub fn get_name(&self) -> String {
+        match self {
+            WaveTemplate::Saved(_) => "Saved".to_string(),
+            _ => format!("{:?}", self),
+        }
+    }
+
+    pub fn get_preset(self) -> WavePreset {
+        match self {
+            WaveTemplate::TwoSourceSim => two_source(),
+            WaveTemplate::SingleSlitSim => single_slit(),
+            WaveTemplate::DoubleSlitSim => double_slit(),
+            WaveTemplate::ReflectionSim => reflection(),
+            WaveTemplate::Saved(preset) => *preset,
+        }
+    }
+}
+
+pub fn get_wave_sim_list() -> [WaveTemplate; 4] {
+    [
+        WaveTemplate::TwoSourceSim,
+        WaveTemplate::SingleSlitSim,
+        WaveTemplate::DoubleSlitSim,
+        WaveTemplate::ReflectionSim,
+    ]
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WavePreset {
+    pub grid: Grid,
+    pub layout: WaveLayout,
+    pub settings: WaveSettings,
+}
+
+impl Default for WavePreset {
+    fn default() -> Self {
+        Self {
+            grid: Grid::new(NVec2::new(-100.0, -100.0), NVec2::new(100.0, 100.0), 1.0),
+            layout: WaveLayout::default(),
+            settings: WaveSettings::default(),
+        }
+    }
+}
+
+// 기본 설정에서 파장은 20 (60 / 3 Hz)
+
+// 점 파원의 파동은 퍼지면서 약해지므로 더 밝게 칠한다.
+fn point_source_settings() -> WaveSettings {
+    WaveSettings {
+        contrast: 4.0,
+        ..WaveSettings::default()
+    }
+}
+fn plane_wave() -> WaveSource {
+    WaveSource {
+        name: "Plane Wave".to_string(),
+        position: NVec2::new(-80.0, 0.0),
+        angle: FRAC_PI_2,
+        kind: WaveSourceKind::Line { length: 200.0 },
+        ..WaveSource::default()
+    }
+}
+
+fn two_source() -> WavePreset {
+    WavePreset {
+        layout: WaveLayout {
+            sources: vec![
+                WaveSource {
+                    name: "Source A".to_string(),
+                    position: NVec2::new(-40.0, 20.0),
+                    ..WaveSource::default()
+                },
+                WaveSource {
+                    name: "Source B".to_string(),
+                    position: NVec2::new(-40.0, -20.0),
+                    ..WaveSource::default()
+                },
+            ],
+            walls: vec![],
+        },
+        settings: point_source_settings(),
+        ..WavePreset::default()
+    }
+}
+
+fn single_slit() -> WavePreset {
+    WavePreset {
+        layout: WaveLayout {
+            sources: vec![plane_wave()],
+            walls: Wall::barrier_with_slits("Barrier", -40.0, (-100.0, 100.0), &[(0.0, 24.0)], 2.0),
+        },
+        ..WavePreset::default()
+    }
+}
+
+fn double_slit() -> WavePreset {
+    WavePreset {
+        layout: WaveLayout {
+            sources: vec![plane_wave()],
+            walls: Wall::barrier_with_slits(
+                "Barrier",
+                -40.0,
+                (-100.0, 100.0),
+                &[(-20.0, 8.0), (20.0, 8.0)],
+                2.0,
+            ),
+        },
+        settings: WaveSettings {
+            view: WaveView::Intensity,
+            contrast: 3.0,
+            ..WaveSettings::default()
+        },
+        ..WavePreset::default()
+    }
+}
+
+// 벽에서 반사된 파동이 원래 파동과 간섭한다.
+fn reflection() -> WavePreset {
+    WavePreset {
+        layout: WaveLayout {
+            sources: vec![WaveSource {
+                name: "Source".to_string(),
+                position: NVec2::new(-20.0, -25.0),
+                ..WaveSource::default()
+            }],
+            walls: vec![Wall::new(
+                "Mirror Wall",
+                NVec2::new(-90.0, -40.0),
+                NVec2::new(90.0, -40.0),
+                2.0,
+            )],
+        },
+        settings: point_source_settings(),
+        ..WavePreset::default()
+    }
+}
@@ -1,5 +1,8 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+#[cfg(feature = "gui")]
 mod app;
+pub mod engine;
 
+#[cfg(feature = "gui")]
 pub use app::State;